@@ -1,27 +1,28 @@
 
 //! Memory management commands for the MAYA LLM console
 
-use maya_llm::memory::{MemoryBank, MemoryType, MemoryRelationship};
+use maya_llm::memory::{MemoryBank, MemoryId, MemoryType, MemoryRelationship};
 use std::collections::HashMap;
 
 /// Enum representing different memory commands
 pub enum MemoryCommand {
     List { query: Option<String> },
-    Get { id: usize },
-    Add { 
-        content: String, 
+    Get { id: MemoryId },
+    Add {
+        content: String,
         memory_type: MemoryType,
         importance: f32,
-        metadata: Option<HashMap<String, String>> 
+        metadata: Option<HashMap<String, String>>
     },
-    Delete { id: usize },
-    Relate { 
-        source_id: usize, 
-        target_id: usize, 
+    Delete { id: MemoryId },
+    Relate {
+        source_id: MemoryId,
+        target_id: MemoryId,
         relationship: MemoryRelationship,
-        strength: f32 
+        strength: f32
     },
     Search { query: String },
+    Graph { id: MemoryId, depth: usize },
     Stats,
     Help,
 }
@@ -37,9 +38,9 @@ impl MemoryCommand {
             Some(&"get") => {
                 let id = args.get(1)
                     .ok_or("Missing memory ID")?
-                    .parse::<usize>()
+                    .parse::<u64>()
                     .map_err(|_| "Invalid memory ID")?;
-                Ok(MemoryCommand::Get { id })
+                Ok(MemoryCommand::Get { id: MemoryId::from_raw(id) })
             },
             
             Some(&"add") => {
@@ -101,25 +102,25 @@ impl MemoryCommand {
             Some(&"delete") => {
                 let id = args.get(1)
                     .ok_or("Missing memory ID")?
-                    .parse::<usize>()
+                    .parse::<u64>()
                     .map_err(|_| "Invalid memory ID")?;
-                Ok(MemoryCommand::Delete { id })
+                Ok(MemoryCommand::Delete { id: MemoryId::from_raw(id) })
             },
-            
+
             Some(&"relate") => {
                 if args.len() < 4 {
                     return Err("Missing required arguments. Usage: memory relate <id1> <id2> <relationship> [--strength 0.5]".to_string());
                 }
-                
-                let source_id = args[1].parse::<usize>()
+
+                let source_id = args[1].parse::<u64>()
                     .map_err(|_| "Invalid source memory ID".to_string())?;
-                    
-                let target_id = args[2].parse::<usize>()
+
+                let target_id = args[2].parse::<u64>()
                     .map_err(|_| "Invalid target memory ID".to_string())?;
-                    
+
                 let relationship = args[3].parse::<MemoryRelationship>()
                     .map_err(|_| format!("Invalid relationship type: {}", args[3]))?;
-                
+
                 // Default strength is 0.5 if not specified
                 let mut strength = 0.5;
                 let mut i = 4;
@@ -132,24 +133,38 @@ impl MemoryCommand {
                     }
                     i += 1;
                 }
-                
-                Ok(MemoryCommand::Relate { 
-                    source_id, 
-                    target_id, 
-                    relationship, 
-                    strength 
+
+                Ok(MemoryCommand::Relate {
+                    source_id: MemoryId::from_raw(source_id),
+                    target_id: MemoryId::from_raw(target_id),
+                    relationship,
+                    strength
                 })
             },
-            
+
             Some(&"search") => {
                 if args.len() < 2 {
                     return Err("Missing search query".to_string());
                 }
-                Ok(MemoryCommand::Search { 
-                    query: args[1..].join(" ") 
+                Ok(MemoryCommand::Search {
+                    query: args[1..].join(" ")
                 })
             },
-            
+
+            Some(&"graph") => {
+                let id = args.get(1)
+                    .ok_or("Missing memory ID")?
+                    .parse::<u64>()
+                    .map_err(|_| "Invalid memory ID")?;
+
+                let depth = match args.get(2) {
+                    Some(d) => d.parse::<usize>().map_err(|_| format!("Invalid depth: {}", d))?,
+                    None => 2,
+                };
+
+                Ok(MemoryCommand::Graph { id: MemoryId::from_raw(id), depth })
+            },
+
             Some(&"stats") => Ok(MemoryCommand::Stats),
             
             Some(&"help") | None => Ok(MemoryCommand::Help),
@@ -232,29 +247,87 @@ impl MemoryCommand {
             },
             
             MemoryCommand::Search { query } => {
-                let results = memory_bank.recall_memories(query);
+                let results = memory_bank.search(query);
                 if results.is_empty() {
                     "No matching memories found.".to_string()
                 } else {
                     let mut result = format!("Found {} matching memories:\n", results.len());
-                    for (i, mem) in results.iter().enumerate() {
-                        result.push_str(&format!("  {}: {}\n", i, mem));
+                    for (id, _mem, score, snippet) in &results {
+                        result.push_str(&format!("  #{} ({:.3}): {}\n", id, score, snippet));
                     }
                     result
                 }
             },
-            
+
+            MemoryCommand::Graph { id, depth } => {
+                let Some(root) = memory_bank.get_memory(*id) else {
+                    return format!("Memory #{} not found", id);
+                };
+
+                let reachable = memory_bank.traverse(*id, *depth, 0.0, None);
+
+                let root_preview = if root.content.len() > 50 {
+                    format!("{}...", &root.content[..47])
+                } else {
+                    root.content.clone()
+                };
+                let mut result = format!("#{} ({}): {}\n", id, root.memory_type, root_preview);
+                if reachable.is_empty() {
+                    result.push_str("  (no related memories within depth)\n");
+                    return result;
+                }
+
+                for (target_id, path, strength) in &reachable {
+                    let Some(mem) = memory_bank.get_memory(*target_id) else {
+                        continue;
+                    };
+                    let indent = "  ".repeat(path.len() - 1);
+                    let preview = if mem.content.len() > 50 {
+                        format!("{}...", &mem.content[..47])
+                    } else {
+                        mem.content.clone()
+                    };
+                    result.push_str(&format!(
+                        "{}-> #{} ({:.2}): {}\n",
+                        indent, target_id, strength, preview
+                    ));
+                }
+                result
+            },
+
             MemoryCommand::Stats => {
-                // Get all memories and filter out deleted ones
-                let memories: Vec<String> = memory_bank.recall_memories("").into_iter().filter(|m| !m.is_empty()).collect();
+                let memories = memory_bank.recall_memory_objects("");
                 let memory_count = memories.len();
-                
+
                 let mut result = format!("Memory Bank Statistics:\n");
                 result.push_str(&format!("  Total memories: {}\n", memory_count));
-                
-                // Since we only have strings from recall_memories, we can't get memory types
-                // This is a limitation of the current API
-                
+
+                if memory_count == 0 {
+                    return result;
+                }
+
+                let mut by_type: HashMap<String, usize> = HashMap::new();
+                let mut total_importance = 0.0f32;
+                let mut max_importance = f32::MIN;
+                let mut total_relationships = 0usize;
+                for mem in &memories {
+                    *by_type.entry(mem.memory_type.to_string()).or_insert(0) += 1;
+                    total_importance += mem.importance;
+                    max_importance = max_importance.max(mem.importance);
+                    total_relationships += mem.relationships.len();
+                }
+
+                result.push_str("  By type:\n");
+                let mut types: Vec<_> = by_type.into_iter().collect();
+                types.sort_by(|a, b| a.0.cmp(&b.0));
+                for (memory_type, count) in types {
+                    result.push_str(&format!("    {}: {}\n", memory_type, count));
+                }
+
+                result.push_str(&format!("  Average importance: {:.3}\n", total_importance / memory_count as f32));
+                result.push_str(&format!("  Max importance: {:.3}\n", max_importance));
+                result.push_str(&format!("  Total relationships: {}\n", total_relationships));
+
                 result
             },
             
@@ -272,7 +345,8 @@ Memory Management Commands:
   memory delete <id>       - Delete a specific memory
   memory relate <id1> <id2> <relationship> [--strength <0.0-1.0>]
                             - Relate two memories
-  memory search <query>     - Search for memories by content
+  memory search <query>     - Search for memories, ranked by relevance (typo-tolerant)
+  memory graph <id> [depth] - Show memories reachable from <id> via relationships (default depth: 2)
   memory stats              - Show memory statistics
   memory help               - Show this help message
 "#.to_string()
@@ -297,8 +371,8 @@ mod tests {
     #[test]
     fn test_parse_get_command() {
         let cmd = MemoryCommand::parse(&["get", "42"]).unwrap();
-        assert!(matches!(cmd, MemoryCommand::Get { id: 42 }));
-        
+        assert!(matches!(cmd, MemoryCommand::Get { id } if id == MemoryId::from_raw(42)));
+
         assert!(MemoryCommand::parse(&["get"]).is_err());
         assert!(MemoryCommand::parse(&["get", "abc"]).is_err());
     }
@@ -331,23 +405,64 @@ mod tests {
         let cmd = MemoryCommand::parse(&["relate", "1", "2", "hierarchical"]).unwrap();
         match cmd {
             MemoryCommand::Relate { source_id, target_id, relationship, strength } => {
-                assert_eq!(source_id, 1);
-                assert_eq!(target_id, 2);
+                assert_eq!(source_id, MemoryId::from_raw(1));
+                assert_eq!(target_id, MemoryId::from_raw(2));
                 assert_eq!(relationship, MemoryRelationship::ParentOf);
                 assert_eq!(strength, 0.5);
             },
             _ => panic!("Expected Relate command"),
         }
-        
+
         let cmd = MemoryCommand::parse(&["relate", "1", "2", "temporal", "--strength", "0.8"]).unwrap();
         match cmd {
             MemoryCommand::Relate { source_id, target_id, relationship, strength } => {
-                assert_eq!(source_id, 1);
-                assert_eq!(target_id, 2);
+                assert_eq!(source_id, MemoryId::from_raw(1));
+                assert_eq!(target_id, MemoryId::from_raw(2));
                 assert_eq!(relationship, MemoryRelationship::HappenedBefore);
                 assert_eq!(strength, 0.8);
             },
             _ => panic!("Expected Relate command with strength"),
         }
     }
+
+    #[test]
+    fn test_parse_graph_command() {
+        let cmd = MemoryCommand::parse(&["graph", "7"]).unwrap();
+        assert!(matches!(cmd, MemoryCommand::Graph { id, depth } if id == MemoryId::from_raw(7) && depth == 2));
+
+        let cmd = MemoryCommand::parse(&["graph", "7", "4"]).unwrap();
+        assert!(matches!(cmd, MemoryCommand::Graph { id, depth } if id == MemoryId::from_raw(7) && depth == 4));
+
+        assert!(MemoryCommand::parse(&["graph"]).is_err());
+        assert!(MemoryCommand::parse(&["graph", "7", "abc"]).is_err());
+    }
+
+    #[test]
+    fn test_stats_reports_counts_by_type() {
+        use maya_llm::memory::MemoryType;
+
+        let mut memory_bank = MemoryBank::new();
+        memory_bank.remember("one".to_string(), MemoryType::Fact, 0.4, 1.0, None);
+        memory_bank.remember("two".to_string(), MemoryType::Fact, 0.8, 1.0, None);
+        memory_bank.remember("three".to_string(), MemoryType::Goal, 0.6, 1.0, None);
+
+        let output = MemoryCommand::Stats.execute(&mut memory_bank);
+        assert!(output.contains("Total memories: 3"));
+        assert!(output.contains("fact: 2"));
+        assert!(output.contains("goal: 1"));
+    }
+
+    #[test]
+    fn test_graph_command_reports_related_memories() {
+        let mut memory_bank = MemoryBank::new();
+        let a = memory_bank.remember("root".to_string(), MemoryType::Fact, 0.5, 1.0, None);
+        let b = memory_bank.remember("child".to_string(), MemoryType::Fact, 0.5, 1.0, None);
+        if let Some(mem) = memory_bank.get_memory_mut(a) {
+            mem.add_relationship(b, MemoryRelationship::ParentOf, 0.9);
+        }
+
+        let output = MemoryCommand::Graph { id: a, depth: 2 }.execute(&mut memory_bank);
+        assert!(output.contains("root"));
+        assert!(output.contains("child"));
+    }
 }
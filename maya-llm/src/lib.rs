@@ -11,11 +11,17 @@ pub mod pattern;
 pub mod response;
 pub mod persistence;
 pub mod memory;
+pub mod session;
+pub mod settings;
+pub mod store;
 
 use pattern::PatternMatcher;
-use response::ResponseContext;
-use memory::{MemoryBank, MemoryType};
+use response::{FunctionRegistry, ResponseContext};
+use memory::{Embedder, HashingEmbedder, MemoryBank, MemoryType};
 use persistence::PersistenceError;
+use session::SessionManager;
+use settings::SettingsBuilder;
+use store::LlmStore;
 
 /// Core trait defining the LLM interface
 pub trait LLM {
@@ -30,18 +36,65 @@ pub trait LLM {
 }
 
 /// A simple implementation of the LLM trait using pattern matching
+///
+/// `generate_response`/`learn`/`recall_memories` all require `&mut self`:
+/// they mutate `patterns`/`memory`/`context` in place, so a shared
+/// `BasicLLM` can't safely answer concurrent queries from multiple threads
+/// without each caller synchronizing access itself (e.g. behind a `Mutex`).
+/// [`store::LlmStore`] is the one part of this type's state that *is* safe
+/// to share -- it's `Clone`, its `Env` is opened with LMDB's concurrent-
+/// reader flags, and every method takes `&self` -- but it's a durability
+/// log, not the live pattern/memory indexes those methods actually read.
 pub struct BasicLLM {
     name: String,
     patterns: Rc<RefCell<PatternMatcher>>,
     fallback_responses: Vec<String>,
     context: ResponseContext,
     memory: MemoryBank,
+    /// The effective settings map: built-in defaults layered with whatever
+    /// was persisted, an optional overlay file, and environment variables
+    /// -- see [`settings::SettingsBuilder`].
     settings: HashMap<String, String>,
+    /// Built-in defaults, lowest-precedence layer of `settings`. Not
+    /// itself persisted -- see `settings_persisted`.
+    settings_defaults: HashMap<String, String>,
+    /// The persisted/overlay layer of `settings`, i.e. what `save_state`
+    /// writes back out. Kept separate from `settings` so defaults and
+    /// `MAYA_SETTING_*` environment overrides picked up by `load_state`
+    /// never get baked into the saved model file.
+    settings_persisted: HashMap<String, String>,
+    /// TOML file whose contents override `settings_persisted` but are
+    /// overridden by the environment, re-read on every `load_state`. `None`
+    /// skips this layer entirely, matching pre-layering behavior.
+    settings_overlay_path: Option<PathBuf>,
+    /// Environment variable prefix scanned on `load_state` (see
+    /// [`SettingsBuilder::with_env`]). `None` skips this layer entirely,
+    /// matching pre-layering behavior.
+    settings_env_prefix: Option<String>,
     data_dir: Option<PathBuf>,
+    /// Per-conversation state for callers using the session API. Empty and
+    /// unused by callers that only ever talk to `context`/`context_mut`.
+    sessions: SessionManager,
+    /// The session `generate_response`/`extract_variables`/etc. operate
+    /// against. `None` means "use the single global `context` field",
+    /// matching this type's original single-conversation behavior.
+    active_session: Option<String>,
+    /// Functions a pattern's response template can invoke via a
+    /// `{{call:name(args)}}` directive instead of only emitting static text.
+    functions: FunctionRegistry,
+    /// Durable LMDB-backed store with separate `patterns`/`memories`/`context`
+    /// sub-databases, opened lazily under `data_dir/state.lmdb` the first
+    /// time it's needed. `None` until a data dir is set, matching how
+    /// `sessions` lazily load from disk rather than eagerly opening files at
+    /// construction time.
+    store: Option<LlmStore>,
 }
 
 impl Default for BasicLLM {
     fn default() -> Self {
+        let mut memory = MemoryBank::new();
+        memory.set_embedder(HashingEmbedder::default());
+
         Self {
             name: "MAYA".to_string(),
             patterns: Rc::new(RefCell::new(PatternMatcher::new())),
@@ -51,9 +104,17 @@ impl Default for BasicLLM {
                 "I'm still learning. Can you tell me more?".to_string(),
             ],
             context: ResponseContext::new(),
-            memory: MemoryBank::new(),
+            memory,
             settings: HashMap::new(),
+            settings_defaults: HashMap::new(),
+            settings_persisted: HashMap::new(),
+            settings_overlay_path: None,
+            settings_env_prefix: None,
             data_dir: None,
+            sessions: SessionManager::new(),
+            active_session: None,
+            functions: FunctionRegistry::new(),
+            store: None,
         }
     }
 }
@@ -62,19 +123,24 @@ impl LLM for BasicLLM {
     fn generate_response(&mut self, input: &str, context: &[String]) -> String {
         // Update context with previous messages
         for msg in context {
-            self.context.add_previous_message(msg);
+            self.active_context_mut().add_previous_message(msg);
         }
-        
+
+        // Record the raw input in the active session's rolling history, if any.
+        if let Some(session) = self.active_session.clone().and_then(|id| self.sessions.get_mut(&id)) {
+            session.record(input);
+        }
+
         // Extract potential variables from input (e.g., "my name is Alice")
         self.extract_variables(input);
-        
+
         // Prepare context for pattern matching
-        let context_strings: Vec<String> = self.context.previous_messages
+        let context_strings: Vec<String> = self.active_context().previous_messages
             .iter()
             .take(3) // Only use last 3 messages as context
             .map(|s| s.to_string())
             .collect();
-            
+
         // Get relevant memories for this input
         let relevant_memories = self.recall_memories(input);
         
@@ -97,26 +163,26 @@ impl LLM for BasicLLM {
             if let Some(pattern) = patterns.find_best_match_with_context(input, Some(&enhanced_context)) {
                 // Generate response using the template system
                 let response_template = pattern.response.clone();
-                
-                // Prepare context for template rendering
-                let mut template_vars = HashMap::new();
-                
-                // Add user name if available
-                if let Some(name) = &self.context.user_name {
-                    template_vars.insert("user", name.clone());
-                }
-                
-                // Add other context variables
-                for (key, value) in &self.context.custom_vars {
-                    template_vars.insert(key.as_str(), value.clone());
-                }
-                
-                // Render the template with variables
-                let response = ResponseTemplate::new(&response_template).render(&template_vars).to_string();
-                
+
                 // Check if we should learn from this interaction
                 let match_quality = pattern.match_score(input, Some(&enhanced_context));
-                (response, match_quality < 8.0) // Learn if not a very strong match
+
+                // Render the template against the active session's context,
+                // resolving any {{call:name(args)}} directives against the
+                // registered functions. The context itself supplies plain
+                // variables (user, custom vars) as well as iterable data
+                // (e.g. {{#each previous_messages}}). An unregistered
+                // function name degrades gracefully to a fallback response
+                // rather than emitting a half-rendered template.
+                match ResponseTemplate::new(&response_template)
+                    .render_with_functions(self.active_context(), &self.functions)
+                {
+                    Some(response) => (response, match_quality < 8.0), // Learn if not a very strong match
+                    None => {
+                        let idx = (input.len() % self.fallback_responses.len()) as usize;
+                        (self.fallback_responses[idx].clone(), true)
+                    }
+                }
             } else {
                 // No pattern matched, use a fallback response
                 let idx = (input.len() % self.fallback_responses.len()) as usize;
@@ -125,35 +191,41 @@ impl LLM for BasicLLM {
         };
         
         // Update context with the response
-        self.context.add_previous_message(&response);
-        
+        self.active_context_mut().add_previous_message(&response);
+
         // Learn from this interaction if needed
         if should_learn {
             self.learn_from_interaction(input, &response, &context_strings);
         }
-        
+
         response
     }
-    
+
     /// Learn a new pattern or reinforce an existing one
     fn learn(&mut self, input: &str, response: &str) {
         // First check if we need to prune
         if self.needs_pruning() {
             self.prune_patterns();
         }
-        
+
         // Then add the pattern
         let is_new = {
             let mut patterns = self.patterns.borrow_mut();
             patterns.add_pattern(input, response)
         };
-        
+
         if is_new {
             log::debug!("Added new pattern: '{}' -> '{:?}'", input, response);
-            self.context.add_previous_message(&format!("Learned: {} -> {}", input, response));
+            self.active_context_mut().add_previous_message(&format!("Learned: {} -> {}", input, response));
+
+            if let Ok(store) = self.ensure_store() {
+                if let Err(e) = store.put_pattern(input, response) {
+                    log::debug!("Failed to durably persist pattern, continuing with in-memory copy only: {}", e);
+                }
+            }
         }
     }
-    
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -181,7 +253,10 @@ impl BasicLLM {
             "how are you",
             "I'm just a program, but I'm functioning well. Thanks for asking!"
         );
-        
+
+        let mut memory = MemoryBank::new();
+        memory.set_embedder(HashingEmbedder::default());
+
         Self {
             name: "MAYA".to_string(),
             patterns: Rc::new(RefCell::new(pattern_matcher)),
@@ -191,11 +266,36 @@ impl BasicLLM {
                 "I'm still learning. Can you tell me more?".to_string(),
             ],
             context: ResponseContext::new(),
-            memory: MemoryBank::new(),
+            memory,
             settings: HashMap::new(),
+            settings_defaults: HashMap::new(),
+            settings_persisted: HashMap::new(),
+            settings_overlay_path: None,
+            settings_env_prefix: None,
             data_dir: None,
+            sessions: SessionManager::new(),
+            active_session: None,
+            functions: FunctionRegistry::new(),
+            store: None,
         }
     }
+    /// Swap in a custom [`Embedder`] (e.g. backed by a real embedding model)
+    /// for semantic memory recall, replacing the default [`HashingEmbedder`].
+    pub fn set_embedder(&mut self, embedder: impl Embedder + 'static) {
+        self.memory.set_embedder(embedder);
+    }
+
+    /// Register a function that a response template can invoke via a
+    /// `{{call:name(args)}}` directive, e.g. mapping `"what time is it"` to a
+    /// `now()` callback or `"remind me to *"` to a scheduler callback.
+    pub fn register_function(
+        &mut self,
+        name: impl Into<String>,
+        f: impl Fn(&HashMap<String, String>) -> String + 'static,
+    ) {
+        self.functions.register(name, f);
+    }
+
     /// Check if we need to prune patterns before adding a new one
     fn needs_pruning(&self) -> bool {
         let patterns = self.patterns.borrow();
@@ -217,24 +317,45 @@ impl BasicLLM {
         
         if is_new {
             log::debug!("Learned from interaction: '{}' -> '{}'", input, response);
+
+            if let Ok(store) = self.ensure_store() {
+                if let Err(e) = store.put_pattern(input, response) {
+                    log::debug!("Failed to durably persist pattern, continuing with in-memory copy only: {}", e);
+                }
+            }
         }
-        
+
         // Then update similar patterns with context
         self.update_similar_patterns(input, context);
     }
     
     /// Get patterns that are similar to the input
     fn find_similar_patterns(&self, input: &str, context: &[String]) -> Vec<(usize, f32)> {
-        let patterns = self.patterns.borrow();
+        let mut patterns = self.patterns.borrow_mut();
+
+        // Only score patterns candidate_indices thinks are worth it (shares
+        // a literal substring, typo neighborhood, or trigger word with
+        // `input`), falling back to a full scan if none of those prefilters
+        // found anything.
+        let candidates = patterns.candidate_indices(input);
         let mut similar = Vec::new();
-        
-        for (i, pattern) in patterns.patterns.iter().enumerate() {
-            let similarity = pattern.match_score(input, Some(context));
-            if similarity > 0.3 { // If somewhat similar
-                similar.push((i, similarity));
+
+        if candidates.is_empty() {
+            for (i, pattern) in patterns.patterns.iter().enumerate() {
+                let similarity = pattern.match_score(input, Some(context));
+                if similarity > 0.3 { // If somewhat similar
+                    similar.push((i, similarity));
+                }
+            }
+        } else {
+            for i in candidates {
+                let similarity = patterns.patterns[i].match_score(input, Some(context));
+                if similarity > 0.3 {
+                    similar.push((i, similarity));
+                }
             }
         }
-        
+
         similar
     }
     
@@ -310,10 +431,16 @@ impl BasicLLM {
                     .to_string();
                 
                 if !name.is_empty() {
-                    self.context.set_var("name", &name);
-                    if self.context.user_name.is_none() {
-                        self.context.user_name = Some(name.clone());
-                        
+                    self.active_context_mut().set_var("name", &name);
+                    if self.active_context().user_name.is_none() {
+                        self.active_context_mut().user_name = Some(name.clone());
+
+                        if let Ok(store) = self.ensure_store() {
+                            if let Err(e) = store.put_context_field("user_name", &name) {
+                                log::debug!("Failed to durably persist user_name, continuing with in-memory copy only: {}", e);
+                            }
+                        }
+
                         // Store in memory
                         let mut metadata = HashMap::new();
                         metadata.insert("type".to_string(), "user_name".to_string());
@@ -347,7 +474,7 @@ impl BasicLLM {
                     .to_string();
                 
                 if !mood.is_empty() {
-                    self.context.set_var("mood", &mood);
+                    self.active_context_mut().set_var("mood", &mood);
                     
                     // Store in memory
                     let mut metadata = HashMap::new();
@@ -376,7 +503,7 @@ impl BasicLLM {
                 "blue"
             };
             
-            self.context.set_var("color", color);
+            self.active_context_mut().set_var("color", color);
             
             // Store in memory
             let mut metadata = HashMap::new();
@@ -390,22 +517,108 @@ impl BasicLLM {
         }
     }
     
-    /// Set the user's name in the context
+    /// Set the user's name in the active context (the active session's, if
+    /// one is set, otherwise the global context)
     pub fn set_user_name(&mut self, name: &str) {
-        self.context.user_name = Some(name.to_string());
+        self.active_context_mut().user_name = Some(name.to_string());
+
+        if let Ok(store) = self.ensure_store() {
+            if let Err(e) = store.put_context_field("user_name", name) {
+                log::debug!("Failed to durably persist user_name, continuing with in-memory copy only: {}", e);
+            }
+        }
     }
-    
-    /// Get a reference to the context
+
+    /// Get a reference to the active context: the active session's context if
+    /// [`start_session`](Self::start_session)/[`switch_session`](Self::switch_session)
+    /// has been called, otherwise the global context used before sessions existed.
     pub fn context(&self) -> &ResponseContext {
-        &self.context
+        self.active_context()
     }
-    
-    /// Get a mutable reference to the context
+
+    /// Get a mutable reference to the active context. See [`context`](Self::context).
     pub fn context_mut(&mut self) -> &mut ResponseContext {
-        &mut self.context
+        self.active_context_mut()
     }
-    
-    /// Store a new memory
+
+    fn active_context(&self) -> &ResponseContext {
+        match &self.active_session {
+            Some(id) => self
+                .sessions
+                .get(id)
+                .map(|session| &session.context)
+                .unwrap_or(&self.context),
+            None => &self.context,
+        }
+    }
+
+    fn active_context_mut(&mut self) -> &mut ResponseContext {
+        match &self.active_session {
+            Some(id) => &mut self
+                .sessions
+                .get_mut(id)
+                .expect("active_session always names a session started via start_session")
+                .context,
+            None => &mut self.context,
+        }
+    }
+
+    /// Start a new session (or re-activate an existing one) and make it the
+    /// active session for `generate_response`/`learn`/`context`/etc. If
+    /// `data_dir` is set and a session previously saved under this id exists
+    /// on disk but isn't already loaded, it's loaded lazily here.
+    pub fn start_session(&mut self, id: impl Into<String>) {
+        let id = id.into();
+
+        if !self.sessions.contains(&id) {
+            if let Some(data_dir) = &self.data_dir {
+                if let Ok(Some(session)) =
+                    persistence::load_session(data_dir.join("sessions"), &id)
+                {
+                    self.sessions.insert_session(id.clone(), session);
+                }
+            }
+        }
+
+        self.sessions.start_session(id.clone());
+        self.active_session = Some(id);
+    }
+
+    /// Switch to an already-started session. Returns `false` (and leaves the
+    /// active session unchanged) if `id` hasn't been started.
+    pub fn switch_session(&mut self, id: &str) -> bool {
+        if !self.sessions.contains(id) {
+            return false;
+        }
+        self.active_session = Some(id.to_string());
+        true
+    }
+
+    /// List the ids of every currently-started session.
+    pub fn list_sessions(&self) -> Vec<&str> {
+        self.sessions.list_sessions()
+    }
+
+    /// End a session, persisting it first if `data_dir` is set. Falls back to
+    /// the global context if the ended session was active.
+    pub fn end_session(&mut self, id: &str) -> Result<(), PersistenceError> {
+        if let (Some(data_dir), Some(session)) = (&self.data_dir, self.sessions.get(id)) {
+            persistence::save_session(data_dir.join("sessions"), id, session)?;
+        }
+
+        self.sessions.end_session(id);
+        if self.active_session.as_deref() == Some(id) {
+            self.active_session = None;
+        }
+
+        Ok(())
+    }
+
+    /// Store a new memory. If a data directory is set, the memory is also
+    /// durably appended to the `memories` sub-database of the on-disk
+    /// [`LlmStore`] in its own write transaction, so it survives a crash
+    /// even before the next whole-state [`save_state`](Self::save_state)
+    /// snapshot.
     pub fn remember<T: Into<String>>(
         &mut self,
         content: T,
@@ -413,16 +626,53 @@ impl BasicLLM {
         importance: f32,
         metadata: Option<HashMap<String, String>>,
     ) {
-        self.memory.add_memory(content, memory_type, importance, metadata);
+        let content = content.into();
+        let mut memory = memory::Memory::new(content, memory_type);
+        memory.importance = importance.clamp(0.0, 1.0);
+        memory.base_importance = memory.importance;
+        if let Some(meta) = metadata {
+            memory.metadata = meta;
+        }
+
+        if let Ok(store) = self.ensure_store() {
+            if let Err(e) = store.append_memory(&memory) {
+                log::debug!("Failed to durably append memory, continuing with in-memory copy only: {}", e);
+            }
+        }
+
+        self.memory.add_memory(memory);
     }
-    
-    /// Recall relevant memories based on a query
-    pub fn recall_memories<T: AsRef<str>>(&self, query: T) -> Vec<String> {
-        self.memory
-            .recall(query)
-            .into_iter()
-            .map(|m| m.content.clone())
-            .collect()
+
+    /// Iterate every stored memory of `memory_type`, reading straight off
+    /// the `memories` sub-database's cursor rather than the in-RAM
+    /// `MemoryBank` -- a caller that only cares about, say, every
+    /// [`MemoryType::Preference`] doesn't need the bank's token/embedding
+    /// indexes built at all. Requires a data directory to be set.
+    pub fn iter_memories_by_type(&mut self, memory_type: MemoryType) -> Result<Vec<memory::Memory>, PersistenceError> {
+        self.ensure_store()?.iter_memories_by_type(&memory_type)
+    }
+
+    /// Open (and cache) the [`LlmStore`] under `data_dir/state.lmdb`, if a
+    /// data directory is set. Mirrors how sessions are lazily loaded from
+    /// disk only once they're actually needed.
+    fn ensure_store(&mut self) -> Result<&LlmStore, PersistenceError> {
+        if self.store.is_none() {
+            let data_dir = self
+                .data_dir
+                .as_ref()
+                .ok_or(PersistenceError::NotConfigured("data directory not set"))?;
+            self.store = Some(LlmStore::open(data_dir.join("state.lmdb"))?);
+        }
+        Ok(self.store.as_ref().expect("just set above"))
+    }
+
+    /// Recall relevant memories based on a query, ranked by
+    /// [`MemoryBank::recall_memories`]'s text-match/importance/forgetting-
+    /// curve score. Every memory returned is reinforced (see that method's
+    /// docs), so facts asked about repeatedly keep surfacing while one-off
+    /// lines fade.
+    pub fn recall_memories<T: AsRef<str>>(&mut self, query: T) -> Vec<String> {
+        self.memory.recall_memories(query.as_ref())
     }
     
     /// Get a reference to the memory bank
@@ -444,68 +694,172 @@ impl BasicLLM {
     pub fn data_dir(&self) -> Option<&Path> {
         self.data_dir.as_deref()
     }
-    
+
+    /// Set a built-in default for a setting -- the lowest-precedence layer
+    /// of `settings`, overridden by whatever's persisted and by the overlay
+    /// file/environment once [`set_settings_overlay_path`](Self::set_settings_overlay_path)/
+    /// [`set_settings_env_prefix`](Self::set_settings_env_prefix) are configured.
+    pub fn set_setting_default(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.settings_defaults.insert(key.into(), value.into());
+        let mut builder = SettingsBuilder::new()
+            .with_defaults(self.settings_defaults.clone())
+            .with_persisted(self.settings_persisted.clone());
+        if let Some(prefix) = &self.settings_env_prefix {
+            builder = builder.with_env(prefix);
+        }
+        self.settings = builder.build();
+    }
+
+    /// The fully resolved settings map -- defaults, then whatever was
+    /// persisted, then the overlay file, then environment variables.
+    pub fn settings(&self) -> &HashMap<String, String> {
+        &self.settings
+    }
+
+    /// Layer an on-disk TOML overlay file into `settings` on every future
+    /// `load_state`, overriding the persisted map but overridden by the
+    /// environment. `None` (the default) skips this layer entirely.
+    pub fn set_settings_overlay_path<P: Into<PathBuf>>(&mut self, path: Option<P>) {
+        self.settings_overlay_path = path.map(Into::into);
+    }
+
+    /// Layer environment variables named `{prefix}_KEY` into `settings` on
+    /// every future `load_state` -- see [`SettingsBuilder::with_env`].
+    /// `None` (the default) skips this layer entirely.
+    pub fn set_settings_env_prefix(&mut self, prefix: Option<impl Into<String>>) {
+        self.settings_env_prefix = prefix.map(Into::into);
+    }
+
     /// Save the current state to disk
-    pub fn save_state(&self) -> Result<(), PersistenceError> {
-        let data_dir = self.data_dir.as_ref().ok_or_else(|| {
-            PersistenceError::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Data directory not set",
-            ))
-        })?;
+    pub fn save_state(&mut self) -> Result<(), PersistenceError> {
+        let data_dir = self
+            .data_dir
+            .as_ref()
+            .ok_or(PersistenceError::NotConfigured("data directory not set"))?;
         
         // Ensure the directory exists
-        std::fs::create_dir_all(data_dir)?;
-        
+        std::fs::create_dir_all(data_dir).map_err(|source| PersistenceError::Io {
+            resource: persistence::Resource::ParentDir(data_dir.clone()),
+            operation: persistence::Operation::Create,
+            target: None,
+            source,
+        })?;
+
         let state_path = data_dir.join("state.json");
         
-        // Create a temporary file for atomic write
-        let temp_path = state_path.with_extension("tmp");
-        
-        // Save to temporary file first
+        // persistence::save_state writes a temporary sibling and renames it
+        // over state.json, keeping the old file as state.previous. Only the
+        // persisted/overlay layer is
+        // written out -- defaults and MAYA_SETTING_* environment overrides
+        // stay out of the saved model file, see `settings::SettingsBuilder`.
+        let function_names: Vec<String> = self.functions.names().into_iter().map(String::from).collect();
+        let persistable_settings = SettingsBuilder::new()
+            .with_defaults(self.settings_defaults.clone())
+            .with_persisted(self.settings_persisted.clone())
+            .persistable();
         persistence::save_state(
-            &temp_path,
+            &state_path,
             &*self.patterns.borrow(),
             &self.context,
             &self.memory,
             &self.name,
-            Some(self.settings.clone()),
+            Some(persistable_settings),
+            &function_names,
         )?;
         
-        // Atomically rename the temporary file to the target file
-        std::fs::rename(&temp_path, &state_path)?;
-        
         // Ensure directory entries are updated
         if let Some(parent) = state_path.parent() {
-            let _ = std::fs::File::open(parent)?.sync_all();
+            let dir_file = std::fs::File::open(parent).map_err(|source| PersistenceError::Io {
+                resource: persistence::Resource::ParentDir(parent.to_path_buf()),
+                operation: persistence::Operation::Read,
+                target: None,
+                source,
+            })?;
+            let _ = dir_file.sync_all();
         }
-        
+
+        // Sessions live in their own files under sessions/<id>.json, separate
+        // from the shared brain above, so a save doesn't require rewriting
+        // every other active conversation's state.
+        let sessions_dir = data_dir.join("sessions");
+        for (id, session) in self.sessions.iter() {
+            persistence::save_session(&sessions_dir, id, session)?;
+        }
+
+        // Every `remember()`/`learn()`/`set_user_name()` already durably
+        // committed its own write transaction against the LMDB store, so
+        // there's nothing left to flush here -- this call exists only so
+        // callers relying on `save_state()` for durability keep getting it
+        // from the backend that actually provides it now.
+        if let Ok(store) = self.ensure_store() {
+            store.flush()?;
+        }
+
         Ok(())
     }
-    
-    /// Load state from disk
+
+    /// Load state from disk. Only the shared brain (patterns, memory,
+    /// settings, the global context) is loaded eagerly; sessions are loaded
+    /// lazily by [`start_session`](Self::start_session) on first access.
     pub fn load_state(&mut self) -> Result<(), PersistenceError> {
-        let data_dir = self.data_dir.as_ref().ok_or_else(|| {
-            PersistenceError::IoError(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Data directory not set",
-            ))
-        })?;
-        
+        let data_dir = self
+            .data_dir
+            .as_ref()
+            .ok_or(PersistenceError::NotConfigured("data directory not set"))?;
+
         let state_path = data_dir.join("state.json");
-        
+
         if !state_path.exists() {
-            return Ok(()); // No saved state to load
+            // No whole-state snapshot yet, but memories may still have been
+            // durably appended to the LMDB store (e.g. the process crashed
+            // before its first `save_state()`). Rebuild the bank from those
+            // records rather than losing them.
+            let recovered = {
+                let store = self.ensure_store()?;
+                store.load_all_memories()?
+            };
+            for memory in recovered {
+                self.memory.add_memory(memory);
+            }
+            return Ok(());
         }
-        
+
         let state = persistence::load_state(&state_path)?;
-        
+
         // Update the LLM state
         *self.patterns.borrow_mut() = state.pattern_matcher;
         self.context = state.context;
         self.memory = state.memory_bank;
-        self.settings = state.settings;
-        
+
+        // Layer the persisted map with the overlay file (if configured),
+        // then re-derive both the persistable layer (persisted + overlay,
+        // what a future `save_state` writes back out) and the fully
+        // resolved `settings` map (adding environment variables on top) --
+        // see `settings::SettingsBuilder`.
+        let mut builder = SettingsBuilder::new()
+            .with_defaults(self.settings_defaults.clone())
+            .with_persisted(state.settings);
+        if let Some(overlay_path) = &self.settings_overlay_path {
+            builder = builder.with_overlay_file(overlay_path).map_err(|e| {
+                PersistenceError::FormatError(e.to_string())
+            })?;
+        }
+        self.settings_persisted = builder.persistable();
+        if let Some(prefix) = &self.settings_env_prefix {
+            builder = builder.with_env(prefix);
+        }
+        self.settings = builder.build();
+
+        // Closures can't be serialized, so the saved state only recorded
+        // which functions were registered; warn if the caller hasn't
+        // re-registered one that templates may still reference.
+        let registered = self.functions.names();
+        for name in &state.function_names {
+            if !registered.contains(&name.as_str()) {
+                log::debug!("Saved state expects function '{}' to be re-registered", name);
+            }
+        }
+
         Ok(())
     }
 }
@@ -19,14 +19,231 @@ GLIMMER Pattern:
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::pattern::damerau_levenshtein;
+
+/// Default decay rate (λ) for [`MemoryBank::retention_score`]: about a 14-day
+/// half-life for a memory that's never touched again.
+pub const DEFAULT_DECAY_RATE: f32 = 0.05;
+
+/// How strongly repeated access flattens the decay curve (α in
+/// `R = importance * exp(-λ * Δt / (1 + α * access_count))`). Higher values
+/// mean each extra access buys proportionally more protection from decay.
+const ACCESS_DAMPENING: f32 = 0.5;
+
+/// Width of the vectors [`HashingEmbedder`] produces.
+const DEFAULT_EMBEDDING_DIMS: usize = 64;
+
+/// Strength constant (`K`) for [`MemoryBank::recall_memories`]'s retention
+/// factor `R = exp(-elapsed_secs / (K * (1 + reinforcement_count)))` --
+/// one day in seconds, so an unreinforced memory's score falls to `1/e` of
+/// its text-match*importance value after about a day untouched, and each
+/// additional recall stretches that window out by another full day.
+const REINFORCEMENT_STRENGTH_SECS: f32 = 86_400.0;
+
+/// Weight given to embedding cosine similarity, vs. raw `importance`, when
+/// [`MemoryBank::recall`] blends the two: `final = RECALL_COSINE_WEIGHT *
+/// cosine + RECALL_IMPORTANCE_WEIGHT * importance`.
+const RECALL_COSINE_WEIGHT: f32 = 0.7;
+/// See [`RECALL_COSINE_WEIGHT`].
+const RECALL_IMPORTANCE_WEIGHT: f32 = 0.3;
+
+/// Computes a fixed-width embedding vector for a piece of text, so
+/// [`MemoryBank::recall`] can rank candidates by semantic similarity
+/// instead of (or in addition to) keyword overlap. Pluggable: ships with
+/// the dependency-light [`HashingEmbedder`] by default, swappable for a
+/// real model-backed embedder via [`MemoryBank::set_embedder`].
+pub trait Embedder: Send + Sync {
+    /// Embed `text` into a fixed-width vector.
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Dependency-light default [`Embedder`]: feature-hashes each token of the
+/// input into one of `dims` signed buckets (the hashing trick, as used for
+/// large-vocabulary bag-of-words models when an explicit vocabulary isn't
+/// available) and L2-normalizes the result. Works with no external model;
+/// swap in a real one via [`MemoryBank::set_embedder`].
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dims: usize,
+}
+
+impl HashingEmbedder {
+    /// Create a hashing embedder producing `dims`-wide vectors.
+    pub fn new(dims: usize) -> Self {
+        Self { dims: dims.max(1) }
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(DEFAULT_EMBEDDING_DIMS)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dims];
+
+        for token in tokenize(text) {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            token.hash(&mut hasher);
+            let hash = hasher.finish();
+
+            let bucket = (hash % self.dims as u64) as usize;
+            let sign = if hash & 1 == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+
+        vector
+    }
+}
+
+/// Cosine similarity `dot(a,b) / (‖a‖‖b‖)` between two vectors, `0.0` if
+/// either is empty, mismatched in length, or zero-length.
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot / (norm_a * norm_b)
+}
+
+/// A memory lifecycle event, passed to every registered trigger (see
+/// [`MemoryBank::on_event`]). Carries the memory's ID plus its old/new
+/// state, so a trigger can re-embed content into an external store, log an
+/// audit trail, or cascade an update without polling `MemoryBank`.
+#[derive(Debug, Clone)]
+pub enum MemoryEvent {
+    /// A new memory was added via [`MemoryBank::add_memory`].
+    Added {
+        /// The new memory's ID.
+        id: MemoryId,
+        /// The memory as stored.
+        memory: Memory,
+    },
+    /// An existing memory's content, importance, confidence, or metadata
+    /// changed via [`MemoryBank::update_memory`].
+    Updated {
+        /// The updated memory's ID.
+        id: MemoryId,
+        /// The memory's state before the update.
+        before: Memory,
+        /// The memory's state after the update.
+        after: Memory,
+    },
+    /// A memory was deleted via [`MemoryBank::delete`].
+    Removed {
+        /// The deleted memory's ID.
+        id: MemoryId,
+        /// The memory's state immediately before deletion.
+        memory: Memory,
+    },
+    /// A memory was truncated out of the bank by [`MemoryBank`]'s capacity
+    /// cleanup, rather than explicitly deleted.
+    Evicted {
+        /// The evicted memory's ID.
+        id: MemoryId,
+        /// The memory's state immediately before eviction.
+        memory: Memory,
+    },
+}
+
+/// A lifecycle trigger registered with [`MemoryBank::on_event`].
+pub type MemoryTrigger = Arc<dyn Fn(&MemoryEvent) + Send + Sync>;
+
+/// A short window of `content` around the first matched token, so
+/// [`MemoryBank::search`] can show why a memory matched without returning
+/// the whole thing.
+fn snippet_for(content: &str, matched_tokens: &HashSet<String>) -> String {
+    const WINDOW_CHARS: usize = 60;
+    let chars: Vec<char> = content.chars().collect();
+    let lower = content.to_lowercase();
+
+    let hit_char_pos = matched_tokens
+        .iter()
+        .filter_map(|token| lower.find(token.as_str()).map(|byte_pos| lower[..byte_pos].chars().count()))
+        .min();
+
+    let (start, end) = match hit_char_pos {
+        Some(pos) => (
+            pos.saturating_sub(WINDOW_CHARS / 2),
+            (pos + WINDOW_CHARS / 2).min(chars.len()),
+        ),
+        None => (0, WINDOW_CHARS.min(chars.len())),
+    };
+
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet = format!("...{}", snippet);
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// Split `content` into lowercased alphanumeric tokens for the inverted
+/// index, e.g. `"User's name is Alice"` -> `["user", "s", "name", "is",
+/// "alice"]`.
+fn tokenize(content: &str) -> Vec<String> {
+    content
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// A stable identifier for a [`Memory`], independent of where it lives in
+/// `MemoryBank`'s storage. Handed out once by [`MemoryBank::add_memory`] as a
+/// monotonically increasing counter and never reused, so a
+/// [`MemoryLink::target_id`] captured today still resolves correctly after
+/// later inserts, deletes, or a [`MemoryBank::cleanup`] eviction — unlike a
+/// `Vec` position, which shifts under those operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MemoryId(u64);
+
+impl fmt::Display for MemoryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl MemoryId {
+    /// Reconstruct a `MemoryId` previously handed out by
+    /// [`MemoryBank::add_memory`] -- from a console command's parsed
+    /// argument, a persisted record, or a [`fmt::Display`]ed id round-tripped
+    /// through text. Not for minting new ids: only `add_memory`'s internal
+    /// counter may do that, so a `MemoryId` always traces back to a memory
+    /// that was actually inserted.
+    pub fn from_raw(id: u64) -> Self {
+        Self(id)
+    }
+}
 
 /// Represents a relationship between two memories
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryLink {
-    pub target_id: usize,
+    pub target_id: MemoryId,
     pub relationship: MemoryRelationship,
     pub strength: f32,  // 0.0 to 1.0 indicating relationship strength
 }
@@ -34,6 +251,9 @@ pub struct MemoryLink {
 /// Represents a single memory entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Memory {
+    /// This memory's stable ID, assigned once by [`MemoryBank::add_memory`].
+    pub id: MemoryId,
+
     /// The actual content of the memory
     pub content: String,
     
@@ -45,10 +265,34 @@ pub struct Memory {
     
     /// Last time this memory was accessed or modified
     pub last_accessed: DateTime<Utc>,
-    
+
+    /// Number of times this memory has been touched since creation. Used to
+    /// flatten the decay curve in [`MemoryBank::retention_score`] — a
+    /// frequently-recalled memory fades slower than one accessed once and
+    /// forgotten, the same way spaced repetition reinforces a fact each
+    /// time it's reviewed.
+    #[serde(default)]
+    pub access_count: u32,
+
     /// Importance score (0.0 to 1.0)
     pub importance: f32,
-    
+
+    /// The importance this memory was created with (the value passed to
+    /// [`MemoryBank::remember`]), held separately from `importance` so
+    /// [`MemoryBank::recall_memories`]'s decay scoring has a stable
+    /// baseline to decay *from* even if `importance` is later edited via
+    /// [`MemoryBank::update_memory`].
+    #[serde(default)]
+    pub base_importance: f32,
+
+    /// Number of times this memory has been returned by
+    /// [`MemoryBank::recall_memories`], which flattens that method's
+    /// forgetting curve the same way `access_count` flattens
+    /// [`MemoryBank::retention_score`]'s -- a fact recalled often decays
+    /// slower than one asked about once and never again.
+    #[serde(default)]
+    pub reinforcement_count: u32,
+
     /// Confidence in the memory (0.0 to 1.0)
     pub confidence: f32,
     
@@ -62,6 +306,15 @@ pub struct Memory {
     /// Whether this memory should be kept even if it's old/unimportant
     #[serde(default)]
     pub pinned: bool,
+
+    /// This memory's embedding vector, computed by whatever [`Embedder`]
+    /// was configured via [`MemoryBank::set_embedder`] at the time it was
+    /// added or last updated. `None` if no embedder was configured yet,
+    /// in which case [`MemoryBank::recall`] falls back to the keyword
+    /// path for it. Persisted so recall quality survives a restart
+    /// without re-embedding every memory.
+    #[serde(default)]
+    pub embedding: Option<Vec<f32>>,
 }
 
 /// Different types of memories the system can store
@@ -197,11 +450,67 @@ impl fmt::Display for MemoryRelationship {
 }
 
 /// Manages the LLM's memory
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct MemoryBank {
-    memories: Vec<Memory>,
+    memories: HashMap<MemoryId, Memory>,
+    /// Counter handing out the next [`MemoryId`]; monotonically increasing
+    /// and persisted, so IDs stay unique and never get reused even across a
+    /// save/reload.
+    next_id: u64,
     max_memories: usize,
+
+    /// Minimum score [`recall_memories`](MemoryBank::recall_memories)
+    /// requires to return a candidate. See
+    /// [`set_importance_threshold`](MemoryBank::set_importance_threshold).
     importance_threshold: f32,
+
+    /// Decay rate (λ) for [`retention_score`](MemoryBank::retention_score)'s
+    /// forgetting curve. Higher values fade unaccessed memories faster.
+    decay_rate: f32,
+
+    /// Inverted index: lowercased content token -> memory IDs containing it.
+    /// Rebuilt from `memories` on load since the enum/string keys below
+    /// don't round-trip cleanly through JSON object keys.
+    #[serde(skip)]
+    token_index: HashMap<String, Vec<MemoryId>>,
+
+    /// Secondary index: memory type -> memory IDs of that type.
+    #[serde(skip)]
+    type_index: HashMap<MemoryType, Vec<MemoryId>>,
+
+    /// User-registered secondary indexes, keyed by metadata key, each
+    /// mapping that metadata value to the memory IDs carrying it. See
+    /// [`create_index`](MemoryBank::create_index).
+    #[serde(skip)]
+    metadata_indexes: HashMap<String, HashMap<String, Vec<MemoryId>>>,
+
+    /// Lifecycle triggers registered with [`on_event`](MemoryBank::on_event),
+    /// skipped from (de)serialization like the indexes above since closures
+    /// can't round-trip through `Serialize`/`Deserialize`.
+    #[serde(skip)]
+    triggers: Vec<MemoryTrigger>,
+
+    /// The [`Embedder`] used to compute new/updated memories' `embedding`,
+    /// and the query embedding in [`recall`](Self::recall). Skipped from
+    /// (de)serialization like `triggers` above, since a trait object can't
+    /// round-trip through `Serialize`/`Deserialize` — the embeddings it
+    /// produced are persisted on each `Memory` instead, so recall quality
+    /// survives a restart even before [`set_embedder`](Self::set_embedder)
+    /// is called again.
+    #[serde(skip)]
+    embedder: Option<Arc<dyn Embedder>>,
+}
+
+impl fmt::Debug for MemoryBank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBank")
+            .field("memories", &self.memories)
+            .field("max_memories", &self.max_memories)
+            .field("importance_threshold", &self.importance_threshold)
+            .field("decay_rate", &self.decay_rate)
+            .field("triggers", &self.triggers.len())
+            .finish_non_exhaustive()
+    }
 }
 
 impl Memory {
@@ -209,36 +518,46 @@ impl Memory {
     pub fn new<T: Into<String>>(content: T, memory_type: MemoryType) -> Self {
         let now = Utc::now();
         Self {
+            // Overwritten with a real ID by `MemoryBank::add_memory`; a
+            // `Memory` isn't addressable until it's been inserted.
+            id: MemoryId(0),
             content: content.into(),
             memory_type,
             created_at: now,
             last_accessed: now,
+            access_count: 0,
             importance: 0.5,  // Default medium importance
+            base_importance: 0.5,
+            reinforcement_count: 0,
             confidence: 0.8,  // Start with high confidence
             metadata: HashMap::new(),
             relationships: Vec::new(),
             pinned: false,
+            embedding: None,
         }
     }
 
-    /// Update the last_accessed timestamp to now
+    /// Update the last_accessed timestamp to now and record the access
+    /// towards this memory's decay dampening (see
+    /// [`MemoryBank::retention_score`]).
     pub fn touch(&mut self) {
         self.last_accessed = Utc::now();
+        self.access_count += 1;
     }
 
     /// Add a relationship to another memory
-    pub fn add_relationship(&mut self, target_id: usize, relationship: MemoryRelationship, strength: f32) {
+    pub fn add_relationship(&mut self, target_id: MemoryId, relationship: MemoryRelationship, strength: f32) {
         // Don't allow self-references
-        if target_id == self as *const _ as usize {
+        if target_id == self.id {
             return;
         }
-        
+
         let link = MemoryLink {
             target_id,
             relationship: relationship.clone(),
             strength: strength.clamp(0.0, 1.0),
         };
-        
+
         // Update existing relationship if it exists, otherwise add new
         let relationship_clone = relationship;
         if let Some(existing) = self.relationships.iter_mut()
@@ -248,9 +567,9 @@ impl Memory {
             self.relationships.push(link);
         }
     }
-    
+
     /// Remove a relationship to another memory
-    pub fn remove_relationship(&mut self, target_id: usize, relationship: &MemoryRelationship) -> bool {
+    pub fn remove_relationship(&mut self, target_id: MemoryId, relationship: &MemoryRelationship) -> bool {
         if let Some(pos) = self.relationships.iter().position(|r| r.target_id == target_id && &r.relationship == relationship) {
             self.relationships.remove(pos);
             true
@@ -275,20 +594,77 @@ impl MemoryBank {
     /// Create a new MemoryBank with default settings
     pub fn new() -> Self {
         Self {
-            memories: Vec::new(),
+            memories: HashMap::new(),
+            next_id: 0,
             max_memories: 1000,
             importance_threshold: 0.3,
+            decay_rate: DEFAULT_DECAY_RATE,
+            token_index: HashMap::new(),
+            type_index: HashMap::new(),
+            metadata_indexes: HashMap::new(),
+            triggers: Vec::new(),
+            embedder: None,
+        }
+    }
+
+    /// Hand out the next [`MemoryId`]. Monotonically increasing and never
+    /// reused, even after the memory it named is deleted or evicted.
+    fn allocate_id(&mut self) -> MemoryId {
+        let id = MemoryId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    /// Register a trigger that fires on every memory lifecycle event
+    /// (added, updated, removed, evicted), analogous to put/remove/replace
+    /// triggers in a relational store. Useful for keeping an external store
+    /// in sync — re-embedding content into a vector DB, writing an audit
+    /// log, cascading a related update — without polling `MemoryBank`.
+    pub fn on_event(&mut self, trigger: impl Fn(&MemoryEvent) + Send + Sync + 'static) {
+        self.triggers.push(Arc::new(trigger));
+    }
+
+    /// Fire every registered trigger with `event`.
+    fn fire(&self, event: MemoryEvent) {
+        for trigger in &self.triggers {
+            trigger(&event);
+        }
+    }
+
+    /// Use `embedder` to compute `embedding`s for future memories (and for
+    /// [`recall`](Self::recall) query vectors) — no embedder is configured
+    /// by default, so `recall` falls back to the keyword path until this
+    /// is called at least once (see [`HashingEmbedder`] for a
+    /// dependency-light default). Backfills every currently stored memory
+    /// that doesn't have an embedding yet, so memories added before this
+    /// call (or loaded from a save with no embedder configured) benefit
+    /// immediately rather than waiting for their next update.
+    pub fn set_embedder(&mut self, embedder: impl Embedder + 'static) {
+        let embedder: Arc<dyn Embedder> = Arc::new(embedder);
+        for memory in self.memories.values_mut() {
+            if memory.embedding.is_none() && !memory.is_empty() {
+                memory.embedding = Some(embedder.embed(&memory.content));
+            }
         }
+        self.embedder = Some(embedder);
     }
 
     /// Add a new memory and return its ID
-    pub fn add_memory(&mut self, memory: Memory) -> usize {
-        let id = self.memories.len();
-        self.memories.push(memory);
+    pub fn add_memory(&mut self, mut memory: Memory) -> MemoryId {
+        let id = self.allocate_id();
+        memory.id = id;
+        if memory.embedding.is_none() {
+            if let Some(embedder) = &self.embedder {
+                memory.embedding = Some(embedder.embed(&memory.content));
+            }
+        }
+        self.memories.insert(id, memory.clone());
+        self.index_memory(id);
+        self.fire(MemoryEvent::Added { id, memory });
         self.cleanup();
         id
     }
-    
+
     /// Create and add a new memory with the given content and type
     pub fn remember<T: Into<String>>(
         &mut self,
@@ -297,11 +673,12 @@ impl MemoryBank {
         importance: f32,
         confidence: f32,
         metadata: Option<HashMap<String, String>>,
-    ) -> usize {
+    ) -> MemoryId {
         let mut memory = Memory::new(content, memory_type);
         memory.importance = importance.clamp(0.0, 1.0);
+        memory.base_importance = memory.importance;
         memory.confidence = confidence.clamp(0.0, 1.0);
-        
+
         if let Some(meta) = metadata {
             memory.metadata = meta;
         }
@@ -310,169 +687,751 @@ impl MemoryBank {
     }
     
     /// Get a memory by ID (mutable)
-    pub fn get_memory_mut(&mut self, id: usize) -> Option<&mut Memory> {
-        self.memories.get_mut(id)
+    pub fn get_memory_mut(&mut self, id: MemoryId) -> Option<&mut Memory> {
+        self.memories.get_mut(&id)
     }
-    
+
     /// Get a memory by ID (immutable)
-    pub fn get_memory(&self, id: usize) -> Option<&Memory> {
-        self.memories.get(id)
+    pub fn get_memory(&self, id: MemoryId) -> Option<&Memory> {
+        self.memories.get(&id)
     }
-    
+
     /// Update a memory's content and metadata
     pub fn update_memory<T: Into<String>>(
         &mut self,
-        id: usize,
+        id: MemoryId,
         content: Option<T>,
         importance: Option<f32>,
         confidence: Option<f32>,
         metadata: Option<HashMap<String, String>>,
     ) -> bool {
-        if let Some(memory) = self.get_memory_mut(id) {
-            if let Some(content) = content {
-                memory.content = content.into();
-            }
-            if let Some(imp) = importance {
-                memory.importance = imp.clamp(0.0, 1.0);
-            }
-            if let Some(conf) = confidence {
-                memory.confidence = conf.clamp(0.0, 1.0);
-            }
-            if let Some(meta) = metadata {
-                memory.metadata = meta;
+        let Some(before) = self.get_memory(id).cloned() else {
+            return false;
+        };
+
+        // Content and metadata are what the indexes are keyed on, so drop
+        // this memory's old entries before mutating it and re-add them
+        // afterward rather than leaving stale postings behind.
+        self.deindex_memory(id);
+
+        let embedder = self.embedder.clone();
+        let memory = self.get_memory_mut(id).expect("checked above");
+        if let Some(content) = content {
+            memory.content = content.into();
+            if let Some(embedder) = &embedder {
+                memory.embedding = Some(embedder.embed(&memory.content));
             }
-            memory.touch();
-            true
-        } else {
-            false
         }
+        if let Some(imp) = importance {
+            memory.importance = imp.clamp(0.0, 1.0);
+        }
+        if let Some(conf) = confidence {
+            memory.confidence = conf.clamp(0.0, 1.0);
+        }
+        if let Some(meta) = metadata {
+            memory.metadata = meta;
+        }
+        memory.touch();
+        let after = memory.clone();
+
+        self.index_memory(id);
+        self.fire(MemoryEvent::Updated { id, before, after });
+        true
+    }
+
+    /// Set the minimum score [`recall_memories`](Self::recall_memories)
+    /// requires to return a candidate. Scores are `text_match_score *
+    /// base_importance * R` (see `recall_memories`'s docs), all three
+    /// factors in `0.0..=1.0` -- a threshold of `0.0` never drops anything,
+    /// while higher values require progressively closer/more important/more
+    /// recently-reinforced matches. Defaults to `0.3` (see
+    /// [`MemoryBank::new`]).
+    pub fn set_importance_threshold(&mut self, threshold: f32) {
+        self.importance_threshold = threshold.clamp(0.0, 1.0);
     }
 
-    /// Get relevant memories based on a query
-    pub fn recall_memories(&self, query: &str) -> Vec<String> {
-        self.search(query)
+    /// Get relevant memories based on a query, ranked by a combination of
+    /// text-match relevance and a reinforcement-aware forgetting curve.
+    ///
+    /// Each candidate is scored `text_match_score * base_importance * R`:
+    /// `text_match_score` is the fraction of `query`'s tokens found in the
+    /// memory's content, `base_importance` is the importance the memory
+    /// was created with (see [`Memory::base_importance`]), and `R =
+    /// exp(-elapsed_secs / (K * (1 + reinforcement_count)))` is a
+    /// forgetting-curve retention factor -- `elapsed_secs` the time since
+    /// `last_accessed`, `K` the one-day [`REINFORCEMENT_STRENGTH_SECS`].
+    /// Candidates scoring below [`importance_threshold`](Self::set_importance_threshold)
+    /// are dropped; the rest are returned sorted by score descending.
+    ///
+    /// Every memory actually returned has its `reinforcement_count` bumped
+    /// and `last_accessed` reset to now, so facts recalled often (e.g.
+    /// "User's favorite color is blue") decay slower than one-off lines
+    /// nobody asks about again.
+    ///
+    /// This differs from [`recall`](Self::recall), which ranks by cosine
+    /// similarity/BM25 blended with raw `importance` and never mutates the
+    /// memories it returns -- `recall_memories` is the one that models
+    /// recall itself as reinforcement.
+    pub fn recall_memories(&mut self, query: &str) -> Vec<String> {
+        let query_tokens: HashSet<String> = tokenize(&query.to_lowercase()).into_iter().collect();
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let now = Utc::now();
+        let threshold = self.importance_threshold;
+        let mut scored: Vec<(MemoryId, f32)> = self
+            .memories
+            .iter()
+            .filter(|(_, m)| !m.is_empty())
+            .filter_map(|(&id, memory)| {
+                let content_tokens: HashSet<String> = tokenize(&memory.content).into_iter().collect();
+                let matched = query_tokens.intersection(&content_tokens).count();
+                if matched == 0 {
+                    return None;
+                }
+                let text_match_score = matched as f32 / query_tokens.len() as f32;
+
+                let elapsed_secs = (now - memory.last_accessed).num_seconds().max(0) as f32;
+                let retention = (-elapsed_secs
+                    / (REINFORCEMENT_STRENGTH_SECS * (1.0 + memory.reinforcement_count as f32)))
+                    .exp();
+
+                let score = text_match_score * memory.base_importance * retention;
+                if score < threshold {
+                    return None;
+                }
+                Some((id, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        scored
             .into_iter()
-            .map(|(_, mem, _)| mem.content.clone())
+            .filter_map(|(id, _)| {
+                let memory = self.memories.get_mut(&id)?;
+                memory.reinforcement_count += 1;
+                memory.last_accessed = now;
+                Some(memory.content.clone())
+            })
+            .collect()
+    }
+
+    /// Like [`recall_memories`](Self::recall_memories), but returns the
+    /// full [`Memory`] records instead of just their content -- for
+    /// callers that need `memory_type`, `importance`, `created_at`, or
+    /// `relationships`, e.g. `MemoryCommand::Stats` reporting counts per
+    /// type. Doesn't reinforce: recalling a memory's metadata isn't the
+    /// same event as recalling its content, so this takes `&self` and
+    /// never touches `reinforcement_count`/`last_accessed`.
+    ///
+    /// An empty `query` matches every (non-deleted) memory instead of
+    /// none, so `recall_memory_objects("")` doubles as "every memory
+    /// currently in the bank", ranked by importance.
+    pub fn recall_memory_objects(&self, query: &str) -> Vec<&Memory> {
+        let query_tokens: HashSet<String> = tokenize(&query.to_lowercase()).into_iter().collect();
+        let now = Utc::now();
+        let threshold = self.importance_threshold;
+
+        let mut scored: Vec<(MemoryId, f32)> = self
+            .memories
+            .iter()
+            .filter(|(_, m)| !m.is_empty())
+            .filter_map(|(&id, memory)| {
+                if query_tokens.is_empty() {
+                    return Some((id, memory.importance));
+                }
+
+                let content_tokens: HashSet<String> = tokenize(&memory.content).into_iter().collect();
+                let matched = query_tokens.intersection(&content_tokens).count();
+                if matched == 0 {
+                    return None;
+                }
+                let text_match_score = matched as f32 / query_tokens.len() as f32;
+
+                let elapsed_secs = (now - memory.last_accessed).num_seconds().max(0) as f32;
+                let retention = (-elapsed_secs
+                    / (REINFORCEMENT_STRENGTH_SECS * (1.0 + memory.reinforcement_count as f32)))
+                    .exp();
+
+                let score = text_match_score * memory.base_importance * retention;
+                if score < threshold {
+                    return None;
+                }
+                Some((id, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        scored.into_iter().filter_map(|(id, _)| self.memories.get(&id)).collect()
+    }
+
+    /// Rank memories by relevance to `query`.
+    ///
+    /// When an [`Embedder`] is configured (see
+    /// [`set_embedder`](Self::set_embedder) — [`HashingEmbedder`] is a
+    /// good dependency-light default), ranks by cosine similarity between
+    /// `query`'s embedding and each
+    /// memory's, blended with raw `importance`: `final =
+    /// RECALL_COSINE_WEIGHT * cosine + RECALL_IMPORTANCE_WEIGHT *
+    /// importance`. This catches paraphrases a keyword search would miss —
+    /// "Tell me about my pet" recalling "The user has a dog named Max" —
+    /// since the hashing embedder still places topically-similar phrases
+    /// closer together than unrelated ones, even with no shared tokens.
+    ///
+    /// Falls back to the keyword/BM25 ranking in [`search`](Self::search)
+    /// when no embedder is configured, or simply skips memories that don't
+    /// have an embedding yet (e.g. added before any embedder was set).
+    pub fn recall(&self, query: &str) -> Vec<&Memory> {
+        let Some(embedder) = &self.embedder else {
+            return self.search(query).into_iter().map(|(_, mem, _, _)| mem).collect();
+        };
+
+        let query_embedding = embedder.embed(query);
+        let mut scored: Vec<(&Memory, f32)> = self
+            .memories
+            .values()
+            .filter(|m| !m.is_empty())
+            .filter_map(|memory| {
+                let embedding = memory.embedding.as_ref()?;
+                let cosine = cosine_similarity(&query_embedding, embedding);
+                if cosine <= 0.0 {
+                    return None;
+                }
+                let score = RECALL_COSINE_WEIGHT * cosine + RECALL_IMPORTANCE_WEIGHT * memory.importance;
+                Some((memory, score))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.into_iter().map(|(mem, _)| mem).collect()
+    }
+
+    /// Compute a memory's effective retention score, a spaced-repetition-style
+    /// forgetting curve: `R = importance * exp(-λ * Δt / (1 + α * access_count))`,
+    /// where `Δt` is the time since `last_accessed` in days and `λ` is
+    /// [`decay_rate`](MemoryBank). Repeated access (tracked by `access_count`,
+    /// bumped in [`Memory::touch`]) flattens the curve, so a memory recalled
+    /// often fades slower than one touched once and left untouched for
+    /// months, even at equal `importance`.
+    fn retention_score(&self, memory: &Memory) -> f32 {
+        let elapsed_days = (Utc::now() - memory.last_accessed)
+            .num_seconds()
+            .max(0) as f32
+            / 86_400.0;
+        let dampening = 1.0 + ACCESS_DAMPENING * memory.access_count as f32;
+        memory.importance * (-self.decay_rate * elapsed_days / dampening).exp()
+    }
+
+    /// Compute every stored memory's current [`retention_score`](Self::retention_score),
+    /// so callers can inspect which memories are fading before they're
+    /// actually evicted by [`cleanup`](Self::cleanup).
+    pub fn decay_scores(&self) -> Vec<(MemoryId, f32)> {
+        self.memories
+            .iter()
+            .map(|(&id, memory)| (id, self.retention_score(memory)))
             .collect()
     }
 
     /// Clean up less important memories when we reach capacity
     fn cleanup(&mut self) {
         if self.memories.len() > self.max_memories {
-            // First, sort by pinned status (pinned first), then by importance, then by last accessed
-            self.memories.sort_by(|a, b| {
+            // Order every memory by pinned status (pinned first), then by
+            // retention score (the decayed, access-dampened stand-in for raw
+            // importance), then by last accessed, computed over `HashMap`
+            // keys since a `MemoryId` no longer doubles as a storage
+            // position.
+            let mut ordered: Vec<MemoryId> = self.memories.keys().copied().collect();
+            ordered.sort_by(|a, b| {
+                let a = &self.memories[a];
+                let b = &self.memories[b];
+                let score_a = self.retention_score(a);
+                let score_b = self.retention_score(b);
                 b.pinned.cmp(&a.pinned)
-                    .then(b.importance.partial_cmp(&a.importance).unwrap_or(std::cmp::Ordering::Equal))
+                    .then(score_b.partial_cmp(&score_a).unwrap_or(std::cmp::Ordering::Equal))
                     .then(b.last_accessed.cmp(&a.last_accessed))
             });
-            
+
             // Keep only the most important memories, but never remove pinned ones
-            let pinned_count = self.memories.iter().filter(|m| m.pinned).count();
+            let pinned_count = self.memories.values().filter(|m| m.pinned).count();
             let to_keep = std::cmp::max(self.max_memories, pinned_count);
-            
-            // Remove unpinned memories that exceed the limit
-            self.memories.truncate(to_keep);
-            
-            // Rebuild any indexes or caches if needed
-            self.rebuild_indexes();
+
+            // Remove unpinned memories that exceed the limit, firing an
+            // "evicted" trigger for each so downstream systems learn when a
+            // memory is forgotten rather than explicitly deleted.
+            for id in ordered.split_off(to_keep) {
+                if let Some(memory) = self.memories.remove(&id) {
+                    self.deindex_memory(id);
+                    self.fire(MemoryEvent::Evicted { id, memory });
+                }
+            }
+
+            // Evicted IDs may still be the `target_id` of links on memories
+            // that survived, so sweep those away too.
+            self.repair_dangling_links();
         }
     }
-    
-    /// Rebuild any internal indexes (placeholder for future use)
-    fn rebuild_indexes(&mut self) {
-        // This can be implemented to maintain secondary indexes for faster lookups
+
+    /// Rebuild the token, type, and registered metadata indexes from
+    /// scratch. `add_memory`/`update_memory`/`delete` keep them up to date
+    /// incrementally; this is only needed after deserializing a
+    /// `MemoryBank` (the indexes aren't persisted, since `MemoryType`
+    /// doesn't round-trip as a JSON map key).
+    pub(crate) fn rebuild_indexes(&mut self) {
+        self.token_index.clear();
+        self.type_index.clear();
+        for postings in self.metadata_indexes.values_mut() {
+            postings.clear();
+        }
+        let ids: Vec<MemoryId> = self.memories.keys().copied().collect();
+        for id in ids {
+            self.index_memory(id);
+        }
     }
-    
+
+    /// Remove every [`MemoryLink`] whose `target_id` no longer resolves to a
+    /// stored memory, e.g. after [`delete`](Self::delete) or an eviction in
+    /// [`cleanup`](Self::cleanup) removes the memory a link pointed at —
+    /// otherwise the relationship graph would silently corrupt, left
+    /// pointing at memories that are gone.
+    fn repair_dangling_links(&mut self) {
+        let live: HashSet<MemoryId> = self.memories.keys().copied().collect();
+        for memory in self.memories.values_mut() {
+            memory.relationships.retain(|link| live.contains(&link.target_id));
+        }
+    }
+
+    /// Add memory `id`'s content tokens, type, and any registered metadata
+    /// values into the indexes. A no-op for deleted (empty) memories.
+    fn index_memory(&mut self, id: MemoryId) {
+        let Some(memory) = self.memories.get(&id) else { return };
+        if memory.is_empty() {
+            return;
+        }
+
+        let tokens: HashSet<String> = tokenize(&memory.content).into_iter().collect();
+        for token in tokens {
+            let ids = self.token_index.entry(token).or_default();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+
+        let type_ids = self.type_index.entry(memory.memory_type.clone()).or_default();
+        if !type_ids.contains(&id) {
+            type_ids.push(id);
+        }
+
+        for (key, postings) in self.metadata_indexes.iter_mut() {
+            if let Some(value) = memory.metadata.get(key) {
+                let ids = postings.entry(value.clone()).or_default();
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+
+    /// Remove every index entry pointing at memory `id`, ahead of a content
+    /// update or deletion that would otherwise leave them stale.
+    fn deindex_memory(&mut self, id: MemoryId) {
+        for ids in self.token_index.values_mut() {
+            ids.retain(|&existing| existing != id);
+        }
+        for ids in self.type_index.values_mut() {
+            ids.retain(|&existing| existing != id);
+        }
+        for postings in self.metadata_indexes.values_mut() {
+            for ids in postings.values_mut() {
+                ids.retain(|&existing| existing != id);
+            }
+        }
+    }
+
+    /// Register a secondary index over `metadata_key`, backfilling it from
+    /// memories that already carry it. Mirrors the create/drop index API
+    /// `KnowledgeGraph` exposes for node properties.
+    ///
+    /// A no-op if the index already exists. Once created, `add_memory`,
+    /// `update_memory`, and `delete` keep it up to date automatically; query
+    /// it with [`find_by_metadata`](Self::find_by_metadata) instead of
+    /// scanning every memory.
+    pub fn create_index(&mut self, metadata_key: &str) {
+        if self.metadata_indexes.contains_key(metadata_key) {
+            return;
+        }
+        let mut postings: HashMap<String, Vec<MemoryId>> = HashMap::new();
+        for (&id, memory) in self.memories.iter() {
+            if let Some(value) = memory.metadata.get(metadata_key) {
+                postings.entry(value.clone()).or_default().push(id);
+            }
+        }
+        self.metadata_indexes.insert(metadata_key.to_string(), postings);
+    }
+
+    /// Remove a secondary index previously registered with
+    /// [`create_index`](Self::create_index). A no-op if it doesn't exist.
+    pub fn drop_index(&mut self, metadata_key: &str) {
+        self.metadata_indexes.remove(metadata_key);
+    }
+
+    /// Look up memories by an indexed metadata key/value pair. Returns
+    /// nothing if `metadata_key` hasn't been registered with
+    /// [`create_index`](Self::create_index).
+    pub fn find_by_metadata(&self, metadata_key: &str, value: &str) -> Vec<&Memory> {
+        self.metadata_indexes
+            .get(metadata_key)
+            .and_then(|postings| postings.get(value))
+            .into_iter()
+            .flatten()
+            .filter_map(|&id| self.memories.get(&id))
+            .collect()
+    }
+
     /// Find memories by type
     pub fn find_by_type(&self, memory_type: &MemoryType) -> Vec<&Memory> {
-        self.memories
-            .iter()
-            .filter(|m| &m.memory_type == memory_type)
+        self.type_index
+            .get(memory_type)
+            .into_iter()
+            .flatten()
+            .filter_map(|&id| self.memories.get(&id))
             .collect()
     }
-    
-    /// Find memories matching a query string
-    pub fn search(&self, query: &str) -> Vec<(usize, &Memory, f32)> {
-        // Simple implementation - could be enhanced with more sophisticated search
-        let query = query.to_lowercase();
-        self.memories
+
+    /// BM25 term-frequency saturation constant.
+    const BM25_K1: f32 = 1.2;
+    /// BM25 document-length normalization strength.
+    const BM25_B: f32 = 0.75;
+
+    /// Find memories matching a query string, ranked by BM25 relevance.
+    ///
+    /// Both the query and stored content are tokenized (see [`tokenize`]);
+    /// a query token that isn't in the index is also matched against every
+    /// indexed token within a small Damerau-Levenshtein distance (1 for
+    /// tokens of 5+ characters, 2 for 8+), so near-miss spellings like
+    /// "chocolat" still recall memories containing "chocolate". The BM25
+    /// score is finally scaled by `importance * confidence`, same as the
+    /// old scorer, and results are sorted descending. Each hit carries a
+    /// short snippet of `content` around a matched token, for display
+    /// without pulling the whole memory back out.
+    pub fn search(&self, query: &str) -> Vec<(MemoryId, &Memory, f32, String)> {
+        let query_tokens = tokenize(&query.to_lowercase());
+        if query_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matched_tokens: HashSet<String> = HashSet::new();
+        for token in &query_tokens {
+            if self.token_index.contains_key(token) {
+                matched_tokens.insert(token.clone());
+                continue;
+            }
+            let max_distance = match token.chars().count() {
+                n if n >= 8 => 2,
+                n if n >= 5 => 1,
+                _ => 0,
+            };
+            if max_distance == 0 {
+                continue;
+            }
+            for indexed_token in self.token_index.keys() {
+                if damerau_levenshtein(token, indexed_token) <= max_distance {
+                    matched_tokens.insert(indexed_token.clone());
+                }
+            }
+        }
+        if matched_tokens.is_empty() {
+            return Vec::new();
+        }
+
+        let mut candidate_ids: HashSet<MemoryId> = HashSet::new();
+        for token in &matched_tokens {
+            if let Some(postings) = self.token_index.get(token) {
+                candidate_ids.extend(postings.iter().copied());
+            }
+        }
+        if candidate_ids.is_empty() {
+            return Vec::new();
+        }
+
+        let doc_lengths: Vec<(MemoryId, f32)> = self
+            .memories
             .iter()
-            .enumerate()
-            .filter_map(|(id, mem)| {
-                let score = if mem.content.to_lowercase().contains(&query) {
-                    // Simple relevance scoring - could be enhanced
-                    let content = mem.content.to_lowercase();
-                    let matches = content.matches(&query).count() as f32;
-                    let position = content.find(&query).unwrap_or(0) as f32;
-                    
-                    // Higher score for earlier matches and more matches
-                    (1.0 / (position + 1.0)) * (1.0 + matches * 0.5)
-                } else {
-                    0.0
-                };
-                
+            .filter(|(_, m)| !m.is_empty())
+            .map(|(&id, m)| (id, tokenize(&m.content).len() as f32))
+            .collect();
+        let doc_count = doc_lengths.len() as f32;
+        let avg_doc_len = if doc_lengths.is_empty() {
+            0.0
+        } else {
+            doc_lengths.iter().map(|(_, len)| len).sum::<f32>() / doc_count
+        };
+
+        let mut results: Vec<(MemoryId, &Memory, f32, String)> = candidate_ids
+            .into_iter()
+            .filter_map(|id| {
+                let mem = self.memories.get(&id)?;
+                if mem.is_empty() {
+                    return None;
+                }
+                let doc_tokens = tokenize(&mem.content);
+                let doc_len = doc_tokens.len() as f32;
+                let length_norm = if avg_doc_len > 0.0 { doc_len / avg_doc_len } else { 0.0 };
+
+                let mut term_freq: HashMap<&str, f32> = HashMap::new();
+                for t in &doc_tokens {
+                    *term_freq.entry(t.as_str()).or_insert(0.0) += 1.0;
+                }
+
+                let mut score = 0.0f32;
+                for token in &matched_tokens {
+                    let f = *term_freq.get(token.as_str()).unwrap_or(&0.0);
+                    if f == 0.0 {
+                        continue;
+                    }
+                    let n_t = self.token_index.get(token).map(|p| p.len()).unwrap_or(0) as f32;
+                    let idf = (1.0 + (doc_count - n_t + 0.5) / (n_t + 0.5)).ln();
+                    let numerator = f * (Self::BM25_K1 + 1.0);
+                    let denominator = f + Self::BM25_K1 * (1.0 - Self::BM25_B + Self::BM25_B * length_norm);
+                    score += idf * (numerator / denominator);
+                }
+
                 if score > 0.0 {
-                    Some((id, mem, score * mem.importance * mem.confidence))
+                    let snippet = snippet_for(&mem.content, &matched_tokens);
+                    Some((id, mem, score * mem.importance * mem.confidence, snippet))
                 } else {
                     None
                 }
             })
-            .collect()
+            .collect();
+
+        results.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+        results
     }
 
     /// Get all memories of a specific type
     pub fn get_memories_by_type(&self, memory_type: MemoryType) -> Vec<&Memory> {
-        self.memories
-            .iter()
-            .filter(|m| m.memory_type == memory_type)
-            .collect()
+        self.find_by_type(&memory_type)
     }
     
     /// Get memories related to a specific memory
-    pub fn get_related_memories(&self, memory_id: usize, min_strength: f32) -> Vec<(usize, &Memory, &MemoryRelationship, f32)> {
+    pub fn get_related_memories(&self, memory_id: MemoryId, min_strength: f32) -> Vec<(MemoryId, &Memory, &MemoryRelationship, f32)> {
         let mut related = Vec::new();
-        
-        if let Some(memory) = self.memories.get(memory_id) {
+
+        if let Some(memory) = self.memories.get(&memory_id) {
             for link in &memory.relationships {
                 if link.strength >= min_strength {
-                    if let Some(related_mem) = self.memories.get(link.target_id) {
+                    if let Some(related_mem) = self.memories.get(&link.target_id) {
                         related.push((link.target_id, related_mem, &link.relationship, link.strength));
                     }
                 }
             }
         }
-        
+
         // Sort by strength (highest first)
         related.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal));
         related
     }
 
+    /// Walk outward from `start_id` over [`MemoryLink`]s, breadth-first,
+    /// returning every memory reachable within `max_depth` hops whose
+    /// cumulative path strength (the product of each edge's strength along
+    /// the way) is at least `min_strength`. `relationship_filter`, if set,
+    /// restricts traversal to edges of that relationship only.
+    ///
+    /// Each result is `(memory_id, path, strength)`, where `path` is the
+    /// sequence of memory IDs from `start_id` to `memory_id` inclusive.
+    /// Multi-hop generalization of [`get_related_memories`](Self::get_related_memories),
+    /// which only follows one hop. A visited set guards against cycles.
+    pub fn traverse(
+        &self,
+        start_id: MemoryId,
+        max_depth: usize,
+        min_strength: f32,
+        relationship_filter: Option<&MemoryRelationship>,
+    ) -> Vec<(MemoryId, Vec<MemoryId>, f32)> {
+        let mut results = Vec::new();
+        let mut visited: HashSet<MemoryId> = HashSet::new();
+        visited.insert(start_id);
+
+        let mut queue: VecDeque<(MemoryId, Vec<MemoryId>, f32)> = VecDeque::new();
+        queue.push_back((start_id, vec![start_id], 1.0));
+
+        while let Some((current_id, path, strength)) = queue.pop_front() {
+            if path.len() - 1 >= max_depth {
+                continue;
+            }
+
+            let Some(memory) = self.memories.get(&current_id) else {
+                continue;
+            };
+            for link in &memory.relationships {
+                if let Some(filter) = relationship_filter {
+                    if &link.relationship != filter {
+                        continue;
+                    }
+                }
+
+                let combined_strength = strength * link.strength;
+                if combined_strength < min_strength || !visited.insert(link.target_id) {
+                    continue;
+                }
+
+                let mut next_path = path.clone();
+                next_path.push(link.target_id);
+                results.push((link.target_id, next_path.clone(), combined_strength));
+                queue.push_back((link.target_id, next_path, combined_strength));
+            }
+        }
+
+        results
+    }
+
+    /// Find the strongest chain of [`MemoryLink`]s connecting `from_id` to
+    /// `to_id`, following any relationship. Returns the path of memory IDs
+    /// (inclusive of both ends) and its cumulative strength, or `None` if
+    /// they aren't connected.
+    ///
+    /// Since every edge strength is in `0.0..=1.0`, a path's cumulative
+    /// strength only shrinks as it grows — so, like Dijkstra expanding the
+    /// lowest-cost frontier node first, greedily expanding the
+    /// highest-strength frontier path first guarantees the first time a
+    /// memory is settled, it's via its strongest path.
+    pub fn find_path(&self, from_id: MemoryId, to_id: MemoryId) -> Option<(Vec<MemoryId>, f32)> {
+        if from_id == to_id {
+            return Some((vec![from_id], 1.0));
+        }
+
+        let mut best_strength: HashMap<MemoryId, f32> = HashMap::new();
+        best_strength.insert(from_id, 1.0);
+        let mut frontier: Vec<(MemoryId, Vec<MemoryId>, f32)> = vec![(from_id, vec![from_id], 1.0)];
+        let mut visited: HashSet<MemoryId> = HashSet::new();
+
+        while !frontier.is_empty() {
+            let (index, _) = frontier
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.2.partial_cmp(&b.1.2).unwrap_or(std::cmp::Ordering::Equal))
+                .unwrap();
+            let (current_id, path, strength) = frontier.remove(index);
+
+            if !visited.insert(current_id) {
+                continue;
+            }
+            if current_id == to_id {
+                return Some((path, strength));
+            }
+
+            let Some(memory) = self.memories.get(&current_id) else {
+                continue;
+            };
+            for link in &memory.relationships {
+                if visited.contains(&link.target_id) {
+                    continue;
+                }
+                let combined_strength = strength * link.strength;
+                if combined_strength <= *best_strength.get(&link.target_id).unwrap_or(&0.0) {
+                    continue;
+                }
+                best_strength.insert(link.target_id, combined_strength);
+                let mut next_path = path.clone();
+                next_path.push(link.target_id);
+                frontier.push((link.target_id, next_path, combined_strength));
+            }
+        }
+
+        None
+    }
+
+    /// Materialize indirect links for a transitive relationship: if `A` has
+    /// `relationship` to `B` and `B` has `relationship` to `C`, add a direct
+    /// `A -> C` link of the same relationship to `A`, with strength equal to
+    /// the product of the two edges. Meaningful for relationships that are
+    /// actually transitive, like [`MemoryRelationship::ParentOf`],
+    /// [`MemoryRelationship::DependsOn`], or [`MemoryRelationship::HappenedBefore`].
+    ///
+    /// Only adds links that don't already exist as direct edges; a visited
+    /// set per source memory guards against cycles. Returns the number of
+    /// new links materialized.
+    pub fn transitive_closure(&mut self, relationship: &MemoryRelationship) -> usize {
+        // Snapshot the relevant adjacency up front so the BFS below can run
+        // over plain data instead of borrowing `self.memories` while we
+        // accumulate the links to add afterward.
+        let adjacency: HashMap<MemoryId, Vec<(MemoryId, f32)>> = self
+            .memories
+            .iter()
+            .map(|(&id, memory)| {
+                let edges = memory
+                    .relationships
+                    .iter()
+                    .filter(|link| &link.relationship == relationship)
+                    .map(|link| (link.target_id, link.strength))
+                    .collect();
+                (id, edges)
+            })
+            .collect();
+
+        let mut new_links: Vec<(MemoryId, MemoryId, f32)> = Vec::new();
+        for (&source, direct_edges) in &adjacency {
+            let direct: HashSet<MemoryId> = direct_edges.iter().map(|&(target, _)| target).collect();
+            let mut visited: HashSet<MemoryId> = HashSet::new();
+            visited.insert(source);
+            direct.iter().for_each(|&target| {
+                visited.insert(target);
+            });
+
+            let mut queue: VecDeque<(MemoryId, f32)> = direct_edges.iter().copied().collect();
+            while let Some((current, strength_so_far)) = queue.pop_front() {
+                let Some(edges) = adjacency.get(&current) else {
+                    continue;
+                };
+                for &(next, edge_strength) in edges {
+                    if !visited.insert(next) {
+                        continue;
+                    }
+                    let combined_strength = strength_so_far * edge_strength;
+                    if next != source && !direct.contains(&next) {
+                        new_links.push((source, next, combined_strength));
+                    }
+                    queue.push_back((next, combined_strength));
+                }
+            }
+        }
+
+        let materialized = new_links.len();
+        for (source, target, strength) in new_links {
+            if let Some(memory) = self.memories.get_mut(&source) {
+                memory.add_relationship(target, relationship.clone(), strength);
+            }
+        }
+        materialized
+    }
+
     /// Get the number of stored memories
     pub fn len(&self) -> usize {
         self.memories.len()
     }
-    
+
     /// Check if the memory bank is empty
     pub fn is_empty(&self) -> bool {
         self.memories.is_empty()
     }
-    
+
     /// Delete a memory by ID
-    pub fn delete(&mut self, id: usize) -> bool {
-        if id < self.memories.len() {
-            // Mark the memory as deleted by setting its content to empty string
-            // This preserves the ID space while effectively removing the content
-            if let Some(memory) = self.memories.get_mut(id) {
-                memory.content = String::new();
-                memory.importance = 0.0;
-                memory.confidence = 0.0;
-                memory.metadata.clear();
-                memory.relationships.clear();
-                memory.pinned = false;
-                return true;
-            }
-        }
-        false
+    pub fn delete(&mut self, id: MemoryId) -> bool {
+        let Some(removed) = self.memories.remove(&id) else {
+            return false;
+        };
+        self.deindex_memory(id);
+        // The memory is actually gone now (unlike the old `Vec`-backed
+        // tombstone, which kept a blanked entry around to preserve ID
+        // space), so any link elsewhere in the bank that pointed at it is
+        // now dangling and needs sweeping.
+        self.repair_dangling_links();
+        self.fire(MemoryEvent::Removed { id, memory: removed });
+        true
     }
 }
 
@@ -483,15 +1442,20 @@ mod tests {
 
     fn create_test_memory(content: &str, memory_type: MemoryType, importance: f32) -> Memory {
         Memory {
+            id: MemoryId(0),
             content: content.to_string(),
             memory_type,
             created_at: Utc::now(),
             last_accessed: Utc::now(),
+            access_count: 0,
             importance,
+            base_importance: importance,
+            reinforcement_count: 0,
             confidence: 0.9,
             metadata: HashMap::new(),
             relationships: Vec::new(),
             pinned: false,
+            embedding: None,
         }
     }
 
@@ -562,15 +1526,64 @@ mod tests {
         assert_eq!(parents[0].1.content, "Complete project documentation");
         assert!(matches!(parents[0].2, MemoryRelationship::ChildOf));
     }
-    
+
+    #[test]
+    fn test_multi_hop_traversal() {
+        let mut memory_bank = MemoryBank::new();
+
+        let a = memory_bank.add_memory(create_test_memory("Grandparent task", MemoryType::Task, 0.8));
+        let b = memory_bank.add_memory(create_test_memory("Parent task", MemoryType::Task, 0.8));
+        let c = memory_bank.add_memory(create_test_memory("Child task", MemoryType::Task, 0.8));
+
+        memory_bank.get_memory_mut(a).unwrap().add_relationship(b, MemoryRelationship::ParentOf, 0.9);
+        memory_bank.get_memory_mut(b).unwrap().add_relationship(c, MemoryRelationship::ParentOf, 0.8);
+
+        // One hop only misses the grandchild.
+        let one_hop = memory_bank.traverse(a, 1, 0.0, Some(&MemoryRelationship::ParentOf));
+        assert_eq!(one_hop.len(), 1);
+        assert_eq!(one_hop[0].0, b);
+
+        // Two hops reaches it, with cumulative strength = 0.9 * 0.8.
+        let two_hops = memory_bank.traverse(a, 2, 0.0, Some(&MemoryRelationship::ParentOf));
+        let reached: Vec<MemoryId> = two_hops.iter().map(|(id, _, _)| *id).collect();
+        assert!(reached.contains(&c));
+        let to_c = two_hops.iter().find(|(id, _, _)| *id == c).unwrap();
+        assert_eq!(to_c.1, vec![a, b, c]);
+        assert!((to_c.2 - 0.72).abs() < 1e-5);
+
+        // A strength floor above the combined strength prunes it away.
+        let pruned = memory_bank.traverse(a, 2, 0.8, Some(&MemoryRelationship::ParentOf));
+        assert!(pruned.iter().all(|(id, _, _)| *id != c));
+
+        // find_path finds the same strongest chain.
+        let (path, strength) = memory_bank.find_path(a, c).unwrap();
+        assert_eq!(path, vec![a, b, c]);
+        assert!((strength - 0.72).abs() < 1e-5);
+
+        // transitive_closure materializes the direct A -> C edge.
+        let added = memory_bank.transitive_closure(&MemoryRelationship::ParentOf);
+        assert_eq!(added, 1);
+        let direct = memory_bank.get_related_memories(a, 0.0);
+        assert!(direct.iter().any(|(id, _, rel, strength)| {
+            *id == c && matches!(rel, MemoryRelationship::ParentOf) && (*strength - 0.72).abs() < 1e-5
+        }));
+    }
+
     #[test]
     fn test_cleanup_preserves_pinned() {
         let mut memory_bank = MemoryBank {
-            memories: Vec::new(),
+            memories: HashMap::new(),
+            next_id: 0,
             max_memories: 2,
             importance_threshold: 0.0,
+            decay_rate: DEFAULT_DECAY_RATE,
+            token_index: HashMap::new(),
+            type_index: HashMap::new(),
+            metadata_indexes: HashMap::new(),
+            triggers: Vec::new(),
+            embedder: None,
         };
-        
+
         // Add memories including one that's pinned
         memory_bank.add_memory(create_test_memory("Important memory", MemoryType::Fact, 0.9));
         
@@ -588,15 +1601,132 @@ mod tests {
         memory_bank.add_memory(create_test_memory("New memory 2", MemoryType::Fact, 0.7));
         
         // Verify pinned memory is still there
-        let memories: Vec<_> = memory_bank.memories.iter().map(|m| &m.content[..]).collect();
+        let memories: Vec<_> = memory_bank.memories.values().map(|m| &m.content[..]).collect();
         assert!(memories.contains(&"Pinned memory"), "Pinned memory should be preserved");
         assert_eq!(memory_bank.memories.len(), 2, "Should have exactly 2 memories");
-        
+
         // The remaining memories should be the pinned one and the most important one
-        let has_pinned = memory_bank.memories.iter().any(|m| m.pinned);
+        let has_pinned = memory_bank.memories.values().any(|m| m.pinned);
         assert!(has_pinned, "Pinned memory should be in the remaining memories");
-        
-        let has_important = memory_bank.memories.iter().any(|m| m.importance == 0.9);
+
+        let has_important = memory_bank.memories.values().any(|m| m.importance == 0.9);
         assert!(has_important, "Most important memory should be preserved");
     }
+
+    #[test]
+    fn test_delete_repairs_dangling_links() {
+        let mut memory_bank = MemoryBank::new();
+
+        let a = memory_bank.add_memory(create_test_memory("Task A", MemoryType::Task, 0.8));
+        let b = memory_bank.add_memory(create_test_memory("Task B", MemoryType::Task, 0.8));
+
+        memory_bank.get_memory_mut(a).unwrap().add_relationship(b, MemoryRelationship::ParentOf, 0.9);
+        assert_eq!(memory_bank.get_related_memories(a, 0.0).len(), 1);
+
+        // Deleting B should sweep A's dangling link to it, not just remove B.
+        assert!(memory_bank.delete(b));
+        assert!(memory_bank.get_memory(b).is_none());
+        assert!(memory_bank.get_related_memories(a, 0.0).is_empty());
+        assert!(memory_bank.get_memory(a).unwrap().relationships.is_empty());
+    }
+
+    #[test]
+    fn test_ids_are_stable_and_never_reused() {
+        let mut memory_bank = MemoryBank::new();
+
+        let a = memory_bank.add_memory(create_test_memory("First", MemoryType::Fact, 0.5));
+        let b = memory_bank.add_memory(create_test_memory("Second", MemoryType::Fact, 0.5));
+        assert_ne!(a, b);
+
+        // Deleting an earlier memory must not cause a later one's ID to shift,
+        // and the freed ID must never be handed out again.
+        assert!(memory_bank.delete(a));
+        let c = memory_bank.add_memory(create_test_memory("Third", MemoryType::Fact, 0.5));
+        assert_ne!(c, a);
+        assert_eq!(memory_bank.get_memory(b).unwrap().content, "Second");
+    }
+
+    #[test]
+    fn test_self_relationship_is_ignored() {
+        let mut memory_bank = MemoryBank::new();
+        let a = memory_bank.add_memory(create_test_memory("Task A", MemoryType::Task, 0.8));
+
+        memory_bank.get_memory_mut(a).unwrap().add_relationship(a, MemoryRelationship::RelatedTo, 0.9);
+        assert!(memory_bank.get_memory(a).unwrap().relationships.is_empty());
+    }
+
+    #[test]
+    fn test_decay_scores_reflect_access_and_importance() {
+        let mut memory_bank = MemoryBank::new();
+
+        let fresh = memory_bank.add_memory(create_test_memory("Fresh fact", MemoryType::Fact, 0.5));
+
+        let mut stale_memory = create_test_memory("Stale fact", MemoryType::Fact, 0.5);
+        stale_memory.last_accessed = Utc::now() - chrono::Duration::days(365);
+        let stale = memory_bank.add_memory(stale_memory);
+
+        let mut reinforced_memory = create_test_memory("Reinforced fact", MemoryType::Fact, 0.5);
+        reinforced_memory.last_accessed = Utc::now() - chrono::Duration::days(365);
+        reinforced_memory.access_count = 100;
+        let reinforced = memory_bank.add_memory(reinforced_memory);
+
+        let scores: HashMap<MemoryId, f32> = memory_bank.decay_scores().into_iter().collect();
+
+        // A memory untouched for a year should have decayed well below a
+        // freshly added memory of the same importance.
+        assert!(scores[&stale] < scores[&fresh]);
+
+        // A memory accessed many times in the past fades slower than one
+        // accessed just once, even with the same last_accessed.
+        assert!(scores[&reinforced] > scores[&stale]);
+    }
+
+    #[test]
+    fn test_search_returns_snippet_around_matched_token() {
+        let mut memory_bank = MemoryBank::new();
+        memory_bank.add_memory(create_test_memory(
+            "The user's favorite dessert is chocolate cake",
+            MemoryType::Preference,
+            0.7,
+        ));
+
+        let results = memory_bank.search("chocolate");
+        assert_eq!(results.len(), 1);
+        assert!(results[0].3.to_lowercase().contains("chocolate"));
+    }
+
+    #[test]
+    fn test_search_tolerates_a_typo() {
+        let mut memory_bank = MemoryBank::new();
+        let id = memory_bank.add_memory(create_test_memory(
+            "User likes chocolate",
+            MemoryType::Preference,
+            0.7,
+        ));
+
+        let results = memory_bank.search("chocolat");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, id);
+    }
+
+    #[test]
+    fn test_recall_memory_objects_empty_query_returns_everything() {
+        let mut memory_bank = MemoryBank::new();
+        memory_bank.add_memory(create_test_memory("Fact one", MemoryType::Fact, 0.5));
+        memory_bank.add_memory(create_test_memory("Fact two", MemoryType::Fact, 0.8));
+
+        let results = memory_bank.recall_memory_objects("");
+        assert_eq!(results.len(), 2);
+        // Ranked by importance when there's no text match to score by.
+        assert_eq!(results[0].content, "Fact two");
+    }
+
+    #[test]
+    fn test_recall_memory_objects_does_not_reinforce() {
+        let mut memory_bank = MemoryBank::new();
+        let id = memory_bank.add_memory(create_test_memory("User likes chocolate", MemoryType::Preference, 0.7));
+
+        memory_bank.recall_memory_objects("chocolate");
+        assert_eq!(memory_bank.get_memory(id).unwrap().reinforcement_count, 0);
+    }
 }
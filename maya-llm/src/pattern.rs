@@ -1,6 +1,498 @@
+use maya_knowledge_graph::models::{Edge, Node, PropertyValue};
 use serde::{Serialize, Deserialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+// Scoring constants for the fuzzy matcher, loosely modeled on the
+// nucleo/fzf character-alignment scheme.
+const FUZZY_MATCH_BASE: f32 = 4.0;
+const FUZZY_CONSECUTIVE_BONUS: f32 = 3.0;
+const FUZZY_BOUNDARY_BONUS: f32 = 5.0;
+const FUZZY_GAP_PENALTY: f32 = 1.0;
+
+/// Score deducted per confirmed edit distance for candidates surfaced only
+/// through the anagram index (see [`PatternMatcher::typo_candidates`]).
+const TYPO_DISTANCE_PENALTY: f32 = 0.5;
+
+/// Minimum directed similarity (see [`PatternMatcher::ensure_similarity_index`])
+/// for one pattern to be reinforced when another is matched.
+const REINFORCEMENT_SIMILARITY_THRESHOLD: f32 = 0.3;
+
+/// Default Jaccard word-set similarity (see [`jaccard_similarity`]) above
+/// which `PatternMatcher::add_pattern` merges a new pattern into an
+/// existing one instead of adding it. Tunable via
+/// [`PatternMatcher::with_merge_similarity_threshold`].
+const DEFAULT_MERGE_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Default minimum score (on the same 0.0-10.0 scale as
+/// [`Pattern::match_score`]) below which `find_best_match_with_context`
+/// tries the [`PatternMatcher::fuzzy_correct`] word-by-word edit-distance
+/// fallback. Tunable via [`PatternMatcher::with_fuzzy_threshold`]; setting
+/// it to `0.0` disables the fallback.
+const DEFAULT_FUZZY_THRESHOLD: f32 = 3.0;
+
+/// Returns true if `text[pos]` starts a new "word" - i.e. it's the first
+/// character, or the preceding character is whitespace/punctuation.
+fn is_word_boundary(text: &[char], pos: usize) -> bool {
+    pos == 0 || !text[pos - 1].is_alphanumeric()
+}
+
+/// Lowercased, punctuation-trimmed trigger words for `text`, used to key
+/// [`PatternMatcher::token_index`].
+fn trigger_tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Character-level fuzzy alignment score between `input` and `pattern`,
+/// normalized to the 0.0-10.0 range used by [`Pattern::match_score`].
+///
+/// This runs a dynamic-programming alignment over a (input_len+1) x
+/// (pattern_len+1) matrix. Each cell tracks the best score reachable at
+/// that point along with whether the step into it was a character match,
+/// so consecutive runs of matched characters earn an increasing bonus.
+/// Matching at a word boundary (start of string, or after
+/// whitespace/punctuation) earns an extra bonus. Characters that have to
+/// be skipped to align the two strings incur a small gap penalty.
+fn fuzzy_match_score(input: &str, pattern: &str) -> f32 {
+    let input_chars: Vec<char> = input.chars().collect();
+    let pattern_chars: Vec<char> = pattern.chars().collect();
+
+    if input_chars.is_empty() || pattern_chars.is_empty() {
+        return 0.0;
+    }
+
+    let rows = input_chars.len() + 1;
+    let cols = pattern_chars.len() + 1;
+
+    // dp[i][j] = (best score aligning input[..i] with pattern[..j], was the
+    // transition into this cell a character match?)
+    let mut dp = vec![vec![(0.0_f32, false); cols]; rows];
+
+    for i in 1..rows {
+        dp[i][0] = (dp[i - 1][0].0 - FUZZY_GAP_PENALTY, false);
+    }
+    for j in 1..cols {
+        dp[0][j] = (dp[0][j - 1].0 - FUZZY_GAP_PENALTY, false);
+    }
+
+    let mut best = 0.0_f32;
+
+    for i in 1..rows {
+        for j in 1..cols {
+            let mut candidates = [
+                (dp[i - 1][j].0 - FUZZY_GAP_PENALTY, false),
+                (dp[i][j - 1].0 - FUZZY_GAP_PENALTY, false),
+            ];
+
+            if input_chars[i - 1] == pattern_chars[j - 1] {
+                let (prev_score, prev_matched) = dp[i - 1][j - 1];
+                let mut match_score = prev_score + FUZZY_MATCH_BASE;
+                if prev_matched {
+                    match_score += FUZZY_CONSECUTIVE_BONUS;
+                }
+                if is_word_boundary(&input_chars, i - 1) {
+                    match_score += FUZZY_BOUNDARY_BONUS;
+                }
+                dp[i][j] = (match_score, true);
+            } else {
+                candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+                dp[i][j] = candidates[1];
+            }
+
+            if dp[i][j].0 > best {
+                best = dp[i][j].0;
+            }
+        }
+    }
+
+    if best <= 0.0 {
+        return 0.0;
+    }
+
+    // Normalize against the score a perfect, fully-boundary-matched
+    // alignment of the shorter string would achieve, so the result
+    // composes with weight/time_decay the same way the old overlap score
+    // did. That ceiling isn't `shorter_len * (base + consecutive +
+    // boundary)` -- the first matched character of any alignment can
+    // never also earn the consecutive-match bonus, and not every
+    // character is a word boundary -- so simulate the actual best-case
+    // alignment instead.
+    let shorter_chars: &[char] =
+        if input_chars.len() <= pattern_chars.len() { &input_chars } else { &pattern_chars };
+    let max_possible = best_case_alignment_score(shorter_chars);
+
+    if max_possible <= 0.0 {
+        return 0.0;
+    }
+
+    ((best / max_possible) * 10.0).clamp(0.0, 9.9)
+}
+
+/// The score a perfect, consecutive, every-char-matched alignment of
+/// `chars` against itself would earn: every character gets the match
+/// base, every character but the first also gets the consecutive-match
+/// bonus, and whichever characters sit at a word boundary (per
+/// [`is_word_boundary`]) get the boundary bonus too. This is the true
+/// ceiling [`fuzzy_match_score`] normalizes against.
+fn best_case_alignment_score(chars: &[char]) -> f32 {
+    let mut score = 0.0;
+    for (i, _) in chars.iter().enumerate() {
+        score += FUZZY_MATCH_BASE;
+        if i > 0 {
+            score += FUZZY_CONSECUTIVE_BONUS;
+        }
+        if is_word_boundary(chars, i) {
+            score += FUZZY_BOUNDARY_BONUS;
+        }
+    }
+    score
+}
+
+/// A small table of distinct primes used to build the "anagram value" of a
+/// pattern's text (see [`anagram_value`]). Characters are mapped onto this
+/// table by position, so the mapping isn't guaranteed collision-free for
+/// exotic input, but that's fine: the anagram index is only used to narrow
+/// down candidates, which then get a real [`damerau_levenshtein`] check
+/// before they're trusted.
+const ANAGRAM_PRIME_TABLE: [u64; 64] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311,
+];
+
+/// Maps a character onto one of the primes in [`ANAGRAM_PRIME_TABLE`].
+fn char_prime(c: char) -> u64 {
+    ANAGRAM_PRIME_TABLE[(c as usize) % ANAGRAM_PRIME_TABLE.len()]
+}
+
+/// The "anagram value" of a string: the sum of the primes of its
+/// (lowercased) characters. Strings that are anagrams of each other always
+/// share a value, and single-character edits shift the value by a single
+/// prime, which is what lets [`nearby_anagram_values`] enumerate the
+/// neighborhood of a value without rescanning every pattern.
+fn anagram_value(text: &str) -> u64 {
+    text.to_lowercase().chars().map(char_prime).sum()
+}
+
+/// Expand `value` to every anagram value reachable within `depth`
+/// single-character insertions or deletions (each a `+prime`/`-prime`
+/// step). A deletion followed by an insertion is a substitution and a pair
+/// of opposite steps is a transposition, so this also covers those edit
+/// types without any extra cases.
+fn nearby_anagram_values(value: u64, depth: usize, out: &mut HashSet<u64>) {
+    if depth == 0 {
+        return;
+    }
+
+    for &prime in ANAGRAM_PRIME_TABLE.iter() {
+        let inserted = value + prime;
+        if out.insert(inserted) {
+            nearby_anagram_values(inserted, depth - 1, out);
+        }
+        if value >= prime {
+            let deleted = value - prime;
+            if out.insert(deleted) {
+                nearby_anagram_values(deleted, depth - 1, out);
+            }
+        }
+    }
+}
+
+/// Plain (non-Damerau) Levenshtein edit distance between two words, used by
+/// [`PatternMatcher::fuzzy_correct`] to match a whole input against a
+/// pattern trigger word-by-word. Unlike [`damerau_levenshtein`] this doesn't
+/// special-case adjacent transpositions, since the per-word budget here is
+/// already tight enough that a transposition and a substitution cost the
+/// same in practice.
+///
+/// Bails out early (returning `usize::MAX`) once `|a.len() - b.len()| >
+/// max_dist`, since the DP's final distance can never be smaller than that
+/// length difference - this keeps `fuzzy_correct` cheap to run against
+/// every pattern trigger on every fallback lookup.
+fn bounded_levenshtein(a: &str, b: &str, max_dist: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_dist {
+        return usize::MAX;
+    }
+
+    let (rows, cols) = (a.len() + 1, b.len() + 1);
+    let mut d = vec![vec![0usize; cols]; rows];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..cols {
+        d[0][j] = j;
+    }
+
+    for i in 1..rows {
+        for j in 1..cols {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+        }
+    }
+
+    d[rows - 1][cols - 1]
+}
+
+/// Restricted (OSA) Damerau-Levenshtein edit distance between two strings,
+/// counting insertions, deletions, substitutions, and adjacent
+/// transpositions as a single edit each.
+pub(crate) fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (rows, cols) = (a.len() + 1, b.len() + 1);
+
+    let mut d = vec![vec![0usize; cols]; rows];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..cols {
+        d[0][j] = j;
+    }
+
+    for i in 1..rows {
+        for j in 1..cols {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[rows - 1][cols - 1]
+}
+
+/// Number of independent hash functions combined into a pattern's MinHash
+/// fingerprint (see [`minhash_signature`]). Two word sets that agree on `k`
+/// of these slots have an expected Jaccard similarity of about
+/// `k / MINHASH_SIGNATURE_LEN`.
+const MINHASH_SIGNATURE_LEN: usize = 8;
+
+/// Distinct odd multipliers used to decorrelate the `MINHASH_SIGNATURE_LEN`
+/// hash functions from a single word hash, splitmix64-style.
+const MINHASH_SEEDS: [u64; MINHASH_SIGNATURE_LEN] = [
+    0x9E3779B97F4A7C15,
+    0xC2B2AE3D27D4EB4F,
+    0x165667B19E3779F9,
+    0x27D4EB2F165667C5,
+    0x85EBCA77C2B2AE63,
+    0xFF51AFD7ED558CCD,
+    0xC4CEB9FE1A85EC53,
+    0x94D049BB133111EB,
+];
+
+/// Number of signature slots grouped into each LSH band (see
+/// [`minhash_band_keys`]). Patterns are only considered merge candidates in
+/// [`PatternMatcher::add_pattern`] if one of their bands collides.
+const MINHASH_BAND_WIDTH: usize = 2;
+
+/// Hash an arbitrary `Hash` value with the standard library's hasher. Used
+/// both to seed a word's MinHash mixing and to collapse a band's signature
+/// slots into a single bucket key.
+fn std_hash(value: impl std::hash::Hash) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// MinHash signature of `text`'s whitespace-separated word set: for each of
+/// `MINHASH_SIGNATURE_LEN` hash functions, the minimum mixed hash across all
+/// of `text`'s words. Borrowed from the hash-bucket-then-confirm strategy
+/// czkawka uses for near-duplicate detection - two texts with high word-set
+/// Jaccard similarity are likely to agree on most signature slots, without
+/// ever comparing the sets directly.
+fn minhash_signature(text: &str) -> [u64; MINHASH_SIGNATURE_LEN] {
+    let mut signature = [u64::MAX; MINHASH_SIGNATURE_LEN];
+
+    for word in text.split_whitespace() {
+        let base = std_hash(word);
+        for (seed, slot) in MINHASH_SEEDS.iter().zip(signature.iter_mut()) {
+            let mixed = (base ^ seed).wrapping_mul(0x2545_F491_4F6C_DD1D);
+            *slot = (*slot).min(mixed);
+        }
+    }
+
+    signature
+}
+
+/// LSH bucket keys for `text`: one `(band index, band hash)` pair per band
+/// of [`minhash_signature`], suitable as keys into
+/// [`PatternMatcher::lsh_buckets`]. Two texts sharing any key are candidates
+/// for the Jaccard-similarity merge check; texts sharing none almost
+/// certainly have low word-set overlap.
+fn minhash_band_keys(text: &str) -> Vec<(usize, u64)> {
+    minhash_signature(text)
+        .chunks(MINHASH_BAND_WIDTH)
+        .enumerate()
+        .map(|(band, chunk)| (band, std_hash(chunk)))
+        .collect()
+}
+
+/// Jaccard similarity (`|A ∩ B| / |A ∪ B|`) between two word sets, used by
+/// [`PatternMatcher::add_pattern`] in place of the old hard-coded
+/// `common*2 >= min(len)` merge rule.
+fn jaccard_similarity(a: &HashSet<&str>, b: &HashSet<&str>) -> f32 {
+    let intersection = a.intersection(b).count();
+    if intersection == 0 {
+        return 0.0;
+    }
+
+    let union = a.union(b).count();
+    intersection as f32 / union as f32
+}
+
+/// Fuzzy score of a single atom against an already-lowercased `input`,
+/// short-circuiting to the maximum score on an exact match. This is the
+/// same rule [`Pattern::match_score`] applies to `Pattern::text`; it's
+/// pulled out here so [`CompositePattern`] can reuse it for its atoms.
+fn atom_score(input: &str, atom: &str) -> f32 {
+    if atom == input {
+        10.0
+    } else {
+        fuzzy_match_score(input, atom)
+    }
+}
+
+/// A structured trigger for a pattern, built out of fuzzy atoms combined
+/// with boolean/unordered-token logic, as an alternative to matching a
+/// single flat phrase. Parse one from a query string with
+/// [`parse_composite_pattern`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CompositePattern {
+    /// A single fuzzy-matched phrase
+    Atom(String),
+    /// Matches when every child matches (score is the minimum of the children's scores)
+    And(Vec<CompositePattern>),
+    /// Matches when any child matches (score is the maximum of the children's scores)
+    Or(Vec<CompositePattern>),
+    /// Matches when the child does not (score is 10.0 if the child scores 0.0, else 0.0)
+    Not(Box<CompositePattern>),
+    /// Matches when every whitespace-separated token appears somewhere in
+    /// the input, regardless of order (score is the average of the
+    /// per-token scores, or 0.0 if any token fails to match)
+    Tokens(Vec<String>),
+}
+
+impl CompositePattern {
+    /// Calculate a match score (0.0-10.0) for this composite against `input`
+    pub fn match_score(&self, input: &str) -> f32 {
+        self.match_score_lower(&input.to_lowercase())
+    }
+
+    fn match_score_lower(&self, input: &str) -> f32 {
+        match self {
+            CompositePattern::Atom(atom) => atom_score(input, atom),
+            CompositePattern::And(children) => children
+                .iter()
+                .map(|c| c.match_score_lower(input))
+                .fold(None, |acc: Option<f32>, score| {
+                    Some(acc.map_or(score, |best| best.min(score)))
+                })
+                .unwrap_or(0.0),
+            CompositePattern::Or(children) => children
+                .iter()
+                .map(|c| c.match_score_lower(input))
+                .fold(0.0_f32, f32::max),
+            CompositePattern::Not(child) => {
+                if child.match_score_lower(input) > 0.0 {
+                    0.0
+                } else {
+                    10.0
+                }
+            }
+            CompositePattern::Tokens(tokens) => {
+                if tokens.is_empty() {
+                    return 0.0;
+                }
+                let scores: Vec<f32> = tokens.iter().map(|t| atom_score(input, t)).collect();
+                if scores.iter().any(|&s| s <= 0.0) {
+                    0.0
+                } else {
+                    scores.iter().sum::<f32>() / scores.len() as f32
+                }
+            }
+        }
+    }
+}
+
+/// Parse a composite query string into a [`CompositePattern`] tree.
+///
+/// Terms are separated by `&` (AND, lowest precedence), then `|` (OR),
+/// with a `!` prefix negating a term (NOT). A query with neither operator
+/// is treated as unordered, whitespace-separated tokens, e.g.
+/// `"cat & !dog"` becomes `And([Atom("cat"), Not(Atom("dog"))])`, while
+/// `"cute cat"` becomes `Tokens(["cute", "cat"])`.
+pub fn parse_composite_pattern(query: &str) -> CompositePattern {
+    let query = query.trim();
+
+    if !query.contains('&') && !query.contains('|') {
+        let tokens: Vec<String> = query
+            .split_whitespace()
+            .map(|t| t.to_lowercase())
+            .collect();
+
+        return match tokens.len() {
+            0 | 1 => CompositePattern::Atom(query.to_lowercase()),
+            _ => CompositePattern::Tokens(tokens),
+        };
+    }
+
+    let mut and_terms: Vec<CompositePattern> = query
+        .split('&')
+        .map(|term| parse_or_term(term.trim()))
+        .collect();
+
+    if and_terms.len() == 1 {
+        and_terms.remove(0)
+    } else {
+        CompositePattern::And(and_terms)
+    }
+}
+
+fn parse_or_term(term: &str) -> CompositePattern {
+    let mut or_atoms: Vec<CompositePattern> = term
+        .split('|')
+        .map(|atom| parse_negated_atom(atom.trim()))
+        .collect();
+
+    if or_atoms.len() == 1 {
+        or_atoms.remove(0)
+    } else {
+        CompositePattern::Or(or_atoms)
+    }
+}
+
+fn parse_negated_atom(atom: &str) -> CompositePattern {
+    match atom.strip_prefix('!') {
+        Some(rest) => CompositePattern::Not(Box::new(parse_negated_atom(rest.trim()))),
+        None => CompositePattern::Atom(atom.to_lowercase()),
+    }
+}
+
+/// A single ranked candidate yielded by
+/// [`PatternMatcher::find_matches_streamed`]: the index into
+/// [`PatternMatcher::get_patterns`] alongside its match score.
+#[cfg(feature = "parallel-match")]
+#[derive(Debug, Clone, Copy)]
+pub struct StreamedMatch {
+    pub index: usize,
+    pub score: f32,
+}
 
 /// Represents a pattern with its associated response and learning metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +504,10 @@ pub struct Pattern {
     pub last_used: u64,
     pub context_triggers: HashSet<String>,
     pub created_at: u64,
+    /// A structured boolean/unordered-token trigger (see
+    /// [`CompositePattern`]), used in place of `text` when present
+    #[serde(default)]
+    pub composite: Option<CompositePattern>,
 }
 
 impl Pattern {
@@ -20,7 +516,7 @@ impl Pattern {
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-            
+
         Self {
             text: text.to_lowercase(),
             response: response.to_string(),
@@ -29,37 +525,31 @@ impl Pattern {
             last_used: now,
             context_triggers: HashSet::new(),
             created_at: now,
+            composite: None,
         }
     }
 
+    /// Give this pattern a composite boolean query trigger, parsed from
+    /// `query` (see [`parse_composite_pattern`]), instead of matching
+    /// `text` as a single fuzzy phrase
+    pub fn with_composite_trigger(mut self, query: &str) -> Self {
+        self.composite = Some(parse_composite_pattern(query));
+        self
+    }
+
     /// Calculate a score for how well this pattern matches the input
     pub fn match_score(&self, input: &str, context: Option<&[String]>) -> f32 {
         let input = input.to_lowercase();
-        let mut score = 0.0;
-        
-        // Exact match gets highest score
-        if self.text == input {
-            score = 10.0;
-        }
-        // Check if pattern is contained in input or vice versa
-        else if input.contains(&self.text) || self.text.contains(&input) {
-            score = 5.0;
-        }
-        // Check for word overlap
-        else {
-            let input_words: Vec<&str> = input.split_whitespace().collect();
-            let pattern_words: Vec<&str> = self.text.split_whitespace().collect();
-            
-            let common_words: Vec<&&str> = input_words
-                .iter()
-                .filter(|word| pattern_words.contains(word))
-                .collect();
-            
-            if !common_words.is_empty() {
-                score = common_words.len() as f32 / pattern_words.len().max(1) as f32;
-            }
-        }
-        
+
+        let score = if let Some(composite) = &self.composite {
+            composite.match_score_lower(&input)
+        } else if self.text == input {
+            // Exact match short-circuits to the maximum score
+            10.0
+        } else {
+            fuzzy_match_score(&input, &self.text)
+        };
+
         if score > 0.0 {
             // Apply context similarity if context is provided
             let context_score = context
@@ -138,6 +628,74 @@ pub struct PatternMatcher {
     pub learning_rate: f32,
     /// Maximum number of patterns to store
     pub max_patterns: usize,
+    /// Maximum Damerau-Levenshtein distance for a spelling-variant
+    /// candidate (found via `anagram_index`) to be confirmed as a typo
+    /// match of a pattern's text.
+    pub max_edit_distance: usize,
+    /// Aho-Corasick automaton over `patterns[i].text`, used to prefilter
+    /// candidates before running the full `match_score` on each pattern.
+    /// Rebuilt lazily from `index_up_to_date`, so it is never serialized.
+    #[serde(skip)]
+    automaton: Option<aho_corasick::AhoCorasick>,
+    /// Maps each pattern's anagram value (see [`anagram_value`]) to the
+    /// indices of patterns sharing it, for cheap spelling-variant lookup.
+    /// Rebuilt alongside `automaton`, so it is never serialized.
+    #[serde(skip)]
+    anagram_index: HashMap<u64, Vec<usize>>,
+    /// Maps each lowercased trigger word (see [`trigger_tokens`]) appearing
+    /// in some pattern's text to the indices of patterns containing it.
+    /// Complements `automaton` (literal substrings) and `anagram_index`
+    /// (whole-string typos): paraphrased input that shares no substring or
+    /// anagram neighborhood with a pattern still shares individual words
+    /// most of the time, so this is what keeps `find_best_match_with_context`
+    /// from falling back to a full scan on most ordinary conversational
+    /// input. Rebuilt alongside `automaton`, so it is never serialized.
+    #[serde(skip)]
+    token_index: HashMap<String, Vec<usize>>,
+    /// Whether `automaton`/`anagram_index` reflect the current contents of
+    /// `patterns`.
+    #[serde(skip)]
+    index_up_to_date: bool,
+    /// Directed similarity edges `(from, to, similarity)`: when the pattern
+    /// at `from` is the best match, `to` scored above
+    /// `REINFORCEMENT_SIMILARITY_THRESHOLD` against its text and should be
+    /// reinforced. Recomputed lazily via `ensure_similarity_index` and
+    /// walked by `find_best_match_with_context` instead of rescoring every
+    /// other pattern on each match. Mirrored to/from the knowledge graph as
+    /// `SIMILAR_TO` edges by `to_graph`/`from_graph`.
+    #[serde(skip)]
+    similarity_edges: Vec<(usize, usize, f32)>,
+    /// Whether `similarity_edges` reflects the current contents of
+    /// `patterns`.
+    #[serde(skip)]
+    similarity_up_to_date: bool,
+    /// Jaccard word-set similarity threshold (0.0 to 1.0, see
+    /// [`jaccard_similarity`]) above which `add_pattern` merges a new
+    /// pattern into an existing one instead of adding it.
+    pub merge_similarity_threshold: f32,
+    /// LSH bucket index mapping each `(band, band hash)` key from
+    /// [`minhash_band_keys`] to the indices of patterns colliding there.
+    /// Unlike `automaton`/`anagram_index`, this is maintained incrementally
+    /// by `add_pattern`/`add_composite_pattern`/`evict_if_full` rather than
+    /// rebuilt wholesale on every mutation, so a merge check only has to
+    /// re-score patterns sharing a band instead of scanning all of
+    /// `patterns`. Never serialized; rebuilt once from `lsh_up_to_date`.
+    #[serde(skip)]
+    lsh_buckets: HashMap<(usize, u64), Vec<usize>>,
+    /// Whether `lsh_buckets` has been built at least once for the current
+    /// contents of `patterns` (e.g. after construction or deserialization).
+    /// Once true, `add_pattern`/`add_composite_pattern`/`evict_if_full`
+    /// keep it true by updating `lsh_buckets` incrementally instead of
+    /// clearing this flag.
+    #[serde(skip)]
+    lsh_up_to_date: bool,
+    /// Minimum match score (same scale as [`Pattern::match_score`])
+    /// below which [`PatternMatcher::find_best_match_with_context`] tries
+    /// the [`PatternMatcher::fuzzy_correct`] fallback - set to `0.0` to
+    /// disable the fuzzy fallback entirely. The per-word edit-distance
+    /// budget inside `fuzzy_correct`, `max(1, word_len / 3)`, is fixed
+    /// and does not depend on this field.
+    pub fuzzy_threshold: f32,
 }
 
 impl PatternMatcher {
@@ -146,52 +704,116 @@ impl PatternMatcher {
             patterns: Vec::new(),
             learning_rate: 0.1,
             max_patterns: 1000,
+            max_edit_distance: 2,
+            automaton: None,
+            anagram_index: HashMap::new(),
+            token_index: HashMap::new(),
+            index_up_to_date: false,
+            similarity_edges: Vec::new(),
+            similarity_up_to_date: false,
+            merge_similarity_threshold: DEFAULT_MERGE_SIMILARITY_THRESHOLD,
+            lsh_buckets: HashMap::new(),
+            lsh_up_to_date: false,
+            fuzzy_threshold: DEFAULT_FUZZY_THRESHOLD,
         }
     }
-    
+
     /// Set the learning rate (0.0 to 1.0)
     pub fn with_learning_rate(mut self, rate: f32) -> Self {
         self.learning_rate = rate.clamp(0.0, 1.0);
         self
     }
-    
+
     /// Set the maximum number of patterns to store
     pub fn with_max_patterns(mut self, max: usize) -> Self {
         self.max_patterns = max.max(1);
         self
     }
-    
+
+    /// Set the maximum edit distance allowed for typo-tolerant candidate
+    /// retrieval via the anagram index
+    pub fn with_max_edit_distance(mut self, max_edit_distance: usize) -> Self {
+        self.max_edit_distance = max_edit_distance;
+        self
+    }
+
+    /// Set the Jaccard word-set similarity threshold above which
+    /// `add_pattern` merges a new pattern into an existing one instead of
+    /// adding it, replacing the default of
+    /// `DEFAULT_MERGE_SIMILARITY_THRESHOLD`.
+    pub fn with_merge_similarity_threshold(mut self, threshold: f32) -> Self {
+        self.merge_similarity_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the minimum score below which `find_best_match_with_context`
+    /// tries the word-by-word edit-distance fallback (see
+    /// [`fuzzy_correct`](Self::fuzzy_correct)), replacing the default of
+    /// `DEFAULT_FUZZY_THRESHOLD`. Pass `0.0` to disable the fallback
+    /// entirely.
+    pub fn with_fuzzy_threshold(mut self, threshold: f32) -> Self {
+        self.fuzzy_threshold = threshold.max(0.0);
+        self
+    }
+
     /// Add a new pattern with optional context
     pub fn add_pattern(&mut self, text: &str, response: &str) -> bool {
-        // Check for similar existing patterns first
-        let similar_patterns: Vec<_> = self.patterns
-            .iter_mut()
-            .filter(|p| {
-                // Consider patterns similar if they share at least half their words
-                let pattern_words: HashSet<_> = p.text.split_whitespace().collect();
-                let new_words: HashSet<_> = text.split_whitespace().collect();
-                let common: HashSet<_> = pattern_words.intersection(&new_words).collect();
-                common.len() > 0 && common.len() * 2 >= pattern_words.len().min(new_words.len())
-            })
-            .collect();
-        
-        if !similar_patterns.is_empty() {
+        self.ensure_lsh_index();
+
+        // Only patterns sharing an LSH band with `text` are candidates for
+        // the merge test, instead of scanning every stored pattern.
+        let new_words: HashSet<&str> = text.split_whitespace().collect();
+        let mut most_similar: Option<(usize, f32)> = None;
+
+        for i in self.lsh_candidate_indices(text) {
+            let pattern_words: HashSet<&str> = self.patterns[i].text.split_whitespace().collect();
+            if jaccard_similarity(&pattern_words, &new_words) < self.merge_similarity_threshold {
+                continue;
+            }
+
+            let score = self.patterns[i].match_score(text, None);
+            if most_similar.map_or(true, |(_, best)| score > best) {
+                most_similar = Some((i, score));
+            }
+        }
+
+        if let Some((index, _)) = most_similar {
             // Update the most similar pattern instead of adding a new one
-            if let Some(most_similar) = similar_patterns.into_iter().max_by(|a, b| {
-                a.match_score(text, None)
-                    .partial_cmp(&b.match_score(text, None))
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }) {
-                // Update the response with a weighted average of old and new
-                if most_similar.response != response {
-                    most_similar.response = format!("{} {}", most_similar.response, response);
-                }
-                most_similar.record_usage(None);
-                return false; // Pattern was merged, not added
+            let pattern = &mut self.patterns[index];
+            if pattern.response != response {
+                pattern.response = format!("{} {}", pattern.response, response);
             }
+            pattern.record_usage(None);
+            return false; // Pattern was merged, not added
         }
-        
-        // If we have too many patterns, remove the least used one
+
+        self.evict_if_full();
+        self.patterns.push(Pattern::new(text, response));
+        let new_index = self.patterns.len() - 1;
+        self.insert_into_lsh_index(new_index);
+        self.index_up_to_date = false;
+        self.similarity_up_to_date = false;
+        true // Pattern was added
+    }
+
+    /// Add a new pattern whose trigger is a composite boolean query (see
+    /// [`CompositePattern`]) rather than a single fuzzy phrase, e.g.
+    /// `"cat & !dog"` or `"cute cat"`. Bypasses the similar-pattern merge
+    /// that `add_pattern` does, since word-overlap similarity doesn't
+    /// apply to a boolean query string.
+    pub fn add_composite_pattern(&mut self, query: &str, response: &str) -> bool {
+        self.ensure_lsh_index();
+        self.evict_if_full();
+        self.patterns.push(Pattern::new(query, response).with_composite_trigger(query));
+        let new_index = self.patterns.len() - 1;
+        self.insert_into_lsh_index(new_index);
+        self.index_up_to_date = false;
+        self.similarity_up_to_date = false;
+        true // Pattern was added
+    }
+
+    /// If we have too many patterns, remove the least used one
+    fn evict_if_full(&mut self) {
         if self.patterns.len() >= self.max_patterns {
             if let Some(min_index) = self.patterns
                 .iter()
@@ -202,80 +824,500 @@ impl PatternMatcher {
                 })
                 .map(|(i, _)| i)
             {
+                // Update lsh_buckets for the swap_remove below *before*
+                // mutating patterns: dropping min_index's own bucket
+                // entries, then repointing the displaced last pattern's
+                // entries from its old index to min_index.
+                self.remove_from_lsh_index(min_index);
+                let last_index = self.patterns.len() - 1;
+                if last_index != min_index {
+                    self.relocate_lsh_index(last_index, min_index);
+                }
+
                 self.patterns.swap_remove(min_index);
+                self.index_up_to_date = false;
+                self.similarity_up_to_date = false;
             }
         }
-        
-        self.patterns.push(Pattern::new(text, response));
-        true // Pattern was added
     }
-    
+
+    /// Rebuild `lsh_buckets` from scratch for the current contents of
+    /// `patterns`. Only needed once per matcher (construction or
+    /// deserialization, guarded by `lsh_up_to_date`) - after that,
+    /// `add_pattern`/`add_composite_pattern`/`evict_if_full` keep it
+    /// current incrementally, so merge checks stay sub-linear.
+    fn ensure_lsh_index(&mut self) {
+        if self.lsh_up_to_date {
+            return;
+        }
+
+        self.lsh_buckets.clear();
+        for i in 0..self.patterns.len() {
+            self.insert_into_lsh_index(i);
+        }
+
+        self.lsh_up_to_date = true;
+    }
+
+    /// Candidate indices sharing at least one LSH band with `text`, per
+    /// `minhash_band_keys`, deduplicated.
+    fn lsh_candidate_indices(&self, text: &str) -> Vec<usize> {
+        let mut candidates = Vec::new();
+        for key in minhash_band_keys(text) {
+            if let Some(bucket) = self.lsh_buckets.get(&key) {
+                for &i in bucket {
+                    if !candidates.contains(&i) {
+                        candidates.push(i);
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Add `patterns[index]` to every LSH bucket its text's bands hash to.
+    fn insert_into_lsh_index(&mut self, index: usize) {
+        for key in minhash_band_keys(&self.patterns[index].text) {
+            self.lsh_buckets.entry(key).or_default().push(index);
+        }
+    }
+
+    /// Remove every occurrence of `index` from the LSH buckets its text's
+    /// bands hash to, dropping any bucket left empty.
+    fn remove_from_lsh_index(&mut self, index: usize) {
+        for key in minhash_band_keys(&self.patterns[index].text) {
+            if let Some(bucket) = self.lsh_buckets.get_mut(&key) {
+                bucket.retain(|&i| i != index);
+                if bucket.is_empty() {
+                    self.lsh_buckets.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Repoint every occurrence of `old_index` in the LSH buckets for
+    /// `patterns[old_index]`'s text to `new_index`, for the index shift a
+    /// `swap_remove` causes.
+    fn relocate_lsh_index(&mut self, old_index: usize, new_index: usize) {
+        for key in minhash_band_keys(&self.patterns[old_index].text) {
+            if let Some(bucket) = self.lsh_buckets.get_mut(&key) {
+                for slot in bucket.iter_mut() {
+                    if *slot == old_index {
+                        *slot = new_index;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rebuild the Aho-Corasick automaton over the current pattern texts.
+    ///
+    /// This is cheap relative to a full rescoring pass and only needs to
+    /// run once per batch of pattern mutations, guarded by
+    /// `index_up_to_date`.
+    pub fn rebuild_index(&mut self) {
+        self.automaton = if self.patterns.is_empty() {
+            None
+        } else {
+            let texts: Vec<&str> = self.patterns.iter().map(|p| p.text.as_str()).collect();
+            aho_corasick::AhoCorasick::new(texts).ok()
+        };
+
+        self.anagram_index.clear();
+        self.token_index.clear();
+        for (i, pattern) in self.patterns.iter().enumerate() {
+            self.anagram_index
+                .entry(anagram_value(&pattern.text))
+                .or_default()
+                .push(i);
+
+            for token in trigger_tokens(&pattern.text) {
+                self.token_index.entry(token).or_default().push(i);
+            }
+        }
+
+        self.index_up_to_date = true;
+    }
+
+    /// Indices of patterns worth scoring against `input`: the union of the
+    /// automaton's literal-substring matches, the anagram index's
+    /// whole-string typo matches, and `token_index`'s shared-trigger-word
+    /// matches, plus any composite-triggered patterns (never indexed by the
+    /// above, since their `text` is a boolean query rather than a phrase).
+    /// Rebuilds the indexes first if they're stale. An empty result means
+    /// none of the prefilters found anything, so the caller should fall
+    /// back to scoring every pattern.
+    pub fn candidate_indices(&mut self, input: &str) -> Vec<usize> {
+        if !self.index_up_to_date {
+            self.rebuild_index();
+        }
+
+        let lower_input = input.to_lowercase();
+
+        let mut candidates: Vec<usize> = self.automaton
+            .as_ref()
+            .map(|ac| ac.find_iter(&lower_input).map(|m| m.pattern().as_usize()).collect())
+            .unwrap_or_default();
+
+        for &i in self.typo_candidates(&lower_input).keys() {
+            if !candidates.contains(&i) {
+                candidates.push(i);
+            }
+        }
+
+        for i in self.token_candidates(&lower_input) {
+            if !candidates.contains(&i) {
+                candidates.push(i);
+            }
+        }
+
+        if !candidates.is_empty() {
+            for (i, pattern) in self.patterns.iter().enumerate() {
+                if pattern.composite.is_some() && !candidates.contains(&i) {
+                    candidates.push(i);
+                }
+            }
+        }
+
+        candidates
+    }
+
+    /// Indices of patterns sharing at least one trigger word with `input`,
+    /// via `token_index`.
+    fn token_candidates(&self, input: &str) -> Vec<usize> {
+        let mut candidates = Vec::new();
+        for token in trigger_tokens(input) {
+            if let Some(indices) = self.token_index.get(&token) {
+                for &i in indices {
+                    if !candidates.contains(&i) {
+                        candidates.push(i);
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Retrieve spelling-variant candidates for `input` via the anagram
+    /// index: expand the input's anagram value to its neighborhood within
+    /// `max_edit_distance` single-character edits, collect the patterns
+    /// bucketed under those values, then confirm each with a real
+    /// Damerau-Levenshtein check. Returns confirmed candidates paired with
+    /// their edit distance, so callers can penalize the score accordingly.
+    fn typo_candidates(&self, input: &str) -> HashMap<usize, usize> {
+        let input = input.to_lowercase();
+        let mut nearby = HashSet::new();
+        let input_value = anagram_value(&input);
+        nearby.insert(input_value);
+        nearby_anagram_values(input_value, self.max_edit_distance, &mut nearby);
+
+        let mut confirmed = HashMap::new();
+        for value in nearby {
+            if let Some(indices) = self.anagram_index.get(&value) {
+                for &i in indices {
+                    let distance = damerau_levenshtein(&input, &self.patterns[i].text);
+                    if distance <= self.max_edit_distance {
+                        confirmed
+                            .entry(i)
+                            .and_modify(|d| *d = distance.min(*d))
+                            .or_insert(distance);
+                    }
+                }
+            }
+        }
+
+        confirmed
+    }
+
+    /// Word-by-word edit-distance fallback for input that doesn't clear the
+    /// normal scoring path at all, e.g. "helllo" or "whats yuor name" -
+    /// typos severe enough to throw off both the automaton prefilter and
+    /// the anagram index's whole-string distance check, but still
+    /// obviously a typo of a known trigger rather than an unrelated
+    /// sentence.
+    ///
+    /// Only compares `input` against patterns with the same word count as
+    /// `text` (composite-triggered patterns are skipped, since their
+    /// `text` is a boolean query, not a phrase). Each word pair is checked
+    /// with [`bounded_levenshtein`] against a per-word budget of
+    /// `max(1, min(len) / 3)`, so only genuine typos - not unrelated words
+    /// of similar length - can match; a pattern is a candidate only if
+    /// every one of its words is within budget. Returns the candidate
+    /// minimizing total distance across all words, paired with that total.
+    fn fuzzy_correct(&self, input: &str) -> Option<(usize, usize)> {
+        let input_words: Vec<&str> = input.split_whitespace().collect();
+        if input_words.is_empty() {
+            return None;
+        }
+
+        let mut best: Option<(usize, usize)> = None;
+
+        for (i, pattern) in self.patterns.iter().enumerate() {
+            if pattern.composite.is_some() {
+                continue;
+            }
+
+            let pattern_words: Vec<&str> = pattern.text.split_whitespace().collect();
+            if pattern_words.len() != input_words.len() {
+                continue;
+            }
+
+            let mut total = 0usize;
+            let mut within_budget = true;
+            for (a, b) in input_words.iter().zip(pattern_words.iter()) {
+                let max_dist = (a.chars().count().min(b.chars().count()) / 3).max(1);
+                let distance = bounded_levenshtein(a, b, max_dist);
+                if distance > max_dist {
+                    within_budget = false;
+                    break;
+                }
+                total += distance;
+            }
+
+            if within_budget && best.map_or(true, |(_, best_total)| total < best_total) {
+                best = Some((i, total));
+            }
+        }
+
+        best
+    }
+
+    /// Rebuild `similarity_edges` for the current contents of `patterns`.
+    ///
+    /// For every ordered pair `(from, to)`, scores `to` against `from`'s
+    /// text the same way `find_best_match_with_context` used to when
+    /// reinforcing matches, and keeps the edge if it clears
+    /// `REINFORCEMENT_SIMILARITY_THRESHOLD`. This is the same O(N^2) work
+    /// the old reinforcement pass repeated on every single match; doing it
+    /// once per batch of pattern mutations, guarded by
+    /// `similarity_up_to_date`, turns each match's reinforcement step into
+    /// an O(degree) edge walk.
+    fn ensure_similarity_index(&mut self) {
+        if self.similarity_up_to_date {
+            return;
+        }
+
+        self.similarity_edges.clear();
+        for from in 0..self.patterns.len() {
+            let from_text = self.patterns[from].text.clone();
+            let from_context = vec![from_text.clone()];
+            for to in 0..self.patterns.len() {
+                if from == to {
+                    continue;
+                }
+                let similarity = self.patterns[to].match_score(&from_text, Some(&from_context));
+                if similarity > REINFORCEMENT_SIMILARITY_THRESHOLD {
+                    self.similarity_edges.push((from, to, similarity));
+                }
+            }
+        }
+
+        self.similarity_up_to_date = true;
+    }
+
     /// Find the best matching pattern for the input with optional context
     pub fn find_best_match(&mut self, input: &str) -> Option<&mut Pattern> {
         let context: Vec<String> = Vec::new(); // Empty context for backward compatibility
         self.find_best_match_with_context(input, Some(&context))
     }
-    
+
     /// Find the best matching pattern for the input, considering context
     pub fn find_best_match_with_context<'a>(
         &'a mut self,
         input: &str,
         context: Option<&[String]>
     ) -> Option<&'a mut Pattern> {
-        // First pass: find the best matching pattern and its score
+        // Narrow down to patterns worth scoring via the automaton (literal
+        // substrings), anagram index (whole-string typos), and token index
+        // (shared trigger words) before falling back to scoring every
+        // pattern, which only happens if none of those three find anything
+        // in common with `input`.
+        let lower_input = input.to_lowercase();
+        let candidates = self.candidate_indices(&lower_input);
+        let typo_candidates = self.typo_candidates(&lower_input);
+        let scan_all = candidates.is_empty();
+
+        // First pass: find the best matching pattern and its score. A
+        // candidate found only via the anagram index gets its score
+        // penalized in proportion to its confirmed edit distance, since
+        // it's a typo match rather than a literal or exact one.
         let (best_index, best_score) = {
             let mut best_index = None;
             let mut best_score = 0.0;
-            
-            for (i, pattern) in self.patterns.iter_mut().enumerate() {
-                let score = pattern.match_score(input, context);
-                if score > best_score {
-                    best_score = score;
-                    best_index = Some(i);
+
+            let score_candidate = |i: usize, pattern: &mut Pattern| {
+                let mut score = pattern.match_score(input, context);
+                if let Some(&distance) = typo_candidates.get(&i) {
+                    score -= distance as f32 * TYPO_DISTANCE_PENALTY;
+                }
+                score
+            };
+
+            if scan_all {
+                for (i, pattern) in self.patterns.iter_mut().enumerate() {
+                    let score = score_candidate(i, pattern);
+                    if score > best_score {
+                        best_score = score;
+                        best_index = Some(i);
+                    }
+                }
+            } else {
+                for i in candidates {
+                    let pattern = &mut self.patterns[i];
+                    let score = score_candidate(i, pattern);
+                    if score > best_score {
+                        best_score = score;
+                        best_index = Some(i);
+                    }
                 }
             }
-            
+
             (best_index, best_score)
         };
-        
-        // If we found a match, record its usage and reinforce similar patterns
-        if let Some(index) = best_index {
-            // Get a mutable reference to the best pattern
-            let pattern = &mut self.patterns[index];
-            
-            // Record usage
-            pattern.record_usage(context);
-            
-            // Only reinforce if the match is good but not perfect
-            if best_score > 0.3 && best_score < 9.0 {
-                // Make a copy of the pattern's text to avoid borrowing issues
-                let pattern_text = pattern.text.clone();
-                
-                // Find and reinforce similar patterns
-                for other_pattern in &mut self.patterns {
-                    if other_pattern.text == pattern_text {
-                        continue; // Skip the pattern itself
-                    }
-                    
-                    // Create a context with the pattern text
-                    let pattern_context = vec![pattern_text.clone()];
-                    
-                    // Calculate similarity based on context
-                    let similarity = other_pattern.match_score(&pattern_text, Some(&pattern_context));
-                    if similarity > 0.3 { // If somewhat similar
-                        let boost = 0.05 * similarity * best_score;
-                        other_pattern.weight = (other_pattern.weight + boost).min(5.0);
-                    }
+
+        // Neither the literal/automaton prefilter nor the anagram index's
+        // whole-string distance check found anything usable - try matching
+        // what's left word-by-word before giving up.
+        let (best_index, best_score) = if self.fuzzy_threshold > 0.0
+            && (best_index.is_none() || best_score < self.fuzzy_threshold)
+        {
+            match self.fuzzy_correct(&lower_input) {
+                Some((i, distance)) => {
+                    let corrected_text = self.patterns[i].text.clone();
+                    let mut score = self.patterns[i].match_score(&corrected_text, context);
+                    score -= distance as f32 * TYPO_DISTANCE_PENALTY;
+                    log::debug!(
+                        "Fuzzy-corrected '{}' to pattern '{}' (edit distance {})",
+                        input,
+                        corrected_text,
+                        distance
+                    );
+                    (Some(i), score)
                 }
+                None => (best_index, best_score),
             }
-            
-            // Return a mutable reference to the pattern
-            Some(&mut self.patterns[index])
+        } else {
+            (best_index, best_score)
+        };
+
+        // If we found a match, record its usage and reinforce similar patterns
+        if let Some(index) = best_index {
+            Some(self.record_match(index, best_score, context))
         } else {
             None
         }
     }
-    
+
+    /// Record usage of the pattern at `index` and, if `score` was good but
+    /// not perfect, reinforce the patterns `similarity_edges` says are
+    /// similar to it. Shared by `find_best_match_with_context` and
+    /// `find_best_match_parallel_with_context`, so both pay for the
+    /// reinforcement walk the same way once a winning index is known.
+    fn record_match(&mut self, index: usize, score: f32, context: Option<&[String]>) -> &mut Pattern {
+        self.patterns[index].record_usage(context);
+
+        // Only reinforce if the match is good but not perfect
+        if score > 0.3 && score < 9.0 {
+            self.ensure_similarity_index();
+
+            // Walk the precomputed similarity edges out of `index` instead
+            // of rescoring every other pattern against it.
+            for &(from, to, similarity) in &self.similarity_edges {
+                if from == index {
+                    let boost = 0.05 * similarity * score;
+                    self.patterns[to].weight = (self.patterns[to].weight + boost).min(5.0);
+                }
+            }
+        }
+
+        &mut self.patterns[index]
+    }
+
+    /// Rayon-backed counterpart to `find_best_match`, for pattern sets
+    /// large enough that scoring them serially is the bottleneck. Requires
+    /// the `parallel-match` feature.
+    #[cfg(feature = "parallel-match")]
+    pub fn find_best_match_parallel(&mut self, input: &str) -> Option<&mut Pattern> {
+        let context: Vec<String> = Vec::new();
+        self.find_best_match_parallel_with_context(input, Some(&context))
+    }
+
+    /// Rayon-backed counterpart to `find_best_match_with_context`.
+    ///
+    /// Scores every pattern against `input` on the rayon thread pool -
+    /// `Pattern::match_score` takes `&self`, so this pass is fully
+    /// immutable - reducing chunk-local bests down to a single global
+    /// `(index, score)`, then applies the same usage/reinforcement step
+    /// `find_best_match_with_context` does via `record_match`. Does not use
+    /// the Aho-Corasick/anagram prefilter, since scoring every pattern in
+    /// parallel is the point. Requires the `parallel-match` feature.
+    #[cfg(feature = "parallel-match")]
+    pub fn find_best_match_parallel_with_context<'a>(
+        &'a mut self,
+        input: &str,
+        context: Option<&[String]>,
+    ) -> Option<&'a mut Pattern> {
+        use rayon::prelude::*;
+
+        if !self.index_up_to_date {
+            self.rebuild_index();
+        }
+
+        let typo_candidates = self.typo_candidates(&input.to_lowercase());
+
+        let (best_index, best_score) = self
+            .patterns
+            .par_iter()
+            .enumerate()
+            .map(|(i, pattern)| {
+                let mut score = pattern.match_score(input, context);
+                if let Some(&distance) = typo_candidates.get(&i) {
+                    score -= distance as f32 * TYPO_DISTANCE_PENALTY;
+                }
+                (i, score)
+            })
+            .reduce(|| (usize::MAX, 0.0_f32), |a, b| if b.1 > a.1 { b } else { a });
+
+        if best_index == usize::MAX || best_score <= 0.0 {
+            return None;
+        }
+
+        Some(self.record_match(best_index, best_score, context))
+    }
+
+    /// Streaming counterpart to `find_best_match_parallel_with_context`:
+    /// scores every pattern against `input` in parallel and sends each
+    /// candidate scoring above `threshold` to the returned channel as its
+    /// chunk of the rayon work finishes, instead of only surfacing the
+    /// single best match. Callers drain the channel for a ranked list of
+    /// candidates (order is not guaranteed, since chunks finish out of
+    /// order); nothing is recorded or reinforced; commit to a candidate
+    /// through `find_best_match_with_context` instead. Requires the
+    /// `parallel-match` feature.
+    #[cfg(feature = "parallel-match")]
+    pub fn find_matches_streamed(
+        &self,
+        input: &str,
+        context: Option<&[String]>,
+        threshold: f32,
+    ) -> std::sync::mpsc::Receiver<StreamedMatch> {
+        use rayon::prelude::*;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        self.patterns
+            .par_iter()
+            .enumerate()
+            .for_each_with(tx, |tx, (index, pattern)| {
+                let score = pattern.match_score(input, context);
+                if score > threshold {
+                    let _ = tx.send(StreamedMatch { index, score });
+                }
+            });
+
+        rx
+    }
+
     // This method is kept for future use but currently not called directly
     #[allow(dead_code)]
     fn reinforce_similar_patterns(
@@ -297,6 +1339,120 @@ impl PatternMatcher {
     pub fn get_patterns(&self) -> &[Pattern] {
         &self.patterns
     }
+
+    /// Materialize every pattern as a knowledge-graph `Node` (one per
+    /// `Pattern`, carrying `text`, `response`, `weight`, `usage_count`,
+    /// `created_at`, `last_used` and `context_triggers` as properties, plus
+    /// the serialized `composite` trigger when present) and every
+    /// reinforcement relationship discovered by `ensure_similarity_index`
+    /// as a weighted `SIMILAR_TO` `Edge` between the corresponding nodes.
+    ///
+    /// `nodes[i]` corresponds to `self.patterns[i]`; pass the result to
+    /// `from_graph` to reconstruct an equivalent matcher.
+    pub fn to_graph(&mut self) -> (Vec<Node>, Vec<Edge>) {
+        self.ensure_similarity_index();
+
+        let nodes: Vec<Node> = self.patterns.iter().map(Self::pattern_to_node).collect();
+
+        let edges = self
+            .similarity_edges
+            .iter()
+            .map(|&(from, to, similarity)| {
+                Edge::new("SIMILAR_TO", nodes[from].id, nodes[to].id)
+                    .with_property("similarity", similarity)
+            })
+            .collect();
+
+        (nodes, edges)
+    }
+
+    /// Reconstruct a `PatternMatcher` from the `Node`s and `SIMILAR_TO`
+    /// `Edge`s produced by `to_graph`. Nodes are rebuilt into `patterns` in
+    /// the order given, and edges whose endpoints resolve to one of those
+    /// nodes repopulate `similarity_edges`, so `find_best_match_with_context`
+    /// can walk them immediately without an initial `ensure_similarity_index`
+    /// pass.
+    pub fn from_graph(nodes: &[Node], edges: &[Edge]) -> Self {
+        let mut matcher = Self::new();
+
+        let mut index_of: HashMap<Uuid, usize> = HashMap::new();
+        for node in nodes {
+            index_of.insert(node.id, matcher.patterns.len());
+            matcher.patterns.push(Self::pattern_from_node(node));
+        }
+
+        matcher.similarity_edges = edges
+            .iter()
+            .filter(|edge| edge.label == "SIMILAR_TO")
+            .filter_map(|edge| {
+                let from = *index_of.get(&edge.source)?;
+                let to = *index_of.get(&edge.target)?;
+                let similarity = edge.get_property("similarity")?.as_f64()? as f32;
+                Some((from, to, similarity))
+            })
+            .collect();
+        matcher.similarity_up_to_date = true;
+
+        matcher
+    }
+
+    /// Build the `Node` representation of a single pattern (see `to_graph`).
+    fn pattern_to_node(pattern: &Pattern) -> Node {
+        let mut node = Node::new("Pattern")
+            .with_property("text", pattern.text.clone())
+            .with_property("response", pattern.response.clone())
+            .with_property("weight", pattern.weight)
+            .with_property("usage_count", pattern.usage_count)
+            .with_property("created_at", pattern.created_at)
+            .with_property("last_used", pattern.last_used)
+            .with_property(
+                "context_triggers",
+                pattern.context_triggers.iter().cloned().collect::<Vec<String>>(),
+            );
+
+        if let Some(composite) = &pattern.composite {
+            node = node.with_property(
+                "composite",
+                serde_json::to_value(composite).unwrap_or(PropertyValue::Null),
+            );
+        }
+
+        node
+    }
+
+    /// Recover a `Pattern` from one of `pattern_to_node`'s nodes, falling
+    /// back to `Pattern::new`'s defaults for any property that's missing or
+    /// the wrong type.
+    fn pattern_from_node(node: &Node) -> Pattern {
+        let text = node.get_property("text").and_then(|v| v.as_str()).unwrap_or_default();
+        let response = node.get_property("response").and_then(|v| v.as_str()).unwrap_or_default();
+
+        let mut pattern = Pattern::new(text, response);
+
+        if let Some(weight) = node.get_property("weight").and_then(|v| v.as_f64()) {
+            pattern.weight = weight as f32;
+        }
+        if let Some(usage_count) = node.get_property("usage_count").and_then(|v| v.as_u64()) {
+            pattern.usage_count = usage_count as u32;
+        }
+        if let Some(created_at) = node.get_property("created_at").and_then(|v| v.as_u64()) {
+            pattern.created_at = created_at;
+        }
+        if let Some(last_used) = node.get_property("last_used").and_then(|v| v.as_u64()) {
+            pattern.last_used = last_used;
+        }
+        if let Some(triggers) = node.get_property("context_triggers").and_then(|v| v.as_array()) {
+            pattern.context_triggers = triggers
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+        }
+        if let Some(composite) = node.get_property("composite") {
+            pattern.composite = serde_json::from_value(composite.clone()).ok();
+        }
+
+        pattern
+    }
 }
 
 #[cfg(test)]
@@ -392,5 +1548,286 @@ mod tests {
         
         // After adding 10 patterns, it should prune down to 1 (10 * 0.1 = 1)
         assert_eq!(matcher.patterns.len(), 1, "Should keep only 1 pattern after pruning");
-    }    
+    }
+
+    #[test]
+    fn test_fuzzy_typo_tolerance() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+
+        // A typo should still match, just with a lower score than exact match
+        let pattern = matcher.find_best_match("helo").unwrap();
+        assert_eq!(pattern.response, "Hi there!");
+    }
+
+    #[test]
+    fn test_fuzzy_abbreviation_tolerance() {
+        let matcher_pattern = Pattern::new("how are you", "response");
+
+        // Abbreviated input should score higher than a completely unrelated one
+        let abbrev_score = matcher_pattern.match_score("how r u", None);
+        let unrelated_score = matcher_pattern.match_score("xyz123", None);
+        assert!(abbrev_score > unrelated_score);
+        assert!(abbrev_score > 0.0);
+    }
+
+    #[test]
+    fn test_fuzzy_exact_match_still_short_circuits() {
+        let pattern = Pattern::new("test", "response");
+        assert_eq!(pattern.match_score("test", None), 10.0);
+    }
+
+    #[test]
+    fn test_automaton_prefilter_finds_literal_match() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+        matcher.add_pattern("goodbye", "See you later!");
+
+        // The input contains "hello" literally, so the automaton should
+        // narrow the candidates down to that one pattern.
+        let pattern = matcher.find_best_match("well, hello!").unwrap();
+        assert_eq!(pattern.response, "Hi there!");
+    }
+
+    #[test]
+    fn test_automaton_prefilter_falls_back_for_fuzzy_only_matches() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+
+        // No literal substring hit for the automaton, but the anagram
+        // index still offers "hello" (edit distance 1) as a typo candidate.
+        let pattern = matcher.find_best_match("helo").unwrap();
+        assert_eq!(pattern.response, "Hi there!");
+    }
+
+    #[test]
+    fn test_anagram_value_is_order_independent() {
+        assert_eq!(anagram_value("listen"), anagram_value("silent"));
+        assert_ne!(anagram_value("listen"), anagram_value("hello"));
+    }
+
+    #[test]
+    fn test_damerau_levenshtein_counts_transposition_as_one_edit() {
+        assert_eq!(damerau_levenshtein("ab", "ba"), 1);
+        assert_eq!(damerau_levenshtein("kitten", "sitting"), 3);
+        assert_eq!(damerau_levenshtein("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_anagram_index_retrieves_transposed_candidate() {
+        let mut matcher = PatternMatcher::new().with_max_edit_distance(1);
+        matcher.add_pattern("form", "A shape or document.");
+        matcher.rebuild_index();
+
+        let candidates = matcher.typo_candidates("from");
+        assert!(candidates.contains_key(&0));
+        assert_eq!(candidates[&0], 1);
+    }
+
+    #[test]
+    fn test_typo_match_scores_lower_than_exact_match() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("form", "A shape or document.");
+
+        let exact_score = matcher.find_best_match("form").unwrap().match_score("form", None);
+        assert_eq!(exact_score, 10.0);
+
+        let typo_pattern = matcher.find_best_match("from").unwrap();
+        assert_eq!(typo_pattern.response, "A shape or document.");
+    }
+
+    #[test]
+    fn test_parse_composite_pattern_and_not() {
+        let composite = parse_composite_pattern("cat & !dog");
+        match composite {
+            CompositePattern::And(children) => {
+                assert_eq!(children.len(), 2);
+                assert!(matches!(&children[0], CompositePattern::Atom(a) if a == "cat"));
+                assert!(matches!(&children[1], CompositePattern::Not(_)));
+            }
+            other => panic!("expected And, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_composite_pattern_unordered_tokens() {
+        let composite = parse_composite_pattern("cute cat");
+        assert!(matches!(composite, CompositePattern::Tokens(ref t) if t == &["cute", "cat"]));
+    }
+
+    #[test]
+    fn test_composite_and_requires_all_children() {
+        let composite = parse_composite_pattern("cat & dog");
+        assert!(composite.match_score("I have a cat and a dog") > 0.0);
+        assert_eq!(composite.match_score("I have a cat"), 0.0);
+    }
+
+    #[test]
+    fn test_composite_not_excludes_matches() {
+        let composite = parse_composite_pattern("cat & !dog");
+        assert!(composite.match_score("I have a cat") > 0.0);
+        assert_eq!(composite.match_score("I have a cat and a dog"), 0.0);
+    }
+
+    #[test]
+    fn test_composite_pattern_fires_in_find_best_match() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+        matcher.add_composite_pattern("cat & dog", "Sounds like a full house!");
+
+        let pattern = matcher.find_best_match("I have a cat and a dog").unwrap();
+        assert_eq!(pattern.response, "Sounds like a full house!");
+    }
+
+    #[test]
+    fn test_to_graph_round_trips_pattern_fields() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello world", "Hi there!");
+        matcher.find_best_match("hello world"); // bump usage_count/weight
+
+        let (nodes, _edges) = matcher.to_graph();
+        assert_eq!(nodes.len(), 1);
+
+        let restored = PatternMatcher::from_graph(&nodes, &[]);
+        assert_eq!(restored.patterns.len(), 1);
+        assert_eq!(restored.patterns[0].text, matcher.patterns[0].text);
+        assert_eq!(restored.patterns[0].response, matcher.patterns[0].response);
+        assert_eq!(restored.patterns[0].usage_count, matcher.patterns[0].usage_count);
+        assert_eq!(restored.patterns[0].weight, matcher.patterns[0].weight);
+    }
+
+    #[test]
+    fn test_to_graph_emits_similarity_edge_for_reinforced_patterns() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello world", "Hi there!");
+        matcher.add_pattern("hello there world", "Hey!");
+
+        let (nodes, edges) = matcher.to_graph();
+        assert_eq!(nodes.len(), 2);
+        assert!(edges.iter().any(|e| e.label == "SIMILAR_TO"));
+    }
+
+    #[test]
+    fn test_from_graph_round_trip_preserves_similarity_edges() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello world", "Hi there!");
+        matcher.add_pattern("hello there world", "Hey!");
+
+        let (nodes, edges) = matcher.to_graph();
+        let restored = PatternMatcher::from_graph(&nodes, &edges);
+
+        assert_eq!(restored.similarity_edges.len(), matcher.similarity_edges.len());
+        assert!(restored.similarity_up_to_date);
+    }
+
+    #[cfg(feature = "parallel-match")]
+    #[test]
+    fn test_find_best_match_parallel_agrees_with_serial() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+        matcher.add_pattern("how are you", "I'm doing great, thanks!");
+
+        let pattern = matcher.find_best_match_parallel("hello").unwrap();
+        assert_eq!(pattern.response, "Hi there!");
+    }
+
+    #[cfg(feature = "parallel-match")]
+    #[test]
+    fn test_find_matches_streamed_only_yields_candidates_above_threshold() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello", "Hi there!");
+        matcher.add_pattern("goodbye", "See you later!");
+
+        let matches: Vec<_> = matcher
+            .find_matches_streamed("hello", None, 0.3)
+            .into_iter()
+            .collect();
+
+        assert!(matches.iter().any(|m| m.index == 0));
+        assert!(matches.iter().all(|m| m.score > 0.3));
+    }
+
+    #[test]
+    fn test_minhash_band_keys_share_every_band_for_the_same_word_set() {
+        // Same word set, different order: the MinHash signature only
+        // depends on the set of words, so every band should collide.
+        let a = minhash_band_keys("hello world");
+        let b = minhash_band_keys("world hello");
+        let c = minhash_band_keys("completely unrelated sentence");
+
+        assert_eq!(a, b);
+        assert!(!a.iter().any(|key| c.contains(key)));
+    }
+
+    #[test]
+    fn test_add_pattern_merge_respects_custom_threshold() {
+        let mut matcher = PatternMatcher::new().with_merge_similarity_threshold(0.9);
+        matcher.add_pattern("hello world", "Response 1");
+
+        // With the merge threshold raised well above these two texts'
+        // Jaccard similarity (2/3), they should no longer be merged.
+        let added = matcher.add_pattern("hello there world", "Response 2");
+        assert!(added);
+        assert_eq!(matcher.patterns.len(), 2);
+    }
+
+    #[test]
+    fn test_evict_relocates_lsh_index_after_swap_remove() {
+        let mut matcher = PatternMatcher::new().with_max_patterns(2);
+        matcher.add_pattern("alpha pattern", "A");
+        matcher.add_pattern("beta pattern", "B");
+        // Evicts whichever of the two has the lowest usage_count (ties go
+        // to the most recently used), swap-removing the last pattern into
+        // its slot.
+        matcher.add_pattern("gamma pattern", "C");
+
+        assert_eq!(matcher.patterns.len(), 2);
+
+        // The merge path for a near-duplicate of whatever survived must
+        // still find it via the relocated LSH bucket entry, not miss it
+        // because the bucket still pointed at the pre-swap index.
+        let survivor_text = matcher.patterns[0].text.clone();
+        let words: Vec<&str> = survivor_text.split(' ').collect();
+        let near_duplicate = format!("{} {} extra", words[0], words.get(1).copied().unwrap_or(""));
+        let added = matcher.add_pattern(&near_duplicate, "D");
+        assert!(!added);
+    }
+
+    #[test]
+    fn test_token_index_finds_shared_word_candidate_without_substring_match() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("what is your favorite color", "Blue, probably.");
+        matcher.add_pattern("tell me a joke", "Why did the chicken cross the road?");
+
+        // No literal substring or anagram-distance relationship between the
+        // input and the first pattern, but they share the word "favorite".
+        let candidates = matcher.candidate_indices("my favorite season is summer");
+        assert!(candidates.contains(&0));
+        assert!(!candidates.contains(&1));
+    }
+
+    #[test]
+    fn test_candidate_indices_empty_when_nothing_shares_a_token() {
+        let mut matcher = PatternMatcher::new();
+        matcher.add_pattern("hello there", "Hi!");
+
+        assert!(matcher.candidate_indices("xyzzy plugh").is_empty());
+    }
+
+    #[test]
+    fn test_find_best_match_scales_to_many_patterns() {
+        let mut matcher = PatternMatcher::new().with_max_patterns(10_000);
+        // Every pattern's trigger words are unique to it, so the token index
+        // can narrow the candidate set down to (effectively) one entry
+        // instead of rescoring all 10,000 patterns.
+        for i in 0..10_000 {
+            matcher.add_pattern(&format!("topic{i} stuff{i}"), &format!("response {i}"));
+        }
+
+        let candidates = matcher.candidate_indices("topic42 stuff42");
+        assert!(candidates.len() < matcher.patterns.len());
+
+        let best = matcher.find_best_match("topic42 stuff42").unwrap();
+        assert_eq!(best.response, "response 42");
+    }
 }
@@ -3,6 +3,132 @@
 use std::collections::HashMap;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
+/// Supplies values to [`ResponseTemplate::render`] for plain variables,
+/// `{{if}}`/`{{#if}}` conditions, and `{{#each}}` iteration, so callers can
+/// hand the template a source of computed/lazy values (timestamps, graph
+/// lookups, a stored message list) instead of pre-materializing every
+/// variable into a flat map first. This is the single source of truth both
+/// `render` and `render_with_functions` consult -- there's no separate path
+/// for conditionals versus plain substitution.
+///
+/// `HashMap<&str, String>` implements this directly, so every existing
+/// `render(&some_map)` call site keeps compiling and behaving exactly as
+/// before; it just has no iterable data; `resolve_each` always returns
+/// `None` for it.
+pub trait VariableResolver {
+    /// The scalar value of `name`, used for `{{var}}`, `{{var|default}}`,
+    /// and `{{if var|...}}`/`{{#if var}}` truthiness checks (empty or
+    /// missing counts as false).
+    fn resolve(&self, name: &str) -> Option<String>;
+
+    /// The items of `name`, for an `{{#each name}}...{{/each}}` block.
+    /// Defaults to `None` (not iterable), which renders the block as empty.
+    fn resolve_each(&self, _name: &str) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Whether this resolver has *any* values at all, backing the legacy
+    /// `{{if context:...|...}}` form, which (unlike a named `{{if var|...}}`)
+    /// doesn't check one specific variable -- it checks whether there's any
+    /// context to speak of. Defaults to `true`.
+    fn has_any_context(&self) -> bool {
+        true
+    }
+}
+
+impl VariableResolver for HashMap<&str, String> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        self.get(name).cloned()
+    }
+
+    fn has_any_context(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+/// The resolver in scope inside an `{{#each}}` body: `{{.}}` is the current
+/// item's own string value, and any other name falls back to whatever
+/// resolver the loop itself was rendered with (so a loop body can still
+/// reference an outer variable, or nest another `{{#each}}`/`{{#if}}`).
+struct EachItemResolver<'p, P: VariableResolver> {
+    item: String,
+    parent: &'p P,
+}
+
+impl<'p, P: VariableResolver> VariableResolver for EachItemResolver<'p, P> {
+    fn resolve(&self, name: &str) -> Option<String> {
+        if name == "." {
+            Some(self.item.clone())
+        } else {
+            self.parent.resolve(name)
+        }
+    }
+
+    fn resolve_each(&self, name: &str) -> Option<Vec<String>> {
+        self.parent.resolve_each(name)
+    }
+
+    fn has_any_context(&self) -> bool {
+        self.parent.has_any_context()
+    }
+}
+
+/// Find the first (leftmost, outermost) `{{#each NAME}}...{{/each}}` block
+/// in `template`, tracking nesting depth so a body that itself contains
+/// another `{{#each}}` doesn't end the outer block at the inner block's
+/// `{{/each}}`. Returns `(prefix, var_name, body, suffix)`, or `None` if
+/// there's no `{{#each}}` (or it's never closed).
+fn find_each_block(template: &str) -> Option<(&str, &str, &str, &str)> {
+    find_block(template, "{{#each", "{{/each}}")
+}
+
+/// Find the first (leftmost, outermost) `{{#if COND}}...{{/if}}` block in
+/// `template`, with the same nesting-aware matching as [`find_each_block`].
+/// Returns `(prefix, condition, body, suffix)`, or `None` if there's no
+/// `{{#if}}` (or it's never closed). Distinct from the older single-line
+/// `{{if COND|then text}}` form, which [`ResponseTemplate::process_conditionals`]
+/// still handles separately.
+fn find_if_block(template: &str) -> Option<(&str, &str, &str, &str)> {
+    find_block(template, "{{#if", "{{/if}}")
+}
+
+/// Shared nesting-aware block matcher behind [`find_each_block`] and
+/// [`find_if_block`]: `open_tag` is the opening tag's fixed prefix up to
+/// (not including) its `}}`, and `close_tag` is the literal closing tag.
+fn find_block<'t>(template: &'t str, open_tag: &str, close_tag: &str) -> Option<(&'t str, &'t str, &'t str, &'t str)> {
+    let open_start = template.find(open_tag)?;
+    let header_rel_end = template[open_start..].find("}}")?;
+    let header_end = open_start + header_rel_end + 2;
+    let header = template[open_start + open_tag.len()..header_end - 2].trim();
+
+    let mut depth = 1usize;
+    let mut cursor = header_end;
+    loop {
+        let next_open = template[cursor..].find(open_tag);
+        let next_close = template[cursor..].find(close_tag);
+        match (next_open, next_close) {
+            (_, None) => return None,
+            (Some(open_rel), Some(close_rel)) if open_rel < close_rel => {
+                depth += 1;
+                cursor += open_rel + open_tag.len();
+            }
+            (_, Some(close_rel)) => {
+                depth -= 1;
+                let close_abs = cursor + close_rel;
+                if depth == 0 {
+                    let body = &template[header_end..close_abs];
+                    let suffix = &template[close_abs + close_tag.len()..];
+                    let prefix = &template[..open_start];
+                    return Some((prefix, header, body, suffix));
+                }
+                cursor = close_abs + close_tag.len();
+            }
+        }
+    }
+}
+
 /// Represents a response template that can contain variables and conditionals
 #[derive(Debug, Clone)]
 pub struct ResponseTemplate {
@@ -16,22 +142,91 @@ impl ResponseTemplate {
             template: template.to_string(),
         }
     }
-    
+
     /// Render the template with the provided variables
-    pub fn render(&self, context: &HashMap<&str, String>) -> String {
-        let mut result = self.template.clone();
-        
-        // First handle conditionals
-        result = self.process_conditionals(&result, context);
-        
-        // Then handle variables with defaults
-        result = self.process_variables(&result, context);
-        
-        result
+    pub fn render<R: VariableResolver>(&self, context: &R) -> String {
+        let result = self.process_each(&self.template, context);
+        let result = self.process_if_blocks(&result, context);
+        let result = self.process_conditionals(&result, context);
+        self.process_variables(&result, context)
     }
-    
+
+    /// Render the template like [`render`](Self::render), additionally
+    /// resolving `{{call:name(arg1,arg2)}}` directives against `functions`:
+    /// each listed arg name is looked up in `context`, the named function is
+    /// invoked with the resulting map, and the directive is replaced with its
+    /// return value.
+    ///
+    /// Returns `None` if a directive names a function that isn't registered
+    /// in `functions`, so callers (e.g. `BasicLLM::generate_response`) can
+    /// degrade to a fallback response instead of emitting a half-rendered
+    /// template.
+    pub fn render_with_functions<R: VariableResolver>(
+        &self,
+        context: &R,
+        functions: &FunctionRegistry,
+    ) -> Option<String> {
+        let result = self.process_each(&self.template, context);
+        let result = self.process_if_blocks(&result, context);
+        let result = self.process_conditionals(&result, context);
+        let result = self.process_calls(&result, context, functions)?;
+        Some(self.process_variables(&result, context))
+    }
+
+    /// Expand every `{{#each name}}...{{/each}}` block, rendering the body
+    /// once per item `resolver.resolve_each(name)` returns (nothing if it
+    /// returns `None`, e.g. `name` isn't iterable or doesn't exist). Each
+    /// iteration gets its own [`EachItemResolver`] exposing `.` as the
+    /// item's value, and the body is fully rendered -- nested `{{#each}}`,
+    /// `{{#if}}`, and `{{var}}` all included -- before moving to the next
+    /// item or the text after the block.
+    fn process_each<R: VariableResolver>(&self, template: &str, resolver: &R) -> String {
+        match find_each_block(template) {
+            None => template.to_string(),
+            Some((prefix, var_name, body, suffix)) => {
+                let mut rendered = String::from(prefix);
+                if let Some(items) = resolver.resolve_each(var_name) {
+                    for item in items {
+                        let item_resolver = EachItemResolver { item, parent: resolver };
+                        let expanded = self.process_each(body, &item_resolver);
+                        let expanded = self.process_if_blocks(&expanded, &item_resolver);
+                        let expanded = self.process_conditionals(&expanded, &item_resolver);
+                        let expanded = self.process_variables(&expanded, &item_resolver);
+                        rendered.push_str(&expanded);
+                    }
+                }
+                rendered.push_str(&self.process_each(suffix, resolver));
+                rendered
+            }
+        }
+    }
+
+    /// Expand every `{{#if cond}}...{{/if}}` block: the body is kept (and
+    /// recursively processed for further nested blocks) if `cond` is
+    /// truthy, dropped otherwise. See [`find_if_block`] for how `cond` is
+    /// parsed and how nesting is tracked.
+    fn process_if_blocks<R: VariableResolver>(&self, template: &str, resolver: &R) -> String {
+        match find_if_block(template) {
+            None => template.to_string(),
+            Some((prefix, condition, body, suffix)) => {
+                let condition_met = if condition.starts_with("context:") {
+                    resolver.has_any_context()
+                } else {
+                    resolver.resolve(condition).map_or(false, |v| !v.is_empty())
+                };
+
+                let mut rendered = String::from(prefix);
+                if condition_met {
+                    rendered.push_str(&self.process_if_blocks(body, resolver));
+                }
+                rendered.push_str(&self.process_if_blocks(suffix, resolver));
+                rendered
+            }
+        }
+    }
+
     /// Process conditionals in the template ({{if var|then text}})
-    fn process_conditionals(&self, template: &str, context: &HashMap<&str, String>) -> String {
+    fn process_conditionals<R: VariableResolver>(&self, template: &str, context: &R) -> String {
         let mut result = template.to_string();
         let mut start = 0;
         
@@ -48,10 +243,10 @@ impl ResponseTemplate {
                     
                     let condition_met = if condition.starts_with("context:") {
                         // Check if context has previous messages
-                        !context.is_empty()
+                        context.has_any_context()
                     } else {
                         // Check if variable exists and is not empty
-                        context.get(condition).map_or(false, |v| !v.is_empty())
+                        context.resolve(condition).map_or(false, |v| !v.is_empty())
                     };
                     
                     let replacement = if condition_met { then_text } else { "" };
@@ -75,36 +270,28 @@ impl ResponseTemplate {
     }
     
     /// Process variables in the template ({{var}} or {{var|default}})
-    fn process_variables(&self, template: &str, context: &HashMap<&str, String>) -> String {
+    fn process_variables<R: VariableResolver>(&self, template: &str, context: &R) -> String {
         let mut result = template.to_string();
         let mut start = 0;
-        
+
         while let Some(begin) = result[start..].find("{{") {
             let begin = start + begin;
             if let Some(end) = result[begin..].find("}}") {
                 let end = begin + end + 2; // +2 for '}}'
                 let var_block = &result[begin + 2..end - 2].trim(); // Remove '{{' and '}}'
-                
+
                 let (var_name, default_value) = if let Some(pipe) = var_block.find('|') {
                     let (var, default) = var_block.split_at(pipe);
                     (var.trim(), default[1..].trim()) // Skip '|'
                 } else {
                     (var_block.as_ref(), "")
                 };
-                
-                let replacement = context.get(var_name)
-                    .map(|s| s.as_str())
+
+                let replacement = context.resolve(var_name)
                     .filter(|s| !s.is_empty())
-                    .or_else(|| {
-                        if default_value.is_empty() {
-                            None
-                        } else {
-                            Some(default_value)
-                        }
-                    })
-                    .unwrap_or("");
-                
-                let new_result = result[..begin].to_string() + replacement + &result[end..];
+                    .unwrap_or_else(|| default_value.to_string());
+
+                let new_result = result[..begin].to_string() + &replacement + &result[end..];
                 let new_start = begin + replacement.len();
                 result = new_result;
                 start = new_start;
@@ -112,9 +299,55 @@ impl ResponseTemplate {
                 break;
             }
         }
-        
+
         result
     }
+
+    /// Process `{{call:name(arg1,arg2)}}` directives ({{call:name}} with no
+    /// parens is also accepted, for zero-argument functions).
+    fn process_calls<R: VariableResolver>(
+        &self,
+        template: &str,
+        context: &R,
+        functions: &FunctionRegistry,
+    ) -> Option<String> {
+        let mut result = template.to_string();
+        let mut start = 0;
+
+        while let Some(begin) = result[start..].find("{{call:") {
+            let begin = start + begin;
+            let end = result[begin..].find("}}")?;
+            let end = begin + end + 2; // +2 for '}}'
+            let directive = result[begin + 7..end - 2].trim(); // Remove '{{call:' and '}}'
+
+            let (name, arg_names) = match directive.find('(') {
+                Some(paren) if directive.ends_with(')') => {
+                    let name = directive[..paren].trim();
+                    let arg_list = &directive[paren + 1..directive.len() - 1];
+                    let args = if arg_list.trim().is_empty() {
+                        Vec::new()
+                    } else {
+                        arg_list.split(',').map(|a| a.trim()).collect()
+                    };
+                    (name, args)
+                }
+                _ => (directive, Vec::new()),
+            };
+
+            let call_args: HashMap<String, String> = arg_names
+                .into_iter()
+                .filter_map(|arg| context.resolve(arg).map(|value| (arg.to_string(), value)))
+                .collect();
+
+            let replacement = functions.call(name, &call_args)?;
+
+            let new_result = result[..begin].to_string() + &replacement + &result[end..];
+            start = begin + replacement.len();
+            result = new_result;
+        }
+
+        Some(result)
+    }
 }
 
 impl fmt::Display for ResponseTemplate {
@@ -123,8 +356,54 @@ impl fmt::Display for ResponseTemplate {
     }
 }
 
+/// A function a response template can invoke via a `{{call:name(args)}}`
+/// directive instead of only emitting static text. Receives the current
+/// template variables (user name, extracted variables, any named args
+/// requested by the directive).
+pub type TemplateFunction = Box<dyn Fn(&HashMap<String, String>) -> String>;
+
+/// Maps callable names to [`TemplateFunction`]s, turning the pattern matcher
+/// into a lightweight intent router: a pattern like `"what time is it"` can
+/// map to a `{{call:now}}` directive, `"remind me to *"` to a
+/// `{{call:schedule(task)}}` callback, and so on.
+#[derive(Default)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, TemplateFunction>,
+}
+
+impl FunctionRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a function under `name`, overwriting any previous
+    /// registration with the same name.
+    pub fn register(&mut self, name: impl Into<String>, f: impl Fn(&HashMap<String, String>) -> String + 'static) {
+        self.functions.insert(name.into(), Box::new(f));
+    }
+
+    /// Names of every currently registered function.
+    pub fn names(&self) -> Vec<&str> {
+        self.functions.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Invoke the named function with `args`, if it's registered.
+    fn call(&self, name: &str, args: &HashMap<String, String>) -> Option<String> {
+        self.functions.get(name).map(|f| f(args))
+    }
+}
+
+impl fmt::Debug for FunctionRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionRegistry")
+            .field("functions", &self.names())
+            .finish()
+    }
+}
+
 /// Context for response generation
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseContext {
     pub user_name: Option<String>,
     pub previous_messages: Vec<String>,
@@ -157,25 +436,30 @@ impl ResponseContext {
     }
 }
 
-/// Generate a response using the template and context
-pub fn generate_response(template: &str, context: &ResponseContext) -> String {
-    let template = ResponseTemplate::new(template);
-    let mut vars = HashMap::new();
-    
-    // Add user name if available
-    if let Some(name) = &context.user_name {
-        vars.insert("user", name.clone());
+impl VariableResolver for ResponseContext {
+    fn resolve(&self, name: &str) -> Option<String> {
+        match name {
+            "user" => self.user_name.clone(),
+            "message_count" => Some(self.previous_messages.len().to_string()),
+            _ => self.custom_vars.get(name).cloned(),
+        }
     }
-    
-    // Add previous message count
-    vars.insert("message_count", context.previous_messages.len().to_string());
-    
-    // Add custom variables
-    for (key, value) in &context.custom_vars {
-        vars.insert(key.as_str(), value.clone());
+
+    fn resolve_each(&self, name: &str) -> Option<Vec<String>> {
+        match name {
+            "previous_messages" => Some(self.previous_messages.clone()),
+            _ => None,
+        }
     }
-    
-    template.render(&vars)
+
+    fn has_any_context(&self) -> bool {
+        self.user_name.is_some() || !self.previous_messages.is_empty() || !self.custom_vars.is_empty()
+    }
+}
+
+/// Generate a response using the template and context
+pub fn generate_response(template: &str, context: &ResponseContext) -> String {
+    ResponseTemplate::new(template).render(context)
 }
 
 #[cfg(test)]
@@ -266,4 +550,94 @@ mod tests {
             "Hi Charlie! I see you're feeling excited. You've sent 1 messages."
         );
     }
+
+    #[test]
+    fn test_call_directive_invokes_registered_function() {
+        let mut functions = FunctionRegistry::new();
+        functions.register("now", |_args| "3:00 PM".to_string());
+
+        let template = ResponseTemplate::new("It's currently {{call:now}}.");
+        let context = HashMap::new();
+
+        assert_eq!(
+            template.render_with_functions(&context, &functions),
+            Some("It's currently 3:00 PM.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_call_directive_passes_named_args() {
+        let mut functions = FunctionRegistry::new();
+        functions.register("weather", |args| {
+            format!("sunny in {}", args.get("city").map(String::as_str).unwrap_or("?"))
+        });
+
+        let template = ResponseTemplate::new("{{call:weather(city)}}");
+        let mut context = HashMap::new();
+        context.insert("city", "Boston".to_string());
+
+        assert_eq!(
+            template.render_with_functions(&context, &functions),
+            Some("sunny in Boston".to_string())
+        );
+    }
+
+    #[test]
+    fn test_call_directive_unregistered_function_returns_none() {
+        let functions = FunctionRegistry::new();
+        let template = ResponseTemplate::new("{{call:unknown}}");
+        let context = HashMap::new();
+
+        assert_eq!(template.render_with_functions(&context, &functions), None);
+    }
+
+    #[test]
+    fn test_each_block_renders_once_per_item() {
+        let mut context = ResponseContext::new();
+        context.add_previous_message("hi");
+        context.add_previous_message("how are you");
+
+        let template = ResponseTemplate::new("{{#each previous_messages}}- {{.}}\n{{/each}}");
+        assert_eq!(template.render(&context), "- hi\n- how are you\n");
+    }
+
+    #[test]
+    fn test_each_block_with_unresolvable_name_renders_empty() {
+        let context = ResponseContext::new();
+        let template = ResponseTemplate::new("before[{{#each previous_messages}}{{.}}{{/each}}]after");
+        assert_eq!(template.render(&context), "before[]after");
+    }
+
+    #[test]
+    fn test_each_body_can_contain_nested_if_and_var() {
+        let mut context = ResponseContext::new();
+        context.add_previous_message("");
+        context.add_previous_message("hello");
+
+        let template = ResponseTemplate::new("{{#each previous_messages}}{{#if .}}({{.}}){{/if}}{{/each}}");
+        assert_eq!(template.render(&context), "(hello)");
+    }
+
+    #[test]
+    fn test_each_can_reference_outer_variable_inside_loop_body() {
+        let mut context = ResponseContext::new();
+        context.add_previous_message("a");
+        context.set_var("sep", " | ");
+
+        let template = ResponseTemplate::new("{{#each previous_messages}}{{.}}{{sep}}{{/each}}");
+        assert_eq!(template.render(&context), "a | ");
+    }
+
+    #[test]
+    fn test_response_context_as_resolver_powers_generate_response() {
+        let mut context = ResponseContext::new().with_user_name("Dana");
+        context.add_previous_message("hey");
+
+        let response = generate_response(
+            "{{user}} has {{message_count}} messages.{{#each previous_messages}} [{{.}}]{{/each}}",
+            &context,
+        );
+
+        assert_eq!(response, "Dana has 1 messages. [hey]");
+    }
 }
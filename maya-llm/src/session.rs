@@ -0,0 +1,133 @@
+//! Named conversation sessions for `BasicLLM`.
+//!
+//! `BasicLLM` keeps a single shared `PatternMatcher` and `MemoryBank` --
+//! learned knowledge is global -- but conversation-local state like the
+//! extracted `user_name`/`mood` and message history needs to stay separate
+//! per user or thread. `SessionManager` tracks that per-session state so one
+//! `BasicLLM` instance can serve several conversations without
+//! cross-contaminating their extracted variables.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::response::ResponseContext;
+
+/// Per-session conversation state: a [`ResponseContext`] plus the session's
+/// own rolling history of raw input messages. `ResponseContext::previous_messages`
+/// is capped at 5 entries for pattern-matching purposes; `history` keeps the
+/// full log for the life of the session.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub context: ResponseContext,
+    pub history: Vec<String>,
+}
+
+impl Session {
+    /// Create a new, empty session.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a raw input message in the session's history.
+    pub fn record(&mut self, input: &str) {
+        self.history.push(input.to_string());
+    }
+}
+
+/// Holds every active [`Session`], keyed by caller-chosen session id.
+#[derive(Debug, Default)]
+pub struct SessionManager {
+    sessions: HashMap<String, Session>,
+}
+
+impl SessionManager {
+    /// Create an empty session manager.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the session if it doesn't exist yet, returning it either way.
+    pub fn start_session(&mut self, id: impl Into<String>) -> &mut Session {
+        self.sessions.entry(id.into()).or_insert_with(Session::new)
+    }
+
+    /// Insert or replace a session wholesale, e.g. after loading one from disk.
+    pub fn insert_session(&mut self, id: impl Into<String>, session: Session) {
+        self.sessions.insert(id.into(), session);
+    }
+
+    /// Look up a session by id.
+    pub fn get(&self, id: &str) -> Option<&Session> {
+        self.sessions.get(id)
+    }
+
+    /// Look up a session by id, mutably.
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut Session> {
+        self.sessions.get_mut(id)
+    }
+
+    /// Whether a session with this id is currently held in memory.
+    pub fn contains(&self, id: &str) -> bool {
+        self.sessions.contains_key(id)
+    }
+
+    /// Remove and return a session.
+    pub fn end_session(&mut self, id: &str) -> Option<Session> {
+        self.sessions.remove(id)
+    }
+
+    /// List the ids of every session currently held in memory.
+    pub fn list_sessions(&self) -> Vec<&str> {
+        self.sessions.keys().map(|s| s.as_str()).collect()
+    }
+
+    /// Iterate over every session, e.g. to save them all to disk.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Session)> {
+        self.sessions.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_session_creates_and_reuses() {
+        let mut manager = SessionManager::new();
+        manager.start_session("alice").context.set_var("mood", "happy");
+
+        assert!(manager.contains("alice"));
+        assert_eq!(
+            manager.get("alice").unwrap().context.get_var("mood"),
+            Some(&"happy".to_string())
+        );
+
+        // Starting the same id again should not wipe out existing state.
+        manager.start_session("alice");
+        assert_eq!(
+            manager.get("alice").unwrap().context.get_var("mood"),
+            Some(&"happy".to_string())
+        );
+    }
+
+    #[test]
+    fn end_session_removes_it() {
+        let mut manager = SessionManager::new();
+        manager.start_session("bob");
+        assert!(manager.end_session("bob").is_some());
+        assert!(!manager.contains("bob"));
+        assert!(manager.end_session("bob").is_none());
+    }
+
+    #[test]
+    fn list_sessions_reflects_active_set() {
+        let mut manager = SessionManager::new();
+        manager.start_session("a");
+        manager.start_session("b");
+
+        let mut ids = manager.list_sessions();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,254 @@
+//! Layered configuration for `BasicLLM`'s `settings` map.
+//!
+//! [`SettingsBuilder`] resolves the effective settings map from up to four
+//! layers, each overriding the previous one:
+//!
+//! 1. built-in defaults ([`SettingsBuilder::with_defaults`])
+//! 2. the map persisted by a previous [`crate::persistence::save_state`]
+//!    ([`SettingsBuilder::with_persisted`])
+//! 3. an on-disk TOML overlay file ([`SettingsBuilder::with_overlay_file`])
+//! 4. environment variables under a configurable prefix
+//!    ([`SettingsBuilder::with_env`])
+//!
+//! [`SettingsBuilder::persistable`] returns the subset of the result that's
+//! safe to write back out via `save_state` -- the persisted/overlay layers,
+//! minus anything that just echoes a default and minus anything sourced
+//! from the environment -- so a deployment's `MAYA_SETTING_*` overrides
+//! never get baked into the saved model file.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// An error building the effective settings map, i.e. reading or parsing
+/// [`SettingsBuilder::with_overlay_file`]'s TOML file.
+#[derive(Debug)]
+pub enum SettingsError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "failed to read settings overlay file: {e}"),
+            SettingsError::Toml(e) => write!(f, "failed to parse settings overlay file: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+impl From<io::Error> for SettingsError {
+    fn from(err: io::Error) -> Self {
+        SettingsError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SettingsError {
+    fn from(err: toml::de::Error) -> Self {
+        SettingsError::Toml(err)
+    }
+}
+
+/// Builds the effective `settings: HashMap<String, String>` from layered
+/// sources -- see the module doc comment for precedence order.
+///
+/// Each `with_*` method takes `self` by value so calls can be chained; the
+/// builder itself isn't consumed by [`build`](Self::build) or
+/// [`persistable`](Self::persistable), since callers typically need both.
+#[derive(Debug, Default, Clone)]
+pub struct SettingsBuilder {
+    defaults: HashMap<String, String>,
+    persisted: HashMap<String, String>,
+    overlay: HashMap<String, String>,
+    env: HashMap<String, String>,
+}
+
+impl SettingsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Built-in defaults, lowest precedence -- overridden by every other
+    /// layer.
+    pub fn with_defaults(mut self, defaults: HashMap<String, String>) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// The map most recently loaded by `load_state`, or an empty map for a
+    /// fresh model.
+    pub fn with_persisted(mut self, persisted: HashMap<String, String>) -> Self {
+        self.persisted = persisted;
+        self
+    }
+
+    /// Reads `path` as TOML and flattens it into dotted keys (a `[section]`
+    /// table with `foo = "bar"` becomes the key `section.foo`), overriding
+    /// `with_persisted`'s layer. Non-string leaf values (integers, floats,
+    /// booleans, ...) are rendered via their `Display` impl, since the
+    /// resolved map is always `HashMap<String, String>`.
+    pub fn with_overlay_file<P: AsRef<Path>>(mut self, path: P) -> Result<Self, SettingsError> {
+        let text = std::fs::read_to_string(path.as_ref())?;
+        let value: toml::Value = toml::from_str(&text)?;
+        self.overlay = flatten_toml(&value);
+        Ok(self)
+    }
+
+    /// Highest-precedence layer: every environment variable named
+    /// `{prefix}_KEY` becomes `key = value` (lowercased), with `__` in the
+    /// suffix splitting into a dotted nested key -- e.g. with
+    /// `prefix = "MAYA_SETTING"`, `MAYA_SETTING_FOO=bar` sets `foo` and
+    /// `MAYA_SETTING_SECTION__FOO=bar` sets `section.foo`, matching the
+    /// dotted keys [`with_overlay_file`](Self::with_overlay_file) produces
+    /// from nested TOML tables.
+    pub fn with_env(mut self, prefix: &str) -> Self {
+        let scan_prefix = format!("{prefix}_");
+        for (key, value) in std::env::vars() {
+            if let Some(rest) = key.strip_prefix(&scan_prefix) {
+                let normalized = rest.to_lowercase().replace("__", ".");
+                self.env.insert(normalized, value);
+            }
+        }
+        self
+    }
+
+    /// The fully resolved settings map: defaults, then persisted, then the
+    /// overlay file, then environment variables, each layer overwriting
+    /// keys the previous ones set.
+    pub fn build(&self) -> HashMap<String, String> {
+        let mut effective = self.defaults.clone();
+        effective.extend(self.persisted.clone());
+        effective.extend(self.overlay.clone());
+        effective.extend(self.env.clone());
+        effective
+    }
+
+    /// The subset of [`build`](Self::build)'s result that `save_state`
+    /// should actually write back to disk: explicit overrides from the
+    /// persisted map and the overlay file, excluding keys that merely
+    /// repeat a default (so upgrading a default later isn't pinned by an
+    /// old save) and excluding anything sourced from the environment (so a
+    /// deployment's `MAYA_SETTING_*` overrides never get baked into the
+    /// saved model file).
+    pub fn persistable(&self) -> HashMap<String, String> {
+        let mut result = self.persisted.clone();
+        result.extend(self.overlay.clone());
+        result.retain(|key, value| self.defaults.get(key) != Some(value));
+        result
+    }
+}
+
+fn flatten_toml(value: &toml::Value) -> HashMap<String, String> {
+    let mut out = HashMap::new();
+    flatten_toml_into(value, String::new(), &mut out);
+    out
+}
+
+fn flatten_toml_into(value: &toml::Value, prefix: String, out: &mut HashMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, v) in table {
+                let full_key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_toml_into(v, full_key, out);
+            }
+        }
+        toml::Value::String(s) => {
+            out.insert(prefix, s.clone());
+        }
+        other => {
+            out.insert(prefix, other.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_layers_override_in_precedence_order() {
+        let built = SettingsBuilder::new()
+            .with_defaults(map(&[("theme", "light"), ("greeting", "hi")]))
+            .with_persisted(map(&[("theme", "dark")]))
+            .build();
+
+        assert_eq!(built.get("theme"), Some(&"dark".to_string()));
+        assert_eq!(built.get("greeting"), Some(&"hi".to_string()));
+    }
+
+    #[test]
+    fn test_overlay_file_flattens_nested_tables() {
+        let temp_dir = tempdir().unwrap();
+        let overlay_path = temp_dir.path().join("overlay.toml");
+        std::fs::write(&overlay_path, "theme = \"dark\"\n\n[limits]\nmax_tokens = 256\n").unwrap();
+
+        let built = SettingsBuilder::new()
+            .with_defaults(map(&[("theme", "light")]))
+            .with_overlay_file(&overlay_path)
+            .expect("overlay file should parse")
+            .build();
+
+        assert_eq!(built.get("theme"), Some(&"dark".to_string()));
+        assert_eq!(built.get("limits.max_tokens"), Some(&"256".to_string()));
+    }
+
+    #[test]
+    fn test_env_layer_wins_over_overlay_and_splits_nested_keys() {
+        let temp_dir = tempdir().unwrap();
+        let overlay_path = temp_dir.path().join("overlay.toml");
+        std::fs::write(&overlay_path, "theme = \"dark\"\n").unwrap();
+
+        std::env::set_var("MAYA_SETTING_THEME", "solarized");
+        std::env::set_var("MAYA_SETTING_LIMITS__MAX_TOKENS", "512");
+
+        let built = SettingsBuilder::new()
+            .with_overlay_file(&overlay_path)
+            .expect("overlay file should parse")
+            .with_env("MAYA_SETTING")
+            .build();
+
+        std::env::remove_var("MAYA_SETTING_THEME");
+        std::env::remove_var("MAYA_SETTING_LIMITS__MAX_TOKENS");
+
+        assert_eq!(built.get("theme"), Some(&"solarized".to_string()));
+        assert_eq!(built.get("limits.max_tokens"), Some(&"512".to_string()));
+    }
+
+    #[test]
+    fn test_persistable_excludes_defaults_and_env() {
+        let temp_dir = tempdir().unwrap();
+        let overlay_path = temp_dir.path().join("overlay.toml");
+        std::fs::write(&overlay_path, "theme = \"dark\"\n").unwrap();
+
+        std::env::set_var("MAYA_SETTING_GREETING", "yo");
+
+        let builder = SettingsBuilder::new()
+            .with_defaults(map(&[("theme", "light"), ("greeting", "hi")]))
+            .with_persisted(map(&[("theme", "light"), ("volume", "11")]))
+            .with_overlay_file(&overlay_path)
+            .expect("overlay file should parse")
+            .with_env("MAYA_SETTING");
+
+        std::env::remove_var("MAYA_SETTING_GREETING");
+
+        let persistable = builder.persistable();
+
+        // `volume` is an explicit override with no matching default: kept.
+        assert_eq!(persistable.get("volume"), Some(&"11".to_string()));
+        // `theme` was overridden by the overlay file to a non-default value: kept.
+        assert_eq!(persistable.get("theme"), Some(&"dark".to_string()));
+        // `greeting` only ever came from env: excluded.
+        assert!(!persistable.contains_key("greeting"));
+    }
+}
@@ -0,0 +1,351 @@
+//! LMDB-backed incremental durability for `BasicLLM`'s state.
+//!
+//! [`crate::persistence::save_state`]/`load_state` snapshot the *entire*
+//! `BasicLLM` state (patterns, memory bank, context, settings) to
+//! `state.json` on every call, which gets expensive as the amount of state
+//! grows -- a single `remember()` or `learn()` shouldn't have to pay for
+//! re-serializing everything that came before it. `LlmStore` is a narrower,
+//! incrementally-writable complement: one LMDB environment (in the style of
+//! `maya_knowledge_graph::storage::LmdbStore`, which wraps the same
+//! `heed` crate) holding three named sub-databases, mirroring the
+//! rkv/heed `open_database(Some(name))` convention so each kind of state
+//! lives in its own namespace rather than sharing one keyspace:
+//!
+//! - `patterns`: learned `learn()` stimulus -> response pairs.
+//! - `memories`: `remember()`-stored [`Memory`] records, keyed by a
+//!   monotonically increasing id local to this store.
+//! - `context`: scalar context fields like `user_name`, keyed by field name.
+//!
+//! Splitting into named sub-databases means a caller that only needs one
+//! kind of state (e.g. pattern matching for a reply) only has to read the
+//! `patterns` database, not page in memories or context it doesn't need.
+//!
+//! This is durability plumbing, not a replacement for `MemoryBank`'s
+//! in-memory indexes: ranking and recall still go through `MemoryBank`,
+//! which needs every memory's tokens/embedding resident to score a query.
+//! `LlmStore` instead answers "what's been durably written since the last
+//! full `state.json` snapshot" -- `BasicLLM::load_state` uses `load_all_memories`
+//! to rebuild the bank when there's no snapshot yet, and
+//! `remember`/`learn`/`save_state` use the `append_memory`/`put_pattern`/
+//! `put_context_field`/`flush` methods to keep it current.
+//!
+//! The environment is opened with `NO_TLS` (read transactions aren't pinned
+//! to the thread that opened them), `WRITE_MAP`, and `NO_READAHEAD` (LMDB's
+//! recommended flags for an append-heavy workload with many short-lived
+//! readers), and `LlmStore` is cheap to [`Clone`] -- the `Env` handle and
+//! memory id counter are both `Arc`-backed, so a background writer and many
+//! concurrent readers can each hold their own handle onto the same
+//! environment, same as a typical `StateDB` setup. Every method here
+//! already takes `&self` and opens its own short-lived transaction, so
+//! `LlmStore` itself supports exactly the concurrent-read/single-writer
+//! pattern this module was asked for. `BasicLLM::generate_response` and
+//! `recall_memories` still require `&mut self`, though: they mutate
+//! `MemoryBank`'s indexes and `PatternMatcher`'s learned patterns in RAM
+//! (including `recall_memories`'s own reinforcement bump), and neither is
+//! safe to share across threads without its own interior-mutability
+//! redesign -- a much larger change than configuring this store's LMDB
+//! flags, and out of scope here. See `BasicLLM`'s doc comment for that
+//! explicit boundary.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use heed::byteorder::BigEndian;
+use heed::types::{Bytes, Str, U64};
+use heed::{Database, Env, EnvFlags, EnvOpenOptions};
+
+use crate::memory::{Memory, MemoryType};
+use crate::persistence::PersistenceError;
+
+/// Default size of the LMDB memory map, in bytes (1 GiB). LMDB reserves this
+/// much virtual address space up front; it only bounds the maximum database
+/// size, not actual disk or memory usage. Matches `LmdbStore`'s default in
+/// the knowledge-graph crate.
+const DEFAULT_MAP_SIZE: usize = 1024 * 1024 * 1024;
+
+fn store_err(e: impl std::fmt::Display) -> PersistenceError {
+    PersistenceError::StoreError(e.to_string())
+}
+
+/// An LMDB environment holding `BasicLLM`'s incrementally-writable state,
+/// split into the `patterns`, `memories`, and `context` sub-databases
+/// described at the module level. Cheaply [`Clone`]able so a reader thread
+/// and the writer thread can each hold their own handle onto the same
+/// environment.
+#[derive(Clone)]
+pub struct LlmStore {
+    env: Arc<Env>,
+    patterns: Database<Str, Str>,
+    memories: Database<U64<BigEndian>, Bytes>,
+    context: Database<Str, Str>,
+    next_memory_id: Arc<AtomicU64>,
+}
+
+impl LlmStore {
+    /// Open or create the store under `path` (typically
+    /// `data_dir/state.lmdb`), resuming the memories id counter just past
+    /// the highest key already written.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+        std::fs::create_dir_all(path.as_ref())?;
+
+        // Safety: this path is only ever opened as a single `Env` per
+        // process, so the map-size/no-subdir requirements LMDB places on
+        // `open` hold, same as `LmdbStore::open`. `NO_TLS` lets read
+        // transactions move freely between threads instead of being pinned
+        // to whichever thread opened them; `WRITE_MAP`/`NO_READAHEAD` suit
+        // the append-heavy, rarely-re-read access pattern `append_memory`/
+        // `put_pattern` produce.
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(DEFAULT_MAP_SIZE)
+                // Three named sub-databases plus the default one LMDB
+                // always reserves.
+                .max_dbs(4)
+                .flags(EnvFlags::NO_TLS | EnvFlags::WRITE_MAP | EnvFlags::NO_READAHEAD)
+                .open(path.as_ref())
+        }
+        .map_err(store_err)?;
+
+        let mut wtxn = env.write_txn().map_err(store_err)?;
+        let patterns: Database<Str, Str> = env
+            .create_database(&mut wtxn, Some("patterns"))
+            .map_err(store_err)?;
+        let memories: Database<U64<BigEndian>, Bytes> = env
+            .create_database(&mut wtxn, Some("memories"))
+            .map_err(store_err)?;
+        let context: Database<Str, Str> = env
+            .create_database(&mut wtxn, Some("context"))
+            .map_err(store_err)?;
+        wtxn.commit().map_err(store_err)?;
+
+        let rtxn = env.read_txn().map_err(store_err)?;
+        let next_memory_id = memories
+            .last(&rtxn)
+            .map_err(store_err)?
+            .map(|(id, _)| id + 1)
+            .unwrap_or(0);
+
+        Ok(Self {
+            env: Arc::new(env),
+            patterns,
+            memories,
+            context,
+            next_memory_id: Arc::new(AtomicU64::new(next_memory_id)),
+        })
+    }
+
+    /// Record a learned stimulus -> response pair in the `patterns`
+    /// sub-database, overwriting any existing response for the same
+    /// stimulus (matching `PatternMatcher::add_pattern`'s merge-on-repeat
+    /// behavior).
+    pub fn put_pattern(&self, stimulus: &str, response: &str) -> Result<(), PersistenceError> {
+        let mut wtxn = self.env.write_txn().map_err(store_err)?;
+        self.patterns
+            .put(&mut wtxn, stimulus, response)
+            .map_err(store_err)?;
+        wtxn.commit().map_err(store_err)?;
+        Ok(())
+    }
+
+    /// Read every stimulus/response pair out of the `patterns` sub-database
+    /// through a single read transaction, without touching `memories` or
+    /// `context`.
+    pub fn iter_patterns(&self) -> Result<Vec<(String, String)>, PersistenceError> {
+        let rtxn = self.env.read_txn().map_err(store_err)?;
+        let mut pairs = Vec::new();
+        for entry in self.patterns.iter(&rtxn).map_err(store_err)? {
+            let (stimulus, response) = entry.map_err(store_err)?;
+            pairs.push((stimulus.to_string(), response.to_string()));
+        }
+        Ok(pairs)
+    }
+
+    /// Append `memory` as a new record in the `memories` sub-database
+    /// inside its own write transaction, returning the key it was stored
+    /// under. Durable as soon as this returns -- no separate flush is
+    /// needed per record.
+    pub fn append_memory(&self, memory: &Memory) -> Result<u64, PersistenceError> {
+        let id = self.next_memory_id.fetch_add(1, Ordering::SeqCst);
+        let bytes = serde_json::to_vec(memory)?;
+
+        let mut wtxn = self.env.write_txn().map_err(store_err)?;
+        self.memories.put(&mut wtxn, &id, &bytes).map_err(store_err)?;
+        wtxn.commit().map_err(store_err)?;
+
+        Ok(id)
+    }
+
+    /// Read every record currently in the `memories` sub-database through a
+    /// single read transaction, in key order. Used to rebuild a
+    /// `MemoryBank` when no `state.json` snapshot exists yet, or when
+    /// recovering memories written after the last snapshot.
+    pub fn load_all_memories(&self) -> Result<Vec<Memory>, PersistenceError> {
+        let rtxn = self.env.read_txn().map_err(store_err)?;
+        let mut memories = Vec::new();
+        for entry in self.memories.iter(&rtxn).map_err(store_err)? {
+            let (_, bytes) = entry.map_err(store_err)?;
+            memories.push(serde_json::from_slice(bytes)?);
+        }
+        Ok(memories)
+    }
+
+    /// Walk the `memories` sub-database with a cursor, returning only
+    /// records of `memory_type`. Reads through the whole database (LMDB has
+    /// no secondary index to narrow by value), but still touches only the
+    /// `memories` sub-database, not `patterns` or `context`.
+    pub fn iter_memories_by_type(&self, memory_type: &MemoryType) -> Result<Vec<Memory>, PersistenceError> {
+        let rtxn = self.env.read_txn().map_err(store_err)?;
+        let mut memories = Vec::new();
+        for entry in self.memories.iter(&rtxn).map_err(store_err)? {
+            let (_, bytes) = entry.map_err(store_err)?;
+            let memory: Memory = serde_json::from_slice(bytes)?;
+            if &memory.memory_type == memory_type {
+                memories.push(memory);
+            }
+        }
+        Ok(memories)
+    }
+
+    /// Set a scalar context field (e.g. `user_name`) in the `context`
+    /// sub-database.
+    pub fn put_context_field(&self, key: &str, value: &str) -> Result<(), PersistenceError> {
+        let mut wtxn = self.env.write_txn().map_err(store_err)?;
+        self.context.put(&mut wtxn, key, value).map_err(store_err)?;
+        wtxn.commit().map_err(store_err)?;
+        Ok(())
+    }
+
+    /// Look up a scalar context field from the `context` sub-database.
+    pub fn get_context_field(&self, key: &str) -> Result<Option<String>, PersistenceError> {
+        let rtxn = self.env.read_txn().map_err(store_err)?;
+        Ok(self
+            .context
+            .get(&rtxn, key)
+            .map_err(store_err)?
+            .map(|value| value.to_string()))
+    }
+
+    /// Compatibility shim for callers that still call `save_state()`
+    /// expecting it to durably commit everything written so far. Every
+    /// `put_pattern`/`append_memory`/`put_context_field` call already
+    /// commits its own write transaction before returning, so there's
+    /// nothing pending here -- this exists purely so existing call sites
+    /// don't need to know the backend changed under them.
+    pub fn flush(&self) -> Result<(), PersistenceError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_append_and_load_all_memories() {
+        let dir = tempdir().unwrap();
+        let store = LlmStore::open(dir.path()).expect("open store");
+
+        let mut a = Memory::new("User's name is Alice", MemoryType::UserDetail);
+        a.importance = 0.9;
+        let mut b = Memory::new("User likes chocolate", MemoryType::Preference);
+        b.importance = 0.7;
+
+        let first_id = store.append_memory(&a).expect("append a");
+        let second_id = store.append_memory(&b).expect("append b");
+        assert_eq!(second_id, first_id + 1);
+
+        let loaded = store.load_all_memories().expect("load all");
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].content, "User's name is Alice");
+        assert_eq!(loaded[1].content, "User likes chocolate");
+    }
+
+    #[test]
+    fn test_reopen_resumes_memory_id_counter() {
+        let dir = tempdir().unwrap();
+        {
+            let store = LlmStore::open(dir.path()).expect("open store");
+            store
+                .append_memory(&Memory::new("first", MemoryType::Fact))
+                .expect("append");
+        }
+
+        let store = LlmStore::open(dir.path()).expect("reopen store");
+        let id = store
+            .append_memory(&Memory::new("second", MemoryType::Fact))
+            .expect("append after reopen");
+        assert_eq!(id, 1);
+
+        let loaded = store.load_all_memories().expect("load all");
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[test]
+    fn test_iter_memories_by_type_filters_other_types() {
+        let dir = tempdir().unwrap();
+        let store = LlmStore::open(dir.path()).expect("open store");
+
+        store
+            .append_memory(&Memory::new("User likes tea", MemoryType::Preference))
+            .expect("append");
+        store
+            .append_memory(&Memory::new("The sky is blue", MemoryType::Fact))
+            .expect("append");
+        store
+            .append_memory(&Memory::new("User likes coffee", MemoryType::Preference))
+            .expect("append");
+
+        let preferences = store
+            .iter_memories_by_type(&MemoryType::Preference)
+            .expect("iter by type");
+        assert_eq!(preferences.len(), 2);
+        assert!(preferences.iter().all(|m| m.memory_type == MemoryType::Preference));
+    }
+
+    #[test]
+    fn test_patterns_sub_database_is_independent_of_memories() {
+        let dir = tempdir().unwrap();
+        let store = LlmStore::open(dir.path()).expect("open store");
+
+        store.put_pattern("hello", "Hi there!").expect("put pattern");
+        store
+            .append_memory(&Memory::new("unrelated memory", MemoryType::Fact))
+            .expect("append memory");
+
+        let patterns = store.iter_patterns().expect("iter patterns");
+        assert_eq!(patterns, vec![("hello".to_string(), "Hi there!".to_string())]);
+    }
+
+    #[test]
+    fn test_context_field_roundtrip() {
+        let dir = tempdir().unwrap();
+        let store = LlmStore::open(dir.path()).expect("open store");
+
+        assert_eq!(store.get_context_field("user_name").unwrap(), None);
+
+        store.put_context_field("user_name", "Alice").expect("put context field");
+        assert_eq!(
+            store.get_context_field("user_name").unwrap(),
+            Some("Alice".to_string())
+        );
+
+        // Overwriting replaces the old value rather than erroring.
+        store.put_context_field("user_name", "Bob").expect("put context field");
+        assert_eq!(
+            store.get_context_field("user_name").unwrap(),
+            Some("Bob".to_string())
+        );
+    }
+
+    #[test]
+    fn test_flush_is_a_no_op_compatibility_shim() {
+        let dir = tempdir().unwrap();
+        let store = LlmStore::open(dir.path()).expect("open store");
+        store
+            .append_memory(&Memory::new("already durable", MemoryType::Fact))
+            .expect("append");
+        store.flush().expect("flush should always succeed");
+    }
+}
@@ -0,0 +1,397 @@
+//! Graph traversal and flow algorithms over [`KnowledgeGraph`].
+//!
+//! These build directly on the `node_edges:<id>:outgoing`/`incoming`
+//! adjacency lists `add_edge` already maintains, rather than introducing a
+//! separate adjacency structure.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use ordered_float::OrderedFloat;
+use uuid::Uuid;
+
+use crate::error::{KnowledgeGraphError, Result};
+use crate::graph::KnowledgeGraph;
+use crate::models::Edge;
+use crate::storage::{Storage, WriteBatchExt};
+
+/// A path through the graph: the nodes visited, in order, and the edge
+/// taken between each consecutive pair.
+#[derive(Debug, Clone)]
+pub struct Path {
+    /// Node IDs visited, starting with the source and ending with the target.
+    pub nodes: Vec<Uuid>,
+    /// The edge taken between each consecutive pair of `nodes`.
+    pub edges: Vec<Edge>,
+}
+
+/// Outcome of [`KnowledgeGraph::weighted_shortest_path`]: a [`Path`] plus
+/// the sum of its edges' weights.
+#[derive(Debug, Clone)]
+pub struct WeightedPath {
+    /// The path itself.
+    pub path: Path,
+    /// Sum of `weight` across every edge in `path`.
+    pub total_weight: f64,
+}
+
+/// An edge's `weight` property, defaulting to `1.0` when absent. Errors if
+/// the property is present but isn't a non-negative number -- Dijkstra's
+/// relaxation step isn't valid against negative edge weights.
+fn edge_weight(edge: &Edge) -> Result<f64> {
+    let weight = match edge.get_property("weight") {
+        Some(value) => value.as_f64().ok_or_else(|| {
+            KnowledgeGraphError::InvalidOperation(format!(
+                "edge {} has a non-numeric weight property",
+                edge.id
+            ))
+        })?,
+        None => 1.0,
+    };
+
+    if weight < 0.0 {
+        return Err(KnowledgeGraphError::InvalidOperation(format!(
+            "edge {} has a negative weight ({weight}), which Dijkstra's relaxation can't handle",
+            edge.id
+        )));
+    }
+
+    Ok(weight)
+}
+
+/// Outcome of [`KnowledgeGraph::max_flow`].
+#[derive(Debug, Clone)]
+pub struct MaxFlowResult {
+    /// The maximum flow value, i.e. the number of edge-disjoint paths
+    /// between the source and the sink.
+    pub flow_value: i64,
+    /// Nodes still reachable from the source in the final residual graph.
+    /// The forward edges crossing out of this set into the rest of the
+    /// graph are exactly the min cut.
+    pub reachable: HashSet<Uuid>,
+}
+
+impl<S> KnowledgeGraph<S>
+where
+    S: Storage + WriteBatchExt,
+{
+    /// Find a shortest path (by edge count) from `from` to `to` via BFS
+    /// over the outgoing-edge adjacency lists.
+    ///
+    /// `edge_label_filter`, if set, restricts traversal to edges whose
+    /// `label` matches it. Returns `Ok(None)` if no path exists.
+    pub fn shortest_path(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        edge_label_filter: Option<&str>,
+    ) -> Result<Option<Path>> {
+        if from == to {
+            return Ok(Some(Path {
+                nodes: vec![from],
+                edges: Vec::new(),
+            }));
+        }
+
+        let mut visited: HashSet<Uuid> = HashSet::new();
+        let mut predecessor: HashMap<Uuid, (Uuid, Edge)> = HashMap::new();
+        let mut queue: VecDeque<Uuid> = VecDeque::new();
+
+        visited.insert(from);
+        queue.push_back(from);
+
+        while let Some(current) = queue.pop_front() {
+            for edge in self.query_edges_from(current)? {
+                if let Some(label) = edge_label_filter {
+                    if edge.label != label {
+                        continue;
+                    }
+                }
+
+                let next = edge.target;
+                if !visited.insert(next) {
+                    continue;
+                }
+                predecessor.insert(next, (current, edge));
+
+                if next == to {
+                    return Ok(Some(reconstruct_path(from, to, &predecessor)));
+                }
+                queue.push_back(next);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Return every node reachable from `start` by following outgoing
+    /// edges, including `start` itself.
+    pub fn connected_component(&self, start: Uuid) -> Result<Vec<Uuid>> {
+        let mut visited: HashSet<Uuid> = HashSet::new();
+        let mut queue: VecDeque<Uuid> = VecDeque::new();
+
+        visited.insert(start);
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            for edge in self.query_edges_from(current)? {
+                if visited.insert(edge.target) {
+                    queue.push_back(edge.target);
+                }
+            }
+        }
+
+        Ok(visited.into_iter().collect())
+    }
+
+    /// Find the minimum-weight path from `from` to `to` via Dijkstra's
+    /// algorithm, using each edge's `weight` property as its cost
+    /// (see [`edge_weight`]).
+    ///
+    /// `undirected`, when set, also relaxes edges backwards
+    /// (target -> source) via [`KnowledgeGraph::query_edges_to`], treating
+    /// the adjacency as undirected rather than following only the
+    /// `source -> target` direction. Returns `Ok(None)` if no path exists.
+    pub fn weighted_shortest_path(
+        &self,
+        from: Uuid,
+        to: Uuid,
+        undirected: bool,
+    ) -> Result<Option<WeightedPath>> {
+        if from == to {
+            return Ok(Some(WeightedPath {
+                path: Path {
+                    nodes: vec![from],
+                    edges: Vec::new(),
+                },
+                total_weight: 0.0,
+            }));
+        }
+
+        let mut dist: HashMap<Uuid, f64> = HashMap::new();
+        let mut predecessor: HashMap<Uuid, (Uuid, Edge)> = HashMap::new();
+        let mut frontier: BinaryHeap<Reverse<(OrderedFloat<f64>, Uuid)>> = BinaryHeap::new();
+
+        dist.insert(from, 0.0);
+        frontier.push(Reverse((OrderedFloat(0.0), from)));
+
+        while let Some(Reverse((OrderedFloat(current_dist), node))) = frontier.pop() {
+            if current_dist > *dist.get(&node).unwrap_or(&f64::INFINITY) {
+                // A shorter route to `node` was already relaxed after this
+                // entry was pushed; this stale entry can be skipped.
+                continue;
+            }
+            if node == to {
+                break;
+            }
+
+            for edge in self.traversal_edges(node, undirected)? {
+                let weight = edge_weight(&edge)?;
+                let next = if edge.source == node { edge.target } else { edge.source };
+                let candidate = current_dist + weight;
+
+                if candidate < *dist.get(&next).unwrap_or(&f64::INFINITY) {
+                    dist.insert(next, candidate);
+                    predecessor.insert(next, (node, edge));
+                    frontier.push(Reverse((OrderedFloat(candidate), next)));
+                }
+            }
+        }
+
+        let Some(&total_weight) = dist.get(&to) else {
+            return Ok(None);
+        };
+
+        Ok(Some(WeightedPath {
+            path: reconstruct_path(from, to, &predecessor),
+            total_weight,
+        }))
+    }
+
+    /// Return every node reachable from `start` within `max_hops` edges via
+    /// unweighted BFS, including `start` itself (reachable in 0 hops).
+    ///
+    /// `undirected`, when set, walks both `source -> target` and
+    /// `target -> source` edges; otherwise only outgoing edges are followed.
+    pub fn k_hop(&self, start: Uuid, max_hops: usize, undirected: bool) -> Result<HashSet<Uuid>> {
+        let mut visited: HashSet<Uuid> = HashSet::new();
+        let mut frontier: VecDeque<(Uuid, usize)> = VecDeque::new();
+
+        visited.insert(start);
+        frontier.push_back((start, 0));
+
+        while let Some((current, hops)) = frontier.pop_front() {
+            if hops >= max_hops {
+                continue;
+            }
+
+            for edge in self.traversal_edges(current, undirected)? {
+                let next = if edge.source == current { edge.target } else { edge.source };
+                if visited.insert(next) {
+                    frontier.push_back((next, hops + 1));
+                }
+            }
+        }
+
+        Ok(visited)
+    }
+
+    /// Edges touching `node_id`: outgoing only, or both directions (via
+    /// [`query_edges_to`](Self::query_edges_to)) when `undirected` is set.
+    fn traversal_edges(&self, node_id: Uuid, undirected: bool) -> Result<Vec<Edge>> {
+        let mut edges = self.query_edges_from(node_id)?;
+        if undirected {
+            edges.extend(self.query_edges_to(node_id)?);
+        }
+        Ok(edges)
+    }
+
+    /// Compute the maximum number of edge-disjoint paths from `source` to
+    /// `sink` via Edmonds-Karp max-flow over a unit-capacity residual
+    /// graph built from the outgoing-edge adjacency lists.
+    ///
+    /// The returned [`MaxFlowResult::flow_value`] equals the number of
+    /// edge-disjoint paths, and the forward edges crossing out of
+    /// [`MaxFlowResult::reachable`] form a minimum edge cut between the
+    /// two nodes.
+    pub fn max_flow(&self, source: Uuid, sink: Uuid) -> Result<MaxFlowResult> {
+        let mut residual: HashMap<(Uuid, Uuid), i64> = HashMap::new();
+        let mut adjacency: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+
+        for node in self.get_nodes()? {
+            for edge in self.query_edges_from(node.id)? {
+                *residual.entry((edge.source, edge.target)).or_insert(0) += 1;
+                residual.entry((edge.target, edge.source)).or_insert(0);
+                adjacency.entry(edge.source).or_default().insert(edge.target);
+                adjacency.entry(edge.target).or_default().insert(edge.source);
+            }
+        }
+
+        let mut flow_value: i64 = 0;
+
+        while let Some(path) = bfs_augmenting_path(&residual, &adjacency, source, sink) {
+            let bottleneck = path
+                .windows(2)
+                .map(|pair| residual[&(pair[0], pair[1])])
+                .min()
+                .unwrap_or(0);
+
+            if bottleneck <= 0 {
+                break;
+            }
+
+            for pair in path.windows(2) {
+                *residual.get_mut(&(pair[0], pair[1])).unwrap() -= bottleneck;
+                *residual.get_mut(&(pair[1], pair[0])).unwrap() += bottleneck;
+            }
+
+            flow_value += bottleneck;
+        }
+
+        let reachable = bfs_reachable(&residual, &adjacency, source);
+
+        Ok(MaxFlowResult {
+            flow_value,
+            reachable,
+        })
+    }
+}
+
+fn reconstruct_path(from: Uuid, to: Uuid, predecessor: &HashMap<Uuid, (Uuid, Edge)>) -> Path {
+    let mut nodes = vec![to];
+    let mut edges = Vec::new();
+    let mut current = to;
+
+    while current != from {
+        let (prev, edge) = predecessor[&current].clone();
+        edges.push(edge);
+        nodes.push(prev);
+        current = prev;
+    }
+
+    nodes.reverse();
+    edges.reverse();
+    Path { nodes, edges }
+}
+
+/// BFS for an augmenting path from `source` to `sink` in the residual
+/// graph, following only edges with remaining positive capacity.
+fn bfs_augmenting_path(
+    residual: &HashMap<(Uuid, Uuid), i64>,
+    adjacency: &HashMap<Uuid, HashSet<Uuid>>,
+    source: Uuid,
+    sink: Uuid,
+) -> Option<Vec<Uuid>> {
+    let mut visited: HashSet<Uuid> = HashSet::new();
+    let mut predecessor: HashMap<Uuid, Uuid> = HashMap::new();
+    let mut queue: VecDeque<Uuid> = VecDeque::new();
+
+    visited.insert(source);
+    queue.push_back(source);
+
+    while let Some(current) = queue.pop_front() {
+        if current == sink {
+            return Some(reconstruct_node_path(sink, &predecessor));
+        }
+
+        let Some(neighbors) = adjacency.get(&current) else {
+            continue;
+        };
+
+        for &next in neighbors {
+            if visited.contains(&next) {
+                continue;
+            }
+            if *residual.get(&(current, next)).unwrap_or(&0) <= 0 {
+                continue;
+            }
+            visited.insert(next);
+            predecessor.insert(next, current);
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+fn reconstruct_node_path(sink: Uuid, predecessor: &HashMap<Uuid, Uuid>) -> Vec<Uuid> {
+    let mut path = vec![sink];
+    let mut current = sink;
+
+    while let Some(&prev) = predecessor.get(&current) {
+        path.push(prev);
+        current = prev;
+    }
+
+    path.reverse();
+    path
+}
+
+/// Every node still reachable from `source` via edges with remaining
+/// positive residual capacity, used to read off the min cut once the flow
+/// is saturated.
+fn bfs_reachable(
+    residual: &HashMap<(Uuid, Uuid), i64>,
+    adjacency: &HashMap<Uuid, HashSet<Uuid>>,
+    source: Uuid,
+) -> HashSet<Uuid> {
+    let mut visited: HashSet<Uuid> = HashSet::new();
+    let mut queue: VecDeque<Uuid> = VecDeque::new();
+
+    visited.insert(source);
+    queue.push_back(source);
+
+    while let Some(current) = queue.pop_front() {
+        let Some(neighbors) = adjacency.get(&current) else {
+            continue;
+        };
+
+        for &next in neighbors {
+            if *residual.get(&(current, next)).unwrap_or(&0) > 0 && visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    visited
+}
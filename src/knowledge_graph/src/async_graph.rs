@@ -0,0 +1,253 @@
+//! An async-native mirror of [`KnowledgeGraph`] for backends that implement
+//! [`GraphBackend`] instead of [`Storage`](crate::storage::Storage) — e.g. a
+//! remote HTTP or S3-style store where blocking on every call would tie up
+//! a thread per in-flight request instead of yielding it back to the
+//! runtime. This sits alongside the existing synchronous `KnowledgeGraph`
+//! rather than replacing it: every `Storage` backend written against it
+//! (Sled, RocksDB, LMDB, the Raft/hybrid/encrypted wrappers, ...) keeps
+//! working unchanged.
+//!
+//! [`SledGraphBackend`] adapts the existing blocking `KnowledgeGraph<SledStore>`
+//! to [`GraphBackend`] via `spawn_blocking`, so the default backend doesn't
+//! need a from-scratch async Sled client.
+//!
+//! [`AsyncQueryBuilder::stream`] returns results as a [`Stream`] instead of
+//! a materialized `Vec`, so a traversal over a graph too large to fit in
+//! memory can be consumed incrementally.
+
+use std::sync::Arc;
+
+use futures::stream::{self, Stream};
+use uuid::Uuid;
+
+use crate::error::{KnowledgeGraphError, Result};
+use crate::graph::KnowledgeGraph;
+use crate::models::{Edge, Node};
+use crate::storage::{GraphBackend, SledStore};
+
+/// A knowledge graph generic over the async [`GraphBackend`] trait instead
+/// of the synchronous [`Storage`](crate::storage::Storage) trait
+/// [`KnowledgeGraph`] uses.
+pub struct AsyncKnowledgeGraph<B: GraphBackend> {
+    backend: B,
+}
+
+impl<B: GraphBackend> AsyncKnowledgeGraph<B> {
+    /// Wrap an existing backend.
+    pub fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    /// Add a node to the graph.
+    pub async fn add_node(&self, node: Node) -> Result<()> {
+        self.backend.put_node(node).await
+    }
+
+    /// Look up a node by ID.
+    pub async fn get_node(&self, id: Uuid) -> Result<Option<Node>> {
+        self.backend.get_node(id).await
+    }
+
+    /// Remove a node by ID.
+    pub async fn remove_node(&self, id: Uuid) -> Result<()> {
+        self.backend.delete_node(id).await
+    }
+
+    /// Add an edge to the graph.
+    pub async fn add_edge(&self, edge: Edge) -> Result<()> {
+        self.backend.put_edge(edge).await
+    }
+
+    /// Every edge whose source is `node_id`.
+    pub async fn query_edges_from(&self, node_id: Uuid) -> Result<Vec<Edge>> {
+        self.backend.get_edges_from(node_id).await
+    }
+
+    /// Start building a streamed query against this graph.
+    pub fn query(&self) -> AsyncQueryBuilder<'_, B> {
+        AsyncQueryBuilder::new(&self.backend)
+    }
+}
+
+/// Builds a streamed traversal over an [`AsyncKnowledgeGraph`], mirroring
+/// [`QueryBuilder`](crate::query::QueryBuilder) but yielding matches one at
+/// a time through [`stream`](Self::stream) instead of collecting them all
+/// into a [`QueryResult`](crate::query::QueryResult) up front.
+pub struct AsyncQueryBuilder<'a, B: GraphBackend> {
+    backend: &'a B,
+    label: Option<String>,
+    limit: Option<usize>,
+}
+
+impl<'a, B: GraphBackend> AsyncQueryBuilder<'a, B> {
+    fn new(backend: &'a B) -> Self {
+        Self {
+            backend,
+            label: None,
+            limit: None,
+        }
+    }
+
+    /// Restrict results to nodes with this label.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Stop after this many matches.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Stream matching nodes one at a time instead of materializing the
+    /// whole result set, fetching each node from the backend lazily as the
+    /// caller polls for the next item.
+    pub fn stream(self) -> impl Stream<Item = Result<Node>> + 'a {
+        struct State<'a, B: GraphBackend> {
+            backend: &'a B,
+            label: Option<String>,
+            limit: Option<usize>,
+            ids: Option<Vec<Uuid>>,
+            next: usize,
+            yielded: usize,
+            done: bool,
+        }
+
+        let state = State {
+            backend: self.backend,
+            label: self.label,
+            limit: self.limit,
+            ids: None,
+            next: 0,
+            yielded: 0,
+            done: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if state.done {
+                    return None;
+                }
+                if let Some(limit) = state.limit {
+                    if state.yielded >= limit {
+                        return None;
+                    }
+                }
+
+                if state.ids.is_none() {
+                    let Some(label) = state.label.clone() else {
+                        state.done = true;
+                        return Some((
+                            Err(KnowledgeGraphError::QueryError(
+                                "AsyncQueryBuilder::stream currently requires with_label()"
+                                    .to_string(),
+                            )),
+                            state,
+                        ));
+                    };
+                    match state.backend.find_node_ids_by_label(&label).await {
+                        Ok(ids) => state.ids = Some(ids),
+                        Err(e) => {
+                            state.done = true;
+                            return Some((Err(e), state));
+                        }
+                    }
+                }
+
+                let ids = state.ids.as_ref().unwrap();
+                if state.next >= ids.len() {
+                    return None;
+                }
+                let id = ids[state.next];
+                state.next += 1;
+
+                match state.backend.get_node(id).await {
+                    Ok(Some(node)) => {
+                        state.yielded += 1;
+                        return Some((Ok(node), state));
+                    }
+                    // The node was removed between listing its ID and
+                    // fetching it; skip it rather than surfacing an error.
+                    Ok(None) => continue,
+                    Err(e) => {
+                        state.done = true;
+                        return Some((Err(e), state));
+                    }
+                }
+            }
+        })
+    }
+}
+
+fn blocking_panic(e: tokio::task::JoinError) -> KnowledgeGraphError {
+    KnowledgeGraphError::Other(format!("blocking task panicked: {e}"))
+}
+
+/// Default [`GraphBackend`] implementation, adapting the existing blocking
+/// [`KnowledgeGraph<SledStore>`] to the async trait via `spawn_blocking`
+/// instead of reimplementing Sled access from scratch. Every call pays one
+/// thread-pool hop, so this is meant as the easy default rather than the
+/// fast path — a backend talking to an already-async store (HTTP, S3-style
+/// object storage) should implement [`GraphBackend`] directly.
+pub struct SledGraphBackend {
+    graph: Arc<KnowledgeGraph<SledStore>>,
+}
+
+impl SledGraphBackend {
+    /// Wrap an existing Sled-backed graph.
+    pub fn new(graph: KnowledgeGraph<SledStore>) -> Self {
+        Self {
+            graph: Arc::new(graph),
+        }
+    }
+}
+
+impl GraphBackend for SledGraphBackend {
+    async fn get_node(&self, id: Uuid) -> Result<Option<Node>> {
+        let graph = self.graph.clone();
+        tokio::task::spawn_blocking(move || graph.get_node(id))
+            .await
+            .map_err(blocking_panic)?
+    }
+
+    async fn put_node(&self, node: Node) -> Result<()> {
+        let graph = self.graph.clone();
+        tokio::task::spawn_blocking(move || graph.add_node(node))
+            .await
+            .map_err(blocking_panic)?
+    }
+
+    async fn delete_node(&self, id: Uuid) -> Result<()> {
+        let graph = self.graph.clone();
+        tokio::task::spawn_blocking(move || graph.remove_node(id))
+            .await
+            .map_err(blocking_panic)?
+    }
+
+    async fn put_edge(&self, edge: Edge) -> Result<()> {
+        let graph = self.graph.clone();
+        tokio::task::spawn_blocking(move || graph.add_edge(&edge))
+            .await
+            .map_err(blocking_panic)?
+    }
+
+    async fn get_edges_from(&self, node_id: Uuid) -> Result<Vec<Edge>> {
+        let graph = self.graph.clone();
+        tokio::task::spawn_blocking(move || graph.query_edges_from(node_id))
+            .await
+            .map_err(blocking_panic)?
+    }
+
+    async fn find_node_ids_by_label(&self, label: &str) -> Result<Vec<Uuid>> {
+        let graph = self.graph.clone();
+        let label = label.to_string();
+        tokio::task::spawn_blocking(move || {
+            graph
+                .find_nodes_by_label(&label)
+                .map(|nodes| nodes.into_iter().map(|n| n.id).collect())
+        })
+        .await
+        .map_err(blocking_panic)?
+    }
+}
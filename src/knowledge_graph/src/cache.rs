@@ -17,32 +17,99 @@ GLIMMER Pattern:
 
 //! Caching layer for the knowledge graph
 
-use std::sync::{Arc, RwLock, RwLockWriteGuard, RwLockReadGuard};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use lru::LruCache;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 use std::fmt;
 use std::fmt::Debug;
 use std::cmp::Eq;
 use anyhow::{Result, anyhow};
+use log::debug;
+
+use crate::models::DynamicUsage;
+
+/// Configuration for [`LruCacheWrapper`], covering eviction beyond plain
+/// LRU-by-count for long-running graph servers: an optional per-entry TTL
+/// and an optional overall byte budget, on top of the usual capacity.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    /// Maximum number of entries, the same count-based bound
+    /// [`LruCacheWrapper::new`] always used.
+    pub capacity: usize,
+    /// When set, an entry older than this is treated as absent on its next
+    /// [`get`](LruCacheWrapper::get) or [`sweep`](LruCacheWrapper::sweep),
+    /// regardless of how recently it was otherwise used.
+    pub ttl: Option<Duration>,
+    /// When set, [`put`](LruCacheWrapper::put)/[`sweep`](LruCacheWrapper::sweep)
+    /// evict least-recently-used entries (on top of whatever the capacity
+    /// bound already evicts) until the cache's estimated byte usage is back
+    /// under this budget.
+    pub byte_budget: Option<usize>,
+}
+
+impl CacheConfig {
+    /// The count-based, no-TTL, no-byte-budget configuration
+    /// [`LruCacheWrapper::new`] uses by default.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ttl: None,
+            byte_budget: None,
+        }
+    }
+}
+
+/// Hit/miss/eviction/expiry counters for a [`LruCacheWrapper`], surfaced via
+/// [`LruCacheWrapper::stats`] so operators can size the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    /// Reads served from a resident, non-expired entry.
+    pub hits: u64,
+    /// Reads that found no entry, or found one whose TTL had expired.
+    pub misses: u64,
+    /// Entries removed to stay within capacity or the byte budget.
+    pub evictions: u64,
+    /// Entries removed because their TTL had elapsed.
+    pub expirations: u64,
+    /// Entries currently resident.
+    pub entries: usize,
+    /// Sum of every resident entry's size estimate.
+    pub estimated_bytes: usize,
+}
+
+struct Entry<V> {
+    value: Arc<V>,
+    inserted_at: Instant,
+    size_bytes: usize,
+}
 
 /// A thread-safe LRU cache wrapper
-pub struct LruCacheWrapper<K, V> 
+pub struct LruCacheWrapper<K, V>
 where
     K: Hash + Eq + Clone + Send + Sync + 'static,
     V: Clone + Send + Sync + 'static,
 {
-    cache: RwLock<LruCache<K, Arc<V>>>,
+    cache: RwLock<LruCache<K, Entry<V>>>,
+    config: CacheConfig,
+    total_bytes: AtomicUsize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    expirations: AtomicU64,
 }
 
-impl<K, V> Debug for LruCacheWrapper<K, V> 
+impl<K, V> Debug for LruCacheWrapper<K, V>
 where
     K: Hash + Eq + Clone + Debug + Send + Sync + 'static,
     V: Clone + Debug + Send + Sync + 'static,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LruCacheWrapper")
-         .field("cache", &self.cache)
+         .field("config", &self.config)
+         .field("stats", &self.stats())
          .finish()
     }
 }
@@ -52,51 +119,198 @@ where
     K: Eq + Hash + Clone + Send + Sync + 'static,
     V: Clone + Send + Sync + 'static,
 {
-    /// Create a new LRU cache with the given capacity
+    /// Create a new LRU cache with the given capacity, no TTL, and no byte
+    /// budget -- the zero-config, count-only default this type always had.
     pub fn new(capacity: usize) -> Self {
-        let cache = LruCache::new(
-            NonZeroUsize::new(capacity).unwrap_or_else(|| NonZeroUsize::new(1000).unwrap())
-        );
-        
+        Self::with_config(CacheConfig::with_capacity(capacity))
+    }
+
+    /// Create a cache with a richer [`CacheConfig`] (TTL and/or byte
+    /// budget on top of the capacity bound).
+    pub fn with_config(config: CacheConfig) -> Self {
+        let capacity = NonZeroUsize::new(config.capacity)
+            .unwrap_or_else(|| NonZeroUsize::new(1000).unwrap());
         Self {
-            cache: RwLock::new(cache),
+            cache: RwLock::new(LruCache::new(capacity)),
+            config,
+            total_bytes: AtomicUsize::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+            expirations: AtomicU64::new(0),
         }
     }
-    
+
     /// Get a write lock on the cache
-    fn write(&self) -> Result<RwLockWriteGuard<'_, LruCache<K, Arc<V>>>> {
+    fn write(&self) -> Result<RwLockWriteGuard<'_, LruCache<K, Entry<V>>>> {
         self.cache.write().map_err(|_| anyhow!("Failed to acquire write lock on cache"))
     }
-    
-    /// Get a read lock on the cache
-    fn read(&self) -> Result<RwLockReadGuard<'_, LruCache<K, Arc<V>>>, anyhow::Error> {
-        self.cache.read().map_err(|_| anyhow!("Failed to acquire read lock on cache"))
+
+    fn is_expired(&self, entry: &Entry<V>) -> bool {
+        self.config.ttl.is_some_and(|ttl| entry.inserted_at.elapsed() >= ttl)
     }
 
-    /// Get a value from the cache
+    /// Get a value from the cache. If a TTL is configured and the entry has
+    /// expired, it's evicted here and treated as a miss rather than
+    /// returned stale.
     pub fn get(&self, key: &K) -> Result<Option<Arc<V>>> {
-        let cache = self.read()?;
-        Ok(cache.peek(key).map(Arc::clone))
+        let mut cache = self.write()?;
+
+        if let Some(entry) = cache.peek(key) {
+            if self.is_expired(entry) {
+                if let Some(expired) = cache.pop(key) {
+                    self.total_bytes.fetch_sub(expired.size_bytes, Ordering::Relaxed);
+                }
+                self.expirations.fetch_add(1, Ordering::Relaxed);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return Ok(None);
+            }
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Ok(None);
+        }
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Ok(cache.get(key).map(|entry| Arc::clone(&entry.value)))
     }
 
-    /// Insert a value into the cache
+    /// Insert a value into the cache, with no size estimate (counts as 0
+    /// bytes against [`CacheConfig::byte_budget`]). This is what `put` has
+    /// always done; use [`put_sized`](Self::put_sized) or
+    /// [`put_estimated`](Self::put_estimated) to participate in a byte
+    /// budget.
     pub fn put(&self, key: K, value: V) -> Result<()> {
-        let mut cache = self.write()?;
-        cache.put(key, Arc::new(value));
+        self.put_sized(key, value, 0)
+    }
+
+    /// Insert a value under `key` with an explicit size-in-bytes estimate,
+    /// evicting least-recently-used entries afterward if that pushes the
+    /// cache over [`CacheConfig::byte_budget`].
+    pub fn put_sized(&self, key: K, value: V, size_bytes: usize) -> Result<()> {
+        let entry = Entry {
+            value: Arc::new(value),
+            inserted_at: Instant::now(),
+            size_bytes,
+        };
+
+        {
+            let mut cache = self.write()?;
+
+            let replacing = cache.contains(&key);
+            self.total_bytes.fetch_add(size_bytes, Ordering::Relaxed);
+
+            // `push` returns the old entry when `key` was already present,
+            // and otherwise the entry evicted to make room; only the latter
+            // is counted as an eviction.
+            if let Some((_, old)) = cache.push(key, entry) {
+                self.total_bytes.fetch_sub(old.size_bytes, Ordering::Relaxed);
+                if !replacing {
+                    self.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+
+        self.enforce_byte_budget()?;
         Ok(())
     }
 
+    /// Evict least-recently-used entries until the cache is back under
+    /// `config.byte_budget`, if one is set. Returns how many were removed.
+    fn enforce_byte_budget(&self) -> Result<usize> {
+        let Some(budget) = self.config.byte_budget else {
+            return Ok(0);
+        };
+
+        let mut removed = 0usize;
+        let mut cache = self.write()?;
+        while self.total_bytes.load(Ordering::Relaxed) > budget {
+            let Some((_, evicted)) = cache.pop_lru() else {
+                break;
+            };
+            self.total_bytes.fetch_sub(evicted.size_bytes, Ordering::Relaxed);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
     /// Remove a value from the cache
     pub fn remove(&self, key: &K) -> Result<Option<Arc<V>>> {
-        Ok(self.write()?.pop(key))
+        let mut cache = self.write()?;
+        Ok(cache.pop(key).map(|entry| {
+            self.total_bytes.fetch_sub(entry.size_bytes, Ordering::Relaxed);
+            entry.value
+        }))
     }
 
     /// Clear the cache
     pub fn clear(&self) -> Result<()> {
         let mut cache = self.write()?;
         cache.clear();
+        self.total_bytes.store(0, Ordering::Relaxed);
         Ok(())
     }
+
+    /// Snapshot of this cache's hit/miss/eviction/expiry counters, current
+    /// resident entry count, and estimated byte usage.
+    pub fn stats(&self) -> CacheMetrics {
+        let entries = self.cache.read().map(|cache| cache.len()).unwrap_or(0);
+        CacheMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+            entries,
+            estimated_bytes: self.total_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reclaim every expired entry and, if a byte budget is set, every
+    /// over-budget entry, right now rather than waiting for the next
+    /// `get`/`put` to stumble onto them. Meant to be called on an interval
+    /// by a long-running server; logs the resulting [`stats`](Self::stats)
+    /// at debug level so operators can watch cache pressure over time.
+    pub fn sweep(&self) -> Result<usize> {
+        let mut removed = 0usize;
+
+        if self.config.ttl.is_some() {
+            let mut cache = self.write()?;
+            let expired_keys: Vec<K> = cache
+                .iter()
+                .filter(|(_, entry)| self.is_expired(entry))
+                .map(|(key, _)| key.clone())
+                .collect();
+            drop(cache);
+
+            cache = self.write()?;
+            for key in expired_keys {
+                if let Some(entry) = cache.pop(&key) {
+                    self.total_bytes.fetch_sub(entry.size_bytes, Ordering::Relaxed);
+                    self.expirations.fetch_add(1, Ordering::Relaxed);
+                    removed += 1;
+                }
+            }
+        }
+
+        removed += self.enforce_byte_budget()?;
+
+        debug!("cache sweep removed {removed} entries; stats = {:?}", self.stats());
+        Ok(removed)
+    }
+}
+
+impl<K, V> LruCacheWrapper<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + DynamicUsage + 'static,
+{
+    /// Like [`put_sized`](Self::put_sized), estimating the size from `V`'s
+    /// own [`DynamicUsage`] impl instead of requiring the caller to compute
+    /// one.
+    pub fn put_estimated(&self, key: K, value: V) -> Result<()> {
+        let size_bytes = value.dynamic_usage();
+        self.put_sized(key, value, size_bytes)
+    }
 }
 
 #[cfg(test)]
@@ -106,28 +320,112 @@ mod tests {
     #[test]
     fn test_lru_cache_basic() -> Result<()> {
         let cache = LruCacheWrapper::new(2);
-        
+
         // Test insert and get
         cache.put("key1", "value1")?;
         assert_eq!(cache.get(&"key1")?.as_deref(), Some(&"value1"));
-        
+
         // Test eviction
         cache.put("key2", "value2")?;
         cache.put("key3", "value3")?;
-        
+
         // key1 should be evicted
         assert!(cache.get(&"key1")?.is_none());
         assert_eq!(cache.get(&"key2")?.as_deref(), Some(&"value2"));
         assert_eq!(cache.get(&"key3")?.as_deref(), Some(&"value3"));
-        
+
         // Test remove
         cache.remove(&"key2")?;
         assert!(cache.get(&"key2")?.is_none());
-        
+
         // Test clear
         cache.clear()?;
         assert!(cache.get(&"key3")?.is_none());
-        
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_track_hits_misses_and_capacity_evictions() -> Result<()> {
+        let cache = LruCacheWrapper::new(1);
+
+        cache.get(&"missing")?;
+        cache.put("key1", "value1")?;
+        cache.get(&"key1")?;
+        cache.put("key2", "value2")?; // evicts key1, cache holds 1 entry
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.entries, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ttl_expires_entries_on_read() -> Result<()> {
+        let cache = LruCacheWrapper::with_config(CacheConfig {
+            capacity: 10,
+            ttl: Some(Duration::from_millis(1)),
+            byte_budget: None,
+        });
+
+        cache.put("key1", "value1")?;
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert!(cache.get(&"key1")?.is_none());
+        assert_eq!(cache.stats().expirations, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sweep_reclaims_expired_entries_without_a_read() -> Result<()> {
+        let cache = LruCacheWrapper::with_config(CacheConfig {
+            capacity: 10,
+            ttl: Some(Duration::from_millis(1)),
+            byte_budget: None,
+        });
+
+        cache.put("key1", "value1")?;
+        cache.put("key2", "value2")?;
+        std::thread::sleep(Duration::from_millis(20));
+
+        let removed = cache.sweep()?;
+        assert_eq!(removed, 2);
+        assert_eq!(cache.stats().entries, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_budget_evicts_least_recently_used_first() -> Result<()> {
+        let cache = LruCacheWrapper::with_config(CacheConfig {
+            capacity: 100,
+            ttl: None,
+            byte_budget: Some(150),
+        });
+
+        cache.put_sized("key1", "value1", 100)?;
+        cache.put_sized("key2", "value2", 100)?; // total would be 200 > 150, evicts key1
+
+        assert!(cache.get(&"key1")?.is_none());
+        assert_eq!(cache.get(&"key2")?.as_deref(), Some(&"value2"));
+        assert!(cache.stats().estimated_bytes <= 150);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_put_estimated_uses_dynamic_usage() -> Result<()> {
+        let cache: LruCacheWrapper<&str, serde_json::Value> = LruCacheWrapper::new(10);
+        let value = serde_json::Value::String("hello".to_string());
+        let expected_bytes = value.dynamic_usage();
+
+        cache.put_estimated("key1", value)?;
+        assert_eq!(cache.stats().estimated_bytes, expected_bytes);
+
         Ok(())
     }
 }
@@ -44,7 +44,29 @@ pub enum KnowledgeGraphError {
     
     /// Sled database error
     SledError(sled::Error),
-    
+
+    /// LMDB database error
+    LmdbError(String),
+
+    /// redb database error
+    RedbError(String),
+
+    /// RocksDB database error
+    RocksDbError(String),
+
+    /// A stored value's checksum didn't match its recomputed digest,
+    /// indicating the underlying bytes were corrupted on disk
+    ChecksumMismatch(String),
+
+    /// An encrypted value failed to decrypt, either because it was
+    /// tampered with or because the wrong master key was used
+    DecryptionFailed(String),
+
+    /// A Raft consensus error: a failed log append, a snapshot
+    /// install/build failure, or a network RPC to another cluster member
+    /// that couldn't be completed
+    RaftError(String),
+
     /// Other error
     Other(String),
 }
@@ -64,6 +86,12 @@ impl fmt::Display for KnowledgeGraphError {
             Self::QueryError(msg) => write!(f, "Query error: {}", msg),
             Self::TransactionError(msg) => write!(f, "Transaction error: {}", msg),
             Self::SledError(e) => write!(f, "Sled error: {}", e),
+            Self::LmdbError(msg) => write!(f, "LMDB error: {}", msg),
+            Self::RedbError(msg) => write!(f, "redb error: {}", msg),
+            Self::RocksDbError(msg) => write!(f, "RocksDB error: {}", msg),
+            Self::ChecksumMismatch(msg) => write!(f, "Checksum mismatch: {}", msg),
+            Self::DecryptionFailed(msg) => write!(f, "Decryption failed: {}", msg),
+            Self::RaftError(msg) => write!(f, "Raft error: {}", msg),
             Self::Other(msg) => write!(f, "Error: {}", msg),
         }
     }
@@ -105,6 +133,48 @@ impl From<sled::transaction::TransactionError> for KnowledgeGraphError {
     }
 }
 
+impl From<heed::Error> for KnowledgeGraphError {
+    fn from(err: heed::Error) -> Self {
+        Self::LmdbError(err.to_string())
+    }
+}
+
+impl From<redb::DatabaseError> for KnowledgeGraphError {
+    fn from(err: redb::DatabaseError) -> Self {
+        Self::RedbError(err.to_string())
+    }
+}
+
+impl From<redb::TransactionError> for KnowledgeGraphError {
+    fn from(err: redb::TransactionError) -> Self {
+        Self::RedbError(err.to_string())
+    }
+}
+
+impl From<redb::TableError> for KnowledgeGraphError {
+    fn from(err: redb::TableError) -> Self {
+        Self::RedbError(err.to_string())
+    }
+}
+
+impl From<redb::StorageError> for KnowledgeGraphError {
+    fn from(err: redb::StorageError) -> Self {
+        Self::RedbError(err.to_string())
+    }
+}
+
+impl From<redb::CommitError> for KnowledgeGraphError {
+    fn from(err: redb::CommitError) -> Self {
+        Self::RedbError(err.to_string())
+    }
+}
+
+impl From<rocksdb::Error> for KnowledgeGraphError {
+    fn from(err: rocksdb::Error) -> Self {
+        Self::RocksDbError(err.to_string())
+    }
+}
+
 impl From<String> for KnowledgeGraphError {
     fn from(err: String) -> Self {
         Self::Other(err)
@@ -48,16 +48,28 @@
 //! # Ok(())
 //! # }
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 use log::info;
 
 use crate::{
     error::{Result, KnowledgeGraphError},
-    models::{Node, Edge},
-    storage::{Storage, WriteBatch, WriteBatchExt},
+    materialize::{Materializer, NodeOp},
+    merkle::{current_edge_hash, current_node_hash, MerkleDiff, MerkleIndex, RecordId, RootHash},
+    models::{Node, Edge, PropertyValue},
+    query::QueryResult,
+    query_cache::{CacheStats, CachePolicy, Dep, QueryCache},
+    reachability::ReachabilityIndex,
+    storage::{self, prefix_successor, GenericWriteBatch, IterDirection, SledStore, Storage, WriteBatch, WriteBatchExt},
 };
 
+/// Name of the Sled tree the query cache persists its entries to under
+/// [`CachePolicy::WriteThrough`]/[`CachePolicy::WriteBack`].
+const QUERY_CACHE_TREE: &str = "query_cache";
+
 /// A high-performance, thread-safe knowledge graph implementation.
 ///
 /// The `KnowledgeGraph` provides methods for creating, reading, updating, and deleting
@@ -99,45 +111,204 @@ use crate::{
 /// - `DuplicateNode`: Attempted to add a node with an existing ID
 /// - `StorageError`: An error occurred in the underlying storage
 /// - `SerializationError`: Failed to serialize or deserialize data
-#[derive(Debug)]
-pub struct KnowledgeGraph<S: Storage> {
+pub struct KnowledgeGraph<S: Storage + WriteBatchExt> {
     storage: S,
+    query_cache: QueryCache,
+    node_triggers: RwLock<HashMap<String, NodeTriggers<S>>>,
+    edge_triggers: RwLock<HashMap<String, EdgeTriggers<S>>>,
+    materializer: Materializer,
+    reachability_index: RwLock<Option<Arc<ReachabilityIndex>>>,
+    reachability_dirty: AtomicBool,
+    merkle_index: RwLock<Option<MerkleIndex>>,
+}
+
+impl<S: Storage + WriteBatchExt + std::fmt::Debug> std::fmt::Debug for KnowledgeGraph<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KnowledgeGraph")
+            .field("storage", &self.storage)
+            .field("query_cache", &self.query_cache)
+            .finish_non_exhaustive()
+    }
 }
 
-impl<S> KnowledgeGraph<S> 
+impl<S> KnowledgeGraph<S>
 where
     S: Storage + WriteBatchExt,
-    for<'a> <S as Storage>::Batch<'a>: WriteBatch + 'static,
-    for<'a> <S as WriteBatchExt>::BatchType<'a>: WriteBatch + 'static,
-    for<'a> &'a S: 'a,
 {
     /// Create a new knowledge graph with a custom storage backend
     pub fn new(storage: S) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            query_cache: QueryCache::new(),
+            node_triggers: RwLock::new(HashMap::new()),
+            edge_triggers: RwLock::new(HashMap::new()),
+            materializer: Materializer::new(),
+            reachability_index: RwLock::new(None),
+            reachability_dirty: AtomicBool::new(true),
+            merkle_index: RwLock::new(None),
+        }
+    }
+
+    /// Register the `on_put`/`on_rm`/`on_replace` callbacks fired whenever a
+    /// node with this `label` is inserted, removed, or overwritten in place,
+    /// mirroring Cozo's `SetTriggers(relation, on_put, on_rm, on_replace)`.
+    ///
+    /// Replaces any triggers previously registered for `label`; pass
+    /// [`NodeTriggers::default`] to clear them.
+    pub fn set_node_triggers(&self, label: &str, triggers: NodeTriggers<S>) {
+        self.node_triggers
+            .write()
+            .unwrap()
+            .insert(label.to_string(), triggers);
+    }
+
+    /// Register the `on_put`/`on_rm`/`on_replace` callbacks fired whenever an
+    /// edge with this type `label` is inserted, removed, or overwritten in
+    /// place, mirroring Cozo's `SetTriggers(relation, on_put, on_rm, on_replace)`.
+    ///
+    /// Replaces any triggers previously registered for `label`; pass
+    /// [`EdgeTriggers::default`] to clear them.
+    pub fn set_edge_triggers(&self, label: &str, triggers: EdgeTriggers<S>) {
+        self.edge_triggers
+            .write()
+            .unwrap()
+            .insert(label.to_string(), triggers);
+    }
+
+    /// Run every callback registered for `change`'s label/event kind against
+    /// `tx`, aborting on the first error so the caller's batch is never
+    /// committed.
+    fn fire_node_triggers(
+        &self,
+        change: &NodeChange,
+        kind: TriggerKind,
+        tx: &mut Transaction<'_, S>,
+    ) -> Result<()> {
+        let registered = self.node_triggers.read().unwrap();
+        let Some(triggers) = registered.get(change.label()) else {
+            return Ok(());
+        };
+        for trigger in kind.select(&triggers.on_put, &triggers.on_rm, &triggers.on_replace) {
+            trigger(change, tx)?;
+        }
+        Ok(())
+    }
+
+    /// Run every callback registered for `change`'s type/event kind against
+    /// `tx`, aborting on the first error so the caller's batch is never
+    /// committed.
+    fn fire_edge_triggers(
+        &self,
+        change: &EdgeChange,
+        kind: TriggerKind,
+        tx: &mut Transaction<'_, S>,
+    ) -> Result<()> {
+        let registered = self.edge_triggers.read().unwrap();
+        let Some(triggers) = registered.get(change.label()) else {
+            return Ok(());
+        };
+        for trigger in kind.select(&triggers.on_put, &triggers.on_rm, &triggers.on_replace) {
+            trigger(change, tx)?;
+        }
+        Ok(())
     }
 
     /// Add a node to the graph
     ///
-    /// Also updates the label index for fast label-based queries.
+    /// Updates the label index and every registered property index
+    /// ([`create_index`](Self::create_index)) in the same batch as the
+    /// node write, so a crash mid-write can never leave an index pointing
+    /// at a node that doesn't exist (or vice versa).
     pub fn add_node(&self, node: Node) -> Result<()> {
         let key = node_key(node.id);
-        
+
         // Check if node already exists
         if self.storage.exists(&key)? {
             return Err(KnowledgeGraphError::DuplicateNode(node.id.to_string()));
         }
-        
-        // Add node to storage using batch for atomicity
-        let batch = <S as Storage>::batch(&self.storage);
-        let value = serde_json::to_vec(&node)
-            .map_err(KnowledgeGraphError::SerializationError)?;
-        
-        let mut batch = batch;
-        batch.put_serialized(&key, &value)?;
-        Box::new(batch).commit()?;
-        
-        // Update label index
-        add_node_to_label_index(&self.storage, &node.label, node.id)?;
+
+        let registry = load_index_registry(&self.storage)?;
+
+        let mut tx = Transaction::new(&self.storage);
+        tx.batch.put(&key, &node)?;
+        stage_node_indexes(&self.storage, &mut tx.batch, &registry, &node, true)?;
+        self.fire_node_triggers(&NodeChange::Put(node.clone()), TriggerKind::Put, &mut tx)?;
+        tx.commit()?;
+
+        self.query_cache.dirty_node_write(&registry, &node);
+        self.reachability_dirty.store(true, Ordering::Relaxed);
+        self.merkle_upsert(RecordId::Node(node.id), current_node_hash(&node)?);
+        Ok(())
+    }
+
+    /// Remove a node, every index entry referencing it, and every edge
+    /// incident to it (as either source or target) from the graph, firing
+    /// `on_rm` triggers for the node and for each cascade-deleted edge.
+    pub fn remove_node(&self, id: Uuid) -> Result<()> {
+        let key = node_key(id);
+        let node: Node = self
+            .storage
+            .get(&key)?
+            .ok_or_else(|| KnowledgeGraphError::NodeNotFound(id.to_string()))?;
+
+        let registry = load_index_registry(&self.storage)?;
+        let incident_edges = collect_incident_edges(&self.storage, id)?;
+
+        let mut tx = Transaction::new(&self.storage);
+        tx.batch.delete(&key)?;
+        stage_node_indexes(&self.storage, &mut tx.batch, &registry, &node, false)?;
+        for edge in &incident_edges {
+            stage_edge_removal(&self.storage, &mut tx.batch, edge)?;
+        }
+
+        self.fire_node_triggers(&NodeChange::Removed(node.clone()), TriggerKind::Rm, &mut tx)?;
+        for edge in &incident_edges {
+            self.fire_edge_triggers(&EdgeChange::Removed(edge.clone()), TriggerKind::Rm, &mut tx)?;
+        }
+        tx.commit()?;
+
+        self.query_cache.dirty_node_write(&registry, &node);
+        for edge in &incident_edges {
+            self.query_cache.dirty_outgoing_edges(edge.source);
+        }
+        self.reachability_dirty.store(true, Ordering::Relaxed);
+        self.merkle_remove(RecordId::Node(id));
+        for edge in &incident_edges {
+            self.merkle_remove(RecordId::Edge(edge.id));
+        }
+        Ok(())
+    }
+
+    /// Overwrite an existing node's properties in place, firing `on_replace`
+    /// triggers registered for its label via
+    /// [`set_node_triggers`](Self::set_node_triggers).
+    ///
+    /// Errors with `NodeNotFound` if no node with this id exists yet; use
+    /// [`add_node`](Self::add_node) to insert a new one.
+    pub fn replace_node(&self, node: Node) -> Result<()> {
+        let key = node_key(node.id);
+        let before: Node = self
+            .storage
+            .get(&key)?
+            .ok_or_else(|| KnowledgeGraphError::NodeNotFound(node.id.to_string()))?;
+
+        let registry = load_index_registry(&self.storage)?;
+
+        let mut tx = Transaction::new(&self.storage);
+        stage_node_indexes(&self.storage, &mut tx.batch, &registry, &before, false)?;
+        tx.batch.put(&key, &node)?;
+        stage_node_indexes(&self.storage, &mut tx.batch, &registry, &node, true)?;
+
+        self.fire_node_triggers(
+            &NodeChange::Replaced { before: before.clone(), after: node.clone() },
+            TriggerKind::Replace,
+            &mut tx,
+        )?;
+        tx.commit()?;
+
+        self.query_cache.dirty_node_write(&registry, &before);
+        self.query_cache.dirty_node_write(&registry, &node);
+        self.merkle_upsert(RecordId::Node(node.id), current_node_hash(&node)?);
         Ok(())
     }
 
@@ -146,20 +317,16 @@ where
         let key = node_key(id);
         self.storage.get(&key)
     }
-    
+
     /// Get all nodes in the graph
     pub fn get_nodes(&self) -> Result<Vec<Node>> {
         let prefix = b"node:";
         let mut nodes = Vec::new();
-        
-        for result in self.storage.iter_prefix(prefix) {
-            let value = result.1; // Extract the owned Vec<u8>
-            match serde_json::from_slice::<Node>(&value) {
-                Ok(node) => nodes.push(node),
-                Err(e) => return Err(KnowledgeGraphError::SerializationError(e)),
-            }
+
+        for (_, value) in self.storage.iter_prefix(prefix) {
+            nodes.push(storage::deserialize(&value)?);
         }
-        
+
         Ok(nodes)
     }
 
@@ -168,44 +335,39 @@ where
         // Verify source and target nodes exist
         let source_key = node_key(edge.source);
         let target_key = node_key(edge.target);
-        
+
         if !self.storage.exists(&source_key)? {
             return Err(KnowledgeGraphError::NodeNotFound(edge.source.to_string()));
         }
-        
+
         if !self.storage.exists(&target_key)? {
             return Err(KnowledgeGraphError::NodeNotFound(edge.target.to_string()));
         }
-        
-        // Add edge to storage using batch for atomicity
-        let batch = <S as Storage>::batch(&self.storage);
+
+        // Add edge to storage using a transaction for atomicity
         let key = edge_key(edge.id);
-        let value = serde_json::to_vec(edge)
-            .map_err(KnowledgeGraphError::SerializationError)?;
-            
-        let mut batch = batch;
-        batch.put_serialized(&key, &value)?;
-        
+        let mut tx = Transaction::new(&self.storage);
+        tx.batch.put(&key, edge)?;
+
         // Add edge to source node's outgoing edges
-        let source_edges_key = format!("node_edges:{}:outgoing", edge.source).into_bytes();
+        let source_edges_key = outgoing_edges_key(edge.source);
         let mut source_edges: Vec<Uuid> = self.storage.get(&source_edges_key)?.unwrap_or_default();
         source_edges.push(edge.id);
-        let source_edges_value = serde_json::to_vec(&source_edges)
-            .map_err(KnowledgeGraphError::SerializationError)?;
-            
-        batch.put_serialized(&source_edges_key, &source_edges_value)?;
-        
+        tx.batch.put(&source_edges_key, &source_edges)?;
+
         // Add edge to target node's incoming edges
-        let target_edges_key = format!("node_edges:{}:incoming", edge.target).into_bytes();
+        let target_edges_key = incoming_edges_key(edge.target);
         let mut target_edges: Vec<Uuid> = self.storage.get(&target_edges_key)?.unwrap_or_default();
         target_edges.push(edge.id);
-        let target_edges_value = serde_json::to_vec(&target_edges)
-            .map_err(KnowledgeGraphError::SerializationError)?;
-            
-        batch.put_serialized(&target_edges_key, &target_edges_value)?;
-        
-        // Commit the batch
-        Box::new(batch).commit()
+        tx.batch.put(&target_edges_key, &target_edges)?;
+
+        self.fire_edge_triggers(&EdgeChange::Put(edge.clone()), TriggerKind::Put, &mut tx)?;
+        tx.commit()?;
+
+        self.query_cache.dirty_outgoing_edges(edge.source);
+        self.reachability_dirty.store(true, Ordering::Relaxed);
+        self.merkle_upsert(RecordId::Edge(edge.id), current_edge_hash(edge)?);
+        Ok(())
     }
 
     /// Get an edge by ID
@@ -214,36 +376,242 @@ where
         self.storage.get(&key)
     }
 
+    /// Remove an edge and prune it from its endpoints' adjacency lists,
+    /// firing `on_rm` triggers registered for its type via
+    /// [`set_edge_triggers`](Self::set_edge_triggers).
+    pub fn remove_edge(&self, id: Uuid) -> Result<()> {
+        let key = edge_key(id);
+        let edge: Edge = self
+            .storage
+            .get(&key)?
+            .ok_or_else(|| KnowledgeGraphError::EdgeNotFound(id.to_string()))?;
+
+        let mut tx = Transaction::new(&self.storage);
+        stage_edge_removal(&self.storage, &mut tx.batch, &edge)?;
+        self.fire_edge_triggers(&EdgeChange::Removed(edge.clone()), TriggerKind::Rm, &mut tx)?;
+        tx.commit()?;
+
+        self.query_cache.dirty_outgoing_edges(edge.source);
+        self.reachability_dirty.store(true, Ordering::Relaxed);
+        self.merkle_remove(RecordId::Edge(id));
+        Ok(())
+    }
+
+    /// Overwrite an existing edge's properties in place, firing `on_replace`
+    /// triggers registered for its type via
+    /// [`set_edge_triggers`](Self::set_edge_triggers).
+    ///
+    /// `edge.source`/`edge.target` are assumed unchanged — this does not
+    /// touch adjacency lists. To move an edge between nodes, remove and
+    /// re-add it instead.
+    ///
+    /// Errors with `EdgeNotFound` if no edge with this id exists yet; use
+    /// [`add_edge`](Self::add_edge) to insert a new one.
+    pub fn replace_edge(&self, edge: &Edge) -> Result<()> {
+        let key = edge_key(edge.id);
+        let before: Edge = self
+            .storage
+            .get(&key)?
+            .ok_or_else(|| KnowledgeGraphError::EdgeNotFound(edge.id.to_string()))?;
+
+        let mut tx = Transaction::new(&self.storage);
+        tx.batch.put(&key, edge)?;
+
+        self.fire_edge_triggers(
+            &EdgeChange::Replaced { before, after: edge.clone() },
+            TriggerKind::Replace,
+            &mut tx,
+        )?;
+        tx.commit()?;
+
+        self.query_cache.dirty_outgoing_edges(edge.source);
+        self.merkle_upsert(RecordId::Edge(edge.id), current_edge_hash(edge)?);
+        Ok(())
+    }
+
     /// Find nodes by label and properties
+    ///
+    /// Results are served from the incremental query cache when nothing
+    /// relevant has changed since the last call.
     pub fn find_nodes_by_label(&self, label: &str) -> Result<Vec<Node>> {
-        let mut nodes = Vec::new();
-        
-        for node in self.get_nodes()? {
-            if node.label == label {
-                nodes.push(node);
+        self.query_cache.find_nodes_by_label(label, || {
+            let mut nodes = Vec::new();
+            for node in self.get_nodes()? {
+                if node.label == label {
+                    nodes.push(node);
+                }
             }
-        }
-        
-        Ok(nodes)
+            Ok(nodes)
+        })
     }
-    
+
     /// Find all edges originating from a specific node
+    ///
+    /// Results are served from the incremental query cache when nothing
+    /// relevant has changed since the last call.
     pub fn query_edges_from(&self, node_id: Uuid) -> Result<Vec<Edge>> {
-        let prefix = b"edge:";
-        let mut edges = Vec::new();
-        
-        for result in self.storage.iter_prefix(prefix) {
-            let value = result.1; // Extract the owned Vec<u8>
-            if let Ok(edge) = serde_json::from_slice::<Edge>(&value) {
+        self.query_cache.query_edges_from(node_id, || {
+            let prefix = b"edge:";
+            let mut edges = Vec::new();
+            for (_, value) in self.storage.iter_prefix(prefix) {
+                let edge: Edge = storage::deserialize(&value)?;
                 if edge.source == node_id {
                     edges.push(edge);
                 }
             }
+            Ok(edges)
+        })
+    }
+
+    /// Find all edges that end at a specific node (the target of the edge).
+    ///
+    /// Unlike [`query_edges_from`](Self::query_edges_from), this isn't
+    /// served from the incremental query cache -- nothing else in this
+    /// crate needs a target-indexed lookup often enough yet to justify a
+    /// second cache dependency kind.
+    pub fn query_edges_to(&self, node_id: Uuid) -> Result<Vec<Edge>> {
+        let prefix = b"edge:";
+        let mut edges = Vec::new();
+        for (_, value) in self.storage.iter_prefix(prefix) {
+            let edge: Edge = storage::deserialize(&value)?;
+            if edge.target == node_id {
+                edges.push(edge);
+            }
         }
-        
         Ok(edges)
     }
 
+    /// Serve a whole [`QueryBuilder::execute`](crate::query::QueryBuilder::execute)
+    /// traversal from the incremental query cache when nothing its
+    /// `base_deps` cover has changed since the last call.
+    pub(crate) fn execute_cached_query(
+        &self,
+        hash: u64,
+        base_deps: Vec<Dep>,
+        compute: impl FnOnce() -> Result<QueryResult>,
+    ) -> Result<QueryResult> {
+        self.query_cache.execute_query(hash, base_deps, compute)
+    }
+
+    /// Create a secondary index over every node with `label` that carries
+    /// `property`, backfilling it from nodes already in the graph.
+    ///
+    /// A no-op if the index already exists. Once created, `add_node` and
+    /// `remove_node` keep it up to date automatically; query it with
+    /// [`find_nodes_by_property`](Self::find_nodes_by_property) instead of
+    /// scanning every node.
+    pub fn create_index(&self, label: &str, property: &str) -> Result<()> {
+        let mut registry = load_index_registry(&self.storage)?;
+        if registry.iter().any(|(l, p)| l == label && p == property) {
+            return Ok(());
+        }
+
+        for node in self.get_nodes()? {
+            if node.label != label {
+                continue;
+            }
+            if let Some(value) = node.get_property(property) {
+                let key = prop_index_key(label, property, value);
+                let mut ids: Vec<Uuid> = self.storage.get(&key)?.unwrap_or_default();
+                if !ids.contains(&node.id) {
+                    ids.push(node.id);
+                    self.storage.put(&key, &ids)?;
+                }
+
+                if let Some(range_key) = range_index_key(label, property, value) {
+                    let mut range_ids: Vec<Uuid> = self.storage.get(&range_key)?.unwrap_or_default();
+                    if !range_ids.contains(&node.id) {
+                        range_ids.push(node.id);
+                        self.storage.put(&range_key, &range_ids)?;
+                    }
+                }
+            }
+        }
+
+        registry.push((label.to_string(), property.to_string()));
+        self.storage.put(&index_registry_key(), &registry)?;
+
+        self.query_cache.dirty_property_index(label, property);
+        Ok(())
+    }
+
+    /// Whether a secondary index over `label`'s `property` was created via
+    /// [`create_index`](Self::create_index) and not since dropped. Used by
+    /// the query planner to decide whether
+    /// [`find_nodes_by_property`](Self::find_nodes_by_property) can answer a
+    /// property-equality filter directly instead of falling back to a full
+    /// scan.
+    pub fn has_index(&self, label: &str, property: &str) -> Result<bool> {
+        let registry = load_index_registry(&self.storage)?;
+        Ok(registry.iter().any(|(l, p)| l == label && p == property))
+    }
+
+    /// Drop a secondary index created by
+    /// [`create_index`](Self::create_index), deleting every entry it owns.
+    ///
+    /// A no-op if no such index exists.
+    pub fn drop_index(&self, label: &str, property: &str) -> Result<()> {
+        let mut registry = load_index_registry(&self.storage)?;
+        let before = registry.len();
+        registry.retain(|(l, p)| !(l == label && p == property));
+        if registry.len() == before {
+            return Ok(());
+        }
+
+        let prefix = format!("prop_index:{}:{}:", label, property).into_bytes();
+        let keys: Vec<Vec<u8>> = self
+            .storage
+            .iter_prefix(&prefix)
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            self.storage.delete(&key)?;
+        }
+
+        let range_prefix = range_index_prefix(label, property);
+        let range_keys: Vec<Vec<u8>> = self
+            .storage
+            .iter_prefix(&range_prefix)
+            .map(|(key, _)| key)
+            .collect();
+        for key in range_keys {
+            self.storage.delete(&key)?;
+        }
+
+        self.storage.put(&index_registry_key(), &registry)?;
+
+        self.query_cache.dirty_property_index(label, property);
+        Ok(())
+    }
+
+    /// Look up every node with `label` whose `property` equals `value`
+    /// using the index built by [`create_index`](Self::create_index)
+    /// instead of a full scan.
+    ///
+    /// Returns an empty vector if no such index exists or no node
+    /// currently matches. Results are served from the incremental query
+    /// cache when nothing relevant has changed since the last call.
+    pub fn find_nodes_by_property(
+        &self,
+        label: &str,
+        property: &str,
+        value: &PropertyValue,
+    ) -> Result<Vec<Node>> {
+        self.query_cache
+            .find_nodes_by_property(label, property, value, || {
+                let key = prop_index_key(label, property, value);
+                let ids: Vec<Uuid> = self.storage.get(&key)?.unwrap_or_default();
+
+                let mut nodes = Vec::with_capacity(ids.len());
+                for id in ids {
+                    if let Some(node) = self.get_node(id)? {
+                        nodes.push(node);
+                    }
+                }
+                Ok(nodes)
+            })
+    }
+
     /// Create a new transaction
     pub fn transaction<F, T>(&self, f: F) -> Result<T>
     where
@@ -254,45 +622,279 @@ where
         tx.commit()?;
         Ok(result)
     }
+
+    /// Append an operation to `node_id`'s materialized-view log, to be
+    /// folded in the next time [`materialize`](Self::materialize) is
+    /// called for it or one of its dependents.
+    ///
+    /// This is independent of [`add_node`](Self::add_node) and
+    /// [`replace_node`](Self::replace_node) — it feeds the separate
+    /// [`Materializer`](crate::materialize::Materializer) subsystem for
+    /// callers ingesting a stream of edits that should converge to a
+    /// consistent view rather than being applied to the graph directly.
+    pub fn record_node_op(&self, node_id: Uuid, op: NodeOp) {
+        self.materializer.record_op(node_id, op);
+    }
+
+    /// Pin `dependent`'s materialized view to `target`'s: whenever
+    /// `target` re-materializes, `dependent` is re-materialized too.
+    pub fn pin_relation(&self, dependent: Uuid, target: Uuid) {
+        self.materializer.pin_relation(dependent, target);
+    }
+
+    /// The current materialized view of `node_id`, folding in any
+    /// operations recorded since the last call.
+    ///
+    /// Returns `None` if `node_id` has no recorded operations, was
+    /// deleted, or is still waiting on a pinned relation to materialize
+    /// (see [`pending_views`](Self::pending_views)).
+    pub fn materialize(&self, node_id: Uuid) -> Result<Option<Node>> {
+        Ok(self.materializer.materialize(node_id))
+    }
+
+    /// IDs of every node whose materialized view is parked waiting on a
+    /// pinned relation's target, for diagnostics.
+    pub fn pending_views(&self) -> Vec<Uuid> {
+        self.materializer.pending_views()
+    }
+
+    /// A bitmap transitive-closure index over the whole graph, answering
+    /// `can_reach`/`reachable_set` queries in `O(1)`/`O(words_per_row)`
+    /// instead of walking storage.
+    ///
+    /// Rebuilt lazily: `add_node`/`remove_node`/`add_edge`/`remove_edge`
+    /// only mark the cached index stale, so this pays for one closure pass
+    /// (`O(n^2 * words_per_row)`) the first time it's called after the
+    /// graph's topology changes, and every call after that until the next
+    /// topology-changing write is effectively free.
+    pub fn reachability_index(&self) -> Result<Arc<ReachabilityIndex>> {
+        if !self.reachability_dirty.swap(false, Ordering::Relaxed) {
+            if let Some(index) = self.reachability_index.read().unwrap().clone() {
+                return Ok(index);
+            }
+        }
+
+        let index = Arc::new(ReachabilityIndex::build(self)?);
+        *self.reachability_index.write().unwrap() = Some(Arc::clone(&index));
+        Ok(index)
+    }
+
+    /// Patch the live Merkle index in place, if it's already been built.
+    /// Left `None` until the first [`snapshot`](Self::snapshot) call, which
+    /// builds it from scratch once; every write after that is a cheap
+    /// incremental patch instead of a rebuild, so there's no dirty flag to
+    /// maintain here the way [`reachability_index`](Self::reachability_index)
+    /// needs one.
+    fn merkle_upsert(&self, id: RecordId, hash: [u8; 32]) {
+        if let Some(index) = self.merkle_index.write().unwrap().as_mut() {
+            index.upsert(id, hash);
+        }
+    }
+
+    /// Patch the live Merkle index in place, if it's already been built.
+    /// See [`merkle_upsert`](Self::merkle_upsert).
+    fn merkle_remove(&self, id: RecordId) {
+        if let Some(index) = self.merkle_index.write().unwrap().as_mut() {
+            index.remove(id);
+        }
+    }
+
+    /// A content-addressed root hash over every node and edge currently in
+    /// the graph: a BLAKE3 leaf per record, combined pairwise up a Merkle
+    /// tree (see [`merkle`](crate::merkle)). Builds the index from scratch
+    /// the first time this is called since the graph was opened (mutating
+    /// methods don't build it); after that, every `add_node`/`remove_node`/
+    /// `replace_node`/`add_edge`/`remove_edge`/`replace_edge` call (and
+    /// every committed [`BatchTransaction`]) keeps it incrementally up to
+    /// date, so repeated `snapshot` calls are just a root lookup.
+    ///
+    /// The returned root (and the record set behind it) is remembered, so
+    /// a later [`diff`](Self::diff) or [`verify`](Self::verify) can refer
+    /// back to it even after the graph has moved on.
+    pub fn snapshot(&self) -> Result<RootHash> {
+        let mut guard = self.merkle_index.write().unwrap();
+        if guard.is_none() {
+            *guard = Some(MerkleIndex::build(&self.get_nodes()?, &self.get_all_edges()?)?);
+        }
+        Ok(guard.as_mut().unwrap().snapshot())
+    }
+
+    /// What changed between two roots previously returned by
+    /// [`snapshot`](Self::snapshot): which records were added, removed, or
+    /// overwritten. Errors if either root was never produced by `snapshot`
+    /// on this graph.
+    pub fn diff(&self, old_root: RootHash, new_root: RootHash) -> Result<MerkleDiff> {
+        let guard = self.merkle_index.read().unwrap();
+        let index = guard.as_ref().ok_or_else(|| {
+            KnowledgeGraphError::InvalidOperation("no snapshot has been taken yet".to_string())
+        })?;
+        index.diff(old_root, new_root)
+    }
+
+    /// Re-read every record behind a previously produced `root` straight
+    /// from storage, rehash it, and confirm the recomputed tree still
+    /// produces `root` -- i.e. that nothing behind that snapshot has been
+    /// altered since. Returns `Ok(false)` (rather than an error) if a
+    /// record the snapshot covered has since been deleted, since that's
+    /// itself a verification failure, not a usage error.
+    pub fn verify(&self, root: RootHash) -> Result<bool> {
+        let leaves = {
+            let guard = self.merkle_index.read().unwrap();
+            let index = guard.as_ref().ok_or_else(|| {
+                KnowledgeGraphError::InvalidOperation("no snapshot has been taken yet".to_string())
+            })?;
+            index.leaves_at(root)?
+        };
+
+        let mut live_leaves = HashMap::with_capacity(leaves.len());
+        for id in leaves.keys() {
+            let hash = match id {
+                RecordId::Node(node_id) => match self.get_node(*node_id)? {
+                    Some(node) => current_node_hash(&node)?,
+                    None => return Ok(false),
+                },
+                RecordId::Edge(edge_id) => match self.get_edge(*edge_id)? {
+                    Some(edge) => current_edge_hash(&edge)?,
+                    None => return Ok(false),
+                },
+            };
+            live_leaves.insert(*id, hash);
+        }
+
+        if live_leaves != leaves {
+            return Ok(false);
+        }
+
+        let guard = self.merkle_index.read().unwrap();
+        Ok(guard.as_ref().unwrap().recomputed_root(&live_leaves) == root)
+    }
+
+    /// Every edge currently in the graph, for building the Merkle index
+    /// from scratch. Unlike [`query_edges_from`](Self::query_edges_from),
+    /// this isn't scoped to one node and isn't served from the query cache.
+    fn get_all_edges(&self) -> Result<Vec<Edge>> {
+        let prefix = b"edge:";
+        let mut edges = Vec::new();
+        for (_, value) in self.storage.iter_prefix(prefix) {
+            edges.push(storage::deserialize(&value)?);
+        }
+        Ok(edges)
+    }
+}
+
+impl KnowledgeGraph<SledStore> {
+    /// Open a knowledge graph backed by a Sled database at `path`, with its
+    /// incremental query cache persisted according to `policy`.
+    ///
+    /// Under [`CachePolicy::InMemory`] this behaves exactly like
+    /// [`new`](Self::new)`(SledStore::open(path)?)` — the cache starts
+    /// cold every run. Under `WriteThrough`/`WriteBack`, entries are
+    /// reloaded and revalidated from a dedicated Sled tree in the same
+    /// database, so a long-lived assistant session can reuse expensive
+    /// traversal results computed in an earlier run instead of recomputing
+    /// them from a cold cache.
+    pub fn with_cache_policy(path: impl AsRef<Path>, policy: CachePolicy) -> Result<Self> {
+        let storage = SledStore::open(path)?;
+        let query_cache = match policy {
+            CachePolicy::InMemory => QueryCache::new(),
+            CachePolicy::WriteThrough | CachePolicy::WriteBack => {
+                let tree = storage.inner().open_tree(QUERY_CACHE_TREE)?;
+                QueryCache::open(tree, policy)?
+            }
+        };
+
+        Ok(Self {
+            storage,
+            query_cache,
+            node_triggers: RwLock::new(HashMap::new()),
+            edge_triggers: RwLock::new(HashMap::new()),
+            materializer: Materializer::new(),
+            reachability_index: RwLock::new(None),
+            reachability_dirty: AtomicBool::new(true),
+            merkle_index: RwLock::new(None),
+        })
+    }
+
+    /// Persist every in-memory query cache entry to disk immediately.
+    ///
+    /// Only meaningful under [`CachePolicy::WriteBack`], where entries
+    /// otherwise only reach disk here instead of on every write; a no-op
+    /// under `InMemory`/`WriteThrough`.
+    pub fn flush_cache(&self) -> Result<()> {
+        self.query_cache.flush()
+    }
+
+    /// Drop every cached query result, in memory and (if persisted) on
+    /// disk.
+    pub fn clear_cache(&self) -> Result<()> {
+        self.query_cache.clear()
+    }
+
+    /// Snapshot of the query cache's hit/miss/invalidation counters, for
+    /// observability into how effectively repeated queries (e.g.
+    /// `query_edges_from` called in a loop) are being served from cache
+    /// instead of recomputed.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.query_cache.stats()
+    }
 }
 
 /// A transaction for atomic operations
-pub struct Transaction<'a, S> 
+///
+/// This is also the handle a [`NodeTrigger`]/[`EdgeTrigger`] callback
+/// receives: staging a write through it commits atomically with the
+/// mutation that fired the trigger, and returning `Err` from a trigger
+/// aborts that commit.
+pub struct Transaction<'a, S>
 where
     S: Storage + WriteBatchExt,
-    for<'b> <S as Storage>::Batch<'b>: WriteBatch + 'static,
-    for<'b> <S as WriteBatchExt>::BatchType<'b>: WriteBatch + 'static,
 {
+    storage: &'a S,
     batch: <S as Storage>::Batch<'a>,
-    _marker: std::marker::PhantomData<&'a S>,
 }
 
-impl<'a, S> Transaction<'a, S> 
+impl<'a, S> Transaction<'a, S>
 where
     S: Storage + WriteBatchExt,
-    for<'b> <S as Storage>::Batch<'b>: WriteBatch + 'static,
-    for<'b> <S as WriteBatchExt>::BatchType<'b>: WriteBatch + 'static,
 {
     fn new(storage: &'a S) -> Self {
-        let batch = <S as Storage>::batch(storage);
         Self {
-            batch,
-            _marker: std::marker::PhantomData,
+            storage,
+            batch: storage.create_batch(),
         }
     }
 
+    /// The storage backend this transaction writes to, for reads that
+    /// inform further writes (e.g. a trigger checking a related node
+    /// exists before staging a derived edge).
+    pub fn storage(&self) -> &'a S {
+        self.storage
+    }
+
     /// Add a node within the transaction
     pub fn add_node(&mut self, node: &Node) -> Result<()> {
-        let node_key = node_key(node.id);
-        let value = serde_json::to_vec(node)?;
-        self.batch.put_serialized(&node_key, &value)
+        let key = node_key(node.id);
+        self.batch.put(&key, node)
+    }
+
+    /// Remove a node within the transaction. Unlike
+    /// [`KnowledgeGraph::remove_node`], this touches neither indexes nor
+    /// incident edges.
+    pub fn remove_node(&mut self, id: Uuid) -> Result<()> {
+        self.batch.delete(&node_key(id))
     }
 
     /// Add an edge within the transaction
     pub fn add_edge(&mut self, edge: &Edge) -> Result<()> {
-        let edge_key = edge_key(edge.id);
-        let value = serde_json::to_vec(edge)?;
-        self.batch.put_serialized(&edge_key, &value)
+        let key = edge_key(edge.id);
+        self.batch.put(&key, edge)
+    }
+
+    /// Remove an edge within the transaction. Unlike
+    /// [`KnowledgeGraph::remove_edge`], this does not prune the endpoints'
+    /// adjacency lists.
+    pub fn remove_edge(&mut self, id: Uuid) -> Result<()> {
+        self.batch.delete(&edge_key(id))
     }
 
     /// Commit the transaction
@@ -301,6 +903,368 @@ where
     }
 }
 
+impl<S> KnowledgeGraph<S>
+where
+    S: Storage + WriteBatchExt,
+{
+    /// Start a [`BatchTransaction`] that stages several node/edge creates,
+    /// updates, and deletes and commits them atomically in one Sled batch.
+    ///
+    /// Distinct from [`transaction`](Self::transaction), which hands a
+    /// single-purpose [`Transaction`] to a closure (and is what
+    /// `NodeTrigger`/`EdgeTrigger` callbacks receive); `BatchTransaction`
+    /// is the higher-level, multi-write API that also validates
+    /// referential integrity and dirties the query cache once for the
+    /// whole commit instead of once per write.
+    pub fn begin_transaction(&self) -> BatchTransaction<'_, S> {
+        BatchTransaction::new(self)
+    }
+}
+
+/// One buffered node mutation in a [`BatchTransaction`].
+enum StagedNode {
+    /// Insert a new node; fails at commit if its id already exists and
+    /// isn't also staged for removal in the same batch.
+    Add(Node),
+    /// Overwrite an existing node's properties in place; fails at commit
+    /// if no node with this id exists.
+    Replace(Node),
+    /// Delete a node and cascade-delete its incident edges, mirroring
+    /// [`KnowledgeGraph::remove_node`]; fails at commit if no node with
+    /// this id exists.
+    Remove(Uuid),
+}
+
+/// One buffered edge mutation in a [`BatchTransaction`].
+enum StagedEdge {
+    /// Insert a new edge; its endpoints must exist in storage or be
+    /// staged as added nodes in the same batch.
+    Add(Edge),
+    /// Delete an edge and prune it from its endpoints' adjacency lists;
+    /// fails at commit if no edge with this id exists.
+    Remove(Uuid),
+}
+
+/// A multi-write transaction: buffers node/edge creates, updates, and
+/// deletes, validates referential integrity at [`commit`](Self::commit)
+/// time, and writes everything through a single Sled batch so readers
+/// never observe a partial commit. Any validation failure — a duplicate
+/// node, a missing node or edge, or an edge endpoint that won't exist
+/// after the batch — aborts the whole transaction without writing
+/// anything, since nothing is written until `commit` succeeds.
+///
+/// Obtained via [`KnowledgeGraph::begin_transaction`].
+pub struct BatchTransaction<'a, S: Storage + WriteBatchExt> {
+    graph: &'a KnowledgeGraph<S>,
+    nodes: Vec<StagedNode>,
+    edges: Vec<StagedEdge>,
+}
+
+impl<'a, S> BatchTransaction<'a, S>
+where
+    S: Storage + WriteBatchExt,
+{
+    fn new(graph: &'a KnowledgeGraph<S>) -> Self {
+        Self { graph, nodes: Vec::new(), edges: Vec::new() }
+    }
+
+    /// Stage a new node to insert.
+    pub fn stage_add_node(&mut self, node: Node) -> &mut Self {
+        self.nodes.push(StagedNode::Add(node));
+        self
+    }
+
+    /// Stage an in-place update to an existing node.
+    pub fn stage_replace_node(&mut self, node: Node) -> &mut Self {
+        self.nodes.push(StagedNode::Replace(node));
+        self
+    }
+
+    /// Stage a node (and its incident edges) for deletion.
+    pub fn stage_remove_node(&mut self, id: Uuid) -> &mut Self {
+        self.nodes.push(StagedNode::Remove(id));
+        self
+    }
+
+    /// Stage a new edge to insert.
+    pub fn stage_add_edge(&mut self, edge: Edge) -> &mut Self {
+        self.edges.push(StagedEdge::Add(edge));
+        self
+    }
+
+    /// Stage an edge for deletion.
+    pub fn stage_remove_edge(&mut self, id: Uuid) -> &mut Self {
+        self.edges.push(StagedEdge::Remove(id));
+        self
+    }
+
+    /// Whether `id` will exist once this batch commits: already in
+    /// storage (and not staged for removal), or staged as an added node.
+    fn node_will_exist(&self, id: Uuid) -> Result<bool> {
+        if self.nodes.iter().any(|op| matches!(op, StagedNode::Add(node) if node.id == id)) {
+            return Ok(true);
+        }
+        let removed = self.nodes.iter().any(|op| matches!(op, StagedNode::Remove(removed) if *removed == id));
+        if removed {
+            return Ok(false);
+        }
+        self.graph.storage.exists(&node_key(id))
+    }
+
+    /// Validate referential integrity, then write every staged node/edge
+    /// mutation through a single Sled batch and mark every dependency
+    /// they touch dirty together, bumping the query cache's revision
+    /// exactly once for the whole commit.
+    pub fn commit(self) -> Result<()> {
+        for edge_op in &self.edges {
+            if let StagedEdge::Add(edge) = edge_op {
+                if !self.node_will_exist(edge.source)? {
+                    return Err(KnowledgeGraphError::NodeNotFound(edge.source.to_string()));
+                }
+                if !self.node_will_exist(edge.target)? {
+                    return Err(KnowledgeGraphError::NodeNotFound(edge.target.to_string()));
+                }
+            }
+        }
+
+        let registry = load_index_registry(&self.graph.storage)?;
+        let mut tx = Transaction::new(&self.graph.storage);
+        let mut deps = Vec::new();
+        let mut merkle_upserts: Vec<(RecordId, [u8; 32])> = Vec::new();
+        let mut merkle_removes: Vec<RecordId> = Vec::new();
+
+        for node_op in &self.nodes {
+            match node_op {
+                StagedNode::Add(node) => {
+                    if self.graph.storage.exists(&node_key(node.id))?
+                        && !self.nodes.iter().any(|op| matches!(op, StagedNode::Remove(id) if *id == node.id))
+                    {
+                        return Err(KnowledgeGraphError::DuplicateNode(node.id.to_string()));
+                    }
+                    tx.batch.put(&node_key(node.id), node)?;
+                    stage_node_indexes(&self.graph.storage, &mut tx.batch, &registry, node, true)?;
+                    self.graph.fire_node_triggers(&NodeChange::Put(node.clone()), TriggerKind::Put, &mut tx)?;
+                    deps.extend(self.graph.query_cache.node_write_deps(&registry, node));
+                    merkle_upserts.push((RecordId::Node(node.id), current_node_hash(node)?));
+                }
+                StagedNode::Replace(node) => {
+                    let before: Node = self
+                        .graph
+                        .storage
+                        .get(&node_key(node.id))?
+                        .ok_or_else(|| KnowledgeGraphError::NodeNotFound(node.id.to_string()))?;
+                    stage_node_indexes(&self.graph.storage, &mut tx.batch, &registry, &before, false)?;
+                    tx.batch.put(&node_key(node.id), node)?;
+                    stage_node_indexes(&self.graph.storage, &mut tx.batch, &registry, node, true)?;
+                    self.graph.fire_node_triggers(
+                        &NodeChange::Replaced { before: before.clone(), after: node.clone() },
+                        TriggerKind::Replace,
+                        &mut tx,
+                    )?;
+                    deps.extend(self.graph.query_cache.node_write_deps(&registry, &before));
+                    deps.extend(self.graph.query_cache.node_write_deps(&registry, node));
+                    merkle_upserts.push((RecordId::Node(node.id), current_node_hash(node)?));
+                }
+                StagedNode::Remove(id) => {
+                    let node: Node = self
+                        .graph
+                        .storage
+                        .get(&node_key(*id))?
+                        .ok_or_else(|| KnowledgeGraphError::NodeNotFound(id.to_string()))?;
+                    let incident_edges = collect_incident_edges(&self.graph.storage, *id)?;
+
+                    tx.batch.delete(&node_key(*id))?;
+                    stage_node_indexes(&self.graph.storage, &mut tx.batch, &registry, &node, false)?;
+                    for edge in &incident_edges {
+                        stage_edge_removal(&self.graph.storage, &mut tx.batch, edge)?;
+                        deps.push(Dep::OutgoingEdges(edge.source));
+                        self.graph.fire_edge_triggers(&EdgeChange::Removed(edge.clone()), TriggerKind::Rm, &mut tx)?;
+                        merkle_removes.push(RecordId::Edge(edge.id));
+                    }
+
+                    self.graph.fire_node_triggers(&NodeChange::Removed(node.clone()), TriggerKind::Rm, &mut tx)?;
+                    deps.extend(self.graph.query_cache.node_write_deps(&registry, &node));
+                    merkle_removes.push(RecordId::Node(*id));
+                }
+            }
+        }
+
+        for edge_op in &self.edges {
+            match edge_op {
+                StagedEdge::Add(edge) => {
+                    tx.batch.put(&edge_key(edge.id), edge)?;
+
+                    let source_edges_key = outgoing_edges_key(edge.source);
+                    let mut source_edges: Vec<Uuid> =
+                        self.graph.storage.get(&source_edges_key)?.unwrap_or_default();
+                    source_edges.push(edge.id);
+                    tx.batch.put(&source_edges_key, &source_edges)?;
+
+                    let target_edges_key = incoming_edges_key(edge.target);
+                    let mut target_edges: Vec<Uuid> =
+                        self.graph.storage.get(&target_edges_key)?.unwrap_or_default();
+                    target_edges.push(edge.id);
+                    tx.batch.put(&target_edges_key, &target_edges)?;
+
+                    self.graph.fire_edge_triggers(&EdgeChange::Put(edge.clone()), TriggerKind::Put, &mut tx)?;
+                    deps.push(Dep::OutgoingEdges(edge.source));
+                    merkle_upserts.push((RecordId::Edge(edge.id), current_edge_hash(edge)?));
+                }
+                StagedEdge::Remove(id) => {
+                    let edge: Edge = self
+                        .graph
+                        .storage
+                        .get(&edge_key(*id))?
+                        .ok_or_else(|| KnowledgeGraphError::EdgeNotFound(id.to_string()))?;
+                    stage_edge_removal(&self.graph.storage, &mut tx.batch, &edge)?;
+                    self.graph.fire_edge_triggers(&EdgeChange::Removed(edge.clone()), TriggerKind::Rm, &mut tx)?;
+                    deps.push(Dep::OutgoingEdges(edge.source));
+                    merkle_removes.push(RecordId::Edge(*id));
+                }
+            }
+        }
+
+        tx.commit()?;
+        self.graph.query_cache.dirty_batch(deps);
+        for (id, hash) in merkle_upserts {
+            self.graph.merkle_upsert(id, hash);
+        }
+        for id in merkle_removes {
+            self.graph.merkle_remove(id);
+        }
+        Ok(())
+    }
+}
+
+/// A mutation to a node with a given label, passed to the [`NodeTriggers`]
+/// callbacks registered for that label via
+/// [`KnowledgeGraph::set_node_triggers`].
+#[derive(Debug, Clone)]
+pub enum NodeChange {
+    /// A new node was inserted by [`KnowledgeGraph::add_node`].
+    Put(Node),
+    /// An existing node was deleted by [`KnowledgeGraph::remove_node`].
+    Removed(Node),
+    /// An existing node was overwritten in place by
+    /// [`KnowledgeGraph::replace_node`].
+    Replaced {
+        /// The node's properties before the replace.
+        before: Node,
+        /// The node's properties after the replace.
+        after: Node,
+    },
+}
+
+impl NodeChange {
+    fn label(&self) -> &str {
+        match self {
+            Self::Put(node) | Self::Removed(node) => &node.label,
+            Self::Replaced { after, .. } => &after.label,
+        }
+    }
+}
+
+/// A mutation to an edge with a given type, passed to the [`EdgeTriggers`]
+/// callbacks registered for that type via
+/// [`KnowledgeGraph::set_edge_triggers`].
+#[derive(Debug, Clone)]
+pub enum EdgeChange {
+    /// A new edge was inserted by [`KnowledgeGraph::add_edge`].
+    Put(Edge),
+    /// An existing edge was deleted by [`KnowledgeGraph::remove_edge`],
+    /// directly or as a cascade when one of its endpoints was removed.
+    Removed(Edge),
+    /// An existing edge was overwritten in place by
+    /// [`KnowledgeGraph::replace_edge`].
+    Replaced {
+        /// The edge's properties before the replace.
+        before: Edge,
+        /// The edge's properties after the replace.
+        after: Edge,
+    },
+}
+
+impl EdgeChange {
+    fn label(&self) -> &str {
+        match self {
+            Self::Put(edge) | Self::Removed(edge) => &edge.label,
+            Self::Replaced { after, .. } => &after.label,
+        }
+    }
+}
+
+/// A callback fired synchronously when a matching [`NodeChange`] commits;
+/// see [`KnowledgeGraph::set_node_triggers`].
+pub type NodeTrigger<S> =
+    Arc<dyn for<'a> Fn(&NodeChange, &mut Transaction<'a, S>) -> Result<()> + Send + Sync>;
+
+/// A callback fired synchronously when a matching [`EdgeChange`] commits;
+/// see [`KnowledgeGraph::set_edge_triggers`].
+pub type EdgeTrigger<S> =
+    Arc<dyn for<'a> Fn(&EdgeChange, &mut Transaction<'a, S>) -> Result<()> + Send + Sync>;
+
+/// The `on_put`/`on_rm`/`on_replace` callbacks registered for one node
+/// label, mirroring Cozo's `SetTriggers(relation, on_put, on_rm, on_replace)`.
+/// See [`KnowledgeGraph::set_node_triggers`].
+pub struct NodeTriggers<S: Storage + WriteBatchExt> {
+    /// Fired after a new node with this label is inserted.
+    pub on_put: Vec<NodeTrigger<S>>,
+    /// Fired after a node with this label is removed.
+    pub on_rm: Vec<NodeTrigger<S>>,
+    /// Fired after a node with this label is overwritten in place.
+    pub on_replace: Vec<NodeTrigger<S>>,
+}
+
+impl<S: Storage + WriteBatchExt> Default for NodeTriggers<S> {
+    fn default() -> Self {
+        Self {
+            on_put: Vec::new(),
+            on_rm: Vec::new(),
+            on_replace: Vec::new(),
+        }
+    }
+}
+
+/// The `on_put`/`on_rm`/`on_replace` callbacks registered for one edge
+/// type, mirroring Cozo's `SetTriggers(relation, on_put, on_rm, on_replace)`.
+/// See [`KnowledgeGraph::set_edge_triggers`].
+pub struct EdgeTriggers<S: Storage + WriteBatchExt> {
+    /// Fired after a new edge with this type is inserted.
+    pub on_put: Vec<EdgeTrigger<S>>,
+    /// Fired after an edge with this type is removed.
+    pub on_rm: Vec<EdgeTrigger<S>>,
+    /// Fired after an edge with this type is overwritten in place.
+    pub on_replace: Vec<EdgeTrigger<S>>,
+}
+
+impl<S: Storage + WriteBatchExt> Default for EdgeTriggers<S> {
+    fn default() -> Self {
+        Self {
+            on_put: Vec::new(),
+            on_rm: Vec::new(),
+            on_replace: Vec::new(),
+        }
+    }
+}
+
+/// Which of a [`NodeTriggers`]/[`EdgeTriggers`] callback lists to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriggerKind {
+    Put,
+    Rm,
+    Replace,
+}
+
+impl TriggerKind {
+    fn select<'a, T>(self, on_put: &'a [T], on_rm: &'a [T], on_replace: &'a [T]) -> &'a [T] {
+        match self {
+            Self::Put => on_put,
+            Self::Rm => on_rm,
+            Self::Replace => on_replace,
+        }
+    }
+}
+
 // Helper functions for key generation
 fn node_key(id: Uuid) -> Vec<u8> {
     let mut key = b"node:".to_vec();
@@ -314,40 +1278,240 @@ fn edge_key(id: Uuid) -> Vec<u8> {
     key
 }
 
+/// Key under which a node's outgoing edge ids are stored.
+fn outgoing_edges_key(node_id: Uuid) -> Vec<u8> {
+    format!("node_edges:{}:outgoing", node_id).into_bytes()
+}
+
+/// Key under which a node's incoming edge ids are stored.
+fn incoming_edges_key(node_id: Uuid) -> Vec<u8> {
+    format!("node_edges:{}:incoming", node_id).into_bytes()
+}
+
+/// Every edge with `node_id` as source or target, deduplicated.
+fn collect_incident_edges<S: Storage>(storage: &S, node_id: Uuid) -> Result<Vec<Edge>> {
+    let mut ids: Vec<Uuid> = storage.get(&outgoing_edges_key(node_id))?.unwrap_or_default();
+    ids.extend(storage.get::<Vec<Uuid>>(&incoming_edges_key(node_id))?.unwrap_or_default());
+    ids.sort();
+    ids.dedup();
+
+    let mut edges = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(edge) = storage.get(&edge_key(id))? {
+            edges.push(edge);
+        }
+    }
+    Ok(edges)
+}
+
+/// Stage `edge`'s removal — deleting it and pruning it from both endpoints'
+/// adjacency lists — into `batch`.
+fn stage_edge_removal<S: Storage, B: WriteBatch>(storage: &S, batch: &mut B, edge: &Edge) -> Result<()> {
+    batch.delete(&edge_key(edge.id))?;
+    stage_id_set_update(storage, batch, &outgoing_edges_key(edge.source), edge.id, false)?;
+    stage_id_set_update(storage, batch, &incoming_edges_key(edge.target), edge.id, false)?;
+    Ok(())
+}
+
 // Serialization functions are used through the Storage trait
 
-// Label index helper functions
-use uuid::Uuid;
+// Index helper functions
 
-/// Key format for label index: "label_index:<label>"
+/// Key format for the label index: "label_index:<label>"
 fn label_index_key(label: &str) -> Vec<u8> {
     let mut key = b"label_index:".to_vec();
     key.extend_from_slice(label.as_bytes());
     key
 }
 
-/// Add a node ID to the label index
-fn add_node_to_label_index<S: Storage>(storage: &S, label: &str, node_id: Uuid) -> Result<()> {
-    let key = label_index_key(label);
-    let mut node_ids: Vec<Uuid> = storage.get(&key)?.unwrap_or_default();
-    if !node_ids.contains(&node_id) {
-        node_ids.push(node_id);
-        storage.put(&key, &node_ids)?;
+/// Key format for a secondary property index entry:
+/// "prop_index:<label>:<property>:<value>"
+///
+/// `value` is rendered through `PropertyValue`'s `Display` impl (its JSON
+/// text form), which is stable and collision-free across the scalar and
+/// composite `serde_json::Value` variants alike.
+fn prop_index_key(label: &str, property: &str, value: &PropertyValue) -> Vec<u8> {
+    format!("prop_index:{}:{}:{}", label, property, value).into_bytes()
+}
+
+/// Key format for a range-index entry: the property value is encoded as
+/// order-preserving bytes (see [`encode_range_value`]) instead of rendered
+/// through `Display` the way [`prop_index_key`] is, so a
+/// [`Storage::iter_range`]/[`Storage::scan_prefix`] scan over this keyspace
+/// visits matching nodes in value order without filtering every node in
+/// memory. `None` if `value` has no natural total order (bool, null, array,
+/// object).
+///
+/// "range_index:<label>:<property>:<encoded-value>"
+fn range_index_key(label: &str, property: &str, value: &PropertyValue) -> Option<Vec<u8>> {
+    let mut key = range_index_prefix(label, property);
+    key.extend_from_slice(&encode_range_value(value)?);
+    Some(key)
+}
+
+/// The common prefix of every [`range_index_key`] for `label`/`property`,
+/// i.e. the start of a full range scan over that property.
+fn range_index_prefix(label: &str, property: &str) -> Vec<u8> {
+    format!("range_index:{}:{}:", label, property).into_bytes()
+}
+
+/// Order-preserving byte encoding of a [`PropertyValue`] for the range
+/// index: numbers become a sign-flipped big-endian `f64` (see
+/// [`encode_f64_ordered`]) so byte order matches numeric order across
+/// negatives, zero, and positives alike; strings keep their raw UTF-8 bytes,
+/// which already compare lexicographically the same way `str` does. `None`
+/// for every other JSON value kind.
+fn encode_range_value(value: &PropertyValue) -> Option<Vec<u8>> {
+    match value {
+        PropertyValue::Number(n) => Some(encode_f64_ordered(n.as_f64()?).to_vec()),
+        PropertyValue::String(s) => Some(s.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+/// Map an `f64` to an 8-byte big-endian sequence that sorts the same way
+/// the floats themselves compare: flip the sign bit for non-negative
+/// numbers (so they sort after every negative one) and every bit for
+/// negative numbers (so more-negative values, which have a larger bit
+/// pattern, sort first).
+fn encode_f64_ordered(f: f64) -> [u8; 8] {
+    let bits = f.to_bits();
+    let mapped = if bits & (1 << 63) != 0 { !bits } else { bits | (1 << 63) };
+    mapped.to_be_bytes()
+}
+
+/// Key under which the set of currently-registered `(label, property)`
+/// secondary indexes is persisted.
+fn index_registry_key() -> Vec<u8> {
+    b"index_registry".to_vec()
+}
+
+/// Load the set of `(label, property)` pairs with a live secondary index.
+fn load_index_registry<S: Storage>(storage: &S) -> Result<Vec<(String, String)>> {
+    Ok(storage.get(&index_registry_key())?.unwrap_or_default())
+}
+
+/// Read the `Vec<Uuid>` stored at `key`, add or remove `node_id`, and stage
+/// the result into `batch` (deleting the key once the set is empty). This
+/// is the shared add/remove logic behind both the label index and every
+/// registered property index.
+///
+/// Returns whether the set's membership actually changed, so a caller that
+/// only cares about genuine transitions (e.g. [`stage_node_indexes`]
+/// maintaining the node counters) can ignore no-op writes.
+fn stage_id_set_update<S: Storage, B: WriteBatch>(
+    storage: &S,
+    batch: &mut B,
+    key: &[u8],
+    node_id: Uuid,
+    present: bool,
+) -> Result<bool> {
+    let mut ids: Vec<Uuid> = storage.get(key)?.unwrap_or_default();
+    let changed = if present {
+        if ids.contains(&node_id) {
+            false
+        } else {
+            ids.push(node_id);
+            true
+        }
+    } else {
+        let before = ids.len();
+        ids.retain(|id| id != &node_id);
+        ids.len() != before
+    };
+
+    if !changed {
+        return Ok(false);
+    }
+
+    if ids.is_empty() {
+        batch.delete(key)?;
+    } else {
+        batch.put(key, &ids)?;
     }
+    Ok(true)
+}
+
+/// Key under which the total number of nodes in the graph is maintained.
+fn node_count_key() -> Vec<u8> {
+    b"count:nodes".to_vec()
+}
+
+/// Key under which the number of nodes with a given label is maintained.
+fn label_count_key(label: &str) -> Vec<u8> {
+    let mut key = b"count:label:".to_vec();
+    key.extend_from_slice(label.as_bytes());
+    key
+}
+
+/// Add `delta` to the `i64` counter stored at `key`, treating an absent key
+/// as zero.
+fn bump_counter<S: Storage, B: WriteBatch>(storage: &S, batch: &mut B, key: &[u8], delta: i64) -> Result<()> {
+    let current: i64 = storage.get(key)?.unwrap_or(0);
+    batch.put(key, &(current + delta))
+}
+
+/// Adjust the total node count and `label`'s count by one in the direction
+/// `present` indicates.
+///
+/// Called only when [`stage_node_indexes`] observes that a node's
+/// membership in the label index actually changed, so a label-unchanged
+/// [`replace_node`](KnowledgeGraph::replace_node) -- which stages a removal
+/// and an addition of the same id into the same label-index set -- nets to
+/// zero instead of drifting, while a label-changing replace correctly moves
+/// one count from the old label to the new one.
+fn stage_count_update<S: Storage, B: WriteBatch>(
+    storage: &S,
+    batch: &mut B,
+    label: &str,
+    present: bool,
+) -> Result<()> {
+    let delta = if present { 1 } else { -1 };
+    bump_counter(storage, batch, &node_count_key(), delta)?;
+    bump_counter(storage, batch, &label_count_key(label), delta)?;
     Ok(())
 }
 
-/// Remove a node ID from the label index
-fn remove_node_from_label_index<S: Storage>(storage: &S, label: &str, node_id: Uuid) -> Result<()> {
-    let key = label_index_key(label);
-    let mut node_ids: Vec<Uuid> = storage.get(&key)?.unwrap_or_default();
-    let original_len = node_ids.len();
-    node_ids.retain(|id| id != &node_id);
-    if node_ids.is_empty() {
-        storage.delete(&key)?;
-    } else if node_ids.len() != original_len {
-        storage.put(&key, &node_ids)?;
+/// Stage the label index and every registered property index update for
+/// `node` into `batch`, so they commit atomically with the node write that
+/// triggered them, along with the node/label counters the label index
+/// update feeds (see [`stage_count_update`]).
+fn stage_node_indexes<S: Storage, B: WriteBatch>(
+    storage: &S,
+    batch: &mut B,
+    registry: &[(String, String)],
+    node: &Node,
+    present: bool,
+) -> Result<()> {
+    let label_changed = stage_id_set_update(
+        storage,
+        batch,
+        &label_index_key(&node.label),
+        node.id,
+        present,
+    )?;
+    if label_changed {
+        stage_count_update(storage, batch, &node.label, present)?;
+    }
+
+    for (label, property) in registry {
+        if label != &node.label {
+            continue;
+        }
+        if let Some(value) = node.get_property(property) {
+            stage_id_set_update(
+                storage,
+                batch,
+                &prop_index_key(label, property, value),
+                node.id,
+                present,
+            )?;
+            if let Some(range_key) = range_index_key(label, property, value) {
+                stage_id_set_update(storage, batch, &range_key, node.id, present)?;
+            }
+        }
     }
+
     Ok(())
 }
 
@@ -356,3 +1520,52 @@ pub(crate) fn get_node_ids_by_label<S: Storage>(storage: &S, label: &str) -> Res
     let key = label_index_key(label);
     Ok(storage.get(&key)?.unwrap_or_default())
 }
+
+/// Node ids with `label`'s `property` value within `[min, max]` (either
+/// bound `None` for unbounded), read directly off the range index in value
+/// order via [`Storage::iter_range`] instead of filtering every node in
+/// memory. `min`/`max` that don't encode to an orderable value (see
+/// [`encode_range_value`]) are treated as unbounded on that side.
+pub(crate) fn get_node_ids_by_property_range<S: Storage>(
+    storage: &S,
+    label: &str,
+    property: &str,
+    min: Option<&PropertyValue>,
+    max: Option<&PropertyValue>,
+) -> Result<Vec<Uuid>> {
+    let prefix = range_index_prefix(label, property);
+
+    let start = min
+        .and_then(|value| range_index_key(label, property, value))
+        .unwrap_or_else(|| prefix.clone());
+    let end = match max {
+        Some(value) => match range_index_key(label, property, value) {
+            Some(key) => prefix_successor(&key),
+            None => prefix_successor(&prefix),
+        },
+        None => prefix_successor(&prefix),
+    };
+
+    let mut ids = Vec::new();
+    for (_, value) in storage.iter_range(&start, end.as_deref(), IterDirection::Forward, None, None) {
+        let batch_ids: Vec<Uuid> = storage::deserialize(&value)?;
+        ids.extend(batch_ids);
+    }
+    Ok(ids)
+}
+
+/// Total number of nodes in the graph, maintained incrementally alongside
+/// every insert/remove/replace (see [`stage_count_update`]) instead of
+/// computed by scanning every node key.
+pub(crate) fn get_node_count<S: Storage>(storage: &S) -> Result<usize> {
+    let count: i64 = storage.get(&node_count_key())?.unwrap_or(0);
+    Ok(count.max(0) as usize)
+}
+
+/// Number of nodes with `label`, maintained incrementally alongside every
+/// insert/remove/replace (see [`stage_count_update`]) instead of computed by
+/// scanning [`get_node_ids_by_label`]'s result.
+pub(crate) fn get_label_count<S: Storage>(storage: &S, label: &str) -> Result<usize> {
+    let count: i64 = storage.get(&label_count_key(label))?.unwrap_or(0);
+    Ok(count.max(0) as usize)
+}
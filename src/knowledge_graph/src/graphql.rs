@@ -0,0 +1,295 @@
+//! GraphQL front-end over [`QueryBuilder`], so existing GraphQL tooling can
+//! query the graph without learning the Rust fluent API.
+//!
+//! A [`KnowledgeGraph`] has no compile-time schema — node labels and their
+//! property shapes are only known at runtime — so the GraphQL type system
+//! is assembled dynamically with `async_graphql::dynamic` instead of the
+//! usual `#[Object]` derive. [`schema`] returns a [`SchemaBuilder`];
+//! [`SchemaBuilder::register_label`] declares one label's queryable
+//! property fields and any edge fields that should expand as nested
+//! selections (e.g. `friends` on `Person` walking outgoing `KNOWS` edges
+//! to `Person` targets), and [`SchemaBuilder::build`] turns the
+//! accumulated labels into a runnable [`GraphQlSchema`].
+//!
+//! [`GraphQlSchema::execute`] runs a query string against it, resolving
+//! each top-level field and nested edge selection with a
+//! [`QueryBuilder`]/[`KnowledgeGraph::query_edges_from`] traversal under
+//! the hood, and returns the response shaped to match the query (a JSON
+//! object keyed by field name, not the flat `nodes`/`edges` pair
+//! [`QueryResult`] returns for the Rust API).
+
+use std::sync::Arc;
+
+use async_graphql::dynamic::{
+    Field, FieldFuture, FieldValue, InputValue, Object, Schema, SchemaError, TypeRef,
+};
+use async_graphql::{Value as GraphQlValue, Variables};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::error::{KnowledgeGraphError, Result};
+use crate::graph::KnowledgeGraph;
+use crate::models::{Node, PropertyValue};
+use crate::query::QueryBuilder;
+use crate::storage::{Storage, WriteBatch, WriteBatchExt};
+
+/// Start building a GraphQL schema over `graph`.
+///
+/// `graph` is wrapped in an `Arc` internally so the schema's resolvers
+/// (which `async_graphql::dynamic` requires to be `'static`) can hold
+/// their own handle to it, mirroring how
+/// [`SledGraphBackend`](crate::async_graph::SledGraphBackend) wraps the
+/// graph it adapts.
+pub fn schema<S>(graph: KnowledgeGraph<S>) -> SchemaBuilder<S>
+where
+    S: Storage + WriteBatchExt,
+    for<'a> <S as Storage>::Batch<'a>: WriteBatch + 'static,
+    for<'a> &'a S: 'a,
+{
+    SchemaBuilder {
+        graph: Arc::new(graph),
+        labels: Vec::new(),
+    }
+}
+
+/// A scalar property type surfaced to GraphQL for a [`PropertyField`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    /// Maps to GraphQL `String`.
+    String,
+    /// Maps to GraphQL `Int`.
+    Int,
+    /// Maps to GraphQL `Float`.
+    Float,
+    /// Maps to GraphQL `Boolean`.
+    Boolean,
+}
+
+impl PropertyType {
+    fn type_ref(self) -> TypeRef {
+        let name = match self {
+            PropertyType::String => TypeRef::STRING,
+            PropertyType::Int => TypeRef::INT,
+            PropertyType::Float => TypeRef::FLOAT,
+            PropertyType::Boolean => TypeRef::BOOLEAN,
+        };
+        // Nullable: a node isn't guaranteed to have every declared
+        // property set.
+        TypeRef::named(name)
+    }
+}
+
+/// One property field exposed on a [`LabelSchema`], resolving to
+/// `node.get_property(name)`.
+#[derive(Debug, Clone)]
+pub struct PropertyField {
+    name: String,
+    property_type: PropertyType,
+}
+
+impl PropertyField {
+    /// Expose `name` as a queryable field of type `property_type`.
+    pub fn new(name: impl Into<String>, property_type: PropertyType) -> Self {
+        Self { name: name.into(), property_type }
+    }
+}
+
+/// A nested edge traversal exposed as a sub-selection field, e.g. `friends`
+/// on `Person` expanding outgoing edges labeled `edge_label` to
+/// `target_label` nodes.
+#[derive(Debug, Clone)]
+pub struct EdgeField {
+    name: String,
+    edge_label: String,
+    target_label: String,
+}
+
+impl EdgeField {
+    /// Expose `name` as a sub-selection walking outgoing `edge_label`
+    /// edges to `target_label` nodes.
+    pub fn new(
+        name: impl Into<String>,
+        edge_label: impl Into<String>,
+        target_label: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            edge_label: edge_label.into(),
+            target_label: target_label.into(),
+        }
+    }
+}
+
+/// The GraphQL shape of one node label: its queryable property fields and
+/// any nested edge traversals.
+#[derive(Debug, Clone)]
+pub struct LabelSchema {
+    label: String,
+    fields: Vec<PropertyField>,
+    edges: Vec<EdgeField>,
+}
+
+impl LabelSchema {
+    /// Describe the GraphQL shape of nodes labeled `label`.
+    pub fn new(label: impl Into<String>, fields: Vec<PropertyField>, edges: Vec<EdgeField>) -> Self {
+        Self { label: label.into(), fields, edges }
+    }
+}
+
+/// Accumulates [`LabelSchema`]s and builds them into a runnable
+/// [`GraphQlSchema`]. Returned by [`schema`].
+pub struct SchemaBuilder<S: Storage + WriteBatchExt> {
+    graph: Arc<KnowledgeGraph<S>>,
+    labels: Vec<LabelSchema>,
+}
+
+impl<S> SchemaBuilder<S>
+where
+    S: Storage + WriteBatchExt,
+    for<'a> <S as Storage>::Batch<'a>: WriteBatch + 'static,
+    for<'a> &'a S: 'a,
+{
+    /// Register a label's GraphQL shape, adding a top-level query field
+    /// for it.
+    pub fn register_label(mut self, label_schema: LabelSchema) -> Self {
+        self.labels.push(label_schema);
+        self
+    }
+
+    /// Derive GraphQL object types from every registered label and build
+    /// the schema.
+    pub fn build(self) -> std::result::Result<GraphQlSchema, SchemaError> {
+        let mut query = Object::new("Query");
+        let mut objects = Vec::new();
+
+        for label_schema in &self.labels {
+            let mut object = Object::new(label_schema.label.clone());
+
+            for field in &label_schema.fields {
+                let field_name = field.name.clone();
+                let type_ref = field.property_type.type_ref();
+                object = object.field(Field::new(
+                    field.name.clone(),
+                    type_ref,
+                    move |ctx| {
+                        let field_name = field_name.clone();
+                        FieldFuture::new(async move {
+                            let node = ctx.parent_value.try_downcast_ref::<Node>()?;
+                            Ok(node
+                                .get_property(&field_name)
+                                .map(|value| FieldValue::value(property_to_graphql(value))))
+                        })
+                    },
+                ));
+            }
+
+            for edge_field in &label_schema.edges {
+                let edge_label = edge_field.edge_label.clone();
+                let target_type = TypeRef::named_nn_list_nn(edge_field.target_label.clone());
+                let graph = self.graph.clone();
+                object = object.field(Field::new(
+                    edge_field.name.clone(),
+                    target_type,
+                    move |ctx| {
+                        let edge_label = edge_label.clone();
+                        let graph = graph.clone();
+                        FieldFuture::new(async move {
+                            let node = ctx.parent_value.try_downcast_ref::<Node>()?;
+                            let edges = graph.query_edges_from(node.id)?;
+                            let mut targets = Vec::new();
+                            for edge in edges.into_iter().filter(|e| e.label == edge_label) {
+                                if let Some(target) = graph.get_node(edge.target)? {
+                                    targets.push(FieldValue::owned_any(target));
+                                }
+                            }
+                            Ok(Some(FieldValue::list(targets)))
+                        })
+                    },
+                ));
+            }
+
+            let label = label_schema.label.clone();
+            let graph = self.graph.clone();
+            let list_type = TypeRef::named_nn_list_nn(label.clone());
+            query = query.field(
+                Field::new(label.clone(), list_type, move |ctx| {
+                    let label = label.clone();
+                    let graph = graph.clone();
+                    FieldFuture::new(async move {
+                        if let Some(id) = ctx.args.try_get("id").ok().and_then(|v| v.string().ok().map(str::to_string)) {
+                            let id = Uuid::parse_str(&id)
+                                .map_err(|e| KnowledgeGraphError::QueryError(e.to_string()))?;
+                            return Ok(graph
+                                .get_node(id)?
+                                .filter(|node| node.label == label)
+                                .map(|node| FieldValue::list(vec![FieldValue::owned_any(node)]))
+                                .or_else(|| Some(FieldValue::list(Vec::<FieldValue>::new()))));
+                        }
+
+                        let mut builder = QueryBuilder::new(&graph).with_label(&label);
+                        if let (Ok(property), Ok(value)) =
+                            (ctx.args.try_get("property"), ctx.args.try_get("value"))
+                        {
+                            if let (Some(property), Some(value)) =
+                                (property.string().ok(), value.string().ok())
+                            {
+                                builder = builder
+                                    .with_property(property.to_string(), serde_json::json!(value));
+                            }
+                        }
+
+                        let result = builder.execute()?;
+                        Ok(Some(FieldValue::list(
+                            result.nodes.into_iter().map(FieldValue::owned_any),
+                        )))
+                    })
+                })
+                .argument(InputValue::new("id", TypeRef::named(TypeRef::ID)))
+                .argument(InputValue::new("property", TypeRef::named(TypeRef::STRING)))
+                .argument(InputValue::new("value", TypeRef::named(TypeRef::STRING))),
+            );
+
+            objects.push(object);
+        }
+
+        let mut schema = Schema::build("Query", None, None).register(query);
+        for object in objects {
+            schema = schema.register(object);
+        }
+
+        Ok(GraphQlSchema { inner: schema.finish()? })
+    }
+}
+
+/// A runnable GraphQL schema built by [`SchemaBuilder::build`].
+pub struct GraphQlSchema {
+    inner: Schema,
+}
+
+impl GraphQlSchema {
+    /// Run `query_str` against the graph, substituting `variables`, and
+    /// return the response shaped to match the query's selection set.
+    pub async fn execute(&self, query_str: &str, variables: JsonValue) -> Result<JsonValue> {
+        let request = async_graphql::Request::new(query_str)
+            .variables(Variables::from_json(variables));
+        let response = self.inner.execute(request).await;
+
+        if !response.errors.is_empty() {
+            let message = response
+                .errors
+                .iter()
+                .map(|e| e.message.clone())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(KnowledgeGraphError::QueryError(message));
+        }
+
+        serde_json::to_value(response.data).map_err(KnowledgeGraphError::from)
+    }
+}
+
+/// Convert a stored [`PropertyValue`] into the `async_graphql` value type
+/// its dynamic resolvers return.
+fn property_to_graphql(value: &PropertyValue) -> GraphQlValue {
+    GraphQlValue::from_json(value.clone()).unwrap_or(GraphQlValue::Null)
+}
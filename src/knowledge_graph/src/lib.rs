@@ -87,19 +87,34 @@
 #![warn(rustdoc::missing_crate_level_docs)]
 #![cfg_attr(test, allow(dead_code))] // Allow dead code in tests
 
+pub mod algorithms;
+pub mod async_graph;
 pub mod cache;
 pub mod error;
 pub mod graph;
+pub mod graphql;
+pub mod materialize;
+pub mod merkle;
 pub mod models;
 pub mod query;
+pub(crate) mod query_cache;
+pub mod rdf;
+pub mod reachability;
 pub mod storage;
 
 // Re-exports
+pub use algorithms::{MaxFlowResult, Path};
+pub use async_graph::{AsyncKnowledgeGraph, AsyncQueryBuilder, SledGraphBackend};
 pub use error::{Result, KnowledgeGraphError};
 pub use graph::KnowledgeGraph;
+pub use materialize::{Materializer, NodeOp};
+pub use merkle::{MerkleDiff, RecordId, RootHash};
 pub use models::*;
-pub use query::{QueryBuilder, QueryResult};
-pub use storage::SledStore;
+pub use query::{QueryBuilder, QueryResult, SearchHit};
+pub use query_cache::{CachePolicy, CacheStats};
+pub use rdf::RdfFormat;
+pub use reachability::{BitMatrix, ReachabilityIndex};
+pub use storage::{GraphBackend, SledStore};
 
 /// Prelude module for convenient imports
 pub mod prelude {
@@ -117,6 +132,15 @@ pub mod prelude {
         KnowledgeGraph,
         QueryBuilder,
         QueryResult,
+        CachePolicy,
+        AsyncKnowledgeGraph,
+        AsyncQueryBuilder,
+        GraphBackend,
+        SledGraphBackend,
+        Materializer,
+        NodeOp,
+        MaxFlowResult,
+        Path,
         SledStore,
         Node,
         Edge,
@@ -0,0 +1,253 @@
+//! Materialized-view subsystem: computes and maintains a derived "current
+//! view" of each node from an append-only log of operations, instead of
+//! applying streamed edits to the graph directly.
+//!
+//! Ingested changes are appended to a per-node operation log and folded
+//! into a materialized [`Node`] by a *reduce* task — last-writer-wins per
+//! property by default, or a custom merge via
+//! [`Materializer::with_merge_fn`]. After a reduce task completes, a
+//! *dependency* task enqueues reduce tasks for every node holding a
+//! "pinned relation" pointing at the just-materialized node (registered
+//! via [`Materializer::pin_relation`]), so a downstream view picks up the
+//! change. A pinned relation whose target hasn't materialized yet doesn't
+//! fail the batch: the dependent view is parked as pending and retried
+//! once its target appears, so out-of-order ingestion still converges.
+//!
+//! This lets MAYA ingest streamed edits in whatever order they arrive and
+//! always have [`materialize`](Materializer::materialize) return a
+//! consistent latest state.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+use uuid::Uuid;
+
+use crate::models::{Node, PropertyValue};
+
+/// A single change targeting a node, appended to its operation log.
+#[derive(Debug, Clone)]
+pub enum NodeOp {
+    /// Set one property to a value — last-writer-wins against any earlier
+    /// op for the same property, unless a [`MergeFn`] says otherwise.
+    SetProperty {
+        /// The property key being set.
+        key: String,
+        /// The value to fold in.
+        value: PropertyValue,
+    },
+    /// Replace the node's label.
+    SetLabel(String),
+    /// Remove the node entirely — a later op for the same ID resurrects it
+    /// starting from a clean slate.
+    Delete,
+}
+
+/// Per-property merge strategy, applied when folding a node's operation
+/// log into a materialized view: given the value already folded in and a
+/// later op's value for the same property, returns the value that should
+/// win. Left unset, folding is last-writer-wins (the later op always
+/// replaces the earlier one).
+pub type MergeFn = dyn Fn(&PropertyValue, &PropertyValue) -> PropertyValue + Send + Sync;
+
+/// A unit of work on the materializer's internal queue.
+enum Task {
+    /// Fold every queued operation for this node ID into its materialized
+    /// view.
+    Reduce(Uuid),
+    /// Re-enqueue every node pinned to this node ID, now that it's
+    /// materialized.
+    PropagateDependents(Uuid),
+}
+
+/// Whether a [`Materializer`] reduce produced a fresh view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReduceOutcome {
+    /// The view was folded (or deleted) successfully.
+    Resolved,
+    /// The node has a pinned relation to a target that hasn't
+    /// materialized yet; deferred until that target resolves.
+    Pending,
+}
+
+struct State {
+    /// Every operation appended so far, per node, in append order.
+    ops: HashMap<Uuid, Vec<NodeOp>>,
+    /// The last folded view for each node that's fully materialized.
+    views: HashMap<Uuid, Node>,
+    /// `dependent -> targets`: the pinned relations each node depends on.
+    pinned_targets: HashMap<Uuid, Vec<Uuid>>,
+    /// `target -> dependents`: the reverse index used to propagate a
+    /// reduce to whoever's pinned to it.
+    dependents: HashMap<Uuid, Vec<Uuid>>,
+    /// Dependents waiting on a target that hasn't materialized yet, keyed
+    /// by the missing target's ID.
+    pending: HashMap<Uuid, Vec<Uuid>>,
+    queue: VecDeque<Task>,
+}
+
+/// Computes and maintains materialized node views from an append-only
+/// operation log, as described in the [module docs](self).
+pub struct Materializer {
+    state: RwLock<State>,
+    merge_fn: Option<Box<MergeFn>>,
+}
+
+impl std::fmt::Debug for Materializer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Materializer").finish_non_exhaustive()
+    }
+}
+
+impl Default for Materializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Materializer {
+    /// Create an empty materializer with last-writer-wins property
+    /// folding.
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(State {
+                ops: HashMap::new(),
+                views: HashMap::new(),
+                pinned_targets: HashMap::new(),
+                dependents: HashMap::new(),
+                pending: HashMap::new(),
+                queue: VecDeque::new(),
+            }),
+            merge_fn: None,
+        }
+    }
+
+    /// Use `merge` to resolve a property that's been set more than once
+    /// for the same node, instead of the default last-writer-wins.
+    pub fn with_merge_fn(mut self, merge: impl Fn(&PropertyValue, &PropertyValue) -> PropertyValue + Send + Sync + 'static) -> Self {
+        self.merge_fn = Some(Box::new(merge));
+        self
+    }
+
+    /// Append an operation to `node_id`'s log and enqueue it for
+    /// reduction.
+    pub fn record_op(&self, node_id: Uuid, op: NodeOp) {
+        let mut state = self.state.write().unwrap();
+        state.ops.entry(node_id).or_default().push(op);
+        state.queue.push_back(Task::Reduce(node_id));
+    }
+
+    /// Register a pinned relation: `dependent`'s view should re-materialize
+    /// whenever `target`'s view changes. If `dependent` already has a
+    /// materialized view (or queued operations), it's immediately
+    /// re-enqueued to pick up the new dependency.
+    pub fn pin_relation(&self, dependent: Uuid, target: Uuid) {
+        let mut state = self.state.write().unwrap();
+        state.pinned_targets.entry(dependent).or_default().push(target);
+        state.dependents.entry(target).or_default().push(dependent);
+        state.queue.push_back(Task::Reduce(dependent));
+    }
+
+    /// Drain the work queue, then return `node_id`'s materialized view if
+    /// it has one. Returns `None` if the node has no recorded operations
+    /// yet, was deleted, or is still waiting on a pinned target to
+    /// materialize (see [`pending_views`](Self::pending_views)).
+    pub fn materialize(&self, node_id: Uuid) -> Option<Node> {
+        let mut state = self.state.write().unwrap();
+        self.drain_queue(&mut state);
+        state.views.get(&node_id).cloned()
+    }
+
+    /// IDs of every node whose view is parked waiting on a pinned target
+    /// that hasn't materialized yet, for diagnostics.
+    pub fn pending_views(&self) -> Vec<Uuid> {
+        let state = self.state.read().unwrap();
+        state.pending.values().flatten().copied().collect()
+    }
+
+    fn drain_queue(&self, state: &mut State) {
+        while let Some(task) = state.queue.pop_front() {
+            match task {
+                Task::Reduce(id) => {
+                    if self.reduce_one(state, id) == ReduceOutcome::Resolved {
+                        state.queue.push_back(Task::PropagateDependents(id));
+                    }
+                }
+                Task::PropagateDependents(id) => {
+                    if let Some(waiters) = state.pending.remove(&id) {
+                        state.queue.extend(waiters.into_iter().map(Task::Reduce));
+                    }
+                    if let Some(dependents) = state.dependents.get(&id).cloned() {
+                        state.queue.extend(dependents.into_iter().map(Task::Reduce));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Fold `id`'s operation log into a view, unless it's blocked on a
+    /// pinned target that hasn't materialized — in which case it's parked
+    /// in `state.pending` and retried once that target resolves.
+    fn reduce_one(&self, state: &mut State, id: Uuid) -> ReduceOutcome {
+        if let Some(targets) = state.pinned_targets.get(&id) {
+            for target in targets.clone() {
+                if !state.views.contains_key(&target) {
+                    state.pending.entry(target).or_default().push(id);
+                    return ReduceOutcome::Pending;
+                }
+            }
+        }
+
+        let Some(ops) = state.ops.get(&id) else {
+            return ReduceOutcome::Resolved;
+        };
+
+        match self.fold(id, ops) {
+            Some(node) => {
+                state.views.insert(id, node);
+            }
+            None => {
+                state.views.remove(&id);
+            }
+        }
+        ReduceOutcome::Resolved
+    }
+
+    /// Fold `ops` into a single [`Node`], or `None` if the log ends in a
+    /// delete (or never set a label, so there's nothing to materialize
+    /// yet).
+    fn fold(&self, id: Uuid, ops: &[NodeOp]) -> Option<Node> {
+        let mut label: Option<String> = None;
+        let mut properties: HashMap<String, PropertyValue> = HashMap::new();
+
+        for op in ops {
+            match op {
+                NodeOp::SetLabel(new_label) => {
+                    label = Some(new_label.clone());
+                }
+                NodeOp::SetProperty { key, value } => {
+                    match (properties.get(key), &self.merge_fn) {
+                        (Some(existing), Some(merge)) => {
+                            let merged = merge(existing, value);
+                            properties.insert(key.clone(), merged);
+                        }
+                        _ => {
+                            properties.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+                NodeOp::Delete => {
+                    label = None;
+                    properties.clear();
+                }
+            }
+        }
+
+        let label = label?;
+        let mut node = Node::new(&label);
+        node.id = id;
+        for (key, value) in properties {
+            node.set_property(&key, value);
+        }
+        Some(node)
+    }
+}
@@ -0,0 +1,367 @@
+//! Merkle-hashed content addressing for [`KnowledgeGraph`], giving
+//! reproducible, tamper-evident root hashes over the current node/edge set.
+//!
+//! Every node and edge is hashed with BLAKE3 over its bincode encoding
+//! (the same wire format [`Storage::put`](crate::storage::Storage::put)
+//! already uses) into a leaf, and the leaves are combined pairwise up to a
+//! single 32-byte [`RootHash`] over the whole record set.
+//!
+//! Leaves are ordered by [`RecordId`] (nodes before edges, each sorted by
+//! id) rather than by hash value. Sorting by the hash itself would move a
+//! leaf's tree position on every edit, since BLAKE3 output is effectively
+//! random -- which would defeat the point of keeping this index
+//! incremental. With a stable per-record position instead, overwriting an
+//! existing record ([`KnowledgeGraph::replace_node`]/`replace_edge`) only
+//! touches that leaf's path to the root (`O(log n)`, via
+//! [`MerkleTree::update_leaf`]), exactly the "update only the Merkle
+//! path of changed leaves" this was built for. Inserting or removing a
+//! record changes the leaf count -- and with it every later leaf's
+//! position -- so those still fall back to a full rebuild, the same
+//! structural tradeoff [`ReachabilityIndex`](crate::reachability::ReachabilityIndex)
+//! makes for topology changes it can't patch incrementally.
+//!
+//! [`KnowledgeGraph::snapshot`] records the root it returns (and the full
+//! leaf set behind it) so a later [`KnowledgeGraph::diff`] or
+//! [`KnowledgeGraph::verify`] can still refer back to it, unlike the
+//! transient, live-only caching [`ReachabilityIndex`] uses.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{KnowledgeGraphError, Result};
+use crate::models::{Edge, Node};
+use crate::storage;
+
+/// A stable identifier for one record a [`MerkleIndex`] hashes: a node or
+/// an edge, kept distinct so the two id spaces (both raw UUIDs) never
+/// alias each other's leaf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RecordId {
+    /// A node, identified by its id.
+    Node(Uuid),
+    /// An edge, identified by its id.
+    Edge(Uuid),
+}
+
+/// A BLAKE3 digest over the whole current record set: the output of
+/// [`KnowledgeGraph::snapshot`](crate::graph::KnowledgeGraph::snapshot), and
+/// what [`KnowledgeGraph::diff`](crate::graph::KnowledgeGraph::diff)/
+/// [`KnowledgeGraph::verify`](crate::graph::KnowledgeGraph::verify) use to
+/// refer back to a point in time.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RootHash([u8; 32]);
+
+impl RootHash {
+    /// The root hash's raw bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::fmt::Debug for RootHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RootHash({})", self)
+    }
+}
+
+impl std::fmt::Display for RootHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// What changed between two [`KnowledgeGraph::snapshot`](crate::graph::KnowledgeGraph::snapshot)
+/// roots, returned by [`KnowledgeGraph::diff`](crate::graph::KnowledgeGraph::diff).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MerkleDiff {
+    /// Records present in `new` but not `old`.
+    pub added: Vec<RecordId>,
+    /// Records present in `old` but not `new`.
+    pub removed: Vec<RecordId>,
+    /// Records present in both, but with a different hash.
+    pub changed: Vec<RecordId>,
+}
+
+impl MerkleDiff {
+    /// Whether `old` and `new` were over the same record set with the same
+    /// hashes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Hash a node's bincode encoding with BLAKE3.
+fn hash_node(node: &Node) -> Result<[u8; 32]> {
+    Ok(*blake3::hash(&storage::serialize(node)?).as_bytes())
+}
+
+/// Hash an edge's bincode encoding with BLAKE3.
+fn hash_edge(edge: &Edge) -> Result<[u8; 32]> {
+    Ok(*blake3::hash(&storage::serialize(edge)?).as_bytes())
+}
+
+/// Combine a left and right (sibling, or itself if there's no pair) hash
+/// into their parent.
+fn combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// A balanced binary Merkle tree over a fixed, ordered leaf set, plus the
+/// `RecordId -> leaf index` map [`update_leaf`](Self::update_leaf)
+/// needs to patch one leaf without rehashing the rest.
+#[derive(Debug, Clone)]
+struct MerkleTree {
+    /// `levels[0]` is the leaf hashes in `RecordId` order;
+    /// `levels.last()` is the single-element root level.
+    levels: Vec<Vec<[u8; 32]>>,
+    index_of: HashMap<RecordId, usize>,
+}
+
+impl MerkleTree {
+    /// Build a tree from scratch, sorting `leaves` by `RecordId` so the
+    /// leaf order (and therefore the root) only depends on record
+    /// identity and content, not insertion order.
+    fn build(mut leaves: Vec<(RecordId, [u8; 32])>) -> Self {
+        leaves.sort_by_key(|(id, _)| *id);
+        let index_of = leaves.iter().enumerate().map(|(i, (id, _))| (*id, i)).collect();
+        let leaf_hashes: Vec<[u8; 32]> = leaves.into_iter().map(|(_, hash)| hash).collect();
+
+        let mut levels = vec![leaf_hashes];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| combine(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(next);
+        }
+        if levels.last().unwrap().is_empty() {
+            levels.push(vec![*blake3::hash(b"").as_bytes()]);
+        }
+
+        Self { levels, index_of }
+    }
+
+    fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Overwrite the leaf for `id` and recompute only its path to the
+    /// root. `id` must already have a leaf (the set of ids is unchanged) --
+    /// adding or removing a leaf changes every later leaf's position and
+    /// needs [`build`](Self::build) instead.
+    fn update_leaf(&mut self, id: RecordId, hash: [u8; 32]) {
+        let Some(&leaf_index) = self.index_of.get(&id) else {
+            return;
+        };
+        self.levels[0][leaf_index] = hash;
+
+        let mut index = leaf_index;
+        for level in 0..self.levels.len() - 1 {
+            let parent_index = index / 2;
+            let left = self.levels[level][parent_index * 2];
+            let right = self.levels[level].get(parent_index * 2 + 1).copied().unwrap_or(left);
+            self.levels[level + 1][parent_index] = combine(&left, &right);
+            index = parent_index;
+        }
+    }
+}
+
+/// The live Merkle index [`KnowledgeGraph`](crate::graph::KnowledgeGraph)
+/// keeps incrementally up to date, plus every root it has ever produced via
+/// `snapshot`, each mapped to the leaf-hash set it was computed from so
+/// `diff`/`verify` can still answer against a root from several edits ago.
+#[derive(Debug, Clone)]
+pub(crate) struct MerkleIndex {
+    tree: MerkleTree,
+    leaf_hash: HashMap<RecordId, [u8; 32]>,
+    history: HashMap<RootHash, HashMap<RecordId, [u8; 32]>>,
+}
+
+impl MerkleIndex {
+    /// Build from every node and edge currently in the graph.
+    pub(crate) fn build(nodes: &[Node], edges: &[Edge]) -> Result<Self> {
+        let mut leaf_hash = HashMap::with_capacity(nodes.len() + edges.len());
+        for node in nodes {
+            leaf_hash.insert(RecordId::Node(node.id), hash_node(node)?);
+        }
+        for edge in edges {
+            leaf_hash.insert(RecordId::Edge(edge.id), hash_edge(edge)?);
+        }
+        let tree = MerkleTree::build(leaf_hash.iter().map(|(id, hash)| (*id, *hash)).collect());
+        Ok(Self { tree, leaf_hash, history: HashMap::new() })
+    }
+
+    /// Insert a new record, or overwrite an existing one's hash. Overwrites
+    /// take the incremental path-update fast path; new records fall back
+    /// to a full rebuild, since the leaf count (and every later leaf's
+    /// position) changes.
+    pub(crate) fn upsert(&mut self, id: RecordId, hash: [u8; 32]) {
+        if self.leaf_hash.insert(id, hash) == Some(hash) {
+            return;
+        }
+        if self.tree.index_of.contains_key(&id) {
+            self.tree.update_leaf(id, hash);
+        } else {
+            self.tree = MerkleTree::build(self.leaf_hash.iter().map(|(id, hash)| (*id, *hash)).collect());
+        }
+    }
+
+    /// Remove a record's leaf, rebuilding the tree over what's left.
+    pub(crate) fn remove(&mut self, id: RecordId) {
+        if self.leaf_hash.remove(&id).is_some() {
+            self.tree = MerkleTree::build(self.leaf_hash.iter().map(|(id, hash)| (*id, *hash)).collect());
+        }
+    }
+
+    /// The current root hash.
+    pub(crate) fn root(&self) -> RootHash {
+        RootHash(self.tree.root())
+    }
+
+    /// Record the current root and leaf set into `history` and return the
+    /// root, so a later `diff`/`verify` can refer back to this point.
+    pub(crate) fn snapshot(&mut self) -> RootHash {
+        let root = self.root();
+        self.history.entry(root).or_insert_with(|| self.leaf_hash.clone());
+        root
+    }
+
+    /// Compare the leaf sets behind two previously produced roots.
+    pub(crate) fn diff(&self, old: RootHash, new: RootHash) -> Result<MerkleDiff> {
+        let old_leaves = self.leaves_for(old)?;
+        let new_leaves = self.leaves_for(new)?;
+
+        let mut diff = MerkleDiff::default();
+        for (id, new_hash) in new_leaves {
+            match old_leaves.get(id) {
+                None => diff.added.push(*id),
+                Some(old_hash) if old_hash != new_hash => diff.changed.push(*id),
+                Some(_) => {}
+            }
+        }
+        for id in old_leaves.keys() {
+            if !new_leaves.contains_key(id) {
+                diff.removed.push(*id);
+            }
+        }
+        Ok(diff)
+    }
+
+    /// Build a tree over `leaves` and return its root. The integrity check
+    /// itself lives in
+    /// [`KnowledgeGraph::verify`](crate::graph::KnowledgeGraph::verify),
+    /// which re-reads every record of a snapshot back out of storage,
+    /// rehashes them, passes those hashes here and compares the result
+    /// with the snapshot's root.
+    pub(crate) fn recomputed_root(&self, leaves: &HashMap<RecordId, [u8; 32]>) -> RootHash {
+        let tree = MerkleTree::build(leaves.iter().map(|(id, hash)| (*id, *hash)).collect());
+        RootHash(tree.root())
+    }
+
+    fn leaves_for(&self, root: RootHash) -> Result<&HashMap<RecordId, [u8; 32]>> {
+        self.history
+            .get(&root)
+            .ok_or_else(|| KnowledgeGraphError::InvalidOperation(format!("unknown snapshot root: {root}")))
+    }
+
+    /// The leaf set a previously produced `root` was computed from, for
+    /// [`KnowledgeGraph::verify`](crate::graph::KnowledgeGraph::verify) to
+    /// re-check against live storage.
+    pub(crate) fn leaves_at(&self, root: RootHash) -> Result<HashMap<RecordId, [u8; 32]>> {
+        self.leaves_for(root).cloned()
+    }
+}
+
+/// Hash a node the same way a [`MerkleIndex`] would, for
+/// [`KnowledgeGraph::verify`](crate::graph::KnowledgeGraph::verify) to
+/// recompute a leaf from a freshly re-read node.
+pub(crate) fn current_node_hash(node: &Node) -> Result<[u8; 32]> {
+    hash_node(node)
+}
+
+/// Hash an edge the same way a [`MerkleIndex`] would, for
+/// [`KnowledgeGraph::verify`](crate::graph::KnowledgeGraph::verify) to
+/// recompute a leaf from a freshly re-read edge.
+pub(crate) fn current_edge_hash(edge: &Edge) -> Result<[u8; 32]> {
+    hash_edge(edge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(label: &str) -> Node {
+        Node::new(label)
+    }
+
+    #[test]
+    fn test_same_content_produces_same_root() {
+        let a = node("Person");
+        let b = node("Person");
+        let index_a = MerkleIndex::build(std::slice::from_ref(&a), &[]).unwrap();
+        let index_b = MerkleIndex::build(std::slice::from_ref(&a), &[]).unwrap();
+        let index_c = MerkleIndex::build(std::slice::from_ref(&b), &[]).unwrap();
+
+        assert_eq!(index_a.root(), index_b.root());
+        // Different ids (even with identical labels) hash to a different leaf.
+        assert_ne!(index_a.root(), index_c.root());
+    }
+
+    #[test]
+    fn test_incremental_update_matches_full_rebuild() {
+        let mut nodes = vec![node("A"), node("B"), node("C")];
+        let mut index = MerkleIndex::build(&nodes, &[]).unwrap();
+
+        let mut changed = nodes[1].clone();
+        changed.set_property("touched", true);
+        index.upsert(RecordId::Node(changed.id), current_node_hash(&changed).unwrap());
+        nodes[1] = changed;
+
+        let rebuilt = MerkleIndex::build(&nodes, &[]).unwrap();
+        assert_eq!(index.root(), rebuilt.root());
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed() {
+        let a = node("A");
+        let b = node("B");
+        let mut index = MerkleIndex::build(&[a.clone(), b.clone()], &[]).unwrap();
+        let old_root = index.snapshot();
+
+        index.remove(RecordId::Node(a.id));
+        let mut changed_b = b.clone();
+        changed_b.set_property("touched", true);
+        index.upsert(RecordId::Node(changed_b.id), current_node_hash(&changed_b).unwrap());
+        let c = node("C");
+        index.upsert(RecordId::Node(c.id), current_node_hash(&c).unwrap());
+        let new_root = index.snapshot();
+
+        let diff = index.diff(old_root, new_root).unwrap();
+        assert_eq!(diff.added, vec![RecordId::Node(c.id)]);
+        assert_eq!(diff.removed, vec![RecordId::Node(a.id)]);
+        assert_eq!(diff.changed, vec![RecordId::Node(b.id)]);
+    }
+
+    #[test]
+    fn test_diff_against_unknown_root_errors() {
+        let index = MerkleIndex::build(&[], &[]).unwrap();
+        let bogus = RootHash([7u8; 32]);
+        assert!(index.diff(bogus, bogus).is_err());
+    }
+
+    #[test]
+    fn test_root_hash_display_is_lowercase_hex() {
+        let root = RootHash([0xab; 32]);
+        assert_eq!(root.to_string(), "ab".repeat(32));
+    }
+}
@@ -41,3 +41,34 @@ pub trait GraphElement: Serialize + for<'de> Deserialize<'de> + std::fmt::Debug
 
 /// Type alias for property values
 pub type PropertyValue = serde_json::Value;
+
+/// Trait for estimating a value's heap footprint at runtime.
+///
+/// Used by memory-bounded batch processing (see
+/// [`storage::batch_optimizer::BatchConfig::memory_budget_bytes`](crate::storage::batch_optimizer::BatchConfig))
+/// to estimate, before dispatching a chunk of work, how many heap bytes it
+/// will add to in-flight memory -- not just how many items it contains.
+pub trait DynamicUsage {
+    /// Estimated heap bytes retained by this value's own allocations
+    /// (strings, vecs, nested values). Does not include `size_of::<Self>()`
+    /// itself, since a caller summing usage across a `Vec<Self>` already
+    /// accounts for that from the `Vec`'s own capacity.
+    fn dynamic_usage(&self) -> usize;
+}
+
+impl DynamicUsage for PropertyValue {
+    fn dynamic_usage(&self) -> usize {
+        match self {
+            serde_json::Value::Null | serde_json::Value::Bool(_) | serde_json::Value::Number(_) => 0,
+            serde_json::Value::String(s) => s.capacity(),
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|v| std::mem::size_of::<PropertyValue>() + v.dynamic_usage())
+                .sum(),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| k.capacity() + std::mem::size_of::<PropertyValue>() + v.dynamic_usage())
+                .sum(),
+        }
+    }
+}
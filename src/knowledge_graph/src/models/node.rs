@@ -77,12 +77,20 @@ impl GraphElement for Node {
     fn id(&self) -> Uuid {
         self.id
     }
-    
+
     fn created_at(&self) -> DateTime<Utc> {
         self.created_at
     }
 }
 
+impl DynamicUsage for Node {
+    fn dynamic_usage(&self) -> usize {
+        self.label.capacity()
+            + self.properties.capacity() * std::mem::size_of::<Property>()
+            + self.properties.iter().map(|p| p.dynamic_usage()).sum::<usize>()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +66,12 @@ impl PartialEq<&str> for Property {
     }
 }
 
+impl DynamicUsage for Property {
+    fn dynamic_usage(&self) -> usize {
+        self.key.capacity() + self.value.dynamic_usage()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
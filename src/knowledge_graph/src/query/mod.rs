@@ -19,20 +19,195 @@ GLIMMER Pattern:
 //!
 //! Provides a fluent API for querying the knowledge graph.
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use super::graph::{self, KnowledgeGraph};
 use crate::error::Result;
 use crate::models::{Node, Edge};
+use crate::query_cache::Dep;
 use crate::storage::{Storage, WriteBatch, WriteBatchExt};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use uuid::Uuid;
 
 /// Result of a query execution
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct QueryResult {
     /// Matching nodes
     pub nodes: Vec<Node>,
     /// Matching edges
     pub edges: Vec<Edge>,
+    /// Routes discovered by [`QueryBuilder::traverse`], one per node
+    /// reached, each an ordered sequence of `(node reached, edge taken to
+    /// reach it)` steps starting from one of the traversal's matched start
+    /// nodes. Empty for every other terminal ([`QueryBuilder::execute`],
+    /// [`QueryExt::reachable_from`]), which don't track routes.
+    #[serde(default)]
+    pub paths: Vec<Vec<(Node, Edge)>>,
+}
+
+/// BM25 term-frequency saturation constant used by [`QueryExt::full_text_search`].
+const SEARCH_BM25_K1: f32 = 1.2;
+/// BM25 document-length normalization strength used by [`QueryExt::full_text_search`].
+const SEARCH_BM25_B: f32 = 0.75;
+
+/// Split into lowercased alphanumeric tokens for [`QueryExt::full_text_search`].
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+/// Plain Levenshtein edit distance between `a` and `b`, used to expand a
+/// query token that misses the index to nearby indexed tokens in
+/// [`QueryExt::full_text_search`].
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Concatenate a node's label and every string-valued property into the
+/// text [`QueryExt::full_text_search`] tokenizes and scores against.
+fn node_document(node: &Node) -> String {
+    let mut text = node.label.clone();
+    for property in &node.properties {
+        if let Some(s) = property.value.as_str() {
+            text.push(' ');
+            text.push_str(s);
+        }
+    }
+    text
+}
+
+/// A short window of `text` around the first matched token, so a
+/// [`SearchHit`] can be displayed without pulling the whole node back out.
+fn snippet_for(text: &str, matched_tokens: &HashSet<&str>) -> String {
+    const WINDOW_CHARS: usize = 60;
+    let chars: Vec<char> = text.chars().collect();
+    let lower = text.to_lowercase();
+
+    let hit_char_pos = matched_tokens
+        .iter()
+        .filter_map(|token| lower.find(token).map(|byte_pos| lower[..byte_pos].chars().count()))
+        .min();
+
+    let (start, end) = match hit_char_pos {
+        Some(pos) => (
+            pos.saturating_sub(WINDOW_CHARS / 2),
+            (pos + WINDOW_CHARS / 2).min(chars.len()),
+        ),
+        None => (0, WINDOW_CHARS.min(chars.len())),
+    };
+
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet = format!("...{}", snippet);
+    }
+    if end < chars.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// One ranked match from [`QueryExt::full_text_search`]: the node itself,
+/// its BM25 score, and a short snippet of matched text for display.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// The matching node.
+    pub node: Node,
+    /// BM25 relevance score; higher is more relevant. Only positive scores
+    /// are returned.
+    pub score: f32,
+    /// A short window of the node's label/properties around a matched
+    /// token.
+    pub snippet: String,
+}
+
+/// A single filter `QueryBuilder` was built with, recorded alongside the
+/// filter closure itself so the closure (which has no stable identity) can
+/// still be hashed and turned into cache dependencies for incremental
+/// memoization — see [`QueryBuilder::execute`].
+#[derive(Clone)]
+enum QueryCriterion {
+    Label(String),
+    Property(String, Value),
+    /// Added by `with_property_gte`/`with_property_lte`/`with_property_between`:
+    /// the named property must be within `[min, max]` (either bound `None`
+    /// for unbounded).
+    PropertyRange { property: String, min: Option<Value>, max: Option<Value> },
+}
+
+/// The access path [`QueryBuilder::plan`] chose to seed a query's candidate
+/// node set from, in order of preference.
+enum IndexPlan {
+    /// `label` filter plus a single range filter on a property an index was
+    /// created for: scan the range index.
+    Range { label: String, property: String, min: Option<Value>, max: Option<Value> },
+    /// `label` filter plus a single equality filter on a property an index
+    /// was created for: look it up via [`KnowledgeGraph::find_nodes_by_property`].
+    Property { label: String, property: String, value: Value },
+    /// A lone label filter: scan the label index.
+    Label(String),
+    /// No selective index available: scan every node.
+    FullScan,
+}
+
+/// Compare two property values the same way the range index orders them:
+/// numbers numerically, strings lexicographically. `None` if `a` and `b`
+/// aren't the same orderable kind (or are a kind with no natural order at
+/// all, like bool/null/array/object).
+fn compare_property_values(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+/// Which edges [`QueryBuilder::traverse`] follows from each frontier node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalDirection {
+    /// Only `source -> target` edges.
+    Outgoing,
+    /// Only `target -> source` edges, walked backwards.
+    Incoming,
+    /// Both directions.
+    Both,
+}
+
+/// Edges touching `node_id` in `direction`, via `graph`'s adjacency lists
+/// ([`KnowledgeGraph::query_edges_from`]/[`KnowledgeGraph::query_edges_to`]),
+/// used by [`QueryBuilder::traverse`]'s breadth-first expansion.
+fn traversal_edges<S>(graph: &KnowledgeGraph<S>, node_id: Uuid, direction: TraversalDirection) -> Result<Vec<Edge>>
+where
+    S: Storage + WriteBatchExt,
+    for<'b> <S as Storage>::Batch<'b>: WriteBatch + 'static,
+    for<'b> &'b S: 'b,
+{
+    let mut edges = Vec::new();
+    if direction != TraversalDirection::Incoming {
+        edges.extend(graph.query_edges_from(node_id)?);
+    }
+    if direction != TraversalDirection::Outgoing {
+        edges.extend(graph.query_edges_to(node_id)?);
+    }
+    Ok(edges)
 }
 
 /// Builder for constructing graph queries
@@ -45,8 +220,11 @@ where
     graph: &'a KnowledgeGraph<S>,
     node_filters: Vec<Box<dyn Fn(&Node) -> bool + 'a>>,
     edge_filters: Vec<Box<dyn Fn(&Edge) -> bool + 'static>>,
+    criteria: Vec<QueryCriterion>,
     limit: Option<usize>,
     offset: usize,
+    hops: Option<usize>,
+    traversal_direction: TraversalDirection,
     _marker: PhantomData<S>,
 }
 
@@ -62,8 +240,11 @@ where
             graph,
             node_filters: Vec::new(),
             edge_filters: Vec::new(),
+            criteria: Vec::new(),
             limit: None,
             offset: 0,
+            hops: None,
+            traversal_direction: TraversalDirection::Outgoing,
             _marker: PhantomData,
         }
     }
@@ -71,6 +252,7 @@ where
     /// Filter nodes by label
     pub fn with_node_type(mut self, node_type: &'a str) -> Self {
         let node_type = node_type.to_string();
+        self.criteria.push(QueryCriterion::Label(node_type.clone()));
         self.node_filters.push(Box::new(move |node: &Node| node.label == node_type));
         self
     }
@@ -83,12 +265,84 @@ where
     /// Filter nodes by property
     pub fn with_property<T: Into<String>>(mut self, key: T, value: Value) -> Self {
         let key = key.into();
+        self.criteria.push(QueryCriterion::Property(key.clone(), value.clone()));
         self.node_filters.push(Box::new(move |node: &Node| {
             node.properties.iter().any(|p| p.key == key && p.value == value)
         }));
         self
     }
 
+    /// Restrict edge handling (the single hop [`execute`](Self::execute)
+    /// walks, or every hop [`traverse`](Self::traverse) walks) to edges
+    /// whose label is `edge_type`.
+    pub fn with_edge_type(mut self, edge_type: &str) -> Self {
+        let edge_type = edge_type.to_string();
+        self.edge_filters.push(Box::new(move |edge: &Edge| edge.label == edge_type));
+        self
+    }
+
+    /// Filter nodes whose `key` property is numeric/string and orders at or
+    /// above `value` (see [`compare_property_values`]). Backed by the range
+    /// index (see `graph::get_node_ids_by_property_range`) when this is the
+    /// only filter alongside a single `with_label`/`with_node_type` call
+    /// added first and [`KnowledgeGraph::create_index`] was called for that
+    /// label/property -- see [`run`](Self::run) -- otherwise filtered in
+    /// memory like [`with_property`](Self::with_property). A node whose
+    /// property is missing, or isn't the same orderable kind as `value`,
+    /// never matches.
+    pub fn with_property_gte<T: Into<String>>(mut self, key: T, value: Value) -> Self {
+        let key = key.into();
+        self.criteria.push(QueryCriterion::PropertyRange {
+            property: key.clone(),
+            min: Some(value.clone()),
+            max: None,
+        });
+        self.node_filters.push(Box::new(move |node: &Node| {
+            node.get_property(&key)
+                .and_then(|v| compare_property_values(v, &value))
+                .is_some_and(|ord| ord != std::cmp::Ordering::Less)
+        }));
+        self
+    }
+
+    /// Filter nodes whose `key` property is numeric/string and orders at or
+    /// below `value`. See [`with_property_gte`](Self::with_property_gte) for
+    /// the comparison and fast-path rules.
+    pub fn with_property_lte<T: Into<String>>(mut self, key: T, value: Value) -> Self {
+        let key = key.into();
+        self.criteria.push(QueryCriterion::PropertyRange {
+            property: key.clone(),
+            min: None,
+            max: Some(value.clone()),
+        });
+        self.node_filters.push(Box::new(move |node: &Node| {
+            node.get_property(&key)
+                .and_then(|v| compare_property_values(v, &value))
+                .is_some_and(|ord| ord != std::cmp::Ordering::Greater)
+        }));
+        self
+    }
+
+    /// Filter nodes whose `key` property is numeric/string and orders within
+    /// `[min, max]` inclusive. See
+    /// [`with_property_gte`](Self::with_property_gte) for the comparison and
+    /// fast-path rules.
+    pub fn with_property_between<T: Into<String>>(mut self, key: T, min: Value, max: Value) -> Self {
+        let key = key.into();
+        self.criteria.push(QueryCriterion::PropertyRange {
+            property: key.clone(),
+            min: Some(min.clone()),
+            max: Some(max.clone()),
+        });
+        self.node_filters.push(Box::new(move |node: &Node| {
+            node.get_property(&key).is_some_and(|v| {
+                compare_property_values(v, &min).is_some_and(|ord| ord != std::cmp::Ordering::Less)
+                    && compare_property_values(v, &max).is_some_and(|ord| ord != std::cmp::Ordering::Greater)
+            })
+        }));
+        self
+    }
+
     /// Set the maximum number of results to return
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
@@ -101,14 +355,214 @@ where
         self
     }
 
-    /// Execute the query and return the matching nodes and edges
+    /// Bound [`traverse`](Self::traverse)'s breadth-first expansion to
+    /// `hops` edges out from the matched start nodes. Defaults to `1` (a
+    /// single hop) if never set.
+    pub fn with_hops(mut self, hops: usize) -> Self {
+        self.hops = Some(hops);
+        self
+    }
+
+    /// Which edges [`traverse`](Self::traverse) follows from each frontier
+    /// node. Defaults to [`TraversalDirection::Outgoing`].
+    pub fn direction(mut self, direction: TraversalDirection) -> Self {
+        self.traversal_direction = direction;
+        self
+    }
+
+    /// Run a breadth-first, multi-hop traversal from every node matched by
+    /// this builder's filters, out to [`with_hops`](Self::with_hops)'
+    /// depth bound (1 if never set), following edges in
+    /// [`direction`](Self::direction) and discarding any already-visited
+    /// node so cycles terminate and each node is reached at most once, via
+    /// the shortest route found.
+    ///
+    /// Every edge filter added via [`with_edge_type`](Self::with_edge_type)
+    /// is applied at every hop, not just the first, unlike
+    /// [`execute`](Self::execute)'s single-hop handling.
+    ///
+    /// Unlike `execute`, the result's `nodes`/`edges` cover everything
+    /// visited across the whole expansion (not just the start nodes), and
+    /// [`QueryResult::paths`] carries one route per node reached: an
+    /// ordered sequence of `(node, edge taken to reach it)` steps from a
+    /// start node to that node.
+    pub fn traverse(self) -> Result<QueryResult> {
+        let start_nodes = self.matched_nodes()?;
+        let max_hops = self.hops.unwrap_or(1);
+        let direction = self.traversal_direction;
+        let graph = self.graph;
+        let edge_filters = self.edge_filters;
+
+        let mut visited: HashSet<Uuid> = start_nodes.iter().map(|n| n.id).collect();
+        let mut predecessor: HashMap<Uuid, (Uuid, Edge)> = HashMap::new();
+        let mut frontier: VecDeque<(Uuid, usize)> = start_nodes.iter().map(|n| (n.id, 0)).collect();
+        let mut reached: HashMap<Uuid, Node> = HashMap::new();
+        let mut edges = Vec::new();
+
+        while let Some((current, hops)) = frontier.pop_front() {
+            if hops >= max_hops {
+                continue;
+            }
+
+            for edge in traversal_edges(graph, current, direction)? {
+                if !edge_filters.iter().all(|f| f(&edge)) {
+                    continue;
+                }
+
+                let next = if edge.source == current { edge.target } else { edge.source };
+                if !visited.insert(next) {
+                    continue;
+                }
+
+                predecessor.insert(next, (current, edge.clone()));
+                edges.push(edge);
+                if let Some(node) = graph.get_node(next)? {
+                    reached.insert(next, node);
+                }
+                frontier.push_back((next, hops + 1));
+            }
+        }
+
+        let mut paths: Vec<Vec<(Node, Edge)>> = Vec::new();
+        for (&node_id, node) in &reached {
+            let mut steps = vec![(node.clone(), predecessor[&node_id].1.clone())];
+            let mut current = predecessor[&node_id].0;
+            while let Some((prev, edge)) = predecessor.get(&current) {
+                let node = reached.get(&current).cloned().unwrap_or_else(|| {
+                    start_nodes.iter().find(|n| n.id == current).cloned().expect(
+                        "every predecessor chain bottoms out at a start node or an already-reached node",
+                    )
+                });
+                steps.push((node, edge.clone()));
+                current = *prev;
+            }
+            steps.reverse();
+            paths.push(steps);
+        }
+
+        let mut nodes: Vec<Node> = start_nodes;
+        nodes.extend(reached.into_values());
+
+        Ok(QueryResult { nodes, edges, paths })
+    }
+
+    /// Execute the query and return the matching nodes and edges.
+    ///
+    /// Memoized against [`KnowledgeGraph`]'s incremental query cache,
+    /// keyed by a hash of this builder's filter criteria, limit, and
+    /// offset: a repeated query against an unchanged graph returns the
+    /// cached result instead of re-running the traversal. A query with
+    /// edge filters isn't memoized, since those are arbitrary closures
+    /// with no stable identity to hash or record as a dependency.
     pub fn execute(self) -> Result<QueryResult> {
-        // Optimization: If only a label filter is present, use the label index
-        let mut nodes = if self.node_filters.len() == 1 {
-            // Try to detect if the filter is a label filter
-            // This is a heuristic: if with_label/with_node_type was called, it is always the first filter
+        match self.query_hash() {
+            Some(hash) => {
+                let deps = self.cache_deps();
+                let graph = self.graph;
+                graph.execute_cached_query(hash, deps, move || self.run())
+            }
+            None => self.run(),
+        }
+    }
+
+    /// Hash this builder's filter criteria, limit, and offset into a stable
+    /// key for the query cache, or `None` if it has edge filters (which
+    /// can't be memoized — see [`execute`](Self::execute)).
+    fn query_hash(&self) -> Option<u64> {
+        if !self.edge_filters.is_empty() {
+            return None;
+        }
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for criterion in &self.criteria {
+            match criterion {
+                QueryCriterion::Label(label) => {
+                    0u8.hash(&mut hasher);
+                    label.hash(&mut hasher);
+                }
+                QueryCriterion::Property(key, value) => {
+                    1u8.hash(&mut hasher);
+                    key.hash(&mut hasher);
+                    value.to_string().hash(&mut hasher);
+                }
+                QueryCriterion::PropertyRange { property, min, max } => {
+                    2u8.hash(&mut hasher);
+                    property.hash(&mut hasher);
+                    min.as_ref().map(|v| v.to_string()).hash(&mut hasher);
+                    max.as_ref().map(|v| v.to_string()).hash(&mut hasher);
+                }
+            }
+        }
+        self.limit.hash(&mut hasher);
+        self.offset.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    /// The cache dependencies this query's filters read directly, mirroring
+    /// [`plan`](Self::plan)'s access-path choice: an index-backed filter
+    /// only reads that index's keyspace, everything else falls back to
+    /// scanning every node.
+    fn cache_deps(&self) -> Vec<Dep> {
+        match self.plan() {
+            Ok(IndexPlan::Range { label, property, .. }) | Ok(IndexPlan::Property { label, property, .. }) => {
+                vec![Dep::Property(label, property)]
+            }
+            Ok(IndexPlan::Label(label)) => vec![Dep::Label(label)],
+            _ => vec![Dep::AllNodes],
+        }
+    }
+
+    /// Choose the access path [`matched_nodes`](Self::matched_nodes) should
+    /// seed its candidate set from, picking the most selective index this
+    /// builder's criteria make available rather than always scanning --
+    /// mirroring how a query planner (e.g. Cozo's) picks among index and
+    /// full-scan access paths before running a query. Residual
+    /// `node_filters` closures still run afterward to validate every
+    /// candidate the chosen path returns.
+    fn plan(&self) -> Result<IndexPlan> {
+        if let Some((label, property, min, max)) = self.extract_label_and_range_filter() {
+            // The range index is only written for label/property pairs an
+            // index was created for; without one it would hold nothing.
+            if self.graph.has_index(&label, &property)? {
+                return Ok(IndexPlan::Range { label, property, min, max });
+            }
+        }
+
+        if let Some((label, property, value)) = self.extract_label_and_property_filter() {
+            if self.graph.has_index(&label, &property)? {
+                return Ok(IndexPlan::Property { label, property, value });
+            }
+        }
+
+        if self.node_filters.len() == 1 {
             if let Some(label) = self.extract_label_filter() {
-                // Use the helper function from the graph module
+                return Ok(IndexPlan::Label(label));
+            }
+        }
+
+        Ok(IndexPlan::FullScan)
+    }
+
+    /// Resolve this builder's label/range/property fast-paths and apply
+    /// every `node_filters` closure, without touching edges. Shared between
+    /// [`run`](Self::run)'s single-hop edge handling and
+    /// [`traverse`](Self::traverse)'s multi-hop expansion, both of which
+    /// start from the same matched-node set.
+    fn matched_nodes(&self) -> Result<Vec<Node>> {
+        let mut nodes = match self.plan()? {
+            IndexPlan::Range { label, property, min, max } => {
+                let node_ids =
+                    graph::get_node_ids_by_property_range(&self.graph, &label, &property, min.as_ref(), max.as_ref())?;
+                let mut result_nodes = Vec::new();
+                for node_id in node_ids {
+                    if let Some(node) = self.graph.get_node(node_id)? {
+                        result_nodes.push(node);
+                    }
+                }
+                result_nodes
+            }
+            IndexPlan::Property { label, property, value } => self.graph.find_nodes_by_property(&label, &property, &value)?,
+            IndexPlan::Label(label) => {
                 let node_ids = graph::get_node_ids_by_label(&self.graph, &label)?;
                 let mut result_nodes = Vec::new();
                 for node_id in node_ids {
@@ -117,19 +571,24 @@ where
                     }
                 }
                 result_nodes
-            } else {
-                self.graph.get_nodes()?
             }
-        } else {
-            self.graph.get_nodes()?
+            IndexPlan::FullScan => self.graph.get_nodes()?,
         };
-        let mut edges = Vec::new();
-        
-        // Apply node filters if any
+
         if !self.node_filters.is_empty() {
             nodes.retain(|node| self.node_filters.iter().all(|f| f(node)));
         }
-        
+
+        Ok(nodes)
+    }
+
+    /// Run the query against storage, bypassing the cache. Used directly
+    /// for uncached (edge-filtered) queries, and as the cache-miss
+    /// fallback from [`execute`](Self::execute).
+    fn run(self) -> Result<QueryResult> {
+        let mut nodes = self.matched_nodes()?;
+        let mut edges = Vec::new();
+
         // If we have edge filters, we need to process edges
         if !self.edge_filters.is_empty() {
             // For each node, get its edges and apply edge filters
@@ -164,15 +623,69 @@ where
         
         let nodes = nodes.into_iter().skip(start).take(end - start).collect();
         
-        Ok(QueryResult { nodes, edges })
+        Ok(QueryResult { nodes, edges, paths: Vec::new() })
     }
 
-    /// Try to extract the label from the node_filters if it was set by with_label/with_node_type
+    /// Count matching nodes without materializing them.
+    ///
+    /// Short-circuits to the maintained node/label counters (see
+    /// [`graph::get_node_count`]/[`graph::get_label_count`]) when this
+    /// builder has no filters at all, or exactly the single label filter
+    /// added by `with_label`/`with_node_type` and nothing else -- no
+    /// property or edge filters, no limit/offset, since those all need the
+    /// actual matching nodes to apply. Any other combination falls back to
+    /// [`run`](Self::run) and counts the resulting nodes.
+    pub fn count(self) -> Result<usize> {
+        if self.edge_filters.is_empty() && self.limit.is_none() && self.offset == 0 {
+            match self.criteria.as_slice() {
+                [] => return graph::get_node_count(&self.graph),
+                [QueryCriterion::Label(label)] => return graph::get_label_count(&self.graph, label),
+                _ => {}
+            }
+        }
+
+        Ok(self.run()?.nodes.len())
+    }
+
+    /// Extract the label this builder was filtered by via
+    /// `with_label`/`with_node_type`, if any.
     fn extract_label_filter(&self) -> Option<String> {
-        // This is a heuristic: we know with_label/with_node_type pushes a filter that checks node.label == label
-        // We can't extract the label directly from the closure, so we could store the label in a field when with_label is called
-        // For now, this is a placeholder for future improvement
-        None
+        self.criteria.iter().find_map(|c| match c {
+            QueryCriterion::Label(label) => Some(label.clone()),
+            QueryCriterion::Property(..) | QueryCriterion::PropertyRange { .. } => None,
+        })
+    }
+
+    /// If this builder was built as exactly `with_label`/`with_node_type`
+    /// followed by exactly one `with_property_gte`/`with_property_lte`/
+    /// `with_property_between` call and nothing else, return the label,
+    /// property, and bounds needed to scan the range index directly (see
+    /// `graph::get_node_ids_by_property_range`) instead of filtering every
+    /// node in memory. The same first-filter-is-the-label heuristic
+    /// [`plan`](Self::plan) relies on for its other index fast paths.
+    fn extract_label_and_range_filter(&self) -> Option<(String, String, Option<Value>, Option<Value>)> {
+        match self.criteria.as_slice() {
+            [QueryCriterion::Label(label), QueryCriterion::PropertyRange { property, min, max }] => {
+                Some((label.clone(), property.clone(), min.clone(), max.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// If this builder was built as exactly `with_label`/`with_node_type`
+    /// followed by exactly one `with_property` equality check and nothing
+    /// else, return the label, property, and value so [`plan`](Self::plan)
+    /// can check [`KnowledgeGraph::has_index`] and, if one exists, seed the
+    /// candidate set from [`KnowledgeGraph::find_nodes_by_property`] instead
+    /// of a full scan. The same first-filter-is-the-label heuristic as
+    /// [`extract_label_and_range_filter`](Self::extract_label_and_range_filter).
+    fn extract_label_and_property_filter(&self) -> Option<(String, String, Value)> {
+        match self.criteria.as_slice() {
+            [QueryCriterion::Label(label), QueryCriterion::Property(property, value)] => {
+                Some((label.clone(), property.clone(), value.clone()))
+            }
+            _ => None,
+        }
     }
 }
 
@@ -185,6 +698,32 @@ where
 {
     /// Start building a query
     fn query(&self) -> QueryBuilder<S>;
+
+    /// All nodes reachable from `start` by following edges whose label is
+    /// one of `rel_types` (every label is followed if `rel_types` is
+    /// empty), evaluated by semi-naive fixpoint iteration: the frontier
+    /// starts at `start`'s direct neighbors, and each round joins only the
+    /// previous round's *newly*-derived nodes (not the whole accumulated
+    /// set) against the edge index, stopping as soon as a round derives
+    /// nothing new. A `HashSet<Uuid>` of already-seen nodes guarantees
+    /// termination on cycles. `max_depth` caps the number of hops followed
+    /// from `start`, or `None` for no limit.
+    ///
+    /// Returns a [`QueryResult`] of every newly-reached node (`start`
+    /// itself is not included) together with every matching edge walked to
+    /// reach them, answering transitive-closure / ancestor / descendant
+    /// style questions (e.g. "everyone Alice reports to, transitively").
+    fn reachable_from(&self, start: Uuid, rel_types: &[&str], max_depth: Option<usize>) -> Result<QueryResult>;
+
+    /// Rank every node by BM25 relevance to `query` against its label and
+    /// string-valued properties (see [`node_document`]). A query token
+    /// that isn't in the index is also matched against indexed tokens
+    /// within a bounded Levenshtein distance (1 for tokens of 4+
+    /// characters, 2 for 8+), so a typo like "pythn" still finds nodes
+    /// tagged "python". Results are sorted descending by score, each
+    /// carrying a short snippet of the matched text; nodes with no
+    /// matching token (after typo expansion) are omitted.
+    fn full_text_search(&self, query: &str) -> Result<Vec<SearchHit>>;
 }
 
 impl<S> QueryExt<S> for KnowledgeGraph<S>
@@ -196,6 +735,146 @@ where
     fn query(&self) -> QueryBuilder<S> {
         QueryBuilder::new(self)
     }
+
+    fn reachable_from(&self, start: Uuid, rel_types: &[&str], max_depth: Option<usize>) -> Result<QueryResult> {
+        let mut seen: HashSet<Uuid> = HashSet::new();
+        seen.insert(start);
+
+        let mut result_nodes = Vec::new();
+        let mut result_edges = Vec::new();
+
+        let mut frontier = vec![start];
+        let mut depth = 0usize;
+
+        while !frontier.is_empty() {
+            if let Some(max) = max_depth {
+                if depth >= max {
+                    break;
+                }
+            }
+
+            let mut next_frontier = Vec::new();
+            for node_id in &frontier {
+                for edge in self.query_edges_from(*node_id)? {
+                    if !rel_types.is_empty() && !rel_types.iter().any(|rel| *rel == edge.label) {
+                        continue;
+                    }
+
+                    result_edges.push(edge.clone());
+
+                    if seen.insert(edge.target) {
+                        if let Some(node) = self.get_node(edge.target)? {
+                            result_nodes.push(node);
+                        }
+                        next_frontier.push(edge.target);
+                    }
+                }
+            }
+
+            frontier = next_frontier;
+            depth += 1;
+        }
+
+        Ok(QueryResult { nodes: result_nodes, edges: result_edges, paths: Vec::new() })
+    }
+
+    fn full_text_search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let query_tokens = tokenize(query);
+        if query_tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let nodes = self.get_nodes()?;
+        let doc_tokens: Vec<Vec<String>> =
+            nodes.iter().map(|node| tokenize(&node_document(node))).collect();
+
+        let mut token_index: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (doc_idx, tokens) in doc_tokens.iter().enumerate() {
+            let mut seen_in_doc: HashSet<&str> = HashSet::new();
+            for token in tokens {
+                if seen_in_doc.insert(token.as_str()) {
+                    token_index.entry(token.as_str()).or_default().push(doc_idx);
+                }
+            }
+        }
+
+        let mut matched_tokens: HashSet<&str> = HashSet::new();
+        for token in &query_tokens {
+            if let Some((&key, _)) = token_index.get_key_value(token.as_str()) {
+                matched_tokens.insert(key);
+                continue;
+            }
+            let max_distance = match token.chars().count() {
+                n if n >= 8 => 2,
+                n if n >= 4 => 1,
+                _ => 0,
+            };
+            if max_distance == 0 {
+                continue;
+            }
+            for &indexed in token_index.keys() {
+                if levenshtein(token, indexed) <= max_distance {
+                    matched_tokens.insert(indexed);
+                }
+            }
+        }
+        if matched_tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let doc_count = doc_tokens.len() as f32;
+        let avg_doc_len = if doc_tokens.is_empty() {
+            0.0
+        } else {
+            doc_tokens.iter().map(|tokens| tokens.len()).sum::<usize>() as f32 / doc_count
+        };
+
+        let mut candidate_idxs: HashSet<usize> = HashSet::new();
+        for token in &matched_tokens {
+            if let Some(postings) = token_index.get(token) {
+                candidate_idxs.extend(postings.iter().copied());
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = candidate_idxs
+            .into_iter()
+            .filter_map(|doc_idx| {
+                let tokens = &doc_tokens[doc_idx];
+                let doc_len = tokens.len() as f32;
+                let length_norm = if avg_doc_len > 0.0 { doc_len / avg_doc_len } else { 0.0 };
+
+                let mut term_freq: HashMap<&str, f32> = HashMap::new();
+                for token in tokens {
+                    *term_freq.entry(token.as_str()).or_insert(0.0) += 1.0;
+                }
+
+                let mut score = 0.0f32;
+                for token in &matched_tokens {
+                    let f = *term_freq.get(token).unwrap_or(&0.0);
+                    if f == 0.0 {
+                        continue;
+                    }
+                    let n_t = token_index.get(token).map(|postings| postings.len()).unwrap_or(0) as f32;
+                    let idf = (1.0 + (doc_count - n_t + 0.5) / (n_t + 0.5)).ln();
+                    let numerator = f * (SEARCH_BM25_K1 + 1.0);
+                    let denominator =
+                        f + SEARCH_BM25_K1 * (1.0 - SEARCH_BM25_B + SEARCH_BM25_B * length_norm);
+                    score += idf * (numerator / denominator);
+                }
+
+                if score <= 0.0 {
+                    return None;
+                }
+
+                let node = nodes[doc_idx].clone();
+                let snippet = snippet_for(&node_document(&node), &matched_tokens);
+                Some(SearchHit { node, score, snippet })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(hits)
+    }
 }
 
 #[cfg(test)]
@@ -296,4 +975,434 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_count_uses_maintained_counters_without_scanning() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Alpha"))?;
+        graph.add_node(Node::new("Alpha"))?;
+        graph.add_node(Node::new("Beta"))?;
+
+        assert_eq!(QueryBuilder::new(&graph).count()?, 3);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Alpha").count()?, 2);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Beta").count()?, 1);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Gamma").count()?, 0);
+
+        let beta = graph.get_nodes()?.into_iter().find(|n| n.label == "Beta").unwrap();
+        graph.remove_node(beta.id)?;
+        assert_eq!(QueryBuilder::new(&graph).count()?, 2);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Beta").count()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_replace_with_changed_label_moves_counts_not_total() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let node = Node::new("Alpha");
+        graph.add_node(node.clone())?;
+
+        let mut replacement = node.clone();
+        replacement.label = "Beta".to_string();
+        graph.replace_node(replacement)?;
+
+        assert_eq!(QueryBuilder::new(&graph).count()?, 1);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Alpha").count()?, 0);
+        assert_eq!(QueryBuilder::new(&graph).with_label("Beta").count()?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_falls_back_for_property_filters() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Gadget").with_property("color", "red"))?;
+        graph.add_node(Node::new("Gadget").with_property("color", "blue"))?;
+
+        assert_eq!(
+            QueryBuilder::new(&graph)
+                .with_property("color", serde_json::json!("red"))
+                .count()?,
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_range_filters_use_the_range_index() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Product").with_property("price", 10))?;
+        graph.add_node(Node::new("Product").with_property("price", 20))?;
+        graph.add_node(Node::new("Product").with_property("price", 30))?;
+        graph.add_node(Node::new("Other").with_property("price", 25))?;
+
+        let gte = QueryBuilder::new(&graph)
+            .with_label("Product")
+            .with_property_gte("price", serde_json::json!(20))
+            .execute()?;
+        assert_eq!(gte.nodes.len(), 2);
+        assert!(gte.nodes.iter().all(|n| n.label == "Product"));
+
+        let lte = QueryBuilder::new(&graph)
+            .with_label("Product")
+            .with_property_lte("price", serde_json::json!(20))
+            .execute()?;
+        assert_eq!(lte.nodes.len(), 2);
+
+        let between = QueryBuilder::new(&graph)
+            .with_label("Product")
+            .with_property_between("price", serde_json::json!(15), serde_json::json!(25))
+            .execute()?;
+        assert_eq!(between.nodes.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_range_filter_orders_strings_lexicographically() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Person").with_property("name", "alice"))?;
+        graph.add_node(Node::new("Person").with_property("name", "bob"))?;
+        graph.add_node(Node::new("Person").with_property("name", "carol"))?;
+
+        let result = QueryBuilder::new(&graph)
+            .with_label("Person")
+            .with_property_gte("name", serde_json::json!("bob"))
+            .execute()?;
+
+        let names: HashSet<&str> =
+            result.nodes.iter().filter_map(|n| n.get_property("name")).filter_map(|v| v.as_str()).collect();
+        assert_eq!(names, ["bob", "carol"].into_iter().collect());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_planner_uses_property_index_once_created() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Person").with_property("city", "nyc"))?;
+        graph.add_node(Node::new("Person").with_property("city", "sf"))?;
+        graph.add_node(Node::new("Dog").with_property("city", "nyc"))?;
+
+        graph.create_index("Person", "city")?;
+
+        // A label + property-equality filter is, per `plan`, answered via
+        // the now-existing index (`find_nodes_by_property`) rather than a
+        // full scan -- the "Dog" sharing the same city never even reaches
+        // the residual `node_filters` check.
+        let result = QueryBuilder::new(&graph)
+            .with_label("Person")
+            .with_property("city", serde_json::json!("nyc"))
+            .execute()?;
+        assert_eq!(result.nodes.len(), 1);
+        assert_eq!(result.nodes[0].label, "Person");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_traverse_expands_breadth_first_up_to_hops() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let alice = Node::new("Person").with_property("start", true);
+        let bob = Node::new("Person");
+        let carol = Node::new("Person");
+
+        graph.add_node(alice.clone())?;
+        graph.add_node(bob.clone())?;
+        graph.add_node(carol.clone())?;
+
+        graph.add_edge(&Edge::new("KNOWS", alice.id, bob.id))?;
+        graph.add_edge(&Edge::new("KNOWS", bob.id, carol.id))?;
+
+        // Only Alice carries "start", so she is the sole start node; with
+        // the default single hop she reaches Bob but not Carol.
+        let one_hop = QueryBuilder::new(&graph)
+            .with_label("Person")
+            .with_property("start", serde_json::json!(true))
+            .traverse()?;
+        let reached_ids: HashSet<Uuid> = one_hop.nodes.iter().map(|n| n.id).collect();
+        assert!(reached_ids.contains(&alice.id));
+        assert!(reached_ids.contains(&bob.id));
+        assert!(!reached_ids.contains(&carol.id));
+
+        // With two hops, Alice's traversal also reaches Carol even though
+        // there is no direct edge between them.
+        let two_hops = QueryBuilder::new(&graph)
+            .with_label("Person")
+            .with_property("start", serde_json::json!(true))
+            .with_hops(2)
+            .traverse()?;
+        let path_to_carol = two_hops
+            .paths
+            .iter()
+            .find(|path| path.last().unwrap().0.id == carol.id && path.len() == 2);
+        assert!(path_to_carol.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_traverse_tracks_paths_and_respects_edge_type_and_hops() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let alice = Node::new("Person").with_property("start", true);
+        let bob = Node::new("Person");
+        let carol = Node::new("Person");
+
+        graph.add_node(alice.clone())?;
+        graph.add_node(bob.clone())?;
+        graph.add_node(carol.clone())?;
+
+        graph.add_edge(&Edge::new("KNOWS", alice.id, bob.id))?;
+        graph.add_edge(&Edge::new("WORKS_WITH", alice.id, carol.id))?;
+        graph.add_edge(&Edge::new("KNOWS", bob.id, carol.id))?;
+
+        let result = QueryBuilder::new(&graph)
+            .with_property("start", serde_json::json!(true))
+            .with_node_type("Person")
+            .with_edge_type("KNOWS")
+            .with_hops(2)
+            .traverse()?;
+
+        let reached: HashMap<Uuid, &Vec<(Node, Edge)>> =
+            result.paths.iter().map(|path| (path.last().unwrap().0.id, path)).collect();
+
+        // Alice -> Bob -> Carol is reachable by following only KNOWS edges,
+        // but Alice's direct WORKS_WITH edge to Carol is filtered out, so
+        // Carol's shortest recorded route is the two-hop one through Bob.
+        assert_eq!(reached[&carol.id].len(), 2);
+        assert_eq!(reached[&carol.id][0].1.label, "KNOWS");
+        assert_eq!(reached[&carol.id][1].1.label, "KNOWS");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_cache_invalidates_on_write() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Widget"))?;
+
+        // First call computes and caches; second call against an
+        // unchanged graph should return the same (cached) result.
+        let first = QueryBuilder::new(&graph).with_label("Widget").execute()?;
+        let second = QueryBuilder::new(&graph).with_label("Widget").execute()?;
+        assert_eq!(first.nodes.len(), 1);
+        assert_eq!(second.nodes.len(), 1);
+
+        // Adding another matching node must invalidate the cached result.
+        graph.add_node(Node::new("Widget"))?;
+        let third = QueryBuilder::new(&graph).with_label("Widget").execute()?;
+        assert_eq!(third.nodes.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_cache_invalidates_property_only_query() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        graph.add_node(Node::new("Gadget").with_property("color", "red"))?;
+
+        // No label filter, so this falls back to a full scan and should
+        // still be correctly invalidated by later writes.
+        let first = QueryBuilder::new(&graph)
+            .with_property("color", serde_json::json!("red"))
+            .execute()?;
+        assert_eq!(first.nodes.len(), 1);
+
+        graph.add_node(Node::new("Gizmo").with_property("color", "red"))?;
+        let second = QueryBuilder::new(&graph)
+            .with_property("color", serde_json::json!("red"))
+            .execute()?;
+        assert_eq!(second.nodes.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_from_follows_transitive_edges() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let alice = Node::new("Person");
+        let bob = Node::new("Person");
+        let carol = Node::new("Person");
+        let unrelated = Node::new("Person");
+
+        graph.add_node(alice.clone())?;
+        graph.add_node(bob.clone())?;
+        graph.add_node(carol.clone())?;
+        graph.add_node(unrelated.clone())?;
+
+        graph.add_edge(&Edge::new("KNOWS", alice.id, bob.id))?;
+        graph.add_edge(&Edge::new("KNOWS", bob.id, carol.id))?;
+
+        let result = graph.reachable_from(alice.id, &["KNOWS"], None)?;
+        let ids: std::collections::HashSet<_> = result.nodes.iter().map(|n| n.id).collect();
+
+        assert_eq!(ids, [bob.id, carol.id].into_iter().collect());
+        assert_eq!(result.edges.len(), 2);
+        assert!(!ids.contains(&alice.id));
+        assert!(!ids.contains(&unrelated.id));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_from_respects_rel_type_filter() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let alice = Node::new("Person");
+        let acme = Node::new("Company");
+        let bob = Node::new("Person");
+
+        graph.add_node(alice.clone())?;
+        graph.add_node(acme.clone())?;
+        graph.add_node(bob.clone())?;
+
+        graph.add_edge(&Edge::new("WORKS_AT", alice.id, acme.id))?;
+        graph.add_edge(&Edge::new("KNOWS", alice.id, bob.id))?;
+
+        let result = graph.reachable_from(alice.id, &["WORKS_AT"], None)?;
+        assert_eq!(result.nodes.len(), 1);
+        assert_eq!(result.nodes[0].id, acme.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_from_max_depth_bounds_the_search() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let a = Node::new("Node");
+        let b = Node::new("Node");
+        let c = Node::new("Node");
+
+        graph.add_node(a.clone())?;
+        graph.add_node(b.clone())?;
+        graph.add_node(c.clone())?;
+
+        graph.add_edge(&Edge::new("NEXT", a.id, b.id))?;
+        graph.add_edge(&Edge::new("NEXT", b.id, c.id))?;
+
+        let result = graph.reachable_from(a.id, &["NEXT"], Some(1))?;
+        assert_eq!(result.nodes.len(), 1);
+        assert_eq!(result.nodes[0].id, b.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachable_from_terminates_on_a_cycle() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let a = Node::new("Node");
+        let b = Node::new("Node");
+
+        graph.add_node(a.clone())?;
+        graph.add_node(b.clone())?;
+
+        graph.add_edge(&Edge::new("NEXT", a.id, b.id))?;
+        graph.add_edge(&Edge::new("NEXT", b.id, a.id))?;
+
+        let result = graph.reachable_from(a.id, &["NEXT"], None)?;
+        assert_eq!(result.nodes.len(), 1);
+        assert_eq!(result.nodes[0].id, b.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_text_search_ranks_by_relevance() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let best = Node::new("Language").with_property("name", "python programming language");
+        let partial = Node::new("Language").with_property("name", "python the snake");
+        let unrelated = Node::new("Language").with_property("name", "rust systems language");
+
+        graph.add_node(best.clone())?;
+        graph.add_node(partial.clone())?;
+        graph.add_node(unrelated.clone())?;
+
+        let hits = graph.full_text_search("python programming")?;
+        let ids: Vec<Uuid> = hits.iter().map(|hit| hit.node.id).collect();
+
+        assert!(ids.contains(&best.id));
+        assert!(ids.contains(&partial.id));
+        assert!(!ids.contains(&unrelated.id));
+        assert_eq!(ids[0], best.id); // matches both query tokens, ranks first
+        assert!(hits.iter().all(|hit| hit.score > 0.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_text_search_tolerates_typos() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let node = Node::new("Language").with_property("name", "python programming");
+        graph.add_node(node.clone())?;
+
+        let hits = graph.full_text_search("pythn")?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].node.id, node.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_text_search_includes_a_snippet() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let node = Node::new("Language").with_property("name", "python programming language");
+        graph.add_node(node.clone())?;
+
+        let hits = graph.full_text_search("python")?;
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].snippet.to_lowercase().contains("python"));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,525 @@
+//! Incremental memoization for [`KnowledgeGraph`](crate::graph::KnowledgeGraph)'s
+//! read queries, modeled on rustc's query-system dependency graph.
+//!
+//! Each cached result records the "inputs" it read (a label, a registered
+//! property-index entry, a node's own record, a node's outgoing-edge list)
+//! and a fingerprint of the serialized result. Every write bumps a global
+//! generation counter and marks the inputs it touches dirty at that
+//! generation. A cached result is "green" — safe to reuse without
+//! recomputing — as long as none of its recorded inputs were dirtied after
+//! it was last validated. When a stale result is recomputed but turns out
+//! byte-identical to what was cached, it's re-marked green at the current
+//! generation instead of evicted, so a write that doesn't actually change a
+//! query's answer doesn't force it to redo the same work again next time.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use lru::LruCache;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{Edge, Node, PropertyValue};
+use crate::query::QueryResult;
+use crate::storage;
+
+/// Each sub-cache is bounded to this many entries by LRU eviction.
+const QUERY_CACHE_CAPACITY: usize = 256;
+
+/// An input a cached query result depends on. Writes mark these dirty;
+/// a query compares its recorded inputs against the dirty set to decide
+/// whether its cached result is still valid.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Dep {
+    /// The label index for a label, touched by `add_node`/`remove_node`.
+    Label(String),
+    /// A registered property-index entry, touched whenever a node with
+    /// that label/property is added or removed.
+    Property(String, String),
+    /// A specific node's own record.
+    Node(Uuid),
+    /// A node's outgoing-edge adjacency list, touched by `add_edge`.
+    OutgoingEdges(Uuid),
+    /// Every node in the graph, touched by any `add_node`/`remove_node`/
+    /// `replace_node`. Used for queries whose read isn't scoped to a
+    /// single label or property index — e.g. a full `get_nodes()` scan —
+    /// so there's no narrower input to record without risking a stale hit.
+    AllNodes,
+}
+
+struct Entry<T> {
+    result: T,
+    fingerprint: u64,
+    deps: Vec<Dep>,
+    validated_at: u64,
+}
+
+/// How a [`QueryCache`] persists its entries to disk, set via
+/// [`KnowledgeGraph::with_cache_policy`](crate::graph::KnowledgeGraph::with_cache_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CachePolicy {
+    /// Never touch disk; the cache starts cold and is rebuilt from scratch
+    /// every run. The default.
+    #[default]
+    InMemory,
+    /// Persist every `record()` to the backing Sled tree immediately, so a
+    /// process crash loses at most the in-flight query.
+    WriteThrough,
+    /// Keep entries in memory only until an explicit
+    /// [`flush_cache`](crate::graph::KnowledgeGraph::flush_cache) call,
+    /// trading crash-durability for fewer disk round-trips on a hot cache.
+    WriteBack,
+}
+
+/// On-disk representation of a cached entry, independent of `Entry<T>` so
+/// the in-memory type isn't forced to carry `Deserialize` for callers that
+/// never persist.
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry<T> {
+    result: T,
+    fingerprint: u64,
+    deps: Vec<Dep>,
+    validated_at: u64,
+}
+
+/// Tags distinguishing each memo table's entries within the shared Sled
+/// tree, prefixed onto every persisted key.
+const LABEL_TAG: u8 = 0;
+const PROPERTY_TAG: u8 = 1;
+const EDGES_TAG: u8 = 2;
+const QUERY_TAG: u8 = 3;
+
+/// Fingerprint `value` by bincode-serializing it and hashing the bytes,
+/// so the result type only needs to be `Serialize`, matching how it's
+/// already written to storage.
+fn fingerprint_of<T: Serialize>(value: &T) -> Result<u64> {
+    let bytes = storage::serialize(value)?;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// A single query shape's bounded, generation-aware memo table.
+struct Memo<K, T> {
+    entries: RwLock<LruCache<K, Entry<T>>>,
+}
+
+impl<K: Hash + Eq, T: Clone + Serialize> Memo<K, T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: RwLock::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or_else(|| NonZeroUsize::new(1).unwrap()),
+            )),
+        }
+    }
+
+    /// Return the cached result for `key` if it's green: every dep it was
+    /// recorded against is either untouched or was last dirtied at or
+    /// before the generation the entry was validated at.
+    fn get_green(&self, key: &K, dirty: &HashMap<Dep, u64>) -> Option<T> {
+        let mut entries = self.entries.write().unwrap();
+        let entry = entries.get(key)?;
+        let green = entry
+            .deps
+            .iter()
+            .all(|dep| dirty.get(dep).map_or(true, |&at| at <= entry.validated_at));
+        green.then(|| entry.result.clone())
+    }
+
+    /// Record a freshly computed `result`. If it fingerprints identical to
+    /// what's already cached for `key`, the existing entry is simply
+    /// re-validated at `generation` rather than replaced.
+    fn record(&self, key: K, result: T, deps: Vec<Dep>, generation: u64) -> Result<()> {
+        let fp = fingerprint_of(&result)?;
+        let mut entries = self.entries.write().unwrap();
+
+        if let Some(existing) = entries.peek_mut(&key) {
+            if existing.fingerprint == fp {
+                existing.validated_at = generation;
+                return Ok(());
+            }
+        }
+
+        entries.put(
+            key,
+            Entry {
+                result,
+                fingerprint: fp,
+                deps,
+                validated_at: generation,
+            },
+        );
+        Ok(())
+    }
+
+    fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+}
+
+impl<K: Hash + Eq + Clone + Serialize + DeserializeOwned, T: Clone + Serialize + DeserializeOwned>
+    Memo<K, T>
+{
+    /// Persist every resident entry into `tree`, keyed by `tag` followed by
+    /// the bincode-serialized key, so multiple memo tables can share one
+    /// Sled tree without their keys colliding.
+    fn persist_all(&self, tree: &sled::Tree, tag: u8) -> Result<()> {
+        let entries = self.entries.read().unwrap();
+        for (key, entry) in entries.iter() {
+            let mut sled_key = vec![tag];
+            sled_key.extend(storage::serialize(key)?);
+            let bytes = storage::serialize(&PersistedEntry {
+                result: entry.result.clone(),
+                fingerprint: entry.fingerprint,
+                deps: entry.deps.clone(),
+                validated_at: entry.validated_at,
+            })?;
+            tree.insert(sled_key, bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Reload every entry tagged `tag` from `tree`. Entries keep the
+    /// `validated_at` generation they were saved with; since a freshly
+    /// opened cache's dirty map starts empty, a restored entry stays green
+    /// exactly until this process's first write touches one of its deps —
+    /// at which point it's revalidated like any other entry, never trusted
+    /// purely because it came from disk.
+    fn restore_all(&self, tree: &sled::Tree, tag: u8) -> Result<()> {
+        let mut entries = self.entries.write().unwrap();
+        for item in tree.scan_prefix([tag]) {
+            let (sled_key, bytes) = item?;
+            let key: K = storage::deserialize(&sled_key[1..])?;
+            let persisted: PersistedEntry<T> = storage::deserialize(&bytes)?;
+            entries.put(
+                key,
+                Entry {
+                    result: persisted.result,
+                    fingerprint: persisted.fingerprint,
+                    deps: persisted.deps,
+                    validated_at: persisted.validated_at,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Cache hit/miss/invalidation counters exposed for observability, snapshot
+/// from [`QueryCache::stats`]/[`KnowledgeGraph::cache_stats`](crate::graph::KnowledgeGraph::cache_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Queries served from a green cached entry without recomputing.
+    pub hits: u64,
+    /// Queries that found no entry, or found one that was no longer green,
+    /// and had to recompute.
+    pub misses: u64,
+    /// Dependencies marked dirty by writes, i.e. how many `(Dep, generation)`
+    /// invalidation records have been recorded across every `mark_dirty`
+    /// call. Not the number of cached entries actually evicted -- an entry
+    /// recorded against a dirtied dep is only dropped lazily, on its next
+    /// access.
+    pub invalidations: u64,
+}
+
+/// The incremental query cache backing `find_nodes_by_label`,
+/// `find_nodes_by_property`, `query_edges_from`, and whole
+/// `QueryBuilder::execute()` traversals.
+pub(crate) struct QueryCache {
+    generation: AtomicU64,
+    dirty: RwLock<HashMap<Dep, u64>>,
+    labels: Memo<String, Vec<Node>>,
+    properties: Memo<(String, String, String), Vec<Node>>,
+    edges_from: Memo<Uuid, Vec<Edge>>,
+    queries: Memo<u64, QueryResult>,
+    policy: CachePolicy,
+    /// The dedicated Sled tree entries are persisted to under
+    /// [`CachePolicy::WriteThrough`]/[`CachePolicy::WriteBack`]. `None`
+    /// under [`CachePolicy::InMemory`], where the cache never touches disk.
+    tree: Option<sled::Tree>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl fmt::Debug for QueryCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryCache")
+            .field("policy", &self.policy)
+            .finish_non_exhaustive()
+    }
+}
+
+impl QueryCache {
+    pub(crate) fn new() -> Self {
+        Self::with_policy(CachePolicy::InMemory, None)
+    }
+
+    fn with_policy(policy: CachePolicy, tree: Option<sled::Tree>) -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            dirty: RwLock::new(HashMap::new()),
+            labels: Memo::new(QUERY_CACHE_CAPACITY),
+            properties: Memo::new(QUERY_CACHE_CAPACITY),
+            edges_from: Memo::new(QUERY_CACHE_CAPACITY),
+            queries: Memo::new(QUERY_CACHE_CAPACITY),
+            policy,
+            tree,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    /// Snapshot of this cache's hit/miss/invalidation counters.
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Open a cache backed by `tree`, reloading and revalidating every
+    /// entry persisted by a previous run before returning.
+    pub(crate) fn open(tree: sled::Tree, policy: CachePolicy) -> Result<Self> {
+        let cache = Self::with_policy(policy, Some(tree));
+        cache.reload()?;
+        Ok(cache)
+    }
+
+    fn reload(&self) -> Result<()> {
+        let Some(tree) = &self.tree else {
+            return Ok(());
+        };
+        self.labels.restore_all(tree, LABEL_TAG)?;
+        self.properties.restore_all(tree, PROPERTY_TAG)?;
+        self.edges_from.restore_all(tree, EDGES_TAG)?;
+        self.queries.restore_all(tree, QUERY_TAG)?;
+        Ok(())
+    }
+
+    /// Persist every in-memory entry to the backing Sled tree. A no-op
+    /// under [`CachePolicy::InMemory`] or when no tree was configured.
+    /// [`CachePolicy::WriteBack`] callers should call this before shutting
+    /// down to avoid losing entries accumulated since the last flush.
+    pub(crate) fn flush(&self) -> Result<()> {
+        let Some(tree) = &self.tree else {
+            return Ok(());
+        };
+        self.labels.persist_all(tree, LABEL_TAG)?;
+        self.properties.persist_all(tree, PROPERTY_TAG)?;
+        self.edges_from.persist_all(tree, EDGES_TAG)?;
+        self.queries.persist_all(tree, QUERY_TAG)?;
+        tree.flush()?;
+        Ok(())
+    }
+
+    /// Drop every cached entry, in memory and (if configured) on disk.
+    pub(crate) fn clear(&self) -> Result<()> {
+        self.labels.clear();
+        self.properties.clear();
+        self.edges_from.clear();
+        self.queries.clear();
+        if let Some(tree) = &self.tree {
+            tree.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Persist `memo`'s current entries immediately when running under
+    /// [`CachePolicy::WriteThrough`]; a no-op under any other policy.
+    fn persist_if_write_through<K: Hash + Eq + Clone + Serialize + DeserializeOwned, T: Clone + Serialize + DeserializeOwned>(
+        &self,
+        memo: &Memo<K, T>,
+        tag: u8,
+    ) -> Result<()> {
+        if self.policy != CachePolicy::WriteThrough {
+            return Ok(());
+        }
+        let Some(tree) = &self.tree else {
+            return Ok(());
+        };
+        memo.persist_all(tree, tag)
+    }
+
+    fn mark_dirty(&self, deps: impl IntoIterator<Item = Dep>) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let mut dirty = self.dirty.write().unwrap();
+        let mut count = 0u64;
+        for dep in deps {
+            dirty.insert(dep, generation);
+            count += 1;
+        }
+        self.invalidations.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// The cache dependencies `add_node`/`remove_node` touch for `node`:
+    /// its label index, every registered property index it participates
+    /// in, and its own record. Exposed so a caller committing several
+    /// writes at once (see [`BatchTransaction`](crate::graph::BatchTransaction))
+    /// can collect every touched dependency and mark them all dirty
+    /// together via [`dirty_batch`](Self::dirty_batch), bumping the
+    /// generation once for the whole commit instead of once per write.
+    pub(crate) fn node_write_deps(
+        &self,
+        registry: &[(String, String)],
+        node: &Node,
+    ) -> Vec<Dep> {
+        let mut deps = vec![Dep::Label(node.label.clone()), Dep::Node(node.id), Dep::AllNodes];
+        for (label, property) in registry {
+            if label == &node.label {
+                deps.push(Dep::Property(label.clone(), property.clone()));
+            }
+        }
+        deps
+    }
+
+    /// Mark the inputs `add_node`/`remove_node` touched for `node` dirty:
+    /// its label index, every registered property index it participates
+    /// in, and its own record.
+    pub(crate) fn dirty_node_write(
+        &self,
+        registry: &[(String, String)],
+        node: &Node,
+    ) {
+        self.mark_dirty(self.node_write_deps(registry, node));
+    }
+
+    /// Mark every dependency in `deps` dirty at the same generation, so a
+    /// multi-write commit bumps the revision exactly once instead of once
+    /// per write it contains.
+    pub(crate) fn dirty_batch(&self, deps: impl IntoIterator<Item = Dep>) {
+        self.mark_dirty(deps);
+    }
+
+    /// Mark `node_id`'s outgoing-edge adjacency list dirty, as touched by
+    /// `add_edge`.
+    pub(crate) fn dirty_outgoing_edges(&self, node_id: Uuid) {
+        self.mark_dirty([Dep::OutgoingEdges(node_id)]);
+    }
+
+    /// Mark a property index dirty, as touched by `create_index`/
+    /// `drop_index` coming into or out of existence.
+    pub(crate) fn dirty_property_index(&self, label: &str, property: &str) {
+        self.mark_dirty([Dep::Property(label.to_string(), property.to_string())]);
+    }
+
+    /// Serve `find_nodes_by_label(label)` from cache when green, else run
+    /// `compute` and record the result.
+    pub(crate) fn find_nodes_by_label(
+        &self,
+        label: &str,
+        compute: impl FnOnce() -> Result<Vec<Node>>,
+    ) -> Result<Vec<Node>> {
+        {
+            let dirty = self.dirty.read().unwrap();
+            if let Some(cached) = self.labels.get_green(&label.to_string(), &dirty) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = compute()?;
+        let deps = std::iter::once(Dep::Label(label.to_string()))
+            .chain(result.iter().map(|n| Dep::Node(n.id)))
+            .collect();
+        let generation = self.generation.load(Ordering::SeqCst);
+        self.labels
+            .record(label.to_string(), result.clone(), deps, generation)?;
+        self.persist_if_write_through(&self.labels, LABEL_TAG)?;
+        Ok(result)
+    }
+
+    /// Serve `find_nodes_by_property(label, property, value)` from cache
+    /// when green, else run `compute` and record the result.
+    pub(crate) fn find_nodes_by_property(
+        &self,
+        label: &str,
+        property: &str,
+        value: &PropertyValue,
+        compute: impl FnOnce() -> Result<Vec<Node>>,
+    ) -> Result<Vec<Node>> {
+        let key = (label.to_string(), property.to_string(), value.to_string());
+
+        {
+            let dirty = self.dirty.read().unwrap();
+            if let Some(cached) = self.properties.get_green(&key, &dirty) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = compute()?;
+        let deps = std::iter::once(Dep::Property(label.to_string(), property.to_string()))
+            .chain(result.iter().map(|n| Dep::Node(n.id)))
+            .collect();
+        let generation = self.generation.load(Ordering::SeqCst);
+        self.properties.record(key, result.clone(), deps, generation)?;
+        self.persist_if_write_through(&self.properties, PROPERTY_TAG)?;
+        Ok(result)
+    }
+
+    /// Serve `query_edges_from(node_id)` from cache when green, else run
+    /// `compute` and record the result.
+    pub(crate) fn query_edges_from(
+        &self,
+        node_id: Uuid,
+        compute: impl FnOnce() -> Result<Vec<Edge>>,
+    ) -> Result<Vec<Edge>> {
+        {
+            let dirty = self.dirty.read().unwrap();
+            if let Some(cached) = self.edges_from.get_green(&node_id, &dirty) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = compute()?;
+        let deps = vec![Dep::OutgoingEdges(node_id)];
+        let generation = self.generation.load(Ordering::SeqCst);
+        self.edges_from.record(node_id, result.clone(), deps, generation)?;
+        self.persist_if_write_through(&self.edges_from, EDGES_TAG)?;
+        Ok(result)
+    }
+
+    /// Serve a whole `QueryBuilder::execute()` traversal from cache when
+    /// green, keyed by a hash of its filter criteria, limit, and offset.
+    /// `base_deps` are the inputs the query's filters read directly (a
+    /// `Dep::Label` for a single-label query, `Dep::AllNodes` for anything
+    /// that falls back to a full node scan); every matching node's own
+    /// record is added automatically so an update to a result node also
+    /// invalidates the entry.
+    pub(crate) fn execute_query(
+        &self,
+        hash: u64,
+        base_deps: Vec<Dep>,
+        compute: impl FnOnce() -> Result<QueryResult>,
+    ) -> Result<QueryResult> {
+        {
+            let dirty = self.dirty.read().unwrap();
+            if let Some(cached) = self.queries.get_green(&hash, &dirty) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached);
+            }
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let result = compute()?;
+        let mut deps = base_deps;
+        deps.extend(result.nodes.iter().map(|n| Dep::Node(n.id)));
+        let generation = self.generation.load(Ordering::SeqCst);
+        self.queries.record(hash, result.clone(), deps, generation)?;
+        self.persist_if_write_through(&self.queries, QUERY_TAG)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,486 @@
+//! RDF (N-Triples / Turtle) import and export for [`KnowledgeGraph`].
+//!
+//! Every [`Node`] maps to a subject IRI `urn:maya:node:<uuid>`: its `label`
+//! becomes an `rdf:type` triple, and each scalar [`Property`] becomes a
+//! triple whose predicate IRI is `urn:maya:prop:<key>` and whose object is
+//! a literal typed to match the property's [`PropertyValue`] (a plain
+//! string literal, or `^^xsd:integer`/`^^xsd:double`/`^^xsd:boolean` for
+//! numbers and bools). Properties holding `null`, an array, or an object
+//! have no RDF literal equivalent and are skipped on export. Every [`Edge`]
+//! becomes a triple `(source_iri, urn:maya:rel:<label>, target_iri)`.
+//!
+//! [`export_rdf`](KnowledgeGraph::export_rdf) always writes one triple per
+//! line; under [`RdfFormat::Turtle`] it additionally emits a `@prefix`
+//! header and abbreviates `rdf:type` to `a`. [`import_rdf`](KnowledgeGraph::import_rdf)
+//! parses either back with the same line-oriented grammar: this covers the
+//! common "one assertion per line" dumps most RDF tooling produces and
+//! consumes, but not the full Turtle grammar (no multi-line literals, no
+//! `;`/`,` predicate/object lists, no blank nodes or collections).
+//!
+//! On import, triples are grouped by subject IRI to reconstruct each
+//! [`Node`] (and its properties) and each `urn:maya:rel:*` triple becomes
+//! an [`Edge`], then every node and edge is staged into a single
+//! [`BatchTransaction`](crate::graph::BatchTransaction) so a partially
+//! malformed document leaves the graph untouched.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use uuid::Uuid;
+
+use crate::error::{KnowledgeGraphError, Result};
+use crate::graph::KnowledgeGraph;
+use crate::models::{Edge, Node, Property, PropertyValue};
+use crate::storage::{Storage, WriteBatchExt};
+
+const NODE_NS: &str = "urn:maya:node:";
+const PROP_NS: &str = "urn:maya:prop:";
+const REL_NS: &str = "urn:maya:rel:";
+const TYPE_NS: &str = "urn:maya:type:";
+const RDF_TYPE_IRI: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#type";
+
+/// Which RDF serialization [`KnowledgeGraph::export_rdf`]/
+/// [`KnowledgeGraph::import_rdf`] should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RdfFormat {
+    /// One `<subject> <predicate> object .` triple per line, no prefixes.
+    NTriples,
+    /// N-Triples plus a `@prefix` header and `a` as shorthand for
+    /// `rdf:type`.
+    Turtle,
+}
+
+/// The parsed pieces of one triple's subject/predicate/object.
+struct Triple {
+    subject: String,
+    predicate: String,
+    object: RdfTerm,
+}
+
+/// An object position: either another IRI or a typed/untyped literal.
+enum RdfTerm {
+    Iri(String),
+    Literal { value: String, datatype: Option<String> },
+}
+
+/// Render `value` as the PropertyValue an RDF literal decodes back into,
+/// or `None` if `value` has no RDF literal equivalent (null, array,
+/// object).
+fn literal_for(value: &PropertyValue) -> Option<(String, Option<&'static str>)> {
+    match value {
+        PropertyValue::String(s) => Some((s.clone(), None)),
+        PropertyValue::Bool(b) => Some((b.to_string(), Some("xsd:boolean"))),
+        PropertyValue::Number(n) => {
+            if n.is_i64() || n.is_u64() {
+                Some((n.to_string(), Some("xsd:integer")))
+            } else {
+                Some((n.to_string(), Some("xsd:double")))
+            }
+        }
+        PropertyValue::Null | PropertyValue::Array(_) | PropertyValue::Object(_) => None,
+    }
+}
+
+/// Parse an RDF literal's lexical value/datatype back into the
+/// [`PropertyValue`] it was exported from.
+fn value_for(value: &str, datatype: Option<&str>) -> PropertyValue {
+    match datatype {
+        Some("xsd:boolean") | Some("http://www.w3.org/2001/XMLSchema#boolean") => {
+            PropertyValue::Bool(value == "true")
+        }
+        Some("xsd:integer") | Some("http://www.w3.org/2001/XMLSchema#integer") => value
+            .parse::<i64>()
+            .map(PropertyValue::from)
+            .unwrap_or_else(|_| PropertyValue::String(value.to_string())),
+        Some("xsd:double") | Some("http://www.w3.org/2001/XMLSchema#double") => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(PropertyValue::Number)
+            .unwrap_or_else(|| PropertyValue::String(value.to_string())),
+        _ => PropertyValue::String(value.to_string()),
+    }
+}
+
+/// Escape `"`, `\`, and newlines for an N-Triples/Turtle string literal.
+fn escape_literal(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Reverse of [`escape_literal`].
+fn unescape_literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn write_object<W: Write>(writer: &mut W, object: &RdfTerm) -> Result<()> {
+    match object {
+        RdfTerm::Iri(iri) => write!(writer, "<{}>", iri)?,
+        RdfTerm::Literal { value, datatype } => {
+            write!(writer, "\"{}\"", escape_literal(value))?;
+            if let Some(datatype) = datatype {
+                write!(writer, "^^{}", datatype)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+impl<S> KnowledgeGraph<S>
+where
+    S: Storage + WriteBatchExt,
+{
+    /// Write every node and edge as RDF triples to `writer` in `format`.
+    pub fn export_rdf<W: Write>(&self, writer: &mut W, format: RdfFormat) -> Result<()> {
+        if format == RdfFormat::Turtle {
+            writeln!(writer, "@prefix rdf: <{}> .", "http://www.w3.org/1999/02/22-rdf-syntax-ns#")?;
+            writeln!(writer, "@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .")?;
+            writeln!(writer)?;
+        }
+        // NTriples has no prefixed names, so `rdf:type` needs the full IRI there;
+        // Turtle gets the conventional `a` shorthand.
+        let type_predicate_full = format!("<{}>", RDF_TYPE_IRI);
+        let type_predicate = if format == RdfFormat::Turtle { "a" } else { type_predicate_full.as_str() };
+
+        for node in self.get_nodes()? {
+            let subject = format!("{}{}", NODE_NS, node.id);
+            writeln!(writer, "<{}> {} <{}{}> .", subject, type_predicate, TYPE_NS, node.label)?;
+
+            for property in &node.properties {
+                let Some((value, datatype)) = literal_for(&property.value) else {
+                    continue;
+                };
+                // N-Triples has no prefixed names either, so the datatype is
+                // spelled out as a full `<...>` IRI there.
+                let datatype = datatype.map(|d| match format {
+                    RdfFormat::NTriples => format!("<http://www.w3.org/2001/XMLSchema#{}>", d.trim_start_matches("xsd:")),
+                    RdfFormat::Turtle => d.to_string(),
+                });
+                write!(writer, "<{}> <{}{}> ", subject, PROP_NS, property.key)?;
+                write_object(writer, &RdfTerm::Literal { value, datatype })?;
+                writeln!(writer, " .")?;
+            }
+        }
+
+        for node in self.get_nodes()? {
+            for edge in self.query_edges_from(node.id)? {
+                writeln!(
+                    writer,
+                    "<{}{}> <{}{}> <{}{}> .",
+                    NODE_NS, edge.source, REL_NS, edge.label, NODE_NS, edge.target
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read RDF triples from `reader` in `format`, reconstruct the nodes
+    /// and edges they describe, and insert them through
+    /// [`begin_transaction`](Self::begin_transaction) so the whole import
+    /// commits atomically.
+    ///
+    /// Nodes are created with freshly generated ids unless the subject IRI
+    /// is itself one this module would export (`urn:maya:node:<uuid>`), in
+    /// which case that uuid is reused -- round-tripping a document this
+    /// module exported preserves node identity.
+    pub fn import_rdf<R: Read>(&self, reader: R, format: RdfFormat) -> Result<()> {
+        let mut text = String::new();
+        let mut reader = reader;
+        reader.read_to_string(&mut text)?;
+
+        let mut prefixes: HashMap<String, String> = HashMap::new();
+        prefixes.insert("rdf".to_string(), "http://www.w3.org/1999/02/22-rdf-syntax-ns#".to_string());
+        prefixes.insert("xsd".to_string(), "http://www.w3.org/2001/XMLSchema#".to_string());
+
+        let mut triples = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if format == RdfFormat::Turtle && line.starts_with("@prefix") {
+                if let Some((name, iri)) = parse_prefix_line(line) {
+                    prefixes.insert(name, iri);
+                }
+                continue;
+            }
+            triples.push(parse_triple_line(line, &prefixes)?);
+        }
+
+        let mut nodes_by_subject: HashMap<String, Node> = HashMap::new();
+        let mut edges = Vec::new();
+
+        for triple in &triples {
+            if let Some(label) = triple.predicate.strip_prefix("urn:maya:rel:") {
+                let RdfTerm::Iri(object) = &triple.object else {
+                    return Err(KnowledgeGraphError::InvalidOperation(format!(
+                        "relationship triple's object must be an IRI, got a literal: {}",
+                        triple.subject
+                    )));
+                };
+                edges.push((triple.subject.clone(), label.to_string(), object.clone()));
+                continue;
+            }
+
+            let node = nodes_by_subject
+                .entry(triple.subject.clone())
+                .or_insert_with(|| new_node_for_subject(&triple.subject));
+
+            if triple.predicate == RDF_TYPE_IRI {
+                if let RdfTerm::Iri(label) = &triple.object {
+                    node.label = label.strip_prefix(TYPE_NS).unwrap_or(label).to_string();
+                }
+                continue;
+            }
+
+            if let Some(key) = triple.predicate.strip_prefix(PROP_NS) {
+                if let RdfTerm::Literal { value, datatype } = &triple.object {
+                    let value = unescape_literal(value);
+                    node.properties.push(Property::new(key, value_for(&value, datatype.as_deref())));
+                }
+            }
+        }
+
+        let subject_to_id: HashMap<String, Uuid> =
+            nodes_by_subject.iter().map(|(subject, node)| (subject.clone(), node.id)).collect();
+
+        let mut tx = self.begin_transaction();
+        for node in nodes_by_subject.into_values() {
+            tx.stage_add_node(node);
+        }
+        for (source_subject, label, target_subject) in edges {
+            let source = *subject_to_id.get(&source_subject).ok_or_else(|| {
+                KnowledgeGraphError::InvalidOperation(format!("edge references unknown subject: {}", source_subject))
+            })?;
+            let target = *subject_to_id.get(&target_subject).ok_or_else(|| {
+                KnowledgeGraphError::InvalidOperation(format!("edge references unknown subject: {}", target_subject))
+            })?;
+            tx.stage_add_edge(Edge::new(&label, source, target));
+        }
+        tx.commit()
+    }
+}
+
+/// A freshly-initialized [`Node`] for `subject`, reusing the uuid embedded
+/// in `urn:maya:node:<uuid>` subjects so re-importing a document this
+/// module exported preserves node identity.
+fn new_node_for_subject(subject: &str) -> Node {
+    let mut node = Node::new("Thing");
+    if let Some(uuid_str) = subject.strip_prefix(NODE_NS) {
+        if let Ok(uuid) = Uuid::parse_str(uuid_str) {
+            node.id = uuid;
+        }
+    }
+    node
+}
+
+/// Parse a Turtle `@prefix name: <iri> .` header line.
+fn parse_prefix_line(line: &str) -> Option<(String, String)> {
+    let rest = line.strip_prefix("@prefix")?.trim();
+    let (name, rest) = rest.split_once(':')?;
+    let rest = rest.trim();
+    let iri = rest.strip_prefix('<')?;
+    let iri = iri.split('>').next()?;
+    Some((name.trim().to_string(), iri.to_string()))
+}
+
+/// Expand a Turtle prefixed name (`rdf:type`) or bare `a` shorthand into a
+/// full IRI using `prefixes`; IRIs already in `<...>` form pass through
+/// [`read_iri`] instead and never reach this function.
+fn expand_prefixed(term: &str, prefixes: &HashMap<String, String>) -> Result<String> {
+    if term == "a" {
+        return Ok(RDF_TYPE_IRI.to_string());
+    }
+    match term.split_once(':') {
+        Some((prefix, local)) if prefixes.contains_key(prefix) => {
+            Ok(format!("{}{}", prefixes[prefix], local))
+        }
+        _ => Err(KnowledgeGraphError::InvalidOperation(format!("unrecognized RDF term: {}", term))),
+    }
+}
+
+/// Parse one `<subject> <predicate> object .` (or Turtle-prefixed
+/// equivalent) line into a [`Triple`].
+fn parse_triple_line(line: &str, prefixes: &HashMap<String, String>) -> Result<Triple> {
+    let line = line.strip_suffix('.').unwrap_or(line).trim();
+    let mut rest = line;
+
+    let (subject, tail) = read_term(rest, prefixes)?;
+    rest = tail;
+    let (predicate, tail) = read_term(rest, prefixes)?;
+    rest = tail;
+    let (object, tail) = read_term(rest, prefixes)?;
+    if !tail.trim().is_empty() {
+        return Err(KnowledgeGraphError::InvalidOperation(format!("unexpected trailing tokens: {}", line)));
+    }
+
+    let subject = match subject {
+        RdfTerm::Iri(iri) => iri,
+        RdfTerm::Literal { .. } => {
+            return Err(KnowledgeGraphError::InvalidOperation("triple subject must be an IRI".to_string()))
+        }
+    };
+    let predicate = match predicate {
+        RdfTerm::Iri(iri) => iri,
+        RdfTerm::Literal { .. } => {
+            return Err(KnowledgeGraphError::InvalidOperation("triple predicate must be an IRI".to_string()))
+        }
+    };
+
+    Ok(Triple { subject, predicate, object })
+}
+
+/// Read one whitespace-delimited term (an `<iri>`, a Turtle prefixed
+/// name/`a`, or a `"literal"[^^type]`) from the front of `input`, returning
+/// it plus the unconsumed remainder.
+fn read_term<'a>(input: &'a str, prefixes: &HashMap<String, String>) -> Result<(RdfTerm, &'a str)> {
+    let input = input.trim_start();
+    if let Some(rest) = input.strip_prefix('<') {
+        let (iri, rest) = rest
+            .split_once('>')
+            .ok_or_else(|| KnowledgeGraphError::InvalidOperation("unterminated IRI".to_string()))?;
+        return Ok((RdfTerm::Iri(iri.to_string()), rest));
+    }
+
+    if let Some(rest) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.char_indices().peekable();
+        let mut end_byte = None;
+        while let Some((idx, ch)) = chars.next() {
+            if ch == '\\' {
+                if let Some(&(_, escaped)) = chars.peek() {
+                    value.push(ch);
+                    value.push(escaped);
+                    chars.next();
+                }
+                continue;
+            }
+            if ch == '"' {
+                end_byte = Some(idx);
+                break;
+            }
+            value.push(ch);
+        }
+        let end_byte = end_byte
+            .ok_or_else(|| KnowledgeGraphError::InvalidOperation("unterminated string literal".to_string()))?;
+        let mut rest = &rest[end_byte + 1..];
+
+        let datatype = if let Some(after_marker) = rest.strip_prefix("^^") {
+            let (datatype_term, after_datatype) = read_term(after_marker, prefixes)?;
+            rest = after_datatype;
+            match datatype_term {
+                RdfTerm::Iri(iri) => Some(iri),
+                RdfTerm::Literal { .. } => None,
+            }
+        } else {
+            None
+        };
+
+        return Ok((RdfTerm::Literal { value, datatype }, rest));
+    }
+
+    let end = input.find(char::is_whitespace).unwrap_or(input.len());
+    let (token, rest) = input.split_at(end);
+    if token.is_empty() {
+        return Err(KnowledgeGraphError::InvalidOperation("expected a term, found end of line".to_string()));
+    }
+    Ok((RdfTerm::Iri(expand_prefixed(token, prefixes)?), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SledStore;
+
+    #[test]
+    fn test_export_ntriples_round_trips_through_import() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let alice = Node::new("Person")
+            .with_property("name", "Alice")
+            .with_property("age", 30)
+            .with_property("active", true);
+        let bob = Node::new("Person");
+        graph.add_node(alice.clone())?;
+        graph.add_node(bob.clone())?;
+        graph.add_edge(&Edge::new("KNOWS", alice.id, bob.id))?;
+
+        let mut buffer = Vec::new();
+        graph.export_rdf(&mut buffer, RdfFormat::NTriples)?;
+
+        let dir2 = tempfile::tempdir()?;
+        let store2 = SledStore::open(dir2.path())?;
+        let imported = KnowledgeGraph::new(store2);
+        imported.import_rdf(buffer.as_slice(), RdfFormat::NTriples)?;
+
+        let alice_back = imported.get_node(alice.id)?.expect("alice should round-trip");
+        assert_eq!(alice_back.label, "Person");
+        assert_eq!(alice_back.get_property("name"), Some(&PropertyValue::String("Alice".to_string())));
+        assert_eq!(alice_back.get_property("age"), Some(&PropertyValue::from(30)));
+        assert_eq!(alice_back.get_property("active"), Some(&PropertyValue::Bool(true)));
+
+        let edges = imported.query_edges_from(alice.id)?;
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].label, "KNOWS");
+        assert_eq!(edges[0].target, bob.id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_turtle_round_trips_through_import() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let widget = Node::new("Widget").with_property("sku", "W-1");
+        graph.add_node(widget.clone())?;
+
+        let mut buffer = Vec::new();
+        graph.export_rdf(&mut buffer, RdfFormat::Turtle)?;
+
+        let dir2 = tempfile::tempdir()?;
+        let store2 = SledStore::open(dir2.path())?;
+        let imported = KnowledgeGraph::new(store2);
+        imported.import_rdf(buffer.as_slice(), RdfFormat::Turtle)?;
+
+        let widget_back = imported.get_node(widget.id)?.expect("widget should round-trip");
+        assert_eq!(widget_back.label, "Widget");
+        assert_eq!(widget_back.get_property("sku"), Some(&PropertyValue::String("W-1".to_string())));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_rejects_edge_to_unknown_subject() {
+        let line = format!(
+            "<{}00000000-0000-0000-0000-000000000001> <{}KNOWS> <{}00000000-0000-0000-0000-000000000002> .",
+            NODE_NS, REL_NS, NODE_NS
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let graph = KnowledgeGraph::new(store);
+
+        let result = graph.import_rdf(line.as_bytes(), RdfFormat::NTriples);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Bitmap-backed transitive-closure reachability index for [`KnowledgeGraph`].
+//!
+//! Repeated reachability checks against [`algorithms`](crate::algorithms)'s
+//! BFS/DFS traversals each re-walk storage. [`ReachabilityIndex`] instead
+//! assigns every node a dense integer id, builds the direct adjacency
+//! matrix from every edge as a [`BitMatrix`], and iterates `row[i] |=
+//! row[j]` for every bit `j` set in row `i` until a full pass changes
+//! nothing -- the bitset analogue of Warshall's algorithm. Once built,
+//! `can_reach`/`reachable_set` are a single word lookup/row scan instead of
+//! a traversal.
+//!
+//! That closure pass is `O(n^2 * words_per_row)` in the worst case, so
+//! [`KnowledgeGraph::reachability_index`] doesn't rebuild on every
+//! `add_node`/`add_edge` -- it only marks the cached index stale (the same
+//! dirty-flag approach [`QueryCache`](crate::query_cache::QueryCache) uses)
+//! and rebuilds lazily the next time the index is actually requested.
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::graph::KnowledgeGraph;
+use crate::storage::{Storage, WriteBatchExt};
+
+/// A row-major bit matrix: row `r`, target `t` lives at word `r *
+/// words_per_row + t / 64`, bit `t % 64` of that word.
+#[derive(Debug, Clone)]
+pub struct BitMatrix {
+    num_rows: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// An all-zero matrix with `num_rows` rows and columns, `words_per_row
+    /// = ceil(num_rows / 64)` words wide.
+    pub fn new(num_rows: usize) -> Self {
+        let words_per_row = num_rows.div_ceil(64).max(1);
+        Self {
+            num_rows,
+            words_per_row,
+            words: vec![0u64; num_rows * words_per_row],
+        }
+    }
+
+    /// Number of rows (and columns) in the matrix.
+    pub fn len(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Whether the matrix has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.num_rows == 0
+    }
+
+    /// Set the bit at `(src, tgt)`, returning whether it was previously
+    /// unset -- the "changed" flag [`ReachabilityIndex::build`]'s fixpoint
+    /// iterates on.
+    pub fn set(&mut self, src: usize, tgt: usize) -> bool {
+        let index = src * self.words_per_row + tgt / 64;
+        let mask = 1u64 << (tgt % 64);
+        let changed = self.words[index] & mask == 0;
+        self.words[index] |= mask;
+        changed
+    }
+
+    /// Whether the bit at `(src, tgt)` is set.
+    pub fn get(&self, src: usize, tgt: usize) -> bool {
+        let index = src * self.words_per_row + tgt / 64;
+        let mask = 1u64 << (tgt % 64);
+        self.words[index] & mask != 0
+    }
+
+    /// OR every word of row `from` into row `into`, returning whether
+    /// `into` changed as a result.
+    pub fn union_row(&mut self, into: usize, from: usize) -> bool {
+        let mut changed = false;
+        for word in 0..self.words_per_row {
+            let from_word = self.words[from * self.words_per_row + word];
+            let into_index = into * self.words_per_row + word;
+            if self.words[into_index] | from_word != self.words[into_index] {
+                changed = true;
+                self.words[into_index] |= from_word;
+            }
+        }
+        changed
+    }
+
+    /// The column index of every set bit in `row`.
+    pub fn row_indices(&self, row: usize) -> Vec<usize> {
+        let mut indices = Vec::new();
+        for word in 0..self.words_per_row {
+            let mut remaining = self.words[row * self.words_per_row + word];
+            while remaining != 0 {
+                let bit = remaining.trailing_zeros() as usize;
+                indices.push(word * 64 + bit);
+                remaining &= remaining - 1;
+            }
+        }
+        indices
+    }
+}
+
+/// Dense node ids plus the transitive-closure [`BitMatrix`] they index
+/// into. Built by [`ReachabilityIndex::build`], returned by
+/// [`KnowledgeGraph::reachability_index`]; not constructed directly.
+#[derive(Debug)]
+pub struct ReachabilityIndex {
+    id_of: HashMap<Uuid, usize>,
+    node_of: Vec<Uuid>,
+    matrix: BitMatrix,
+}
+
+impl ReachabilityIndex {
+    /// Assign every node in `graph` a dense integer id, build the direct
+    /// adjacency matrix from every edge, then run the bitset fixpoint to
+    /// full transitive closure.
+    pub fn build<S: Storage + WriteBatchExt>(graph: &KnowledgeGraph<S>) -> Result<Self> {
+        let nodes = graph.get_nodes()?;
+        let node_of: Vec<Uuid> = nodes.iter().map(|node| node.id).collect();
+        let id_of: HashMap<Uuid, usize> = node_of
+            .iter()
+            .enumerate()
+            .map(|(dense_id, node_id)| (*node_id, dense_id))
+            .collect();
+
+        let mut matrix = BitMatrix::new(node_of.len());
+        for node in &nodes {
+            let src = id_of[&node.id];
+            for edge in graph.query_edges_from(node.id)? {
+                if let Some(&tgt) = id_of.get(&edge.target) {
+                    matrix.set(src, tgt);
+                }
+            }
+        }
+
+        loop {
+            let mut changed = false;
+            for row in 0..node_of.len() {
+                for bit in matrix.row_indices(row) {
+                    if bit != row && matrix.union_row(row, bit) {
+                        changed = true;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        Ok(Self { id_of, node_of, matrix })
+    }
+
+    /// Whether `a` can reach `b` via one or more edges. `O(1)`.
+    pub fn can_reach(&self, a: Uuid, b: Uuid) -> bool {
+        match (self.id_of.get(&a), self.id_of.get(&b)) {
+            (Some(&src), Some(&tgt)) => self.matrix.get(src, tgt),
+            _ => false,
+        }
+    }
+
+    /// Every node reachable from `a` via one or more edges. `a` itself is
+    /// only included if it's part of a cycle back to itself.
+    pub fn reachable_set(&self, a: Uuid) -> Vec<Uuid> {
+        match self.id_of.get(&a) {
+            Some(&src) => self
+                .matrix
+                .row_indices(src)
+                .into_iter()
+                .map(|dense_id| self.node_of[dense_id])
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Edge, Node};
+    use crate::storage::SledStore;
+
+    #[test]
+    fn test_bit_matrix_set_get_and_union_row() {
+        let mut matrix = BitMatrix::new(130); // spans more than one word per row
+        assert!(!matrix.get(0, 129));
+        assert!(matrix.set(0, 129));
+        assert!(!matrix.set(0, 129)); // already set: no change
+        assert!(matrix.get(0, 129));
+
+        assert!(matrix.set(1, 5));
+        assert!(matrix.union_row(0, 1));
+        assert!(matrix.get(0, 5));
+        assert!(!matrix.union_row(0, 1)); // nothing new to union
+    }
+
+    #[test]
+    fn test_reachability_index_transitive_closure() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let a = Node::new("Node");
+        let b = Node::new("Node");
+        let c = Node::new("Node");
+        let unrelated = Node::new("Node");
+
+        graph.add_node(a.clone())?;
+        graph.add_node(b.clone())?;
+        graph.add_node(c.clone())?;
+        graph.add_node(unrelated.clone())?;
+
+        graph.add_edge(&Edge::new("NEXT", a.id, b.id))?;
+        graph.add_edge(&Edge::new("NEXT", b.id, c.id))?;
+
+        let index = ReachabilityIndex::build(&graph)?;
+        assert!(index.can_reach(a.id, b.id));
+        assert!(index.can_reach(a.id, c.id)); // transitive, not a direct edge
+        assert!(!index.can_reach(a.id, unrelated.id));
+        assert!(!index.can_reach(c.id, a.id)); // direction matters
+
+        let mut reachable = index.reachable_set(a.id);
+        reachable.sort();
+        let mut expected = vec![b.id, c.id];
+        expected.sort();
+        assert_eq!(reachable, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reachability_index_handles_a_cycle() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SledStore::open(dir.path())?;
+        let graph = KnowledgeGraph::new(store);
+
+        let a = Node::new("Node");
+        let b = Node::new("Node");
+
+        graph.add_node(a.clone())?;
+        graph.add_node(b.clone())?;
+
+        graph.add_edge(&Edge::new("NEXT", a.id, b.id))?;
+        graph.add_edge(&Edge::new("NEXT", b.id, a.id))?;
+
+        let index = ReachabilityIndex::build(&graph)?;
+        assert!(index.can_reach(a.id, b.id));
+        assert!(index.can_reach(b.id, a.id));
+        assert!(index.can_reach(a.id, a.id)); // cycle reaches back to itself
+
+        Ok(())
+    }
+}
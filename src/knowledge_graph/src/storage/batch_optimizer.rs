@@ -17,6 +17,7 @@ GLIMMER Pattern:
 
 //! Batch processing optimizations for storage operations
 
+use std::collections::BTreeMap;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -25,7 +26,9 @@ use std::time::Instant;
 use parking_lot::RwLock;
 use rayon::prelude::*;
 use serde::{Serialize, de::DeserializeOwned};
+use uuid::Uuid;
 use super::*;
+use crate::models::{DynamicUsage, Edge, Node};
 
 /// Configuration for batch processing
 #[derive(Debug, Clone)]
@@ -42,6 +45,11 @@ pub struct BatchConfig {
     pub stats_window_size: usize,
     /// Whether to enable parallel processing
     pub enable_parallel: bool,
+    /// Optional ceiling on estimated heap bytes held by chunks currently
+    /// dispatched to rayon (see [`BatchProcessor::process_batch_memory_bounded`]).
+    /// `None` disables memory-bounded mode entirely, leaving
+    /// [`BatchProcessor::process_batch`]'s duration-only tuning unchanged.
+    pub memory_budget_bytes: Option<usize>,
 }
 
 impl Default for BatchConfig {
@@ -53,6 +61,7 @@ impl Default for BatchConfig {
             target_batch_duration_ms: 10, // 10ms target
             stats_window_size: 100,
             enable_parallel: true,
+            memory_budget_bytes: None,
         }
     }
 }
@@ -70,57 +79,72 @@ struct BatchStats {
     total_ops: AtomicUsize,
     /// Total time spent in processing (microseconds)
     total_duration: AtomicUsize,
+    /// Mirrors `BatchConfig::memory_budget_bytes`, so the auto-tuner can cap
+    /// growth at a batch size whose estimated footprint still fits the
+    /// budget, without `adjust_batch_size` needing the whole config.
+    memory_budget_bytes: Option<usize>,
 }
 
 impl BatchStats {
-    fn new(initial_batch_size: usize) -> Self {
+    fn new(initial_batch_size: usize, memory_budget_bytes: Option<usize>) -> Self {
         Self {
             durations: Vec::with_capacity(100),
             batch_sizes: Vec::with_capacity(100),
             current_batch_size: initial_batch_size,
             total_ops: AtomicUsize::new(0),
             total_duration: AtomicUsize::new(0),
+            memory_budget_bytes,
         }
     }
 
-    /// Record a batch operation
-    fn record_batch(&mut self, size: usize, duration: std::time::Duration) {
+    /// Record a batch operation. `avg_item_bytes`, when known (memory-bounded
+    /// processing estimates it per call via [`DynamicUsage`]), additionally
+    /// caps the auto-tuner's growth so `current_batch_size * avg_item_bytes`
+    /// stays under `memory_budget_bytes`.
+    fn record_batch(&mut self, size: usize, duration: std::time::Duration, avg_item_bytes: Option<f64>) {
         let duration_us = duration.as_micros() as u64;
-        
+
         // Update statistics
         if self.durations.len() >= 100 {
             self.durations.remove(0);
             self.batch_sizes.remove(0);
         }
-        
+
         self.durations.push(duration_us);
         self.batch_sizes.push(size);
-        
+
         // Update totals
         self.total_ops.fetch_add(size, Ordering::Relaxed);
         self.total_duration
             .fetch_add(duration_us as usize, Ordering::Relaxed);
-            
+
         // Adjust batch size based on performance
-        self.adjust_batch_size(duration);
+        self.adjust_batch_size(duration, avg_item_bytes);
     }
-    
+
     /// Adjust batch size based on recent performance
-    fn adjust_batch_size(&mut self, duration: std::time::Duration) {
-        if self.durations.len() < 5 {
-            // Not enough data yet
-            return;
+    fn adjust_batch_size(&mut self, duration: std::time::Duration, avg_item_bytes: Option<f64>) {
+        if self.durations.len() >= 5 {
+            let target_duration = std::time::Duration::from_millis(10); // 10ms target
+            let current_duration = duration.as_millis() as u64;
+
+            if current_duration > target_duration.as_millis() as u64 * 2 {
+                // Too slow, reduce batch size
+                self.current_batch_size = (self.current_batch_size as f64 * 0.8).max(100.0) as usize;
+            } else if current_duration < target_duration.as_millis() as u64 / 2 {
+                // Too fast, increase batch size
+                self.current_batch_size = (self.current_batch_size as f64 * 1.2).min(100_000.0) as usize;
+            }
         }
-        
-        let target_duration = std::time::Duration::from_millis(10); // 10ms target
-        let current_duration = duration.as_millis() as u64;
-        
-        if current_duration > target_duration.as_millis() as u64 * 2 {
-            // Too slow, reduce batch size
-            self.current_batch_size = (self.current_batch_size as f64 * 0.8).max(100.0) as usize;
-        } else if current_duration < target_duration.as_millis() as u64 / 2 {
-            // Too fast, increase batch size
-            self.current_batch_size = (self.current_batch_size as f64 * 1.2).min(100_000.0) as usize;
+
+        // The memory cap is a safety bound, not a performance heuristic, so
+        // it applies from the very first batch rather than waiting for the
+        // duration-based tuner's 5-sample warm-up above.
+        if let (Some(avg_bytes), Some(budget)) = (avg_item_bytes, self.memory_budget_bytes) {
+            if avg_bytes > 0.0 {
+                let max_by_memory = (budget as f64 / avg_bytes).floor() as usize;
+                self.current_batch_size = self.current_batch_size.min(max_by_memory.max(1));
+            }
         }
     }
     
@@ -147,6 +171,10 @@ pub struct BatchProcessor<S> {
     inner: S,
     config: BatchConfig,
     stats: RwLock<BatchStats>,
+    /// Estimated heap bytes held by chunks currently dispatched to rayon
+    /// under [`Self::process_batch_memory_bounded`]. Unused when
+    /// `config.memory_budget_bytes` is `None`.
+    reserved_bytes: AtomicUsize,
 }
 
 impl<S> BatchProcessor<S> {
@@ -154,16 +182,17 @@ impl<S> BatchProcessor<S> {
     pub fn new(inner: S) -> Self {
         Self::with_config(inner, BatchConfig::default())
     }
-    
+
     /// Create a new batch processor with custom configuration
     pub fn with_config(inner: S, config: BatchConfig) -> Self {
         Self {
             inner,
-            stats: RwLock::new(BatchStats::new(config.initial_batch_size)),
+            stats: RwLock::new(BatchStats::new(config.initial_batch_size, config.memory_budget_bytes)),
             config,
+            reserved_bytes: AtomicUsize::new(0),
         }
     }
-    
+
     /// Process a batch of operations
     pub fn process_batch<F, T, R>(&self, items: &[T], process_fn: F) -> Vec<R>
     where
@@ -174,7 +203,7 @@ impl<S> BatchProcessor<S> {
         let start_time = Instant::now();
         let batch_size = self.stats.read().batch_size();
         let config = &self.config;
-        
+
         let results = if config.enable_parallel && items.len() > batch_size {
             // Process in parallel chunks
             items
@@ -198,14 +227,84 @@ impl<S> BatchProcessor<S> {
                 })
                 .collect()
         };
-        
+
         // Update statistics
         let duration = start_time.elapsed();
-        self.stats.write().record_batch(items.len(), duration);
-        
+        self.stats.write().record_batch(items.len(), duration, None);
+
         results
     }
-    
+
+    /// Like [`Self::process_batch`], but for item types whose heap footprint
+    /// can be estimated via [`DynamicUsage`] (e.g. [`crate::models::Node`],
+    /// [`crate::models::Edge`]), bounding how much estimated memory is held
+    /// by chunks dispatched into rayon at once.
+    ///
+    /// Each chunk's bytes are measured immediately before it would be
+    /// dispatched -- not from inside the rayon task -- since items can sit
+    /// queued in rayon's work-stealing queue for a non-negligible time
+    /// before a worker actually picks them up, during which they already
+    /// count against resident memory. If reserving a chunk's bytes would
+    /// push the running total over `config.memory_budget_bytes`, that chunk
+    /// is processed on the calling thread instead of being fanned out
+    /// across the pool, and its bytes are released once it completes.
+    /// With `memory_budget_bytes` unset this behaves like `process_batch`
+    /// except it additionally feeds the observed average item size into the
+    /// auto-tuner (a no-op there too, since the tuner only caps growth when
+    /// a budget is configured).
+    pub fn process_batch_memory_bounded<F, T, R>(&self, items: &[T], process_fn: F) -> Vec<R>
+    where
+        F: Fn(&S, &T) -> R + Send + Sync,
+        T: DynamicUsage + Send + Sync,
+        R: Send,
+    {
+        let start_time = Instant::now();
+        let batch_size = self.stats.read().batch_size().max(1);
+        let config = &self.config;
+
+        let mut results = Vec::with_capacity(items.len());
+        let mut total_bytes = 0usize;
+
+        for chunk in items.chunks(batch_size) {
+            let chunk_bytes: usize = chunk.iter().map(|item| item.dynamic_usage()).sum();
+            total_bytes += chunk_bytes;
+
+            let within_budget = match config.memory_budget_bytes {
+                Some(budget) => {
+                    let total_after_reserve = self.reserved_bytes.fetch_add(chunk_bytes, Ordering::SeqCst) + chunk_bytes;
+                    total_after_reserve <= budget
+                }
+                None => true,
+            };
+
+            let chunk_results: Vec<R> = if config.enable_parallel && within_budget {
+                chunk.par_iter().map(|item| process_fn(&self.inner, item)).collect()
+            } else {
+                // Over budget (or parallelism disabled): run inline on the
+                // calling thread rather than handing more work to rayon
+                // while its queue is already holding as much as we can
+                // account for.
+                chunk.iter().map(|item| process_fn(&self.inner, item)).collect()
+            };
+
+            if config.memory_budget_bytes.is_some() {
+                self.reserved_bytes.fetch_sub(chunk_bytes, Ordering::SeqCst);
+            }
+
+            results.extend(chunk_results);
+        }
+
+        let duration = start_time.elapsed();
+        let avg_item_bytes = if items.is_empty() {
+            0.0
+        } else {
+            total_bytes as f64 / items.len() as f64
+        };
+        self.stats.write().record_batch(items.len(), duration, Some(avg_item_bytes));
+
+        results
+    }
+
     /// Get current statistics
     pub fn stats(&self) -> (usize, f64) {
         let stats = self.stats.read();
@@ -213,6 +312,179 @@ impl<S> BatchProcessor<S> {
     }
 }
 
+/// Key under which [`BatchWriter`] stores a node, mirroring the
+/// `node:`/`edge:` scheme [`crate::graph`] uses on its own storage keys so
+/// a consolidated flush lands on exactly the rows a live `KnowledgeGraph`
+/// would touch.
+fn node_key(id: Uuid) -> Vec<u8> {
+    let mut key = b"node:".to_vec();
+    key.extend_from_slice(id.as_bytes());
+    key
+}
+
+/// Key under which [`BatchWriter`] stores an edge. See [`node_key`].
+fn edge_key(id: Uuid) -> Vec<u8> {
+    let mut key = b"edge:".to_vec();
+    key.extend_from_slice(id.as_bytes());
+    key
+}
+
+/// One graph mutation accumulated by a [`BatchWriter`], pushed ahead of
+/// consolidation.
+#[derive(Debug, Clone)]
+pub enum GraphOp {
+    /// Insert or overwrite a node.
+    UpsertNode(Node),
+    /// Remove a node by id.
+    DeleteNode(Uuid),
+    /// Insert or overwrite an edge.
+    UpsertEdge(Edge),
+    /// Remove an edge by id.
+    DeleteEdge(Uuid),
+}
+
+impl GraphOp {
+    fn key(&self) -> Vec<u8> {
+        match self {
+            Self::UpsertNode(node) => node_key(node.id),
+            Self::DeleteNode(id) => node_key(*id),
+            Self::UpsertEdge(edge) => edge_key(edge.id),
+            Self::DeleteEdge(id) => edge_key(*id),
+        }
+    }
+
+    fn is_delete(&self) -> bool {
+        matches!(self, Self::DeleteNode(_) | Self::DeleteEdge(_))
+    }
+
+    fn is_upsert(&self) -> bool {
+        !self.is_delete()
+    }
+
+    fn serialized_value(&self) -> Result<Option<Vec<u8>>> {
+        Ok(match self {
+            Self::UpsertNode(node) => Some(serialize(node)?),
+            Self::UpsertEdge(edge) => Some(serialize(edge)?),
+            Self::DeleteNode(_) | Self::DeleteEdge(_) => None,
+        })
+    }
+}
+
+/// Result of consolidating one key's queued ops: the last-writer-wins op,
+/// plus whether the very first op pushed for this key (before any
+/// consolidation) was an upsert.
+struct Consolidated {
+    first_was_upsert: bool,
+    last: GraphOp,
+}
+
+/// Counts returned by [`BatchWriter::flush`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlushStats {
+    /// Ops pushed since the previous flush, before consolidation.
+    pub input_ops: usize,
+    /// Ops actually written to the backend after dedup and cancellation.
+    pub consolidated_ops: usize,
+    /// Total bytes of serialized values written (deletes contribute 0).
+    pub bytes_written: usize,
+}
+
+/// Accumulates node/edge mutations and consolidates them into one sorted,
+/// de-duplicated write pass on [`flush`](Self::flush), the way a
+/// log-structured store folds a burst of random-access writes into a
+/// single monotonic commit before it hits disk.
+///
+/// Pushing several ops against the same node/edge id keeps only the
+/// last-writer-wins result; an upsert immediately introducing an id that's
+/// deleted again before the next flush, with nothing else queued for that
+/// id in between, cancels out entirely and writes nothing for it. Survivors
+/// are committed to the backend in ascending key order, so bursty ingest
+/// (e.g. looping `add_edge` calls) turns into one monotonic, de-duplicated
+/// bulk commit instead of random-access churn.
+pub struct BatchWriter<S: Storage + WriteBatchExt> {
+    inner: S,
+    pending: Vec<GraphOp>,
+}
+
+impl<S: Storage + WriteBatchExt> BatchWriter<S> {
+    /// Create a writer with no pending ops over `inner`.
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Queue `op`. Nothing is written to `inner` until [`flush`](Self::flush).
+    pub fn push(&mut self, op: GraphOp) {
+        self.pending.push(op);
+    }
+
+    /// Number of ops queued since the last flush.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Consolidate every pending op and commit the survivors to `inner` in
+    /// one batch, in ascending key order.
+    pub fn flush(&mut self) -> Result<FlushStats> {
+        let input_ops = self.pending.len();
+        if input_ops == 0 {
+            return Ok(FlushStats::default());
+        }
+
+        let mut by_key: BTreeMap<Vec<u8>, Consolidated> = BTreeMap::new();
+        for op in self.pending.drain(..) {
+            let key = op.key();
+            match by_key.get_mut(&key) {
+                Some(consolidated) => consolidated.last = op,
+                None => {
+                    by_key.insert(
+                        key,
+                        Consolidated {
+                            first_was_upsert: op.is_upsert(),
+                            last: op,
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut batch = self.inner.create_batch();
+        let mut consolidated_ops = 0usize;
+        let mut bytes_written = 0usize;
+
+        for (key, consolidated) in by_key {
+            // An id that this flush both created and deleted, with nothing
+            // else queued for it, never needs to reach the backend at all
+            // -- writing it and then immediately removing it again would be
+            // pure write amplification for no observable effect. An id the
+            // backend already holds still needs its delete written.
+            if consolidated.first_was_upsert && consolidated.last.is_delete() && !self.inner.exists(&key)? {
+                continue;
+            }
+
+            match consolidated.last.serialized_value()? {
+                Some(bytes) => {
+                    bytes_written += bytes.len();
+                    batch.put_serialized(&key, &bytes)?;
+                }
+                None => {
+                    batch.delete_serialized(&key)?;
+                }
+            }
+            consolidated_ops += 1;
+        }
+        batch.commit()?;
+
+        Ok(FlushStats {
+            input_ops,
+            consolidated_ops,
+            bytes_written,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,4 +521,148 @@ mod tests {
         assert_eq!(results[0], 0);
         assert_eq!(results[9999], 19_998);
     }
+
+    /// A fixed-size "payload" standing in for large serialized nodes/edges
+    /// in the memory-bounded tests below.
+    struct Payload(Vec<u8>);
+
+    impl DynamicUsage for Payload {
+        fn dynamic_usage(&self) -> usize {
+            self.0.capacity()
+        }
+    }
+
+    #[test]
+    fn test_memory_bounded_processes_every_item() {
+        let config = BatchConfig {
+            initial_batch_size: 10,
+            memory_budget_bytes: Some(1_024),
+            ..Default::default()
+        };
+        let processor = BatchProcessor::with_config((), config);
+
+        let items: Vec<_> = (0..500).map(|_| Payload(vec![0u8; 256])).collect();
+        let results = processor.process_batch_memory_bounded(&items, |_, p| p.0.len());
+
+        assert_eq!(results.len(), 500);
+        assert!(results.iter().all(|&len| len == 256));
+    }
+
+    #[test]
+    fn test_memory_bounded_releases_reservation_after_each_chunk() {
+        let config = BatchConfig {
+            initial_batch_size: 4,
+            memory_budget_bytes: Some(4 * 256),
+            ..Default::default()
+        };
+        let processor = BatchProcessor::with_config((), config);
+
+        let items: Vec<_> = (0..40).map(|_| Payload(vec![0u8; 256])).collect();
+        processor.process_batch_memory_bounded(&items, |_, p| p.0.len());
+
+        // Every chunk's reservation must be released once it's processed,
+        // or a long-running processor would eventually report itself
+        // permanently over budget.
+        assert_eq!(processor.reserved_bytes.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_memory_budget_caps_batch_size_growth() {
+        let config = BatchConfig {
+            initial_batch_size: 1_000,
+            memory_budget_bytes: Some(2_000),
+            ..Default::default()
+        };
+        let processor = BatchProcessor::with_config((), config);
+
+        // Items large enough that 1,000 of them would blow well past the
+        // 2,000 byte budget (avg_item_bytes = 256, so the cap should land
+        // around 2_000 / 256 = 7).
+        let items: Vec<_> = (0..50).map(|_| Payload(vec![0u8; 256])).collect();
+        processor.process_batch_memory_bounded(&items, |_, p| p.0.len());
+
+        let (batch_size, _) = processor.stats();
+        assert!(batch_size <= 2_000 / 256 + 1);
+    }
+
+    #[test]
+    fn test_batch_writer_dedupes_to_last_writer_wins() {
+        let store = InMemoryStore::new();
+        let mut writer = BatchWriter::new(store.clone());
+
+        let node = Node::new("Person").with_property("name", "Alice");
+        let mut updated = node.clone();
+        updated.set_property("name", "Alicia");
+
+        writer.push(GraphOp::UpsertNode(node.clone()));
+        writer.push(GraphOp::UpsertNode(updated.clone()));
+
+        let stats = writer.flush().unwrap();
+        assert_eq!(stats.input_ops, 2);
+        assert_eq!(stats.consolidated_ops, 1);
+
+        let stored: Node = store.get(&node_key(node.id)).unwrap().unwrap();
+        assert_eq!(stored.get_property("name"), updated.get_property("name"));
+    }
+
+    #[test]
+    fn test_batch_writer_cancels_insert_then_delete_of_the_same_id() {
+        let store = InMemoryStore::new();
+        let mut writer = BatchWriter::new(store.clone());
+
+        let node = Node::new("Person");
+        writer.push(GraphOp::UpsertNode(node.clone()));
+        writer.push(GraphOp::DeleteNode(node.id));
+
+        let stats = writer.flush().unwrap();
+        assert_eq!(stats.input_ops, 2);
+        assert_eq!(stats.consolidated_ops, 0);
+        assert!(!store.exists(&node_key(node.id)).unwrap());
+    }
+
+    #[test]
+    fn test_batch_writer_still_deletes_an_id_not_created_in_this_batch() {
+        let store = InMemoryStore::new();
+        let node = Node::new("Person");
+        store.put(&node_key(node.id), &node).unwrap();
+
+        let mut writer = BatchWriter::new(store.clone());
+        writer.push(GraphOp::DeleteNode(node.id));
+
+        let stats = writer.flush().unwrap();
+        assert_eq!(stats.consolidated_ops, 1);
+        assert!(!store.exists(&node_key(node.id)).unwrap());
+    }
+
+    #[test]
+    fn test_batch_writer_writes_nodes_and_edges_in_sorted_key_order() {
+        let store = InMemoryStore::new();
+        let mut writer = BatchWriter::new(store.clone());
+
+        let alice = Node::new("Person");
+        let bob = Node::new("Person");
+        let edge = Edge::new("KNOWS", alice.id, bob.id);
+
+        writer.push(GraphOp::UpsertNode(bob.clone()));
+        writer.push(GraphOp::UpsertEdge(edge.clone()));
+        writer.push(GraphOp::UpsertNode(alice.clone()));
+
+        let stats = writer.flush().unwrap();
+        assert_eq!(stats.input_ops, 3);
+        assert_eq!(stats.consolidated_ops, 3);
+        assert!(stats.bytes_written > 0);
+
+        assert!(store.exists(&node_key(alice.id)).unwrap());
+        assert!(store.exists(&node_key(bob.id)).unwrap());
+        assert!(store.exists(&edge_key(edge.id)).unwrap());
+    }
+
+    #[test]
+    fn test_batch_writer_flush_with_nothing_pending_is_a_no_op() {
+        let store = InMemoryStore::new();
+        let mut writer = BatchWriter::new(store);
+
+        let stats = writer.flush().unwrap();
+        assert_eq!(stats, FlushStats::default());
+    }
 }
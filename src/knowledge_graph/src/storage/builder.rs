@@ -0,0 +1,204 @@
+//! Uniform backend selection for [`Storage`].
+//!
+//! [`StorageBuilder`] is a config value ("which backend, with what
+//! connection details") that setup code -- tests, benchmarks -- can match
+//! against [`StoreBuilder::build`] once to get back an [`AnyStore`], rather
+//! than hand-rolling a match over each backend's own constructor. `AnyStore`
+//! itself dispatches every [`Storage`] method to whichever concrete backend
+//! it was built with, so `bench_sled_store`-style setup code can be written
+//! once against `AnyStore` and run unmodified against any backend the enum
+//! knows how to build.
+
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::memory_store::{InMemoryBatch, InMemoryStore};
+use super::object_store::{GarageBatch, GarageStore, GarageStoreBuilder, StoreBuilder};
+use super::rocksdb_store::{RocksDBStore, RocksWriteBatchWrapper};
+use super::sled_store::{SledStore, SledWriteBatch};
+use crate::error::Result;
+use crate::storage::{Storage, WriteBatch, WriteBatchExt};
+
+/// Which [`Storage`] backend to instantiate, and the connection details it
+/// needs to do so. Call [`build`](StoreBuilder::build) to connect and get
+/// back an [`AnyStore`].
+#[derive(Clone, Debug)]
+pub enum StorageBuilder {
+    /// An [`InMemoryStore`], with no connection details to fill in.
+    InMemory,
+    /// A [`SledStore`] at the given path, created if it doesn't exist.
+    Sled(PathBuf),
+    /// A [`RocksDBStore`] at the given path, created if it doesn't exist.
+    RocksDb(PathBuf),
+    /// A [`GarageStore`] over an S3-compatible bucket.
+    Garage(GarageStoreBuilder),
+}
+
+impl StoreBuilder for StorageBuilder {
+    type Store = AnyStore;
+
+    fn build(self) -> Result<AnyStore> {
+        Ok(match self {
+            StorageBuilder::InMemory => AnyStore::InMemory(InMemoryStore::new()),
+            StorageBuilder::Sled(path) => AnyStore::Sled(SledStore::open(path)?),
+            StorageBuilder::RocksDb(path) => AnyStore::RocksDb(RocksDBStore::open(path)?),
+            StorageBuilder::Garage(builder) => AnyStore::Garage(builder.build()?),
+        })
+    }
+}
+
+/// A [`Storage`] backend built by [`StorageBuilder::build`], dispatching
+/// every call to whichever concrete backend it wraps.
+#[derive(Clone)]
+pub enum AnyStore {
+    /// See [`InMemoryStore`].
+    InMemory(InMemoryStore),
+    /// See [`SledStore`].
+    Sled(SledStore),
+    /// See [`RocksDBStore`].
+    RocksDb(RocksDBStore),
+    /// See [`GarageStore`].
+    Garage(GarageStore),
+}
+
+/// The in-flight batch for whichever backend an [`AnyStore`] was built
+/// with; mirrors [`AnyStore`]'s variants one-to-one. None of the wrapped
+/// batch types borrow from their store (each owns an `Arc` clone of it
+/// instead), so unlike the per-backend `Batch<'a>` GATs this needs no
+/// lifetime of its own.
+pub enum AnyBatch {
+    /// See [`InMemoryBatch`].
+    InMemory(InMemoryBatch),
+    /// See [`SledWriteBatch`].
+    Sled(SledWriteBatch),
+    /// See [`RocksWriteBatchWrapper`].
+    RocksDb(RocksWriteBatchWrapper),
+    /// See [`GarageBatch`](super::object_store::GarageBatch).
+    Garage(GarageBatch),
+}
+
+impl Storage for AnyStore {
+    type Batch<'a> = AnyBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self {
+            AnyStore::InMemory(store) => store.get(key),
+            AnyStore::Sled(store) => store.get(key),
+            AnyStore::RocksDb(store) => store.get(key),
+            AnyStore::Garage(store) => store.get(key),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        match self {
+            AnyStore::InMemory(store) => store.put(key, value),
+            AnyStore::Sled(store) => store.put(key, value),
+            AnyStore::RocksDb(store) => store.put(key, value),
+            AnyStore::Garage(store) => store.put(key, value),
+        }
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        match self {
+            AnyStore::InMemory(store) => store.delete(key),
+            AnyStore::Sled(store) => store.delete(key),
+            AnyStore::RocksDb(store) => store.delete(key),
+            AnyStore::Garage(store) => store.delete(key),
+        }
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        match self {
+            AnyStore::InMemory(store) => store.exists(key),
+            AnyStore::Sled(store) => store.exists(key),
+            AnyStore::RocksDb(store) => store.exists(key),
+            AnyStore::Garage(store) => store.exists(key),
+        }
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        match self {
+            AnyStore::InMemory(store) => store.get_raw(key),
+            AnyStore::Sled(store) => store.get_raw(key),
+            AnyStore::RocksDb(store) => store.get_raw(key),
+            AnyStore::Garage(store) => store.get_raw(key),
+        }
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        match self {
+            AnyStore::InMemory(store) => store.put_raw(key, value),
+            AnyStore::Sled(store) => store.put_raw(key, value),
+            AnyStore::RocksDb(store) => store.put_raw(key, value),
+            AnyStore::Garage(store) => store.put_raw(key, value),
+        }
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        match self {
+            AnyStore::InMemory(store) => store.iter_prefix(prefix),
+            AnyStore::Sled(store) => store.iter_prefix(prefix),
+            AnyStore::RocksDb(store) => store.iter_prefix(prefix),
+            AnyStore::Garage(store) => store.iter_prefix(prefix),
+        }
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        match self {
+            AnyStore::InMemory(store) => AnyBatch::InMemory(store.create_batch()),
+            AnyStore::Sled(store) => AnyBatch::Sled(store.create_batch()),
+            AnyStore::RocksDb(store) => AnyBatch::RocksDb(store.create_batch()),
+            AnyStore::Garage(store) => AnyBatch::Garage(store.create_batch()),
+        }
+    }
+}
+
+impl WriteBatchExt for AnyStore {}
+
+impl WriteBatch for AnyBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        match self {
+            AnyBatch::InMemory(batch) => batch.put_serialized(key, value),
+            AnyBatch::Sled(batch) => batch.put_serialized(key, value),
+            AnyBatch::RocksDb(batch) => batch.put_serialized(key, value),
+            AnyBatch::Garage(batch) => batch.put_serialized(key, value),
+        }
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        match self {
+            AnyBatch::InMemory(batch) => batch.delete_serialized(key),
+            AnyBatch::Sled(batch) => batch.delete_serialized(key),
+            AnyBatch::RocksDb(batch) => batch.delete_serialized(key),
+            AnyBatch::Garage(batch) => batch.delete_serialized(key),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            AnyBatch::InMemory(batch) => batch.clear(),
+            AnyBatch::Sled(batch) => batch.clear(),
+            AnyBatch::RocksDb(batch) => batch.clear(),
+            AnyBatch::Garage(batch) => batch.clear(),
+        }
+    }
+
+    fn commit(self) -> Result<()> {
+        match self {
+            AnyBatch::InMemory(batch) => batch.commit(),
+            AnyBatch::Sled(batch) => batch.commit(),
+            AnyBatch::RocksDb(batch) => batch.commit(),
+            AnyBatch::Garage(batch) => batch.commit(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
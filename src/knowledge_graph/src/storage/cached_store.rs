@@ -1,37 +1,145 @@
 //! Cached storage implementation for the knowledge graph
+//!
+//! [`CachedStore`] fronts another [`Storage`] backend with a memory-bounded
+//! cache admitted by a W-TinyLFU policy: a small LRU "window" segment takes
+//! every newly-seen key, and a key only earns a spot in the larger SLRU
+//! "main" segment (split into probation/protected tiers) if a count-min
+//! sketch estimates it's accessed more often than whatever the main segment
+//! would otherwise evict to make room for it. This keeps one-off scans from
+//! flushing out genuinely hot keys.
+//!
+//! Entries evicted from memory can optionally spill into a second,
+//! disk-backed tier (see [`CacheConfig::disk_tier_path`]) instead of being
+//! discarded, so a larger working set than fits in RAM still avoids going
+//! all the way to the backing store, and survives process restarts.
+//!
+//! A single `CachedStore` can also host several independent, named
+//! keyspaces via [`CollectionId`] (see [`CachedStore::get_in`]/
+//! [`put_in`](CachedStore::put_in)/[`iter_collection`](CachedStore::iter_collection)),
+//! each with its own cache partition, so unrelated graph subsystems (e.g.
+//! nodes, edges, secondary indexes) don't have to manually prefix keys or
+//! risk evicting each other's entries out of a shared cache.
+//!
+//! [`CachedStore::load_many`] coalesces a batch of key lookups (e.g. a
+//! node's neighbor list) into one [`Storage::multi_get_raw`] round trip for
+//! whatever isn't already cached, instead of paying `inner`'s per-call
+//! overhead once per key.
+//!
+//! With [`CacheConfig::cache_negatives`] set, a confirmed-absent key (a
+//! `get`/`get_raw`/`exists` miss against `inner`) is remembered in a
+//! bounded tombstone set so a repeated query for the same missing key
+//! short-circuits before ever reaching `inner` -- useful for edge-existence
+//! checks during traversal, which often probe for neighbors that don't
+//! exist. Every write path clears or sets a key's tombstone as needed
+//! (`put`/`put_raw`/`put_serialized` clear it, `delete`/`delete_serialized`
+//! set it), including inside a [`CachedBatch`], so a tombstone never
+//! outlives the write that should have invalidated it.
 
-use std::sync::Arc;
-use std::collections::{HashMap, HashSet, VecDeque};
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-use parking_lot::RwLock;
-use rayon::prelude::*;
-use crate::storage::batch_optimizer::{BatchConfig, BatchStats};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use lru::LruCache;
+use parking_lot::Mutex;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use lru::LruCache;
+
 use crate::error::Result;
-use crate::storage::{Storage, WriteBatch, WriteBatchExt, serialize, deserialize};
-use crate::error::KnowledgeGraphError;
+use crate::storage::metrics::StorageMetrics;
+use crate::storage::snapshot::Checkpoint;
+use crate::storage::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
 
 /// Configuration for the cached store
 #[derive(Debug, Clone)]
 pub struct CacheConfig {
-    /// Maximum number of items to keep in the cache
+    /// Maximum number of entries to keep in the cache, across all segments
     pub capacity: usize,
+    /// Maximum total size, in bytes, of cached values, enforced alongside
+    /// `capacity` by evicting (via the [`CacheStorage`]'s admission policy,
+    /// [`WTinyLfuCache`] by default) until the true byte total of resident
+    /// entries is back at or under `max_bytes`. `None` means the cache is
+    /// bounded only by `capacity`.
+    pub max_bytes: Option<usize>,
     /// Number of keys to prefetch on a read miss
     pub read_ahead_window: usize,
+    /// Optional on-disk path for a second cache tier: entries evicted from
+    /// the in-memory policy spill here instead of being discarded, and
+    /// survive process restarts, unlike the purely volatile in-memory
+    /// cache. `None` (the default) keeps evictions volatile as before.
+    pub disk_tier_path: Option<PathBuf>,
+    /// Byte budget for the disk tier; once exceeded, the oldest-inserted
+    /// entries are evicted for good. `None` means unbounded, limited only
+    /// by disk space. Ignored if `disk_tier_path` is `None`.
+    pub disk_tier_capacity_bytes: Option<usize>,
+    /// Remember confirmed-absent keys (a `get`/`get_raw`/`exists` miss
+    /// against `inner`) in a bounded tombstone set, so a repeated query for
+    /// the same missing key short-circuits before `inner` -- useful for
+    /// edge-existence checks during traversal, which often probe for
+    /// neighbors that don't exist. `false` by default, since a stale
+    /// tombstone surviving a write it should have been cleared by would
+    /// return a wrong `None`; any `put`/`put_raw`/`put_serialized` clears a
+    /// key's tombstone, and `delete`/`delete_serialized` sets one.
+    pub cache_negatives: bool,
 }
 
 impl Default for CacheConfig {
     fn default() -> Self {
         Self {
-            capacity: 10_000,  // Default cache size
-            read_ahead_window: 0,  // No read-ahead by default
+            capacity: 10_000, // Default cache size
+            max_bytes: None,
+            read_ahead_window: 0, // No read-ahead by default
+            disk_tier_path: None,
+            disk_tier_capacity_bytes: None,
+            cache_negatives: false,
         }
     }
 }
 
+/// Names one of a [`CachedStore`]'s independently-cached keyspaces, e.g.
+/// `nodes`, `edges`, or `indexes`, so graph subsystems that would otherwise
+/// collide in one flat keyspace (and one flat cache) can share a single
+/// `CachedStore` without manually prefixing keys themselves. See
+/// [`CachedStore::get_in`]/[`put_in`](CachedStore::put_in)/
+/// [`iter_collection`](CachedStore::iter_collection).
+///
+/// Wraps the collection's name, which is prefixed onto the logical key to
+/// form the physical key sent to `inner` and the disk tier -- e.g.
+/// `CollectionId::new("nodes")` and key `b"abc"` become the physical key
+/// `b"nodes:abc"`. Each `CollectionId` also gets its own in-memory cache
+/// partition (see [`CachedStore::partition`]), so evicting or clearing one
+/// collection's cache never touches another's.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollectionId(String);
+
+impl CollectionId {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// The physical key `inner`/the disk tier actually store `key` under:
+    /// the collection name, a `:` delimiter, then `key` -- the same
+    /// tag-then-`:`-then-id shape `graph.rs`'s `node_key`/`edge_key` use,
+    /// so `structural_prefix` still does the right thing for prefetch.
+    fn physical_key(&self, key: &[u8]) -> Vec<u8> {
+        let mut physical = self.physical_prefix();
+        physical.extend_from_slice(key);
+        physical
+    }
+
+    /// The physical prefix shared by every key in this collection: the
+    /// name plus its trailing `:` delimiter.
+    fn physical_prefix(&self) -> Vec<u8> {
+        let mut prefix = self.0.as_bytes().to_vec();
+        prefix.push(b':');
+        prefix
+    }
+}
+
 /// Performance metrics for the cached store
 #[derive(Debug, Default)]
 pub(crate) struct CacheMetrics {
@@ -41,6 +149,13 @@ pub(crate) struct CacheMetrics {
     writes: AtomicU64,
     read_bytes: AtomicU64,
     write_bytes: AtomicU64,
+    evictions: AtomicU64,
+    disk_hits: AtomicU64,
+    disk_misses: AtomicU64,
+    prefetched: AtomicU64,
+    prefetch_hits: AtomicU64,
+    negative_caches: AtomicU64,
+    negative_hits: AtomicU64,
 }
 
 impl CacheMetrics {
@@ -50,437 +165,1902 @@ impl CacheMetrics {
         self.reads.fetch_add(1, Ordering::Relaxed);
         self.read_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
     }
-    
+
     /// Record a cache miss
     pub(crate) fn record_miss(&self) {
         self.misses.fetch_add(1, Ordering::Relaxed);
         self.reads.fetch_add(1, Ordering::Relaxed);
     }
-    
+
     /// Record bytes written to cache
     pub(crate) fn record_write(&self, bytes: usize) {
         self.writes.fetch_add(1, Ordering::Relaxed);
         self.write_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
     }
-    
+
+    /// Record an entry being evicted (or rejected admission) from the cache
+    pub(crate) fn record_eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record `n` entries being evicted (or rejected admission) at once --
+    /// used to fold a [`CacheStorage`] implementation's own eviction count
+    /// (see [`CacheStorage::take_evictions`]) into these metrics without
+    /// that trait needing to depend on `CacheMetrics` itself.
+    pub(crate) fn record_evictions(&self, n: u64) {
+        if n > 0 {
+            self.evictions.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
     /// Get cache hit rate
     pub(crate) fn hit_rate(&self) -> f64 {
         let hits = self.hits.load(Ordering::Relaxed) as f64;
         let misses = self.misses.load(Ordering::Relaxed) as f64;
         let total = hits + misses;
-        
-        if total > 0.0 { hits / total } else { 0.0 }
+
+        if total > 0.0 {
+            hits / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Get the number of entries evicted or rejected so far
+    pub(crate) fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Record a hit against the disk tier (a miss in memory, found on disk)
+    pub(crate) fn record_disk_hit(&self, bytes: usize) {
+        self.disk_hits.fetch_add(1, Ordering::Relaxed);
+        self.read_bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Record a miss against the disk tier (absent from both memory and disk)
+    pub(crate) fn record_disk_miss(&self) {
+        self.disk_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of reads served from the disk tier
+    pub(crate) fn disk_hits(&self) -> u64 {
+        self.disk_hits.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of reads that missed both memory and the disk tier
+    pub(crate) fn disk_misses(&self) -> u64 {
+        self.disk_misses.load(Ordering::Relaxed)
+    }
+
+    /// Record `n` entries populated by read-ahead prefetching
+    pub(crate) fn record_prefetched(&self, n: u64) {
+        if n > 0 {
+            self.prefetched.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    /// Record a cache hit that was served by a previously prefetched entry
+    pub(crate) fn record_prefetch_hit(&self) {
+        self.prefetch_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the total number of entries populated by read-ahead prefetching
+    pub(crate) fn prefetched(&self) -> u64 {
+        self.prefetched.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of cache hits served by a previously prefetched entry
+    pub(crate) fn prefetch_hits(&self) -> u64 {
+        self.prefetch_hits.load(Ordering::Relaxed)
+    }
+
+    /// Record a confirmed-absent key being tombstoned in the negative cache
+    pub(crate) fn record_negative_cache(&self) {
+        self.negative_caches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a read short-circuited by a negative-cache tombstone instead
+    /// of reaching `inner`
+    pub(crate) fn record_negative_hit(&self) {
+        self.negative_hits.fetch_add(1, Ordering::Relaxed);
     }
-    
+
+    /// Get the total number of keys tombstoned in the negative cache
+    pub(crate) fn negative_caches(&self) -> u64 {
+        self.negative_caches.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of reads short-circuited by the negative cache
+    pub(crate) fn negative_hits(&self) -> u64 {
+        self.negative_hits.load(Ordering::Relaxed)
+    }
+
     /// Get total read bytes
     pub(crate) fn read_bytes(&self) -> u64 {
         self.read_bytes.load(Ordering::Relaxed)
     }
-    
+
     /// Get total write bytes
     pub(crate) fn write_bytes(&self) -> u64 {
         self.write_bytes.load(Ordering::Relaxed)
     }
 }
 
-/// A storage wrapper that adds an LRU cache in front of another storage implementation
-pub struct CachedStore<S> {
-    inner: S,
-    cache: Arc<RwLock<LruCache<Vec<u8>, Vec<u8>>>>,
-    metrics: Arc<CacheMetrics>,
-    batch_config: BatchConfig,
-    read_ahead_window: usize,
+/// Number of hash functions (rows) in the count-min sketch
+const SKETCH_DEPTH: usize = 4;
+
+/// Age the sketch (halve every counter) once this many increments have been
+/// recorded per `capacity` slot, following Caffeine's `10 * capacity` rule
+/// of thumb so frequency estimates track recent behavior.
+const SKETCH_RESET_MULTIPLIER: u64 = 10;
+
+/// A 4-bit saturating-counter count-min sketch estimating per-key access
+/// frequency, used to decide whether an eviction candidate deserves a spot
+/// in the main cache segment.
+///
+/// Counters are packed two per byte (nibbles) and saturate at 15. Every
+/// `reset_threshold` increments, all counters are halved so the sketch
+/// reflects recent access patterns rather than all-time totals.
+struct CountMinSketch {
+    rows: [Vec<u8>; SKETCH_DEPTH],
+    width: usize,
+    additions: u64,
+    reset_threshold: u64,
 }
 
-impl<S> CachedStore<S> {
-    /// Create a new cached storage wrapper with default configuration
-    pub fn new(inner: S) -> Self {
-        let config = CacheConfig::default();
-        let capacity = std::num::NonZeroUsize::new(config.capacity).unwrap();
-        let cache = Arc::new(RwLock::new(LruCache::new(capacity)));
-        
+impl CountMinSketch {
+    fn new(capacity: usize) -> Self {
+        let width = capacity.max(16).next_power_of_two();
+        let bytes_per_row = width.div_ceil(2);
+        let rows = std::array::from_fn(|_| vec![0u8; bytes_per_row]);
         Self {
-            inner,
-            cache,
-            metrics: Arc::new(CacheMetrics::default()),
-            batch_config: BatchConfig::default(),
-            read_ahead_window: 0,
+            rows,
+            width,
+            additions: 0,
+            reset_threshold: (capacity as u64).saturating_mul(SKETCH_RESET_MULTIPLIER).max(64),
         }
     }
-    
-    /// Create a new cached storage wrapper with custom configuration
-    pub fn with_config(inner: S, config: CacheConfig, batch_config: BatchConfig) -> Self {
-        let capacity = std::num::NonZeroUsize::new(config.capacity).unwrap();
-        let cache = Arc::new(RwLock::new(LruCache::new(capacity)));
-        
-        Self {
-            inner,
-            cache,
-            metrics: Arc::new(CacheMetrics::default()),
-            batch_config,
-            read_ahead_window: config.read_ahead_window,
+
+    fn slot(&self, key_hash: u64, row: usize) -> usize {
+        // Mix the row index into the hash so each row probes a distinct slot.
+        let mixed = key_hash
+            .rotate_left((row as u32) * 16 + 1)
+            .wrapping_mul(0x9E3779B97F4A7C15 ^ (row as u64));
+        (mixed as usize) & (self.width - 1)
+    }
+
+    fn get_nibble(row: &[u8], index: usize) -> u8 {
+        let byte = row[index / 2];
+        if index % 2 == 0 {
+            byte & 0x0F
+        } else {
+            (byte >> 4) & 0x0F
         }
     }
-    
-    /// Get a reference to the inner storage
-    pub fn inner(&self) -> &S {
-        &self.inner
+
+    fn set_nibble(row: &mut [u8], index: usize, value: u8) {
+        let byte = &mut row[index / 2];
+        if index % 2 == 0 {
+            *byte = (*byte & 0xF0) | (value & 0x0F);
+        } else {
+            *byte = (*byte & 0x0F) | (value << 4);
+        }
     }
-    
-    /// Get a mutable reference to the inner storage
-    pub fn inner_mut(&mut self) -> &mut S {
-        &mut self.inner
+
+    /// Estimate the access frequency of `key_hash` (0-15)
+    fn estimate(&self, key_hash: u64) -> u8 {
+        (0..SKETCH_DEPTH)
+            .map(|row| Self::get_nibble(&self.rows[row], self.slot(key_hash, row)))
+            .min()
+            .unwrap_or(0)
     }
-    
-    /// Get the current batch configuration
-    pub fn batch_config(&self) -> &BatchConfig {
-        &self.batch_config
+
+    /// Record an access, aging the whole sketch if the reset threshold is hit
+    fn increment(&mut self, key_hash: u64) {
+        for row in 0..SKETCH_DEPTH {
+            let index = self.slot(key_hash, row);
+            let current = Self::get_nibble(&self.rows[row], index);
+            if current < 15 {
+                Self::set_nibble(&mut self.rows[row], index, current + 1);
+            }
+        }
+
+        self.additions += 1;
+        if self.additions >= self.reset_threshold {
+            self.age();
+            self.additions = 0;
+        }
     }
-    
-    /// Update the batch configuration
-    pub fn set_batch_config(&mut self, config: BatchConfig) {
-        self.batch_config = config;
+
+    /// Halve every counter, keeping relative frequency ordering while
+    /// letting stale hot keys cool off
+    fn age(&mut self) {
+        for row in self.rows.iter_mut() {
+            for byte in row.iter_mut() {
+                let lo = (*byte & 0x0F) >> 1;
+                let hi = ((*byte >> 4) & 0x0F) >> 1;
+                *byte = lo | (hi << 4);
+            }
+        }
     }
-    
-    /// Invalidate the cache for a specific key
-    pub fn invalidate(&self, key: &[u8]) {
-        let mut cache = self.cache.write();
-        cache.pop(key);
+}
+
+fn hash_key(key: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Derive the structural prefix to read ahead under, e.g. `node:` from a
+/// `node:<uuid>` key or `edge:` from `edge:<uuid>` (see `graph.rs`'s
+/// `node_key`/`edge_key`, which join a tag and an id with `:`). Keys with no
+/// `:` delimiter have no derivable prefix, so read-ahead is skipped for them
+/// rather than risking a scan over the entire keyspace.
+fn structural_prefix(key: &[u8]) -> Option<&[u8]> {
+    let delimiter = key.iter().rposition(|&b| b == b':')?;
+    Some(&key[..=delimiter])
+}
+
+/// A pluggable cache backend behind [`CachedStore`]'s lock. The built-in
+/// W-TinyLFU policy ([`WTinyLfuCache`], produced by [`WTinyLfuCacheFactory`])
+/// is the default; implement this trait to drop in an LFU, segmented, or
+/// instrumented policy without forking this module.
+///
+/// Hit/miss metrics are derived by the caller from `get`'s returned
+/// `Option`, not tracked here -- only evictions need to be reported back
+/// explicitly, via [`take_evictions`](Self::take_evictions), since those
+/// happen deep inside a `put` the caller can't otherwise observe.
+pub trait CacheStorage: Send {
+    /// Look up `key`, promoting it within the policy's own eviction
+    /// ordering as a side effect (e.g. touching LRU recency or
+    /// incrementing a frequency sketch). Use [`peek`](Self::peek) for a
+    /// read that must not affect eviction order.
+    fn get(&mut self, key: &[u8]) -> Option<&Vec<u8>>;
+
+    /// Look up `key` without affecting eviction order.
+    fn peek(&self, key: &[u8]) -> Option<&Vec<u8>>;
+
+    /// Insert or overwrite `key`, evicting per the policy if needed.
+    fn put(&mut self, key: &[u8], value: Vec<u8>);
+
+    /// Remove `key`, if present.
+    fn remove(&mut self, key: &[u8]);
+
+    /// Drop every entry.
+    fn clear(&mut self);
+
+    /// Number of entries currently resident.
+    fn len(&self) -> usize;
+
+    /// Whether no entries are currently resident.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
     }
-    
-    /// Clear the entire cache
-    pub fn clear_cache(&self) {
-        let mut cache = self.cache.write();
-        cache.clear();
+
+    /// Whether `key` is resident, without affecting eviction order.
+    fn contains(&self, key: &[u8]) -> bool {
+        self.peek(key).is_some()
     }
-    
-    /// Get cache metrics
-    pub fn metrics(&self) -> &CacheMetrics {
-        &self.metrics
+
+    /// Entries evicted or rejected admission since the last call to this
+    /// method, which resets the count to zero.
+    fn take_evictions(&mut self) -> u64;
+
+    /// The actual key/value pairs evicted or rejected admission since the
+    /// last call to this method, which drains them. Used to spill entries
+    /// into [`CachedStore`]'s optional disk tier instead of discarding them;
+    /// a policy that doesn't need to support that can leave the default
+    /// empty implementation, since [`take_evictions`](Self::take_evictions)
+    /// alone is enough to drive metrics.
+    fn take_evicted(&mut self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        Vec::new()
     }
-    
-    /// Update read-ahead keys
-    pub fn update_read_ahead_keys(&self, key: &[u8]) {
-        if self.read_ahead_window == 0 {
-            return;
+}
+
+/// Produces the [`CacheStorage`] backing a [`CachedStore`]. Implement this
+/// (and pass an instance to [`CachedStore::with_factory`]) to supply a
+/// custom eviction/admission policy; [`WTinyLfuCacheFactory`] is the
+/// default.
+pub trait CacheFactory: Send + Sync {
+    fn create(&self, config: &CacheConfig) -> Box<dyn CacheStorage>;
+}
+
+/// Builds the built-in [`WTinyLfuCache`] policy -- the default
+/// [`CacheFactory`] for [`CachedStore`] when none is given explicitly.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WTinyLfuCacheFactory;
+
+impl CacheFactory for WTinyLfuCacheFactory {
+    fn create(&self, config: &CacheConfig) -> Box<dyn CacheStorage> {
+        Box::new(WTinyLfuCache::new(config))
+    }
+}
+
+/// The admitted cache content, split into a W-TinyLFU window + SLRU main
+/// segment, guarded by a single lock (the sketch and the three LRUs always
+/// move together, so one lock keeps the bookkeeping simple and avoids
+/// lock-ordering hazards).
+struct WTinyLfuCache {
+    sketch: CountMinSketch,
+    /// Small LRU segment every new key is admitted into first (~1% of
+    /// `capacity`, the Caffeine-style window size)
+    window: LruCache<Vec<u8>, Vec<u8>>,
+    /// SLRU "probation" tier: keys that have been accessed exactly once
+    /// since entering the main segment (~20% of the main segment)
+    probation: LruCache<Vec<u8>, Vec<u8>>,
+    /// SLRU "protected" tier: keys that have been accessed at least twice
+    /// (~80% of the main segment)
+    protected: LruCache<Vec<u8>, Vec<u8>>,
+    /// Byte budget this cache is held to; see [`CacheConfig::max_bytes`]
+    max_bytes: Option<usize>,
+    /// Running total of bytes across every entry actually resident in
+    /// `window`/`probation`/`protected`, checked against `max_bytes` on
+    /// every `put` and kept in sync whenever an entry is added, moved
+    /// between segments, or evicted.
+    current_bytes: usize,
+    /// Entries evicted or rejected admission since the last
+    /// [`take_evictions`](CacheStorage::take_evictions) call.
+    evictions: u64,
+    /// The key/value pairs behind `evictions`, since the last
+    /// [`take_evicted`](CacheStorage::take_evicted) call, for the disk tier
+    /// to pick up.
+    evicted: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl WTinyLfuCache {
+    fn new(config: &CacheConfig) -> Self {
+        let capacity = config.capacity.max(3);
+        let window_capacity = (capacity / 100).max(1);
+        let main_capacity = capacity.saturating_sub(window_capacity).max(2);
+        let protected_capacity = (main_capacity * 8 / 10).max(1);
+        let probation_capacity = main_capacity.saturating_sub(protected_capacity).max(1);
+
+        Self {
+            sketch: CountMinSketch::new(capacity),
+            window: LruCache::new(std::num::NonZeroUsize::new(window_capacity).unwrap()),
+            probation: LruCache::new(std::num::NonZeroUsize::new(probation_capacity).unwrap()),
+            protected: LruCache::new(std::num::NonZeroUsize::new(protected_capacity).unwrap()),
+            max_bytes: config.max_bytes,
+            current_bytes: 0,
+            evictions: 0,
+            evicted: Vec::new(),
         }
-        
-        // In a real implementation, this would use the key to determine
-        // related keys that are likely to be accessed next and prefetch them
-        // For now, this is a no-op
     }
-    
-    /// Prefetch keys that are likely to be accessed next
-    pub fn prefetch_keys(&self, _prefix: &[u8]) {
-        // In a real implementation, this would prefetch keys with the given prefix
-        // For now, this is a no-op
+
+    /// Move a probation hit into protected, demoting protected's LRU entry
+    /// back down to probation if protected is already full.
+    fn promote_to_protected(&mut self, key: &[u8]) {
+        let Some(value) = self.probation.pop(key) else {
+            return;
+        };
+        if let Some((demoted_key, demoted_value)) = self.protected.put(key.to_vec(), value) {
+            // probation has exactly one free slot here (we just popped `key`
+            // out of it), so this can't trigger a further eviction.
+            self.probation.put(demoted_key, demoted_value);
+        }
     }
-}
 
-impl<S> Storage for CachedStore<S>
-where
-    S: Storage + 'static,
-    S: Send + Sync,
-    for<'a> S::Batch<'a>: Send + Sync + 'a,
-{
-    type Batch<'a> = CachedBatch<S::Batch<'a>> where Self: 'a;
-    
-    fn get<T: DeserializeOwned + Serialize>(&self, key: &[u8]) -> Result<Option<T>> {
-        // Try to get from cache first
-        {
-            let cache = self.cache.read();
-            if let Some(cached) = cache.peek(key) {
-                self.metrics.record_hit(cached.len());
-                return Ok(Some(bincode::deserialize(cached)
-                    .map_err(|e| KnowledgeGraphError::from(format!("Failed to deserialize cached value: {}", e)))?));
+    /// Decide whether a window-evicted candidate earns a spot in probation.
+    fn admit_or_reject(&mut self, candidate_key: Vec<u8>, candidate_value: Vec<u8>) {
+        if self.probation.len() < self.probation.cap().get() {
+            self.probation.put(candidate_key, candidate_value);
+            return;
+        }
+
+        let (victim_key, _) = self
+            .probation
+            .peek_lru()
+            .expect("probation is at capacity so it must be non-empty");
+        let victim_freq = self.sketch.estimate(hash_key(victim_key));
+        let candidate_freq = self.sketch.estimate(hash_key(&candidate_key));
+
+        if candidate_freq > victim_freq {
+            if let Some((victim_key, victim_value)) = self.probation.pop_lru() {
+                self.current_bytes = self.current_bytes.saturating_sub(victim_value.len());
+                self.evicted.push((victim_key, victim_value));
             }
+            // The candidate's bytes are already counted in current_bytes
+            // from when `put` admitted it into the window; moving it into
+            // probation here doesn't add new bytes to the cache.
+            self.probation.put(candidate_key, candidate_value);
+            self.evictions += 1;
+        } else {
+            // The candidate is leaving the cache for good, so release the
+            // bytes `put` counted for it into the window.
+            self.current_bytes = self.current_bytes.saturating_sub(candidate_value.len());
+            self.evictions += 1;
+            self.evicted.push((candidate_key, candidate_value));
         }
-        
-        // Cache miss, get from inner storage
-        self.metrics.record_miss();
-        let result = self.inner.get(key)?;
-        
-        // If we got a result, cache it
-        if let Some(ref value) = result {
-            let bytes = bincode::serialize(value)
-                .map_err(|e| KnowledgeGraphError::from(format!("Failed to serialize value: {}", e)))?;
-            
-            let mut cache = self.cache.write();
-            cache.put(key.to_vec(), bytes);
-        }
-        
-        Ok(result)
     }
-    
-    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
-        let bytes = bincode::serialize(value)
-            .map_err(|e| KnowledgeGraphError::from(format!("Failed to serialize value: {}", e)))?;
-        self.put_raw(key, &bytes)
+
+    /// Evict from the coldest segment first (window, then probation, then
+    /// protected) until `incoming_bytes` more would fit within the budget.
+    fn enforce_byte_budget(&mut self, incoming_bytes: usize) {
+        let Some(limit) = self.max_bytes else {
+            return;
+        };
+
+        while self.current_bytes + incoming_bytes > limit {
+            let evicted = self
+                .window
+                .pop_lru()
+                .or_else(|| self.probation.pop_lru())
+                .or_else(|| self.protected.pop_lru());
+
+            match evicted {
+                Some((key, value)) => {
+                    self.current_bytes = self.current_bytes.saturating_sub(value.len());
+                    self.evictions += 1;
+                    self.evicted.push((key, value));
+                }
+                None => break, // cache is empty but a single item still exceeds the budget
+            }
+        }
     }
-    
-    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.put(key.to_vec(), value.to_vec());
-            self.metrics.record_write(value.len());
+}
+
+impl CacheStorage for WTinyLfuCache {
+    /// Read `key`, recording the access with the sketch and promoting it
+    /// within/between segments as W-TinyLFU/SLRU dictate.
+    fn get(&mut self, key: &[u8]) -> Option<&Vec<u8>> {
+        if self.window.contains(key) {
+            self.sketch.increment(hash_key(key));
+            return self.window.get(key).map(|v| &*v);
         }
-        
-        // Write to inner storage
-        self.inner.put_raw(key, value)
+
+        if self.probation.contains(key) {
+            self.sketch.increment(hash_key(key));
+            self.promote_to_protected(key);
+            return self.protected.get(key).map(|v| &*v);
+        }
+
+        if self.protected.contains(key) {
+            self.sketch.increment(hash_key(key));
+            return self.protected.get(key).map(|v| &*v);
+        }
+
+        None
     }
-    
-    fn delete(&self, key: &[u8]) -> Result<()> {
-        // Invalidate cache
-        self.invalidate(key);
-        
-        // Delete from inner storage
-        self.inner.delete(key)
+
+    fn peek(&self, key: &[u8]) -> Option<&Vec<u8>> {
+        self.window
+            .peek(key)
+            .or_else(|| self.probation.peek(key))
+            .or_else(|| self.protected.peek(key))
     }
-    
-    fn exists(&self, key: &[u8]) -> Result<bool> {
-        // Check cache first
-        {
-            let cache = self.cache.read();
-            if cache.contains(key) {
-                self.metrics.record_hit(0);
-                return Ok(true);
-            }
+
+    /// Insert (or overwrite) `key` with `value`, admitting it through the
+    /// window segment and contesting a spot in probation if the window is
+    /// full, per W-TinyLFU.
+    fn put(&mut self, key: &[u8], value: Vec<u8>) {
+        // Drop any stale copy first so a re-put doesn't leave duplicates
+        // across segments.
+        if let Some(old) = self.window.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(old.len());
         }
-        
-        // Check inner storage
-        self.metrics.record_miss();
-        self.inner.exists(key)
-    }
-    
-    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        // Try to get from cache first
-        {
-            let cache = self.cache.read();
-            if let Some(cached) = cache.peek(key) {
-                self.metrics.record_hit(cached.len());
-                return Ok(Some(cached.clone()));
-            }
+        if let Some(old) = self.probation.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(old.len());
+        }
+        if let Some(old) = self.protected.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(old.len());
+        }
+
+        self.sketch.increment(hash_key(key));
+        self.enforce_byte_budget(value.len());
+
+        self.current_bytes += value.len();
+        if let Some((candidate_key, candidate_value)) = self.window.put(key.to_vec(), value) {
+            self.admit_or_reject(candidate_key, candidate_value);
         }
-        
-        // Cache miss, get from inner storage
-        self.metrics.record_miss();
-        self.inner.get_raw(key)
     }
-    
-    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.put(key.to_vec(), value.to_vec());
-            self.metrics.record_write(value.len());
+
+    fn remove(&mut self, key: &[u8]) {
+        if let Some(value) = self.window.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(value.len());
+        }
+        if let Some(value) = self.probation.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(value.len());
+        }
+        if let Some(value) = self.protected.pop(key) {
+            self.current_bytes = self.current_bytes.saturating_sub(value.len());
         }
-        
-        // Write to inner storage
-        self.inner.put_raw(key, value)
     }
-    
-    fn iter_prefix(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
-        // In a real implementation, this would iterate over the cache first, then the inner storage
-        // For now, just delegate to the inner storage
-        self.inner.iter_prefix(prefix)
+
+    fn clear(&mut self) {
+        self.window.clear();
+        self.probation.clear();
+        self.protected.clear();
+        self.current_bytes = 0;
     }
-    
-    fn create_batch(&self) -> Self::Batch<'_> {
-        // Create the inner batch first
-        let inner_batch = self.inner.create_batch();
-        
-        CachedBatch::with_config(
-            inner_batch,
-            self.cache.clone(),
-            self.metrics.clone(),
-            self.batch_config.clone(),
-            self.read_ahead_window,
-        )
+
+    fn len(&self) -> usize {
+        self.window.len() + self.probation.len() + self.protected.len()
+    }
+
+    fn take_evictions(&mut self) -> u64 {
+        std::mem::take(&mut self.evictions)
+    }
+
+    fn take_evicted(&mut self) -> Vec<(Vec<u8>, Vec<u8>)> {
+        std::mem::take(&mut self.evicted)
     }
 }
 
-/// A batch of operations that will be applied atomically to the storage
-/// and updates the cache accordingly.
-#[derive(Debug)]
-pub(crate) struct CachedBatch<B> {
-    inner: B,
-    cache: Arc<RwLock<LruCache<Vec<u8>, Vec<u8>>>>,
-    metrics: Arc<CacheMetrics>,
-    pending_puts: HashMap<Vec<u8>, Vec<u8>>,
-    pending_deletes: HashSet<Vec<u8>>,
-    batch_config: BatchConfig,
-    stats: BatchStats,
-    read_ahead_window: usize,
+/// A disk-backed second tier for entries evicted from [`CachedStore`]'s
+/// in-memory policy, so they aren't simply discarded -- see
+/// [`CacheConfig::disk_tier_path`]. Backed by its own [`sled::Db`], separate
+/// from `inner`, so a hit here still saves a trip to (and a deserialize
+/// through) the primary storage backend.
+///
+/// Each value is stored under its key with an 8-byte big-endian insertion
+/// sequence prefixed on, so [`evict_to_budget`](Self::evict_to_budget) can
+/// find the globally oldest entry without a second index. That makes
+/// eviction an O(n) scan over the disk tier, which is acceptable since it
+/// only runs when `disk_tier_capacity_bytes` is actually exceeded and the
+/// disk tier is meant to hold the overflow `inner` already persists anyway.
+struct DiskTier {
+    db: sled::Db,
+    capacity_bytes: Option<usize>,
+    current_bytes: usize,
+    next_seq: u64,
 }
 
-impl<S> WriteBatchExt for CachedStore<S>
-where
-    S: Storage + WriteBatchExt + 'static,
-    for<'a> S::Batch<'a>: Clone + Send + Sync + 'a,
-{
-    type Batch<'a> = CachedBatch<S::Batch<'a>> where Self: 'a;
-    
-    fn batch(&self) -> Self::Batch<'_> {
-        self.create_batch()
+impl DiskTier {
+    fn open(path: &Path, capacity_bytes: Option<usize>) -> Result<Self> {
+        let db = sled::open(path)?;
+        let mut current_bytes = 0usize;
+        let mut max_seq = 0u64;
+        for entry in db.iter() {
+            let (_, packed) = entry?;
+            let (seq, value) = Self::unpack(&packed);
+            current_bytes += value.len();
+            max_seq = max_seq.max(seq);
+        }
+
+        Ok(Self {
+            db,
+            capacity_bytes,
+            current_bytes,
+            next_seq: max_seq.wrapping_add(1),
+        })
     }
-    
-    fn create_batch(&self) -> Self::Batch<'_> {
-        // Create the inner batch first
-        let inner_batch = self.inner.create_batch();
-        
-        CachedBatch::with_config(
-            inner_batch,
-            self.cache.clone(),
-            self.metrics.clone(),
-            self.batch_config.clone(),
-            self.read_ahead_window,
-        )
+
+    fn pack(seq: u64, value: &[u8]) -> Vec<u8> {
+        let mut packed = Vec::with_capacity(8 + value.len());
+        packed.extend_from_slice(&seq.to_be_bytes());
+        packed.extend_from_slice(value);
+        packed
     }
-    
-    // ... [rest of the implementation]
-}
 
-impl<B> CachedBatch<B>
-where
-    B: WriteBatch + 'static,
-    B: Send + Sync,
-{
-    /// Create a new CachedBatch with the given configuration
-    pub(crate) fn with_config(
-        inner: B,
-        cache: Arc<RwLock<LruCache<Vec<u8>, Vec<u8>>>>,
-        metrics: Arc<CacheMetrics>,
-        batch_config: BatchConfig,
-        read_ahead_window: usize,
-    ) -> Self {
-        let stats = BatchStats::new(batch_config.initial_batch_size);
-        
-        Self {
-            inner,
-            cache,
-            metrics,
-            pending_puts: HashMap::new(),
-            pending_deletes: HashSet::new(),
-            batch_config,
-            stats,
-            read_ahead_window,
-        }
-    }
-    
-    /// Create a new CachedBatch with default configuration
-    pub(crate) fn new(
-        inner: B,
-        cache: Arc<RwLock<LruCache<Vec<u8>, Vec<u8>>>>,
-        metrics: Arc<CacheMetrics>,
-    ) -> Self {
-        Self::with_config(
-            inner,
-            cache,
-            metrics,
-            BatchConfig::default(),
-            0, // Default read-ahead window
-        )
+    fn unpack(packed: &[u8]) -> (u64, &[u8]) {
+        let seq = u64::from_be_bytes(packed[..8].try_into().expect("packed entry has 8-byte seq prefix"));
+        (seq, &packed[8..])
     }
-    
-    /// Apply pending operations to the inner batch and clear them
-    fn apply_pending_ops(&mut self) -> Result<()> {
-        if !self.pending_puts.is_empty() || !self.pending_deletes.is_empty() {
-            // Apply pending puts
-            for (key, value) in self.pending_puts.drain() {
-                self.inner.put_serialized(&key, &value)?;
-            }
-            
-            // Apply pending deletes
-            for key in self.pending_deletes.drain() {
-                self.inner.delete_serialized(&key)?;
-            }
-            
-            // Update stats
-            self.stats.record_ops(self.pending_puts.len() + self.pending_deletes.len());
-        }
-        
-        Ok(())
+
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.db.get(key)?.map(|packed| Self::unpack(&packed).1.to_vec()))
     }
-}
 
-impl<B> WriteBatch for CachedBatch<B>
-where
-    B: WriteBatch + 'static,
-    B: Send + Sync,
-{
-    fn put<T: Serialize>(&mut self, key: &[u8], value: &T) -> Result<()> {
-        let bytes = bincode::serialize(value)
-            .map_err(|e| KnowledgeGraphError::from(format!("Failed to serialize value: {}", e)))?;
-        self.put_serialized(key, &bytes)
+    /// Insert `key`/`value`, promoting it to the front of the insertion
+    /// order, then evict down to the byte budget if needed.
+    fn put(&mut self, key: &[u8], value: Vec<u8>) -> Result<()> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if let Some(old) = self.db.insert(key, Self::pack(seq, &value))? {
+            self.current_bytes = self.current_bytes.saturating_sub(Self::unpack(&old).1.len());
+        }
+        self.current_bytes += value.len();
+        self.evict_to_budget()
     }
-    
-    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
-        // Remove from pending deletes if it exists
-        self.pending_deletes.remove(key);
-        
-        // Add to pending puts
-        self.pending_puts.insert(key.to_vec(), value.to_vec());
-        
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.put(key.to_vec(), value.to_vec());
-            self.metrics.record_write(value.len());
+
+    fn remove(&mut self, key: &[u8]) -> Result<()> {
+        if let Some(old) = self.db.remove(key)? {
+            self.current_bytes = self.current_bytes.saturating_sub(Self::unpack(&old).1.len());
         }
-        
         Ok(())
     }
-    
-    fn delete(&mut self, key: &[u8]) -> Result<()> {
-        self.delete_serialized(key)
+
+    fn clear(&mut self) -> Result<()> {
+        self.db.clear()?;
+        self.current_bytes = 0;
+        Ok(())
     }
-    
-    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
-        // Remove from pending puts if it exists
-        self.pending_puts.remove(key);
-        
-        // Add to pending deletes
-        self.pending_deletes.insert(key.to_vec());
-        
-        // Update cache
-        {
-            let mut cache = self.cache.write();
-            cache.pop(key);
+
+    /// Remove every entry whose key starts with `prefix` -- an O(n) scan,
+    /// like [`evict_to_budget`](Self::evict_to_budget), used for the rarer
+    /// collection-scoped [`CachedStore::clear_cache_in`].
+    fn remove_prefix(&mut self, prefix: &[u8]) -> Result<()> {
+        let matching: Vec<sled::IVec> = self
+            .db
+            .scan_prefix(prefix)
+            .keys()
+            .collect::<std::result::Result<_, _>>()?;
+        for key in matching {
+            if let Some(old) = self.db.remove(&key)? {
+                self.current_bytes = self.current_bytes.saturating_sub(Self::unpack(&old).1.len());
+            }
         }
-        
         Ok(())
     }
-    
-    fn clear(&mut self) {
-        self.pending_puts.clear();
-        self.pending_deletes.clear();
-        self.inner.clear();
-    }
-    
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-    
-    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
-        self
-    }
-    
-    fn commit(mut self) -> Result<()> {
-        // Apply all pending operations
-        self.apply_pending_ops()?;
-        
-        // Commit the inner batch
-        self.inner.commit()?;
-        
-        // Update metrics
-        self.metrics.record_write(self.pending_puts.len() + self.pending_deletes.len());
-        
+
+    /// Evict the globally oldest entries (by insertion sequence) until
+    /// `current_bytes` is back within `capacity_bytes`.
+    fn evict_to_budget(&mut self) -> Result<()> {
+        let Some(limit) = self.capacity_bytes else {
+            return Ok(());
+        };
+
+        while self.current_bytes > limit {
+            let mut oldest: Option<(sled::IVec, u64, usize)> = None;
+            for entry in self.db.iter() {
+                let (key, packed) = entry?;
+                let (seq, value) = Self::unpack(&packed);
+                if oldest.as_ref().map_or(true, |(_, oldest_seq, _)| seq < *oldest_seq) {
+                    oldest = Some((key, seq, value.len()));
+                }
+            }
+
+            let Some((key, _, value_len)) = oldest else {
+                break; // disk tier is empty but a single item still exceeds the budget
+            };
+            self.db.remove(&key)?;
+            self.current_bytes = self.current_bytes.saturating_sub(value_len);
+        }
+
         Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    // ... [test module content]
+/// A storage wrapper that adds a memory-bounded cache in front of another
+/// storage implementation. The eviction/admission policy is pluggable via
+/// `C: CacheFactory` -- the built-in W-TinyLFU policy
+/// ([`WTinyLfuCacheFactory`]) is the default, so `CachedStore<S>` (eliding
+/// `C`) keeps meaning exactly what it always has.
+pub struct CachedStore<S, C = WTinyLfuCacheFactory> {
+    inner: S,
+    state: Arc<Mutex<Box<dyn CacheStorage>>>,
+    metrics: Arc<CacheMetrics>,
+    storage_metrics: Arc<StorageMetrics>,
+    read_ahead_window: usize,
+    /// Optional second-tier cache for entries evicted from `state`; see
+    /// [`CacheConfig::disk_tier_path`].
+    disk_tier: Option<Arc<Mutex<DiskTier>>>,
+    /// Keys populated by read-ahead prefetching that haven't been hit yet,
+    /// so [`cache_get`](Self::cache_get) can attribute a later hit to
+    /// [`CacheMetrics::prefetch_hits`]. Removed on that first hit; see
+    /// [`prefetch_keys`](Self::prefetch_keys) for the growth bound on
+    /// prefetched-but-never-hit entries.
+    prefetched_keys: Arc<Mutex<HashSet<Vec<u8>>>>,
+    /// Per-[`CollectionId`] cache partitions (see
+    /// [`get_in`](Self::get_in)/[`put_in`](Self::put_in)), created lazily
+    /// on first use of a given collection via `factory`/`config` so each
+    /// collection evicts independently of the default keyspace's `state`
+    /// and of every other collection.
+    collections: Mutex<HashMap<CollectionId, CollectionPartition>>,
+    /// Bounded tombstone set of keys confirmed absent from `inner`; `None`
+    /// unless [`CacheConfig::cache_negatives`] is set. Capped at
+    /// `config.capacity` entries, evicting least-recently-confirmed-absent
+    /// first, the same bound the main cache itself uses.
+    negative_cache: Option<Arc<Mutex<LruCache<Vec<u8>, ()>>>>,
+    factory: C,
+    config: CacheConfig,
+}
+
+/// One collection's independent cache partition: its own admission policy
+/// instance plus its own metrics, so [`CachedStore::clear_cache_in`]/
+/// [`invalidate_in`](CachedStore::invalidate_in) never affect another
+/// collection or the default keyspace. Cloning just clones the `Arc`s, so
+/// [`CachedStore::partition`] can hand out a handle without holding the
+/// `collections` map locked for the caller's I/O.
+#[derive(Clone)]
+struct CollectionPartition {
+    state: Arc<Mutex<Box<dyn CacheStorage>>>,
+    metrics: Arc<CacheMetrics>,
+}
+
+impl<S> CachedStore<S, WTinyLfuCacheFactory> {
+    /// Create a new cached storage wrapper with default configuration,
+    /// backed by the built-in W-TinyLFU policy.
+    pub fn new(inner: S) -> Self {
+        Self::with_config(inner, CacheConfig::default())
+    }
+
+    /// Create a new cached storage wrapper with custom configuration,
+    /// backed by the built-in W-TinyLFU policy. Use
+    /// [`with_factory`](CachedStore::with_factory) to supply a different
+    /// policy instead.
+    pub fn with_config(inner: S, config: CacheConfig) -> Self {
+        Self::with_factory(inner, config, WTinyLfuCacheFactory)
+    }
+}
+
+impl<S, C: CacheFactory> CachedStore<S, C> {
+    /// Create a new cached storage wrapper whose cache is built by
+    /// `factory` instead of the default W-TinyLFU policy.
+    pub fn with_factory(inner: S, config: CacheConfig, factory: C) -> Self {
+        let disk_tier = open_disk_tier(&config);
+        let negative_cache = config.cache_negatives.then(|| {
+            let capacity = std::num::NonZeroUsize::new(config.capacity.max(1)).unwrap();
+            Arc::new(Mutex::new(LruCache::new(capacity)))
+        });
+        Self {
+            inner,
+            state: Arc::new(Mutex::new(factory.create(&config))),
+            metrics: Arc::new(CacheMetrics::default()),
+            storage_metrics: Arc::new(StorageMetrics::new()),
+            read_ahead_window: config.read_ahead_window,
+            disk_tier,
+            prefetched_keys: Arc::new(Mutex::new(HashSet::new())),
+            collections: Mutex::new(HashMap::new()),
+            negative_cache,
+            config,
+            factory,
+        }
+    }
+}
+
+/// Opens `config.disk_tier_path`'s [`DiskTier`] if one is configured.
+/// Failing to open it (e.g. a permissions error) disables the disk tier
+/// for this store rather than failing construction outright, since it's
+/// an optional performance overflow, not the source of truth -- `inner`
+/// always holds every entry durably regardless.
+fn open_disk_tier(config: &CacheConfig) -> Option<Arc<Mutex<DiskTier>>> {
+    let path = config.disk_tier_path.as_ref()?;
+    match DiskTier::open(path, config.disk_tier_capacity_bytes) {
+        Ok(tier) => Some(Arc::new(Mutex::new(tier))),
+        Err(err) => {
+            log::warn!("failed to open cache disk tier at {}: {err}", path.display());
+            None
+        }
+    }
+}
+
+impl<S, C> CachedStore<S, C> {
+    /// Cross-backend operation/cache/latency counters for this store; see
+    /// [`StorageMetrics`].
+    pub fn storage_metrics(&self) -> &StorageMetrics {
+        &self.storage_metrics
+    }
+
+    /// Get a reference to the inner storage
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner storage
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Invalidate the cache for a specific key, in memory and on the disk
+    /// tier if one is configured.
+    pub fn invalidate(&self, key: &[u8]) {
+        self.state.lock().remove(key);
+        if let Some(disk_tier) = &self.disk_tier {
+            if let Err(err) = disk_tier.lock().remove(key) {
+                log::warn!("cache disk tier remove failed: {err}");
+            }
+        }
+    }
+
+    /// Clear the entire cache, in memory and on the disk tier if one is
+    /// configured.
+    pub fn clear_cache(&self) {
+        self.state.lock().clear();
+        if let Some(disk_tier) = &self.disk_tier {
+            if let Err(err) = disk_tier.lock().clear() {
+                log::warn!("cache disk tier clear failed: {err}");
+            }
+        }
+    }
+
+    /// Get cache metrics
+    pub(crate) fn metrics(&self) -> &CacheMetrics {
+        &self.metrics
+    }
+
+    /// Check whether `key` is currently resident in the cache, without
+    /// affecting its recency/frequency bookkeeping. Callers that need a
+    /// real residency signal (e.g. `HybridStore::route_read`) should use
+    /// this instead of guessing.
+    pub fn contains_cached(&self, key: &[u8]) -> bool {
+        self.state.lock().contains(key)
+    }
+
+    /// Reads `key` from memory, falling back to the disk tier (if
+    /// configured) on a memory miss and promoting a disk-tier hit back into
+    /// memory. Records hit/miss and disk-hit/disk-miss metrics along the
+    /// way; a final `None` means both tiers (and thus `inner`) must be
+    /// consulted.
+    fn cache_get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let hit = self.state.lock().get(key).cloned();
+        if let Some(bytes) = hit {
+            self.metrics.record_hit(bytes.len());
+            if self.prefetched_keys.lock().remove(key) {
+                self.metrics.record_prefetch_hit();
+            }
+            return Some(bytes);
+        }
+        self.metrics.record_miss();
+
+        let disk_tier = self.disk_tier.as_ref()?;
+        match disk_tier.lock().get(key) {
+            Ok(Some(bytes)) => {
+                self.metrics.record_disk_hit(bytes.len());
+                self.cache_put(key, bytes.clone());
+                Some(bytes)
+            }
+            Ok(None) => {
+                self.metrics.record_disk_miss();
+                None
+            }
+            Err(err) => {
+                log::warn!("cache disk tier read failed: {err}");
+                self.metrics.record_disk_miss();
+                None
+            }
+        }
+    }
+
+    /// Inserts `key`/`value` into the memory cache, folds whatever it
+    /// evicted into `metrics`, and spills the evicted entries into the disk
+    /// tier (if configured) instead of discarding them.
+    fn cache_put(&self, key: &[u8], value: Vec<u8>) {
+        let mut state = self.state.lock();
+        state.put(key, value);
+        let evicted = state.take_evicted();
+        self.metrics.record_evictions(state.take_evictions());
+        drop(state);
+
+        spill_to_disk_tier(self.disk_tier.as_ref(), evicted);
+    }
+
+    /// Whether `key` is tombstoned as confirmed-absent (see
+    /// [`CacheConfig::cache_negatives`]). Always `false` when negative
+    /// caching is disabled.
+    fn is_negatively_cached(&self, key: &[u8]) -> bool {
+        self.negative_cache
+            .as_ref()
+            .is_some_and(|cache| cache.lock().contains(key))
+    }
+
+    /// Tombstone `key` as confirmed-absent, if negative caching is enabled.
+    fn cache_negative(&self, key: &[u8]) {
+        if let Some(cache) = &self.negative_cache {
+            cache.lock().put(key.to_vec(), ());
+            self.metrics.record_negative_cache();
+        }
+    }
+
+    /// Clear `key`'s tombstone, if any -- called by every write path so a
+    /// `put` after a cached miss doesn't keep returning the stale `None`.
+    fn clear_negative(&self, key: &[u8]) {
+        if let Some(cache) = &self.negative_cache {
+            cache.lock().pop(key);
+        }
+    }
+}
+
+/// Writes `evicted` entries into `disk_tier`, if one is configured, so
+/// they survive the memory cache discarding them. Disk I/O failures here
+/// are logged and otherwise ignored, since the disk tier is a best-effort
+/// overflow -- `inner` remains the authoritative copy either way.
+fn spill_to_disk_tier(disk_tier: Option<&Arc<Mutex<DiskTier>>>, evicted: Vec<(Vec<u8>, Vec<u8>)>) {
+    if evicted.is_empty() {
+        return;
+    }
+    let Some(disk_tier) = disk_tier else {
+        return;
+    };
+
+    let mut tier = disk_tier.lock();
+    for (key, value) in evicted {
+        if let Err(err) = tier.put(&key, value) {
+            log::warn!("cache disk tier write failed: {err}");
+        }
+    }
+}
+
+impl<S, C> CachedStore<S, C>
+where
+    S: Storage,
+{
+    /// Look up several keys at once, serving whatever's already cached
+    /// immediately and coalescing every miss into a single
+    /// [`Storage::multi_get_raw`] round trip against `inner` instead of one
+    /// call per miss -- the batching win DataLoader-style loaders are named
+    /// for, most useful when expanding a node's many neighbors at once.
+    /// Populates the cache for every key actually fetched from `inner`.
+    /// Missing keys (present in neither the cache nor `inner`) are simply
+    /// absent from the result map.
+    pub fn load_many(&self, keys: &[Vec<u8>]) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+        let mut results = HashMap::with_capacity(keys.len());
+        let mut misses = Vec::new();
+
+        for key in keys {
+            match self.cache_get(key) {
+                Some(bytes) => {
+                    results.insert(key.clone(), bytes);
+                }
+                None => misses.push(key.clone()),
+            }
+        }
+
+        if misses.is_empty() {
+            return Ok(results);
+        }
+
+        let fetched = self.inner.multi_get_raw(&misses)?;
+        for (key, value) in misses.into_iter().zip(fetched) {
+            if let Some(bytes) = value {
+                self.cache_put(&key, bytes.clone());
+                results.insert(key, bytes);
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+impl<S, C> CachedStore<S, C>
+where
+    S: Storage,
+    C: CacheFactory,
+{
+    /// `collection`'s cache partition, creating it via `factory`/`config`
+    /// on first use. Cheap to call repeatedly: the `Arc`s inside
+    /// [`CollectionPartition`] are cloned out from under the `collections`
+    /// lock so the partition's own I/O never holds it.
+    fn partition(&self, collection: &CollectionId) -> CollectionPartition {
+        self.collections
+            .lock()
+            .entry(collection.clone())
+            .or_insert_with(|| CollectionPartition {
+                state: Arc::new(Mutex::new(self.factory.create(&self.config))),
+                metrics: Arc::new(CacheMetrics::default()),
+            })
+            .clone()
+    }
+
+    /// `collection`'s own hit/miss/disk-hit/disk-miss counters, if it's
+    /// been used yet -- a per-collection breakdown alongside the default
+    /// keyspace's [`metrics`](Self::metrics).
+    pub(crate) fn metrics_in(&self, collection: &CollectionId) -> Option<Arc<CacheMetrics>> {
+        self.collections.lock().get(collection).map(|p| p.metrics.clone())
+    }
+
+    /// Like [`Storage::get`], but scoped to `collection`'s own keyspace and
+    /// cache partition, so it can't collide with (or evict) keys from the
+    /// default keyspace or any other collection.
+    pub fn get_in<T: DeserializeOwned>(&self, collection: &CollectionId, key: &[u8]) -> Result<Option<T>> {
+        let physical_key = collection.physical_key(key);
+        let partition = self.partition(collection);
+
+        let hit = partition.state.lock().get(&physical_key).cloned();
+        if let Some(bytes) = hit {
+            partition.metrics.record_hit(bytes.len());
+            return Ok(Some(deserialize(&bytes)?));
+        }
+        partition.metrics.record_miss();
+
+        if let Some(disk_tier) = &self.disk_tier {
+            match disk_tier.lock().get(&physical_key) {
+                Ok(Some(bytes)) => {
+                    partition.metrics.record_disk_hit(bytes.len());
+                    let mut state = partition.state.lock();
+                    state.put(&physical_key, bytes.clone());
+                    partition.metrics.record_evictions(state.take_evictions());
+                    let evicted = state.take_evicted();
+                    drop(state);
+                    spill_to_disk_tier(self.disk_tier.as_ref(), evicted);
+                    return Ok(Some(deserialize(&bytes)?));
+                }
+                Ok(None) => partition.metrics.record_disk_miss(),
+                Err(err) => {
+                    log::warn!("cache disk tier read failed: {err}");
+                    partition.metrics.record_disk_miss();
+                }
+            }
+        }
+
+        let result = self.inner.get_raw(&physical_key)?;
+        if let Some(ref bytes) = result {
+            let mut state = partition.state.lock();
+            state.put(&physical_key, bytes.clone());
+            partition.metrics.record_evictions(state.take_evictions());
+            let evicted = state.take_evicted();
+            drop(state);
+            spill_to_disk_tier(self.disk_tier.as_ref(), evicted);
+        }
+        result.map(|bytes| deserialize(&bytes)).transpose()
+    }
+
+    /// Like [`Storage::put`], but scoped to `collection`.
+    pub fn put_in<T: Serialize>(&self, collection: &CollectionId, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        let physical_key = collection.physical_key(key);
+        let partition = self.partition(collection);
+
+        partition.metrics.record_write(bytes.len());
+        let mut state = partition.state.lock();
+        state.put(&physical_key, bytes.clone());
+        partition.metrics.record_evictions(state.take_evictions());
+        let evicted = state.take_evicted();
+        drop(state);
+        spill_to_disk_tier(self.disk_tier.as_ref(), evicted);
+
+        self.inner.put_raw(&physical_key, &bytes)
+    }
+
+    /// Like [`Storage::delete`], but scoped to `collection`.
+    pub fn delete_in(&self, collection: &CollectionId, key: &[u8]) -> Result<()> {
+        self.invalidate_in(collection, key);
+        self.inner.delete(&collection.physical_key(key))
+    }
+
+    /// Iterate over every key/value pair in `collection`, with keys
+    /// returned in their logical (unprefixed) form. Goes straight to
+    /// `inner`, same as [`Storage::iter_prefix`] -- the cache doesn't
+    /// maintain key ordering across segments.
+    pub fn iter_collection<'a>(
+        &'a self,
+        collection: &CollectionId,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let prefix = collection.physical_prefix();
+        let prefix_len = prefix.len();
+        Box::new(
+            self.inner
+                .iter_prefix(&prefix)
+                .map(move |(key, value)| (key[prefix_len..].to_vec(), value)),
+        )
+    }
+
+    /// Invalidate `key` within `collection`'s cache partition and the
+    /// shared disk tier, without touching any other collection or the
+    /// default keyspace.
+    pub fn invalidate_in(&self, collection: &CollectionId, key: &[u8]) {
+        let physical_key = collection.physical_key(key);
+        self.partition(collection).state.lock().remove(&physical_key);
+        if let Some(disk_tier) = &self.disk_tier {
+            if let Err(err) = disk_tier.lock().remove(&physical_key) {
+                log::warn!("cache disk tier remove failed: {err}");
+            }
+        }
+    }
+
+    /// Clear `collection`'s entire cache partition and its entries in the
+    /// shared disk tier, without affecting any other collection or the
+    /// default keyspace.
+    pub fn clear_cache_in(&self, collection: &CollectionId) {
+        self.partition(collection).state.lock().clear();
+        if let Some(disk_tier) = &self.disk_tier {
+            if let Err(err) = disk_tier.lock().remove_prefix(&collection.physical_prefix()) {
+                log::warn!("cache disk tier prefix remove failed: {err}");
+            }
+        }
+    }
+}
+
+/// Cap on how many never-hit prefetched keys [`CachedStore::prefetch_keys`]
+/// tracks at once (see [`CachedStore::prefetched_keys`]). Read-ahead that's
+/// never actually read would otherwise grow this unboundedly; hitting the
+/// cap just clears it, which only loses `prefetch_hits` attribution for
+/// whichever prefetches happened to be in flight, not correctness.
+const MAX_TRACKED_PREFETCHED_KEYS: usize = 100_000;
+
+impl<S, C> CachedStore<S, C>
+where
+    S: Storage + Clone,
+{
+    /// On a read miss, if read-ahead is enabled, warm the cache with the
+    /// neighboring entries under `key`'s structural prefix (see
+    /// `structural_prefix`) -- e.g. a miss on `node:<uuid>` reads ahead
+    /// other `node:*` entries, since a graph traversal that touched one
+    /// node is likely to touch more of them next. A no-op if `key` has no
+    /// derivable prefix.
+    pub fn update_read_ahead_keys(&self, key: &[u8]) {
+        if self.read_ahead_window == 0 {
+            return;
+        }
+        let Some(prefix) = structural_prefix(key) else {
+            return;
+        };
+        self.prefetch_keys(prefix);
+    }
+
+    /// Eagerly warm the cache with up to `read_ahead_window` entries under
+    /// `prefix`, read from `inner` on a background rayon thread so the
+    /// caller that triggered this (typically a read miss, via
+    /// [`update_read_ahead_keys`](Self::update_read_ahead_keys)) isn't held
+    /// up by the scan.
+    pub fn prefetch_keys(&self, prefix: &[u8]) {
+        if self.read_ahead_window == 0 {
+            return;
+        }
+
+        let inner = self.inner.clone();
+        let prefix = prefix.to_vec();
+        let limit = self.read_ahead_window;
+        let state = self.state.clone();
+        let metrics = self.metrics.clone();
+        let disk_tier = self.disk_tier.clone();
+        let prefetched_keys = self.prefetched_keys.clone();
+
+        rayon::spawn(move || {
+            let mut prefetched = 0u64;
+            for (key, value) in inner.iter_prefix(&prefix).take(limit) {
+                let mut guard = state.lock();
+                if guard.contains(&key) {
+                    // Already resident (e.g. another reader already warmed
+                    // it); nothing to admit or attribute a prefetch to.
+                    continue;
+                }
+                guard.put(&key, value);
+                let evicted = guard.take_evicted();
+                metrics.record_evictions(guard.take_evictions());
+                drop(guard);
+                spill_to_disk_tier(disk_tier.as_ref(), evicted);
+
+                let mut tracked = prefetched_keys.lock();
+                if tracked.len() >= MAX_TRACKED_PREFETCHED_KEYS {
+                    tracked.clear();
+                }
+                tracked.insert(key);
+                drop(tracked);
+
+                prefetched += 1;
+            }
+            metrics.record_prefetched(prefetched);
+        });
+    }
+}
+
+impl<S, C> Storage for CachedStore<S, C>
+where
+    S: Storage + Clone + 'static,
+{
+    type Batch<'a> = CachedBatch<'a, S> where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        self.storage_metrics.record_get();
+        if let Some(bytes) = self.cache_get(key) {
+            self.storage_metrics.record_cache_hit();
+            return Ok(Some(deserialize(&bytes)?));
+        }
+
+        self.storage_metrics.record_cache_miss();
+        if self.is_negatively_cached(key) {
+            self.metrics.record_negative_hit();
+            return Ok(None);
+        }
+
+        self.update_read_ahead_keys(key);
+        let result = self.inner.get(key)?;
+        match result {
+            Some(ref value) => {
+                let bytes = serialize(value)?;
+                self.cache_put(key, bytes);
+            }
+            None => self.cache_negative(key),
+        }
+        Ok(result)
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.storage_metrics.record_put();
+        self.metrics.record_write(value.len());
+        self.clear_negative(key);
+        self.cache_put(key, value.to_vec());
+        self.inner.put_raw(key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.storage_metrics.record_delete();
+        self.invalidate(key);
+        self.cache_negative(key);
+        self.inner.delete(key)
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        if self.state.lock().contains(key) {
+            self.metrics.record_hit(0);
+            self.storage_metrics.record_cache_hit();
+            return Ok(true);
+        }
+
+        self.metrics.record_miss();
+        self.storage_metrics.record_cache_miss();
+        if self.is_negatively_cached(key) {
+            self.metrics.record_negative_hit();
+            return Ok(false);
+        }
+
+        let exists = self.inner.exists(key)?;
+        if !exists {
+            self.cache_negative(key);
+        }
+        Ok(exists)
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.storage_metrics.record_get();
+        if let Some(bytes) = self.cache_get(key) {
+            self.storage_metrics.record_cache_hit();
+            return Ok(Some(bytes));
+        }
+
+        self.storage_metrics.record_cache_miss();
+        if self.is_negatively_cached(key) {
+            self.metrics.record_negative_hit();
+            return Ok(None);
+        }
+
+        self.update_read_ahead_keys(key);
+        let result = self.inner.get_raw(key)?;
+        match result {
+            Some(ref bytes) => self.cache_put(key, bytes.clone()),
+            None => self.cache_negative(key),
+        }
+        Ok(result)
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        // The cache doesn't maintain key ordering across segments, so prefix
+        // scans go straight to the inner store.
+        self.inner.iter_prefix(prefix)
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        CachedBatch::new(
+            self.inner.create_batch(),
+            self.state.clone(),
+            self.metrics.clone(),
+            self.storage_metrics.clone(),
+            self.disk_tier.clone(),
+            self.negative_cache.clone(),
+        )
+    }
+}
+
+impl<S, C> WriteBatchExt for CachedStore<S, C> where S: Storage + 'static {}
+
+impl<S> Checkpoint for CachedStore<S, WTinyLfuCacheFactory>
+where
+    S: Checkpoint + 'static,
+{
+    fn checkpoint_to_path(&self, path: &std::path::Path) -> Result<()> {
+        // `get`/`put`/`delete` write straight through to `inner` already --
+        // there's no pending batch held inside `CachedStore` itself to
+        // drain first, only entries the cache has already admitted that
+        // `inner` also has durably. So a checkpoint here is just delegating
+        // to `inner`'s own checkpoint.
+        self.inner.checkpoint_to_path(path)
+    }
+
+    fn open_checkpoint(path: &std::path::Path) -> Result<Self> {
+        Ok(CachedStore::new(S::open_checkpoint(path)?))
+    }
+}
+
+/// A batch of operations that will be applied atomically to the inner
+/// storage and reflected in the cache once committed.
+pub struct CachedBatch<'a, S: Storage + 'a> {
+    inner: S::Batch<'a>,
+    state: Arc<Mutex<Box<dyn CacheStorage>>>,
+    metrics: Arc<CacheMetrics>,
+    storage_metrics: Arc<StorageMetrics>,
+    disk_tier: Option<Arc<Mutex<DiskTier>>>,
+    negative_cache: Option<Arc<Mutex<LruCache<Vec<u8>, ()>>>>,
+    pending_puts: Vec<(Vec<u8>, Vec<u8>)>,
+    pending_deletes: Vec<Vec<u8>>,
+}
+
+impl<'a, S: Storage + 'a> fmt::Debug for CachedBatch<'a, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedBatch")
+            .field("pending_puts", &self.pending_puts.len())
+            .field("pending_deletes", &self.pending_deletes.len())
+            .finish()
+    }
+}
+
+impl<'a, S: Storage + 'a> CachedBatch<'a, S> {
+    fn new(
+        inner: S::Batch<'a>,
+        state: Arc<Mutex<Box<dyn CacheStorage>>>,
+        metrics: Arc<CacheMetrics>,
+        storage_metrics: Arc<StorageMetrics>,
+        disk_tier: Option<Arc<Mutex<DiskTier>>>,
+        negative_cache: Option<Arc<Mutex<LruCache<Vec<u8>, ()>>>>,
+    ) -> Self {
+        Self {
+            inner,
+            state,
+            metrics,
+            storage_metrics,
+            disk_tier,
+            negative_cache,
+            pending_puts: Vec::new(),
+            pending_deletes: Vec::new(),
+        }
+    }
+}
+
+impl<'a, S: Storage + 'a> WriteBatch for CachedBatch<'a, S> {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.inner.put_serialized(key, value)?;
+        // Keep only each key's last operation, so commit applies it last-wins.
+        self.pending_deletes.retain(|k| k.as_slice() != key);
+        self.pending_puts.push((key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.inner.delete_serialized(key)?;
+        self.pending_puts.retain(|(k, _)| k.as_slice() != key);
+        self.pending_deletes.push(key.to_vec());
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+        self.pending_puts.clear();
+        self.pending_deletes.clear();
+    }
+
+    fn commit(self) -> Result<()> {
+        let start = Instant::now();
+        self.inner.commit()?;
+        self.storage_metrics.record_batch_commit(start.elapsed());
+
+        let mut state = self.state.lock();
+        for (key, value) in self.pending_puts {
+            self.metrics.record_write(value.len());
+            state.put(&key, value);
+            if let Some(negative_cache) = &self.negative_cache {
+                negative_cache.lock().pop(&key);
+            }
+        }
+        let evicted = state.take_evicted();
+        self.metrics.record_evictions(state.take_evictions());
+        for key in &self.pending_deletes {
+            state.remove(key);
+        }
+        drop(state);
+
+        // A key sits in at most one of `pending_puts`/`pending_deletes` (its
+        // last operation in the batch), so a key whose last operation was a
+        // delete ends up negatively cached and one re-put after a delete
+        // does not.
+        if let Some(negative_cache) = &self.negative_cache {
+            let mut negative_cache = negative_cache.lock();
+            for key in &self.pending_deletes {
+                negative_cache.put(key.clone(), ());
+            }
+        }
+
+        spill_to_disk_tier(self.disk_tier.as_ref(), evicted);
+        if let Some(disk_tier) = &self.disk_tier {
+            let mut tier = disk_tier.lock();
+            for key in &self.pending_deletes {
+                if let Err(err) = tier.remove(key) {
+                    log::warn!("cache disk tier remove failed: {err}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sled_store::SledStore;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cached_store_basic_roundtrip() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::new(store);
+
+        cached.put(b"key1", &42u64).unwrap();
+        assert_eq!(cached.get::<u64>(b"key1").unwrap(), Some(42));
+        assert!(cached.contains_cached(b"key1"));
+
+        cached.delete(b"key1").unwrap();
+        assert_eq!(cached.get::<u64>(b"key1").unwrap(), None);
+        assert!(!cached.contains_cached(b"key1"));
+    }
+
+    #[test]
+    fn test_window_eviction_rejects_cold_candidate_over_hot_victim() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(
+            store,
+            CacheConfig {
+                capacity: 300, // window=3, main=297 (protected ~237, probation ~60)
+                max_bytes: None,
+                read_ahead_window: 0,
+                disk_tier_path: None,
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+        );
+
+        // Warm up a probation key with repeated access so its sketch
+        // estimate is clearly higher than a brand-new, single-touch key.
+        cached.put(b"hot", &1u64).unwrap();
+        for _ in 0..5 {
+            let _ = cached.get::<u64>(b"hot").unwrap();
+        }
+
+        // Fill the rest of probation with one-touch keys so it's at capacity.
+        for i in 0..100u32 {
+            cached.put(format!("filler-{i}").as_bytes(), &i).unwrap();
+        }
+
+        assert!(cached.contains_cached(b"hot"));
+    }
+
+    #[test]
+    fn test_byte_budget_evicts_to_stay_under_limit() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(
+            store,
+            CacheConfig {
+                capacity: 1000,
+                max_bytes: Some(256),
+                read_ahead_window: 0,
+                disk_tier_path: None,
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+        );
+
+        for i in 0..64u32 {
+            cached.put(format!("key-{i}").as_bytes(), &vec![0u8; 32]).unwrap();
+        }
+
+        assert!(cached.metrics().evictions() > 0);
+
+        // Independently tally the bytes actually still resident (rather
+        // than trusting the cache's own internal byte counter, which a
+        // prior accounting bug let drift upward unboundedly): each
+        // retrievable key is a 32-byte value, so the true resident total
+        // must never exceed `max_bytes`, and a correctly-accounted cache
+        // should be using nearly all of its budget rather than sitting far
+        // under it from premature eviction.
+        let resident_bytes: usize = (0..64u32)
+            .filter(|i| cached.contains_cached(format!("key-{i}").as_bytes()))
+            .count()
+            * 32;
+        assert!(resident_bytes <= 256, "resident bytes {resident_bytes} exceed max_bytes");
+        assert!(
+            resident_bytes >= 224,
+            "resident bytes {resident_bytes} far under max_bytes, suggesting byte accounting is inflated and evicting prematurely"
+        );
+    }
+
+    /// A trivially simple `CacheStorage` that never evicts anything short of
+    /// an explicit `remove`/`clear`, used to prove `CachedStore::with_factory`
+    /// actually swaps the policy out rather than silently keeping W-TinyLFU.
+    #[derive(Default)]
+    struct UnboundedCache {
+        entries: std::collections::HashMap<Vec<u8>, Vec<u8>>,
+    }
+
+    impl CacheStorage for UnboundedCache {
+        fn get(&mut self, key: &[u8]) -> Option<&Vec<u8>> {
+            self.entries.get(key)
+        }
+
+        fn peek(&self, key: &[u8]) -> Option<&Vec<u8>> {
+            self.entries.get(key)
+        }
+
+        fn put(&mut self, key: &[u8], value: Vec<u8>) {
+            self.entries.insert(key.to_vec(), value);
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.entries.remove(key);
+        }
+
+        fn clear(&mut self) {
+            self.entries.clear();
+        }
+
+        fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        fn take_evictions(&mut self) -> u64 {
+            0
+        }
+    }
+
+    #[derive(Default)]
+    struct UnboundedCacheFactory;
+
+    impl CacheFactory for UnboundedCacheFactory {
+        fn create(&self, _config: &CacheConfig) -> Box<dyn CacheStorage> {
+            Box::new(UnboundedCache::default())
+        }
+    }
+
+    #[test]
+    fn test_with_factory_swaps_in_a_custom_cache_policy() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_factory(
+            store,
+            CacheConfig {
+                capacity: 2, // would evict almost everything under W-TinyLFU
+                max_bytes: None,
+                read_ahead_window: 0,
+                disk_tier_path: None,
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+            UnboundedCacheFactory,
+        );
+
+        for i in 0..50u32 {
+            cached.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        // An unbounded policy keeps every key resident regardless of
+        // `capacity`, unlike the default W-TinyLFU policy this config would
+        // otherwise evict almost all of them under.
+        for i in 0..50u32 {
+            assert!(cached.contains_cached(format!("key-{i}").as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_disk_tier_evicts_oldest_entry_once_over_budget() {
+        let dir = tempdir().unwrap();
+        let mut tier = DiskTier::open(dir.path(), Some(32)).unwrap();
+
+        tier.put(b"a", vec![0u8; 16]).unwrap();
+        tier.put(b"b", vec![0u8; 16]).unwrap();
+        assert_eq!(tier.get(b"a").unwrap(), Some(vec![0u8; 16]));
+
+        // A third 16-byte entry pushes past the 32-byte budget, evicting the
+        // oldest resident entry ("a" -- "b" was inserted after it).
+        tier.put(b"c", vec![0u8; 16]).unwrap();
+
+        assert_eq!(tier.get(b"a").unwrap(), None);
+        assert_eq!(tier.get(b"b").unwrap(), Some(vec![0u8; 16]));
+        assert_eq!(tier.get(b"c").unwrap(), Some(vec![0u8; 16]));
+    }
+
+    #[test]
+    fn test_disk_tier_survives_without_inner_and_is_promoted_on_hit() {
+        let disk_tier_dir = tempdir().unwrap();
+
+        // First pass: a tiny memory cache spills most entries into the disk
+        // tier as it fills up.
+        {
+            let store_dir = tempdir().unwrap();
+            let store = SledStore::open(store_dir.path()).unwrap();
+            let cached = CachedStore::with_config(
+                store,
+                CacheConfig {
+                    capacity: 3,
+                    max_bytes: None,
+                    read_ahead_window: 0,
+                    disk_tier_path: Some(disk_tier_dir.path().to_path_buf()),
+                    disk_tier_capacity_bytes: None,
+                    cache_negatives: false,
+                },
+            );
+
+            for i in 0..20u32 {
+                cached.put(format!("key-{i}").as_bytes(), &i).unwrap();
+            }
+            assert!(cached.metrics().evictions() > 0);
+        }
+
+        // Second pass: a brand-new `inner` store has none of these keys, so
+        // any hit must have come from the disk tier, shared only by path.
+        let empty_store_dir = tempdir().unwrap();
+        let empty_store = SledStore::open(empty_store_dir.path()).unwrap();
+        let cached = CachedStore::with_config(
+            empty_store,
+            CacheConfig {
+                capacity: 3,
+                max_bytes: None,
+                read_ahead_window: 0,
+                disk_tier_path: Some(disk_tier_dir.path().to_path_buf()),
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+        );
+
+        let mut found_via_disk_tier = false;
+        for i in 0..20u32 {
+            if let Some(value) = cached.get::<u32>(format!("key-{i}").as_bytes()).unwrap() {
+                assert_eq!(value, i);
+                found_via_disk_tier = true;
+            }
+        }
+
+        assert!(found_via_disk_tier);
+        assert!(cached.metrics().disk_hits() > 0);
+    }
+
+    #[test]
+    fn test_disk_tier_preserves_data_under_a_real_byte_budget() {
+        let disk_tier_dir = tempdir().unwrap();
+
+        // Unlike `test_disk_tier_survives_without_inner_and_is_promoted_on_hit`
+        // (which uses a count-only `capacity`), this drives the disk tier
+        // from a memory cache that's actually byte-budgeted via
+        // `max_bytes`. Before the chunk1-2 fix, the spillover feeding the
+        // disk tier was driven by an inflated `current_bytes` counter; this
+        // proves every key put through a byte-budgeted memory cache still
+        // round-trips through the disk tier correctly now that the counter
+        // reflects real resident bytes.
+        {
+            let store_dir = tempdir().unwrap();
+            let store = SledStore::open(store_dir.path()).unwrap();
+            let cached = CachedStore::with_config(
+                store,
+                CacheConfig {
+                    capacity: 1000,
+                    max_bytes: Some(256),
+                    read_ahead_window: 0,
+                    disk_tier_path: Some(disk_tier_dir.path().to_path_buf()),
+                    disk_tier_capacity_bytes: None,
+                    cache_negatives: false,
+                },
+            );
+
+            for i in 0..64u32 {
+                cached.put(format!("key-{i}").as_bytes(), &vec![0u8; 32]).unwrap();
+            }
+            assert!(cached.metrics().evictions() > 0);
+        }
+
+        // A brand-new `inner` store has none of these keys, so every one of
+        // the 64 keys must be served by either memory or the disk tier.
+        let empty_store_dir = tempdir().unwrap();
+        let empty_store = SledStore::open(empty_store_dir.path()).unwrap();
+        let cached = CachedStore::with_config(
+            empty_store,
+            CacheConfig {
+                capacity: 1000,
+                max_bytes: Some(256),
+                read_ahead_window: 0,
+                disk_tier_path: Some(disk_tier_dir.path().to_path_buf()),
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+        );
+
+        for i in 0..64u32 {
+            let key = format!("key-{i}");
+            assert_eq!(
+                cached.get::<Vec<u8>>(key.as_bytes()).unwrap(),
+                Some(vec![0u8; 32]),
+                "key {key} missing from both memory and disk tier"
+            );
+        }
+    }
+
+    #[test]
+    fn test_structural_prefix_splits_on_last_colon() {
+        assert_eq!(structural_prefix(b"node:abc"), Some(&b"node:"[..]));
+        assert_eq!(structural_prefix(b"node_edges:n1:outgoing"), Some(&b"node_edges:n1:"[..]));
+        assert_eq!(structural_prefix(b"no-delimiter"), None);
+    }
+
+    #[test]
+    fn test_prefetch_keys_warms_neighbors_and_is_attributed_on_hit() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        for i in 0..5u32 {
+            store.put(format!("node:{i}").as_bytes(), &i).unwrap();
+        }
+
+        let cached = CachedStore::with_config(
+            store,
+            CacheConfig {
+                capacity: 100,
+                max_bytes: None,
+                read_ahead_window: 10,
+                disk_tier_path: None,
+                disk_tier_capacity_bytes: None,
+                cache_negatives: false,
+            },
+        );
+
+        cached.prefetch_keys(b"node:");
+
+        // `rayon::spawn` runs the scan in the background, so poll briefly
+        // rather than assuming it's done the instant `prefetch_keys` returns.
+        let mut warmed = false;
+        for _ in 0..200 {
+            if cached.metrics().prefetched() >= 5 {
+                warmed = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(warmed, "prefetch did not populate the cache in time");
+
+        assert!(cached.contains_cached(b"node:0"));
+        assert_eq!(cached.metrics().prefetch_hits(), 0);
+        assert!(cached.get::<u32>(b"node:0").unwrap().is_some());
+        assert_eq!(cached.metrics().prefetch_hits(), 1);
+    }
+
+    #[test]
+    fn test_collections_keep_independent_keyspaces_and_metrics() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::new(store);
+
+        let nodes = CollectionId::new("nodes");
+        let edges = CollectionId::new("edges");
+
+        cached.put_in(&nodes, b"1", &"alice".to_string()).unwrap();
+        cached.put_in(&edges, b"1", &"friend-of".to_string()).unwrap();
+
+        // Same logical key, different collections: no collision.
+        assert_eq!(cached.get_in::<String>(&nodes, b"1").unwrap(), Some("alice".to_string()));
+        assert_eq!(cached.get_in::<String>(&edges, b"1").unwrap(), Some("friend-of".to_string()));
+
+        // And no collision with the default (unscoped) keyspace either.
+        assert_eq!(cached.get::<String>(b"1").unwrap(), None);
+
+        cached.clear_cache_in(&nodes);
+        // Clearing one collection's cache doesn't evict the underlying
+        // value -- it's still durably in `inner` -- or touch `edges`.
+        assert_eq!(cached.get_in::<String>(&nodes, b"1").unwrap(), Some("alice".to_string()));
+        assert!(cached.metrics_in(&edges).unwrap().evictions() == 0);
+    }
+
+    #[test]
+    fn test_iter_collection_strips_the_collection_prefix() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::new(store);
+
+        let nodes = CollectionId::new("nodes");
+        cached.put_in(&nodes, b"a", &1u32).unwrap();
+        cached.put_in(&nodes, b"b", &2u32).unwrap();
+        cached.put(b"a", &999u32).unwrap(); // default keyspace, must not appear
+
+        let mut found: Vec<_> = cached.iter_collection(&nodes).map(|(k, _)| k).collect();
+        found.sort();
+        assert_eq!(found, vec![b"a".to_vec(), b"b".to_vec()]);
+    }
+
+    #[test]
+    fn test_load_many_serves_cached_hits_and_fetches_the_rest_in_one_pass() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        for i in 0..5u32 {
+            store.put_raw(format!("n{i}").as_bytes(), &i.to_le_bytes()).unwrap();
+        }
+
+        let cached = CachedStore::new(store);
+        // Warm n0 into the cache; n1..n4 stay misses.
+        cached.get_raw(b"n0").unwrap();
+
+        let keys: Vec<Vec<u8>> = (0..5u32).map(|i| format!("n{i}").into_bytes()).collect();
+        let result = cached.load_many(&keys).unwrap();
+
+        assert_eq!(result.len(), 5);
+        for i in 0..5u32 {
+            let expected = i.to_le_bytes().to_vec();
+            assert_eq!(result.get(format!("n{i}").as_bytes()), Some(&expected));
+        }
+        // The misses are now warmed too, for subsequent lookups.
+        assert!(cached.contains_cached(b"n4"));
+
+        // A key absent from both the cache and `inner` is just missing from
+        // the result map, not an error.
+        let mut keys_with_missing = keys;
+        keys_with_missing.push(b"does-not-exist".to_vec());
+        let result = cached.load_many(&keys_with_missing).unwrap();
+        assert_eq!(result.len(), 5);
+        assert!(!result.contains_key(b"does-not-exist".as_slice()));
+    }
+
+    fn negative_caching_config() -> CacheConfig {
+        CacheConfig {
+            capacity: 100,
+            max_bytes: None,
+            read_ahead_window: 0,
+            disk_tier_path: None,
+            disk_tier_capacity_bytes: None,
+            cache_negatives: true,
+        }
+    }
+
+    #[test]
+    fn test_negative_cache_short_circuits_repeated_miss() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(store, negative_caching_config());
+
+        assert_eq!(cached.get::<u64>(b"missing").unwrap(), None);
+        assert_eq!(cached.metrics().negative_caches(), 1);
+        assert_eq!(cached.metrics().negative_hits(), 0);
+
+        assert_eq!(cached.get::<u64>(b"missing").unwrap(), None);
+        assert_eq!(cached.metrics().negative_hits(), 1);
+
+        assert!(!cached.exists(b"missing").unwrap());
+        assert_eq!(cached.metrics().negative_hits(), 2);
+    }
+
+    #[test]
+    fn test_put_after_negative_cache_clears_the_tombstone() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(store, negative_caching_config());
+
+        assert_eq!(cached.get::<u64>(b"key1").unwrap(), None);
+        cached.put(b"key1", &7u64).unwrap();
+
+        assert_eq!(cached.get::<u64>(b"key1").unwrap(), Some(7));
+        // No stale negative hit was recorded for the now-present key.
+        assert_eq!(cached.metrics().negative_hits(), 0);
+    }
+
+    #[test]
+    fn test_delete_tombstones_the_key() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(store, negative_caching_config());
+
+        cached.put(b"key1", &7u64).unwrap();
+        cached.delete(b"key1").unwrap();
+
+        assert_eq!(cached.get::<u64>(b"key1").unwrap(), None);
+        assert_eq!(cached.metrics().negative_hits(), 1);
+    }
+
+    #[test]
+    fn test_batch_put_clears_and_batch_delete_sets_tombstones() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let cached = CachedStore::with_config(store, negative_caching_config());
+
+        // Prime a tombstone for "a", then overwrite it via a batch.
+        assert_eq!(cached.get::<u64>(b"a").unwrap(), None);
+
+        let mut batch = cached.create_batch();
+        batch.put_serialized(b"a", &serialize(&1u64).unwrap()).unwrap();
+        batch.delete_serialized(b"b").unwrap();
+        batch.commit().unwrap();
+
+        assert_eq!(cached.get::<u64>(b"a").unwrap(), Some(1));
+        assert_eq!(cached.get::<u64>(b"b").unwrap(), None);
+        // "b" was never queried before the batch, so its tombstone came
+        // entirely from the batch's delete, not a prior miss.
+        assert_eq!(cached.metrics().negative_hits(), 1);
+    }
 }
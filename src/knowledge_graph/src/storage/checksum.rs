@@ -0,0 +1,121 @@
+//! Per-value integrity envelopes used internally by [`HybridStore`](super::HybridStore).
+//!
+//! Every value is wrapped as `[algorithm tag][digest][payload]` before it's
+//! handed to the primary or cache backend, and unwrapped (with the digest
+//! re-verified) on every read. This catches silent bit-level corruption —
+//! bad sectors, truncated writes, a flipped bit in transit — that a raw
+//! byte roundtrip through `Storage::get_raw`/`put_raw` would otherwise hand
+//! back without complaint.
+
+use crate::error::{KnowledgeGraphError, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+enum Algorithm {
+    Crc32c = 1,
+    Blake3 = 2,
+}
+
+impl Algorithm {
+    fn from_tag(tag: u8) -> Result<Self> {
+        match tag {
+            1 => Ok(Self::Crc32c),
+            2 => Ok(Self::Blake3),
+            other => Err(KnowledgeGraphError::ChecksumMismatch(format!(
+                "unrecognized checksum algorithm tag {other}"
+            ))),
+        }
+    }
+
+    fn digest_len(self) -> usize {
+        match self {
+            Self::Crc32c => 4,
+            Self::Blake3 => 32,
+        }
+    }
+
+    fn digest(self, payload: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Crc32c => crc32c::crc32c(payload).to_le_bytes().to_vec(),
+            Self::Blake3 => blake3::hash(payload).as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Wrap `payload` in a checksum envelope, using BLAKE3 when `strong` is set
+/// (`HybridConfig::strong_checksums`) and the much faster CRC32C otherwise.
+pub(crate) fn wrap(payload: &[u8], strong: bool) -> Vec<u8> {
+    let algo = if strong {
+        Algorithm::Blake3
+    } else {
+        Algorithm::Crc32c
+    };
+    let digest = algo.digest(payload);
+
+    let mut out = Vec::with_capacity(1 + digest.len() + payload.len());
+    out.push(algo as u8);
+    out.extend_from_slice(&digest);
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Validate and strip the envelope `wrap` produced, returning the original
+/// payload, or `KnowledgeGraphError::ChecksumMismatch` if the stored digest
+/// doesn't match the stored bytes (or the envelope is malformed).
+pub(crate) fn unwrap(enveloped: &[u8]) -> Result<Vec<u8>> {
+    let (&tag, rest) = enveloped.split_first().ok_or_else(|| {
+        KnowledgeGraphError::ChecksumMismatch(
+            "value is too short to contain a checksum envelope".to_string(),
+        )
+    })?;
+    let algo = Algorithm::from_tag(tag)?;
+
+    if rest.len() < algo.digest_len() {
+        return Err(KnowledgeGraphError::ChecksumMismatch(
+            "value is too short to contain a checksum envelope".to_string(),
+        ));
+    }
+    let (stored_digest, payload) = rest.split_at(algo.digest_len());
+
+    if algo.digest(payload) != stored_digest {
+        return Err(KnowledgeGraphError::ChecksumMismatch(format!(
+            "stored digest did not match recomputed digest over {} byte payload",
+            payload.len()
+        )));
+    }
+
+    Ok(payload.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_crc32c() {
+        let wrapped = wrap(b"hello world", false);
+        assert_eq!(unwrap(&wrapped).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_roundtrip_blake3() {
+        let wrapped = wrap(b"hello world", true);
+        assert_eq!(unwrap(&wrapped).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn test_detects_corruption() {
+        let mut wrapped = wrap(b"hello world", false);
+        let last = wrapped.len() - 1;
+        wrapped[last] ^= 0xFF;
+
+        let err = unwrap(&wrapped).unwrap_err();
+        assert!(matches!(err, KnowledgeGraphError::ChecksumMismatch(_)));
+    }
+
+    #[test]
+    fn test_rejects_truncated_envelope() {
+        let err = unwrap(&[Algorithm::Blake3 as u8, 1, 2]).unwrap_err();
+        assert!(matches!(err, KnowledgeGraphError::ChecksumMismatch(_)));
+    }
+}
@@ -0,0 +1,106 @@
+//! Typed column-family schema layer on top of [`Storage`].
+//!
+//! [`Storage`] itself is a single flat keyspace -- every backend that has
+//! no native notion of column families just stores everything under one
+//! logical bucket. A [`ColumnFamily`] names a second, independent keyspace
+//! a backend *can* choose to route into physically (its own block cache,
+//! compaction settings, compaction scans that never touch an unrelated
+//! family's keys); [`RocksDBStore`](super::RocksDBStore) is currently the
+//! only backend that does.
+//!
+//! [`ColumnFamilyStore`] and [`WriteBatchCf`] extend [`Storage`] and
+//! [`WriteBatch`] with CF-aware variants whose default methods fall back
+//! to the default keyspace, the same way [`Storage::iter_prefix_lazy`]
+//! defaults to an eager [`Storage::iter_prefix`] collect for any backend
+//! without a cheaper native cursor -- so any `S: Storage` gets a correct,
+//! if unpartitioned, implementation for free.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::models::{Edge, Node};
+use super::{Storage, WriteBatch};
+
+/// A named, typed partition of a [`ColumnFamilyStore`]'s keyspace.
+///
+/// Implementors are zero-sized marker types (see [`NodesCf`], [`EdgesCf`],
+/// [`IndicesCf`]) used purely to pick a column family at the call site,
+/// e.g. `store.get_cf::<NodesCf, _>(key)`. `Key`/`Value` document the
+/// logical schema of the family; `ColumnFamilyStore`'s methods still take
+/// and return raw bytes, same as [`Storage`].
+pub trait ColumnFamily {
+    /// The column family's name, as passed to the backend on open.
+    const NAME: &'static str;
+    /// The logical key type stored under this family.
+    type Key;
+    /// The logical value type stored under this family.
+    type Value;
+}
+
+/// The [`Node`] column family.
+pub struct NodesCf;
+
+impl ColumnFamily for NodesCf {
+    const NAME: &'static str = "nodes";
+    type Key = uuid::Uuid;
+    type Value = Node;
+}
+
+/// The [`Edge`] column family.
+pub struct EdgesCf;
+
+impl ColumnFamily for EdgesCf {
+    const NAME: &'static str = "edges";
+    type Key = uuid::Uuid;
+    type Value = Edge;
+}
+
+/// The secondary-index column family.
+pub struct IndicesCf;
+
+impl ColumnFamily for IndicesCf {
+    const NAME: &'static str = "indices";
+    type Key = Vec<u8>;
+    type Value = Vec<u8>;
+}
+
+/// [`Storage`] extension for backends that can physically partition their
+/// keyspace into [`ColumnFamily`]s.
+pub trait ColumnFamilyStore: Storage {
+    /// Get a value by key from column family `C`.
+    fn get_cf<C: ColumnFamily, T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        self.get(key)
+    }
+
+    /// Put a key-value pair into column family `C`.
+    fn put_cf<C: ColumnFamily, T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        self.put(key, value)
+    }
+
+    /// Delete a key from column family `C`.
+    fn delete_cf<C: ColumnFamily>(&self, key: &[u8]) -> Result<()> {
+        self.delete(key)
+    }
+
+    /// Iterate over key-value pairs with a prefix, scoped to column family `C`.
+    fn iter_prefix_cf<'a, C: ColumnFamily>(
+        &'a self,
+        prefix: &[u8],
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        self.iter_prefix(prefix)
+    }
+}
+
+/// [`WriteBatch`] extension mirroring [`ColumnFamilyStore`] for batched writes.
+pub trait WriteBatchCf: WriteBatch {
+    /// Add a put operation, with a pre-serialized value, scoped to column family `C`.
+    fn put_cf<C: ColumnFamily>(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.put_serialized(key, value)
+    }
+
+    /// Add a delete operation scoped to column family `C`.
+    fn delete_cf<C: ColumnFamily>(&mut self, key: &[u8]) -> Result<()> {
+        self.delete_serialized(key)
+    }
+}
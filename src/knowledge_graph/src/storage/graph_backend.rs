@@ -0,0 +1,49 @@
+//! An async-native alternative to [`Storage`](super::Storage) for pluggable
+//! backends that can't implement blocking I/O efficiently — a remote
+//! HTTP/S3-style service, say, where blocking the calling thread on every
+//! lookup would cost a thread per in-flight request instead of yielding it
+//! back to the runtime.
+//!
+//! Where [`Storage`](super::Storage) works in raw key/value bytes one level
+//! below the graph, [`GraphBackend`] works directly in graph terms
+//! (`Node`/`Edge`) — it's the trait
+//! [`AsyncKnowledgeGraph`](crate::async_graph::AsyncKnowledgeGraph) is
+//! generic over, mirroring how [`KnowledgeGraph`](crate::graph::KnowledgeGraph)
+//! is generic over [`Storage`](super::Storage).
+//! [`SledGraphBackend`](crate::async_graph::SledGraphBackend) is the
+//! default implementation, adapting the existing blocking
+//! `KnowledgeGraph<SledStore>` via `spawn_blocking` rather than
+//! reimplementing Sled access from scratch.
+
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::{Edge, Node};
+
+/// A pluggable async backend for
+/// [`AsyncKnowledgeGraph`](crate::async_graph::AsyncKnowledgeGraph).
+///
+/// Implementors own however they actually store nodes and edges; the only
+/// contract is answering these lookups and writes without blocking the
+/// async runtime they're polled on.
+pub trait GraphBackend: Send + Sync {
+    /// Look up a node by ID.
+    async fn get_node(&self, id: Uuid) -> Result<Option<Node>>;
+
+    /// Insert a new node.
+    async fn put_node(&self, node: Node) -> Result<()>;
+
+    /// Remove a node by ID.
+    async fn delete_node(&self, id: Uuid) -> Result<()>;
+
+    /// Insert a new edge.
+    async fn put_edge(&self, edge: Edge) -> Result<()>;
+
+    /// Every edge whose source is `node_id`.
+    async fn get_edges_from(&self, node_id: Uuid) -> Result<Vec<Edge>>;
+
+    /// Every node ID with `label`, used by
+    /// [`AsyncQueryBuilder`](crate::async_graph::AsyncQueryBuilder) to drive
+    /// a streamed scan instead of materializing the whole match set.
+    async fn find_node_ids_by_label(&self, label: &str) -> Result<Vec<Uuid>>;
+}
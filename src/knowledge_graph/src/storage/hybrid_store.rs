@@ -1,45 +1,60 @@
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
-use std::path::Path;
+use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use parking_lot::RwLock;
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{de::DeserializeOwned, Serialize};
 
 use crate::error::KnowledgeGraphError;
 use crate::storage::{
-    GenericWriteBatch, PrefetchExt, Result, Storage, WriteBatch, WriteBatchExt,
-    prefetch::{PrefetchConfig, PrefetchingIterator},
+    checksum,
+    migrate::{self, MigrationReport, DEFAULT_MIGRATION_BATCH_SIZE},
+    prefetch::PrefetchConfig,
+    snapshot::{Checkpoint, Snapshot},
+    deserialize, serialize, CachedStore, PrefetchExt, Result, Storage, WriteBatch, WriteBatchExt,
 };
 
-// Re-export PrefetchConfig for public use
-pub use crate::storage::prefetch::PrefetchConfig;
-
-/// Adapter to convert PrefetchingIterator's Item type to match Storage's iterator
-struct PrefetchingIteratorAdapter<I>(I);
+/// Outcome of a [`HybridStore::scrub`] run.
+#[derive(Debug, Clone, Default)]
+pub struct ScrubReport {
+    /// Number of keys whose value checksum was verified
+    pub keys_scanned: usize,
+    /// Keys whose stored value failed checksum verification, and so are
+    /// suspected corrupted on disk
+    pub corrupted_keys: Vec<Vec<u8>>,
+}
 
-impl<I, K, V> Iterator for PrefetchingIteratorAdapter<I>
-where
-    I: Iterator<Item = Result<(K, V)>>,
-    K: 'static,
-    V: 'static,
-{
-    type Item = (K, V);
-    
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.0.next() {
-            Some(Ok(item)) => Some(item),
-            Some(Err(_)) => None, // Skip errors
-            None => None,
-        }
+impl ScrubReport {
+    /// Whether every scanned key's checksum verified successfully
+    pub fn is_clean(&self) -> bool {
+        self.corrupted_keys.is_empty()
     }
 }
-use crate::storage::sled_store::SledStore;
-use crate::storage::cached_store::CachedStore;
+
+/// Which durable engine a [`HybridStore`] was opened with.
+///
+/// This is tracked on [`HybridConfig`] for introspection and logging; the
+/// actual choice of backend is made by which concrete `HybridStore<P>` is
+/// constructed (e.g. `HybridStore::<SledStore>::new` vs.
+/// `HybridStore::<LmdbStore>::new`), since Rust generics are resolved at
+/// compile time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// The [`SledStore`](super::SledStore) backend
+    Sled,
+    /// The [`LmdbStore`](super::LmdbStore) backend
+    Lmdb,
+}
 
 /// Configuration for the hybrid storage system
 #[derive(Clone, Debug)]
 pub struct HybridConfig {
+    /// Which durable engine this store was opened with
+    pub backend: StorageBackend,
     /// Initial read/write ratio threshold for using CachedStore (0.0 to 1.0)
     pub initial_read_ratio_threshold: f64,
     /// Minimum number of operations before considering adaptive routing
@@ -48,15 +63,44 @@ pub struct HybridConfig {
     pub stats_window_size: usize,
     /// How often to rebalance (in operations)
     pub rebalance_interval: usize,
+    /// Number of top keys, by decayed access frequency, to promote into the
+    /// cache on each rebalance
+    pub hot_set_size: usize,
+    /// Multiplier applied to every tracked key's decayed access score on
+    /// each rebalance (e.g. 0.9 retains 90% of the previous score)
+    pub decay_factor: f64,
+    /// Decayed access score below which a tracked key is considered cold
+    /// and evicted from the cache on rebalance
+    pub cold_score_floor: f64,
+    /// How often, in operations, to automatically materialize a snapshot
+    /// via [`HybridStore::snapshot`]. `None` disables scheduled snapshots,
+    /// which is the default — callers that want them must also set
+    /// `snapshot_dir`.
+    pub snapshot_interval: Option<usize>,
+    /// Base directory scheduled snapshots are written under, each in its
+    /// own `snapshot-<sequence_id>` subdirectory. Required for scheduled
+    /// snapshots to run; ignored otherwise.
+    pub snapshot_dir: Option<PathBuf>,
+    /// Use BLAKE3 instead of the default CRC32C for the per-value
+    /// checksums every write is wrapped in. BLAKE3 catches more than a
+    /// flipped-bit class of corruption, at a higher per-write cost.
+    pub strong_checksums: bool,
 }
 
 impl Default for HybridConfig {
     fn default() -> Self {
         Self {
+            backend: StorageBackend::Sled,
             initial_read_ratio_threshold: 0.7, // 70% reads
             min_operations_for_adaptive: 1000,
             stats_window_size: 10000,
             rebalance_interval: 1000,
+            hot_set_size: 100,
+            decay_factor: 0.9,
+            cold_score_floor: 0.05,
+            snapshot_interval: None,
+            snapshot_dir: None,
+            strong_checksums: false,
         }
     }
 }
@@ -87,38 +131,38 @@ impl OperationStats {
 
     fn read_ratio(&self) -> f64 {
         let total = self.total_operations() as f64;
-        if total == 0.0 { 0.0 } else { self.reads as f64 / total }
-    }
-
-    fn avg_read_latency_ns(&self) -> u128 {
-        if self.reads == 0 { 0 } else { self.read_latency_ns / self.reads as u128 }
-    }
-
-    fn avg_write_latency_ns(&self) -> u128 {
-        if self.writes == 0 { 0 } else { self.write_latency_ns / self.writes as u128 }
+        if total == 0.0 {
+            0.0
+        } else {
+            self.reads as f64 / total
+        }
     }
 }
 
-/// Hybrid storage that routes requests between SledStore and CachedStore
-pub struct HybridStore {
-    primary: Arc<SledStore>,
-    cache: Arc<CachedStore<SledStore>>,
+/// Hybrid storage that routes requests between a durable primary backend and
+/// a [`CachedStore`] wrapping that same backend.
+///
+/// `HybridStore<P>` is generic over the primary backend `P`, so it works the
+/// same whether `P` is [`SledStore`](super::SledStore) or
+/// [`LmdbStore`](super::LmdbStore) (or any other `Storage` implementation).
+pub struct HybridStore<P: Storage> {
+    primary: Arc<P>,
+    cache: Arc<CachedStore<P>>,
     config: HybridConfig,
     stats: RwLock<OperationStats>,
     operation_count: std::sync::atomic::AtomicUsize,
     key_routing: RwLock<HashMap<Vec<u8>, bool>>, // true if key is in cache
+    /// Exponentially-decayed access frequency per recently-touched key, used
+    /// by `rebalance()` to pick a hot set to promote and a cold set to evict
+    access_scores: RwLock<HashMap<Vec<u8>, f64>>,
+    /// Monotonically increasing counter handed out to each snapshot taken
+    /// from this store, via `snapshot()` or the scheduled variant
+    snapshot_sequence: AtomicU64,
 }
 
-impl HybridStore {
-    /// Create a new HybridStore with default configuration
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let primary = SledStore::open(path.as_ref())?;
-        let cache = CachedStore::new(primary.clone());
-        Self::with_config(primary, cache, HybridConfig::default())
-    }
-
+impl<P: Storage + Clone + Checkpoint> HybridStore<P> {
     /// Create a new HybridStore with custom configuration
-    pub fn with_config(primary: SledStore, cache: CachedStore<SledStore>, config: HybridConfig) -> Result<Self> {
+    pub fn with_config(primary: P, cache: CachedStore<P>, config: HybridConfig) -> Result<Self> {
         Ok(Self {
             primary: Arc::new(primary),
             cache: Arc::new(cache),
@@ -126,12 +170,21 @@ impl HybridStore {
             stats: RwLock::new(OperationStats::default()),
             operation_count: std::sync::atomic::AtomicUsize::new(0),
             key_routing: RwLock::new(HashMap::new()),
+            access_scores: RwLock::new(HashMap::new()),
+            snapshot_sequence: AtomicU64::new(0),
         })
     }
 
     /// Determine which backend to use for a read operation
+    ///
+    /// A key that's actually resident in the cache is always worth reading
+    /// from there; `key_routing` is only a fallback guess for keys the cache
+    /// hasn't made an admission decision about yet.
     fn route_read(&self, key: &[u8]) -> bool {
-        // Check if we have a specific routing for this key
+        if self.cache.contains_cached(key) {
+            return true;
+        }
+
         if let Some(cached) = self.key_routing.read().get(key) {
             return *cached;
         }
@@ -148,17 +201,12 @@ impl HybridStore {
     }
 
     fn should_use_cache(&self, is_read: bool) -> bool {
-        // If we don't have enough data yet, use the initial strategy
-        let stats = match self.stats.read() {
-            Ok(stats) => stats,
-            Err(_) => return is_read && self.config.initial_read_ratio_threshold > 0.0,
-        };
-        
+        let stats = self.stats.read();
         let total_ops = stats.total_operations();
         if total_ops < self.config.min_operations_for_adaptive {
             return is_read && self.config.initial_read_ratio_threshold > 0.0;
         }
-        
+
         // If we have enough data, use the adaptive strategy
         let ratio = stats.read_ratio();
         if is_read {
@@ -170,165 +218,242 @@ impl HybridStore {
     }
 
     /// Update operation statistics
-    fn update_stats<F, R>(&self, is_read: bool, f: F) -> R
+    fn update_stats<F, R>(&self, key: &[u8], is_read: bool, f: F) -> R
     where
         F: FnOnce() -> R,
     {
         let start = Instant::now();
         let result = f();
         let elapsed = start.elapsed();
-        
-        // Update stats
-        let mut stats = self.stats.write().unwrap();
+
+        let mut stats = self.stats.write();
         if is_read {
             stats.add_read(elapsed);
         } else {
             stats.add_write(elapsed);
         }
-        
+        drop(stats);
+
+        *self.access_scores.write().entry(key.to_vec()).or_insert(0.0) += 1.0;
+
         // Periodically rebalance
-        let op_count = self.operation_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        let op_count = self
+            .operation_count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            + 1;
         if op_count % self.config.rebalance_interval == 0 {
             self.rebalance();
         }
+        self.maybe_auto_snapshot(op_count);
 
         result
     }
 
-    /// Rebalance keys between primary and cache based on access patterns
+    /// Materialize a scheduled snapshot if `snapshot_interval` operations
+    /// have elapsed since the last check and a `snapshot_dir` is configured;
+    /// otherwise a no-op. Driven off the same operation counter as
+    /// `rebalance()`.
+    fn maybe_auto_snapshot(&self, op_count: usize) {
+        let Some(interval) = self.config.snapshot_interval else {
+            return;
+        };
+        if interval == 0 || op_count % interval != 0 {
+            return;
+        }
+        let Some(base_dir) = self.config.snapshot_dir.clone() else {
+            return;
+        };
+
+        let next_id = self
+            .snapshot_sequence
+            .load(std::sync::atomic::Ordering::Relaxed)
+            + 1;
+        let dir = base_dir.join(format!("snapshot-{next_id}"));
+        match self.snapshot(&dir) {
+            Ok(snapshot) => log::info!(
+                "scheduled snapshot {} materialized at {:?}",
+                snapshot.sequence_id(),
+                snapshot.path()
+            ),
+            Err(e) => log::warn!("scheduled snapshot failed: {}", e),
+        }
+    }
+
+    /// Produce a crash-consistent, read-only [`Snapshot`] of the primary
+    /// backend at `dir`, tagged with a monotonically increasing sequence id.
+    ///
+    /// The cache is never the source of truth (every write already goes
+    /// straight through to the primary), so this freezes the primary
+    /// directly via its native checkpoint facility (sled: flush + copy;
+    /// LMDB: compacting `mdb_env_copy2`) rather than needing to flush the
+    /// cache first. The returned handle is backed by an independent copy of
+    /// the data, so it stays stable no matter what writes land on this store
+    /// afterward.
+    pub fn snapshot(&self, dir: impl AsRef<Path>) -> Result<Snapshot<P>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let sequence_id = self
+            .snapshot_sequence
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            + 1;
+        Snapshot::capture(&*self.primary, dir, sequence_id)
+    }
+
+    /// Rebalance keys between primary and cache based on decayed per-key
+    /// access frequency: decay every tracked score, proactively promote the
+    /// top `hot_set_size` keys into the cache, and evict keys whose score
+    /// has fallen below `cold_score_floor`.
     fn rebalance(&self) {
-        // This is a simplified version - in a real implementation, you would:
-        // 1. Analyze access patterns
-        // 2. Identify hot/cold keys
-        // 3. Move data between backends
-        // 4. Update routing table
-        
-        // For now, we'll just clear the routing table to force re-evaluation
-        // of routing decisions based on the latest stats
-        self.key_routing.write().clear();
+        let mut ranked: Vec<(Vec<u8>, f64)> = {
+            let mut scores = self.access_scores.write();
+            for score in scores.values_mut() {
+                *score *= self.config.decay_factor;
+            }
+            scores.retain(|_, score| *score > 1e-6);
+            scores.iter().map(|(k, v)| (k.clone(), *v)).collect()
+        };
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let hot = ranked.iter().take(self.config.hot_set_size);
+        let mut promoted = 0usize;
+        for (key, _) in hot {
+            match self.primary.get_raw(key) {
+                Ok(Some(value)) => {
+                    if let Err(e) = self.cache.put_raw(key, &value) {
+                        log::warn!("Failed to promote hot key into cache: {}", e);
+                    }
+                    self.key_routing.write().insert(key.clone(), true);
+                    promoted += 1;
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to read hot key from primary during rebalance: {}", e),
+            }
+        }
+
+        let cold: Vec<&Vec<u8>> = ranked
+            .iter()
+            .filter(|(_, score)| *score < self.config.cold_score_floor)
+            .map(|(key, _)| key)
+            .collect();
+        for key in &cold {
+            self.cache.invalidate(key);
+            self.key_routing.write().insert((*key).clone(), false);
+        }
+
+        log::debug!(
+            "hybrid store rebalance: promoted {} hot keys, demoted {} cold keys, cache hit rate = {:.2}",
+            promoted,
+            cold.len(),
+            self.cache.metrics().hit_rate(),
+        );
     }
 
     /// Get a consistent hash of a key for sharding
+    #[allow(dead_code)]
     fn key_shard(&self, key: &[u8]) -> u64 {
         let mut hasher = DefaultHasher::new();
         key.hash(&mut hasher);
         hasher.finish()
     }
+
+    /// Stream every key/value pair out of this store's primary backend and
+    /// into `dst`, e.g. to move off of a failing engine (sled -> LMDB or
+    /// vice versa) without losing the underlying knowledge graph.
+    ///
+    /// This reads straight from the primary rather than the cache, so it
+    /// always reflects durable state. See [`migrate::migrate`] for details
+    /// on batching and the consistency check it performs.
+    pub fn convert_to<D: Storage>(&self, dst: &D) -> Result<MigrationReport> {
+        migrate::migrate(&*self.primary, dst, DEFAULT_MIGRATION_BATCH_SIZE)
+    }
+
+    /// Walk every key in the primary backend, recomputing and validating its
+    /// checksum envelope, and report which keys (if any) are corrupted.
+    ///
+    /// This reads straight from the primary so it verifies durable state,
+    /// not whatever happens to be cached. Operators should restore any
+    /// corrupted keys reported here from the most recent clean
+    /// [`HybridStore::snapshot`].
+    pub fn scrub(&self) -> ScrubReport {
+        let mut report = ScrubReport::default();
+        for (key, value) in self.primary.iter_prefix(&[]) {
+            report.keys_scanned += 1;
+            if checksum::unwrap(&value).is_err() {
+                report.corrupted_keys.push(key);
+            }
+        }
+        report
+    }
 }
 
-impl Storage for HybridStore {
-    type Batch<'a> = HybridBatch where Self: 'a;
-    
+impl<P: Storage + Clone> Storage for HybridStore<P> {
+    type Batch<'a> = HybridBatch<P::Batch<'a>, <CachedStore<P> as Storage>::Batch<'a>> where Self: 'a;
+
     fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
         // Use prefetching for better performance on sequential scans
         let config = PrefetchConfig {
-            prefetch_size: 64,         // Prefetch 64 items ahead
-            max_buffers: 4,            // Keep up to 4 prefetch buffers
-            buffer_size: 256,          // 256 items per buffer
-            prefetch_timeout_ms: 50,   // 50ms timeout
+            prefetch_size: 64,       // Prefetch 64 items ahead
+            max_buffers: 4,          // Keep up to 4 prefetch buffers
+            buffer_size: 256,        // 256 items per buffer
+            prefetch_timeout_ms: 50, // 50ms timeout
+            ..PrefetchConfig::default()
         };
-        
-        // Create a prefetching iterator
-        match self.iter_prefix_prefetch(prefix, config) {
-            Ok(iter) => {
-                // Convert the PrefetchingIterator into a Box<dyn Iterator>
-                let adapter = PrefetchingIteratorAdapter(iter);
-                Box::new(adapter)
-            },
+
+        match self.primary.iter_prefix_prefetch(prefix, config) {
+            Ok(iter) => Box::new(iter.filter_map(|item| item.ok())),
             Err(e) => {
-                // Fall back to non-prefetching iterator if prefetching fails
-                log::warn!("Failed to create prefetching iterator: {}. Falling back to standard iterator", e);
+                log::warn!(
+                    "Failed to create prefetching iterator: {}. Falling back to standard iterator",
+                    e
+                );
                 self.primary.iter_prefix(prefix)
             }
         }
     }
-    
+
     fn create_batch(&self) -> Self::Batch<'_> {
-        let primary_batch = Box::new(self.primary.create_batch());
-        let cache_batch = Box::new(self.cache.create_batch());
-        let key_routing = Arc::clone(&self.key_routing);
-        
         HybridBatch {
-            primary_batch,
-            cache_batch,
-            key_routing,
+            primary_batch: self.primary.create_batch(),
+            cache_batch: self.cache.create_batch(),
+            key_routing: Arc::new(RwLock::new(HashMap::new())),
+            strong_checksums: self.config.strong_checksums,
         }
     }
-    
+
     fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
-        self.update_stats(true, || {
-            if self.route_read(key) {
-                // Try cache first
-                match self.cache.get(key) {
-                    Ok(Some(value)) => {
-                        // Cache hit
-                        self.key_routing.write().insert(key.to_vec(), true);
-                        Ok(Some(value))
-                    },
-                    Ok(None) => {
-                        // Cache miss, try primary
-                        match self.primary.get(key) {
-                            Ok(Some(value)) => {
-                                // Update cache for next time
-                                if let Err(e) = self.cache.put(key, &value) {
-                                    log::warn!("Failed to update cache: {}", e);
-                                }
-                                self.key_routing.write().insert(key.to_vec(), true);
-                                Ok(Some(value))
-                            },
-                            Ok(None) => Ok(None),
-                            Err(e) => Err(e.into())
-                        }
-                    },
-                    Err(e) => {
-                        // Fallback to primary on cache error
-                        log::warn!("Cache error: {}, falling back to primary", e);
-                        self.primary.get(key).map_err(Into::into)
-                    },
-                }
-            } else {
-                // Read from primary only
-                self.key_routing.write().insert(key.to_vec(), false);
-                self.primary.get(key).map_err(Into::into)
-            }
-        })
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
     }
 
     fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
-        self.update_stats(false, || {
-            let value = bincode::serialize(value)
-                .map_err(|e| KnowledgeGraphError::SerializationError(e.into()))?;
-            
-            let use_cache = self.should_use_cache(false);
-            
-            if use_cache {
-                if let Err(e) = self.cache.put(key, &value) {
-                    log::warn!("Failed to write to cache: {}", e);
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
     fn delete(&self, key: &[u8]) -> Result<()> {
-        self.update_stats(false, || {
+        self.update_stats(key, false, || {
             // Delete from primary
             self.primary.delete(key)?;
-            
+
             // Invalidate cache asynchronously
             let cache = self.cache.clone();
-            let key = key.to_vec();
-            
-            // Spawn a blocking task to invalidate the cache
+            let owned_key = key.to_vec();
             std::thread::spawn(move || {
-                if let Err(e) = cache.delete_serialized(&key) {
+                if let Err(e) = cache.delete(&owned_key) {
                     log::warn!("Failed to invalidate cache: {}", e);
                 }
             });
-            
-            // Update key routing
-            self.key_routing.write().remove(&key);
-            
+
+            self.key_routing.write().remove(key);
+
             Ok(())
         })
     }
 
     fn exists(&self, key: &[u8]) -> Result<bool> {
-        self.update_stats(true, || {
+        self.update_stats(key, true, || {
             if self.route_read(key) {
                 match self.cache.exists(key) {
                     Ok(true) => Ok(true),
@@ -345,221 +470,311 @@ impl Storage for HybridStore {
     }
 
     fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
-        self.update_stats(true, || {
-            if self.route_read(key) {
-                // Try cache first
+        self.update_stats(key, true, || {
+            let enveloped = if self.route_read(key) {
                 match self.cache.get_raw(key) {
                     Ok(Some(value)) => {
-                        // Cache hit
                         self.key_routing.write().insert(key.to_vec(), true);
                         Ok(Some(value))
-                    },
-                    Ok(None) => {
-                        // Cache miss, try primary
-                        match self.primary.get_raw(key) {
-                            Ok(Some(value)) => {
-                                // Update cache for next time
-                                if let Err(e) = self.cache.put_serialized(key, &value) {
-                                    log::warn!("Failed to update cache: {}", e);
-                                }
-                                self.key_routing.write().insert(key.to_vec(), true);
-                                Ok(Some(value))
-                            },
-                            Ok(None) => Ok(None),
-                            Err(e) => Err(e.into())
+                    }
+                    Ok(None) => match self.primary.get_raw(key) {
+                        Ok(Some(value)) => {
+                            if let Err(e) = self.cache.put_raw(key, &value) {
+                                log::warn!("Failed to update cache: {}", e);
+                            }
+                            self.key_routing.write().insert(key.to_vec(), true);
+                            Ok(Some(value))
                         }
+                        Ok(None) => Ok(None),
+                        Err(e) => Err(e),
                     },
                     Err(e) => {
-                        // Fallback to primary on cache error
                         log::warn!("Cache error: {}, falling back to primary", e);
-                        self.primary.get_raw(key).map_err(Into::into)
-                    },
+                        self.primary.get_raw(key)
+                    }
                 }
             } else {
-                // Read from primary only
                 self.key_routing.write().insert(key.to_vec(), false);
-                self.primary.get_raw(key).map_err(Into::into)
+                self.primary.get_raw(key)
+            }?;
+
+            match enveloped {
+                Some(bytes) => Ok(Some(checksum::unwrap(&bytes)?)),
+                None => Ok(None),
             }
         })
     }
-}
 
-impl WriteBatchExt for HybridStore {
-    type BatchType<'a> = HybridBatch where Self: 'a;
-    
-    fn create_batch(&self) -> Self::BatchType<'_> {
-        HybridBatch::new(
-            Box::new(self.primary.create_batch()) as Box<dyn WriteBatch + Send + Sync>,
-            Box::new(self.cache.create_batch()) as Box<dyn WriteBatch + Send + Sync>,
-            self.key_routing.clone(),
-        )
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.update_stats(key, false, || {
+            let enveloped = checksum::wrap(value, self.config.strong_checksums);
+            let value = &enveloped;
+
+            self.primary.put_raw(key, value)?;
+
+            if self.should_use_cache(false) {
+                if let Err(e) = self.cache.put_raw(key, value) {
+                    log::warn!("Failed to write to cache: {}", e);
+                }
+                self.key_routing.write().insert(key.to_vec(), true);
+            } else {
+                self.key_routing.write().insert(key.to_vec(), false);
+            }
+
+            Ok(())
+        })
     }
-    
+}
+
+impl<P: Storage + Clone> WriteBatchExt for HybridStore<P> {
     fn put_serialized<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
-        let bytes = bincode::serialize(value).map_err(KnowledgeGraphError::from)?;
-        self.primary.put_serialized(key, &bytes)?;
-        match self.cache.put_serialized(key, &bytes) {
-            Ok(_) => {}
-            Err(e) => log::warn!("Failed to update cache in put_serialized: {}", e),
-        }
-        self.key_routing.write().insert(key.to_vec(), true);
-        Ok(())
+        // Route through our own `put_raw` so this gets the same checksum
+        // envelope and routing bookkeeping as every other write path.
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
     }
-    
+
     fn delete_serialized(&self, key: &[u8]) -> Result<()> {
-        self.primary.delete_serialized(key)?;
-        if let Err(e) = self.cache.delete_serialized(key) {
+        self.primary.delete(key)?;
+        if let Err(e) = self.cache.delete(key) {
             log::warn!("Failed to delete from cache in delete_serialized: {}", e);
         }
         self.key_routing.write().remove(key);
         Ok(())
     }
-    
-
 }
 
-/// Batch implementation for HybridStore
-#[derive(Debug)]
-pub struct HybridBatch {
-    primary_batch: Box<dyn WriteBatch + Send + Sync>,
-    cache_batch: Box<dyn WriteBatch + Send + Sync>,
-    key_routing: Arc<RwLock<HashMap<Vec<u8>, bool>>>,
+impl HybridStore<super::SledStore> {
+    /// Create a new Sled-backed HybridStore with default configuration
+    pub fn new<Pth: AsRef<Path>>(path: Pth) -> Result<Self> {
+        let primary = super::SledStore::open(path.as_ref())?;
+        let cache = CachedStore::new(primary.clone());
+        Self::with_config(
+            primary,
+            cache,
+            HybridConfig {
+                backend: StorageBackend::Sled,
+                ..HybridConfig::default()
+            },
+        )
+    }
 }
 
-impl HybridBatch {
-    /// Create a new HybridBatch
-    pub fn new(
-        primary_batch: Box<dyn WriteBatch + Send + Sync>,
-        cache_batch: Box<dyn WriteBatch + Send + Sync>,
-        key_routing: Arc<RwLock<HashMap<Vec<u8>, bool>>>,
-    ) -> Self {
-        Self {
-            primary_batch,
-            cache_batch,
-            key_routing,
-        }
+impl HybridStore<super::LmdbStore> {
+    /// Create a new LMDB-backed HybridStore with default configuration
+    pub fn new<Pth: AsRef<Path>>(path: Pth) -> Result<Self> {
+        let primary = super::LmdbStore::open(path.as_ref())?;
+        let cache = CachedStore::new(primary.clone());
+        Self::with_config(
+            primary,
+            cache,
+            HybridConfig {
+                backend: StorageBackend::Lmdb,
+                ..HybridConfig::default()
+            },
+        )
     }
 }
 
-impl WriteBatch for HybridBatch {
+/// Batch implementation for HybridStore, generic over the primary backend's
+/// batch type `PB` and the cache's batch type `CB`.
+#[derive(Debug)]
+pub struct HybridBatch<PB, CB> {
+    primary_batch: PB,
+    cache_batch: CB,
+    key_routing: Arc<RwLock<HashMap<Vec<u8>, bool>>>,
+    /// Mirrors `HybridConfig::strong_checksums` at the time the batch was
+    /// created, so every value it writes gets the same checksum envelope
+    /// `HybridStore::put_raw` would have given it.
+    strong_checksums: bool,
+}
+
+impl<PB, CB> WriteBatch for HybridBatch<PB, CB>
+where
+    PB: WriteBatch,
+    CB: WriteBatch,
+{
     fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        let value = checksum::wrap(value, self.strong_checksums);
+
         // Update primary first
-        self.primary_batch.put_serialized(key, value)?;
-        
+        self.primary_batch.put_serialized(key, &value)?;
+
         // Then update cache batch and routing
-        self.cache_batch.put_serialized(key, value)?;
+        self.cache_batch.put_serialized(key, &value)?;
         self.key_routing.write().insert(key.to_vec(), true);
-        
+
         Ok(())
     }
-    
+
     fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
-        // Delete from both batches
         self.primary_batch.delete_serialized(key)?;
-        
-        // Update cache batch and routing
+
         self.cache_batch.delete_serialized(key)?;
         self.key_routing.write().remove(key);
-        
+
         Ok(())
     }
-    
+
     fn clear(&mut self) {
         self.primary_batch.clear();
         self.cache_batch.clear();
     }
-    
+
     fn commit(self) -> Result<()> {
-        // Commit primary batch first
-        let primary_result = {
-            let batch = Box::into_raw(Box::new(self.primary_batch));
-            let result = unsafe { (*batch).commit() };
-            let _ = unsafe { Box::from_raw(batch) }; // Drop the box
-            result
-        };
-        
-        // Then commit cache batch
-        let cache_result = {
-            let batch = Box::into_raw(Box::new(self.cache_batch));
-            let result = unsafe { (*batch).commit() };
-            let _ = unsafe { Box::from_raw(batch) }; // Drop the box
-            result
-        };
-        
-        // Return primary result (more critical)
-        primary_result?;
-        cache_result?;
-        
+        // Commit primary first, it's the source of truth; the cache batch is
+        // a best-effort mirror of it.
+        self.primary_batch.commit()?;
+        self.cache_batch.commit()?;
+
         Ok(())
     }
-    
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
-    
+
     fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
         self
     }
 }
 
-// Implement GenericWriteBatch for HybridBatch
-impl GenericWriteBatch for HybridBatch {
-    type Error = crate::error::KnowledgeGraphError;
-    
-    fn put<T: Serialize>(&mut self, key: &[u8], value: &T) -> std::result::Result<(), Self::Error> {
-        let bytes = bincode::serialize(value).map_err(|e| crate::error::KnowledgeGraphError::BincodeError(e.to_string()))?;
-        self.put_serialized(key, &bytes)
-    }
-    
-    fn delete(&mut self, key: &[u8]) -> std::result::Result<(), Self::Error> {
-        self.delete_serialized(key)
-    }
-    
-    fn clear(&mut self) {
-        WriteBatch::clear(self)
-    }
-    
-    fn commit(self) -> std::result::Result<(), Self::Error> {
-        WriteBatch::commit(self)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::{GenericWriteBatch, SledStore};
     use tempfile::tempdir;
-    use crate::storage::{GenericWriteBatch, Storage};
-    use crate::storage::sled_store::SledStore;
-    use crate::storage::cached_store::CachedStore;
-    
+
     #[test]
     fn test_hybrid_store_basic() {
         let temp_dir = tempdir().unwrap();
         let primary = SledStore::open(temp_dir.path()).unwrap();
         let cache = CachedStore::new(primary.clone());
-        
-        // Create a new HybridStore with the primary and cache
-        let hybrid = HybridStore::with_config(
-            primary,
-            cache,
-            HybridConfig::default(),
-        ).unwrap();
+
+        let hybrid = HybridStore::with_config(primary, cache, HybridConfig::default()).unwrap();
 
         // Test basic operations
         hybrid.put(b"key1", &42u64).unwrap();
         assert_eq!(hybrid.get::<u64>(b"key1").unwrap(), Some(42));
-        
+
         // Test delete
         hybrid.delete(b"key1").unwrap();
         assert_eq!(hybrid.get::<u64>(b"key1").unwrap(), None);
-        
+
         // Test batch operations
         let mut batch = hybrid.create_batch();
         batch.put(b"batch1", &100u64).unwrap();
         batch.put(b"batch2", &200u64).unwrap();
         batch.commit().unwrap();
-        
+
         assert_eq!(hybrid.get::<u64>(b"batch1").unwrap(), Some(100));
         assert_eq!(hybrid.get::<u64>(b"batch2").unwrap(), Some(200));
     }
+
+    #[test]
+    fn test_hybrid_store_lmdb_backend() {
+        let temp_dir = tempdir().unwrap();
+        let hybrid = HybridStore::<crate::storage::LmdbStore>::new(temp_dir.path()).unwrap();
+
+        hybrid.put(b"key1", &7u64).unwrap();
+        assert_eq!(hybrid.get::<u64>(b"key1").unwrap(), Some(7));
+    }
+
+    #[test]
+    fn test_convert_to_migrates_primary_data() {
+        let sled_dir = tempdir().unwrap();
+        let lmdb_dir = tempdir().unwrap();
+
+        let hybrid = HybridStore::<SledStore>::new(sled_dir.path()).unwrap();
+        for i in 0..20u32 {
+            hybrid.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        let lmdb = crate::storage::LmdbStore::open(lmdb_dir.path()).unwrap();
+        let report = hybrid.convert_to(&lmdb).unwrap();
+
+        assert_eq!(report.keys_migrated, 20);
+        assert!(report.is_consistent());
+        // The primary's values carry a checksum envelope, which convert_to
+        // copies verbatim, so unwrap it before decoding.
+        let raw = lmdb.get_raw(b"key-0").unwrap().unwrap();
+        assert_eq!(deserialize::<u32>(&checksum::unwrap(&raw).unwrap()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_is_stable_across_later_writes() {
+        let store_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+
+        let hybrid = HybridStore::<SledStore>::new(store_dir.path()).unwrap();
+        hybrid.put(b"key", &1u64).unwrap();
+
+        let snapshot = hybrid.snapshot(snapshot_dir.path().join("snap-1")).unwrap();
+        hybrid.put(b"key", &2u64).unwrap();
+
+        assert_eq!(snapshot.get::<u64>(b"key").unwrap(), Some(1));
+        assert_eq!(hybrid.get::<u64>(b"key").unwrap(), Some(2));
+        assert_eq!(snapshot.sequence_id(), 1);
+    }
+
+    #[test]
+    fn test_scheduled_snapshot_fires_on_interval() {
+        let store_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+
+        let config = HybridConfig {
+            snapshot_interval: Some(5),
+            snapshot_dir: Some(snapshot_dir.path().to_path_buf()),
+            ..HybridConfig::default()
+        };
+        let primary = SledStore::open(store_dir.path()).unwrap();
+        let cache = CachedStore::new(primary.clone());
+        let hybrid = HybridStore::with_config(primary, cache, config).unwrap();
+
+        for i in 0..5u64 {
+            hybrid.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        assert!(snapshot_dir.path().join("snapshot-1").exists());
+    }
+
+    #[test]
+    fn test_get_detects_corrupted_value() {
+        let temp_dir = tempdir().unwrap();
+        let hybrid = HybridStore::<SledStore>::new(temp_dir.path()).unwrap();
+
+        hybrid.put(b"key", &42u64).unwrap();
+
+        // Corrupt the payload bytes directly in the primary, bypassing the
+        // checksum-writing path entirely, to simulate on-disk bit rot.
+        let mut raw = hybrid.primary.get_raw(b"key").unwrap().unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xFF;
+        hybrid.primary.put_raw(b"key", &raw).unwrap();
+        hybrid.cache.invalidate(b"key");
+
+        let err = hybrid.get::<u64>(b"key").unwrap_err();
+        assert!(matches!(err, KnowledgeGraphError::ChecksumMismatch(_)));
+    }
+
+    #[test]
+    fn test_scrub_reports_corrupted_keys() {
+        let temp_dir = tempdir().unwrap();
+        let hybrid = HybridStore::<SledStore>::new(temp_dir.path()).unwrap();
+
+        for i in 0..5u64 {
+            hybrid.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        let mut raw = hybrid.primary.get_raw(b"key-2").unwrap().unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xFF;
+        hybrid.primary.put_raw(b"key-2", &raw).unwrap();
+
+        let report = hybrid.scrub();
+
+        assert_eq!(report.keys_scanned, 5);
+        assert_eq!(report.corrupted_keys, vec![b"key-2".to_vec()]);
+        assert!(!report.is_clean());
+    }
 }
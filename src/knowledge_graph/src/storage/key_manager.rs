@@ -0,0 +1,395 @@
+//! Pluggable key-wrapping for [`EncryptedStore`](super::encrypted_store::EncryptedStore).
+//!
+//! `EncryptedStore` seals values with a 32-byte data-encryption key (DEK),
+//! but that DEK has to come from, and be recoverable from, somewhere durable
+//! that isn't "hardcoded in the binary". A [`KeyManager`] is that somewhere:
+//! it wraps a freshly generated DEK under a longer-lived wrapping key (a
+//! key-encryption key, or KEK) so only the *wrapped* bytes need to be
+//! persisted alongside the store, and unwraps them again on open. Wrapping
+//! keys are identified by a `u32` id, so a manager can hold more than one --
+//! the active one new DEKs are wrapped under, plus whichever older ones are
+//! still needed to unwrap DEKs minted before a rotation.
+//!
+//! [`EnvKeyManager`] is the default provider, loading wrapping keys from
+//! environment variables or a file. [`NoopKeyManager`] skips wrapping
+//! entirely and exists only so tests don't need real KEK material.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
+
+use crate::error::{KnowledgeGraphError, Result};
+
+/// The initial value RFC 3394 XORs into the running integrity check value;
+/// [`aes_key_unwrap`] rejects a wrap whose final value doesn't match this,
+/// which is how AES key wrap detects the wrong KEK or a corrupted blob
+/// without needing a separate MAC.
+const IV: [u8; 8] = [0xA6; 8];
+
+/// Number of 64-bit blocks in a 256-bit DEK.
+const N: usize = 4;
+
+/// Wrap a 256-bit key under a 256-bit KEK per RFC 3394 ("AES Key Wrap"),
+/// returning the 40-byte wrapped result (one extra 64-bit integrity block
+/// plus the four wrapped key blocks).
+fn aes_key_wrap(kek: &[u8; 32], data: &[u8; 32]) -> [u8; 40] {
+    let cipher = aes::Aes256::new(GenericArray::from_slice(kek));
+
+    let mut r = [[0u8; 8]; N];
+    for i in 0..N {
+        r[i].copy_from_slice(&data[i * 8..i * 8 + 8]);
+    }
+    let mut a = IV;
+
+    for j in 0..6u64 {
+        for i in 0..N {
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&a);
+            block[8..].copy_from_slice(&r[i]);
+            let mut ga = GenericArray::clone_from_slice(&block);
+            cipher.encrypt_block(&mut ga);
+
+            let t = j * N as u64 + (i as u64 + 1);
+            a = (u64::from_be_bytes(ga[..8].try_into().unwrap()) ^ t).to_be_bytes();
+            r[i].copy_from_slice(&ga[8..]);
+        }
+    }
+
+    let mut out = [0u8; 40];
+    out[..8].copy_from_slice(&a);
+    for i in 0..N {
+        out[8 + i * 8..16 + i * 8].copy_from_slice(&r[i]);
+    }
+    out
+}
+
+/// Reverse [`aes_key_wrap`], returning the original 256-bit key, or a
+/// [`KnowledgeGraphError::DecryptionFailed`] if `wrapped` wasn't produced by
+/// `kek` (the final integrity value won't match [`IV`]).
+fn aes_key_unwrap(kek: &[u8; 32], wrapped: &[u8; 40]) -> Result<[u8; 32]> {
+    let cipher = aes::Aes256::new(GenericArray::from_slice(kek));
+
+    let mut a: [u8; 8] = wrapped[..8].try_into().unwrap();
+    let mut r = [[0u8; 8]; N];
+    for i in 0..N {
+        r[i].copy_from_slice(&wrapped[8 + i * 8..16 + i * 8]);
+    }
+
+    for j in (0..6u64).rev() {
+        for i in (0..N).rev() {
+            let t = j * N as u64 + (i as u64 + 1);
+            let mut block = [0u8; 16];
+            block[..8].copy_from_slice(&(u64::from_be_bytes(a) ^ t).to_be_bytes());
+            block[8..].copy_from_slice(&r[i]);
+            let mut ga = GenericArray::clone_from_slice(&block);
+            cipher.decrypt_block(&mut ga);
+
+            a.copy_from_slice(&ga[..8]);
+            r[i].copy_from_slice(&ga[8..]);
+        }
+    }
+
+    if a != IV {
+        return Err(KnowledgeGraphError::DecryptionFailed(
+            "AES key unwrap integrity check failed -- wrong wrapping key or corrupted blob".to_string(),
+        ));
+    }
+
+    let mut out = [0u8; 32];
+    for i in 0..N {
+        out[i * 8..i * 8 + 8].copy_from_slice(&r[i]);
+    }
+    Ok(out)
+}
+
+/// Wraps and unwraps [`EncryptedStore`](super::encrypted_store::EncryptedStore)'s
+/// data-encryption keys so they're never persisted in the clear.
+///
+/// Wrapping keys are identified by a `u32` id rather than always being "the
+/// one current key", so a manager can keep serving `unwrap_key` calls for
+/// DEKs minted under a previous wrapping key after rotating which one
+/// `wrap_key` uses for new DEKs.
+pub trait KeyManager: Send + Sync {
+    /// Wrap `dek` under this manager's current active wrapping key, and
+    /// return which wrapping key id was used alongside the wrapped bytes.
+    /// Callers persist both -- typically next to the store itself -- and
+    /// pass them back to [`unwrap_key`](Self::unwrap_key) to recover `dek`
+    /// on a later open.
+    fn wrap_key(&self, dek: &[u8; 32]) -> Result<(u32, Vec<u8>)>;
+
+    /// Recover a DEK previously wrapped by `wrap_key`, using the wrapping
+    /// key identified by `key_id`.
+    fn unwrap_key(&self, key_id: u32, wrapped: &[u8]) -> Result<[u8; 32]>;
+}
+
+/// A [`KeyManager`] that doesn't actually wrap anything -- `wrap_key` and
+/// `unwrap_key` pass the DEK through unchanged under key id `0`.
+///
+/// This exists purely so tests (and examples) can exercise
+/// [`EncryptedStore::with_key_manager`](super::encrypted_store::EncryptedStore::with_key_manager)
+/// without provisioning real KEK material. It provides no protection
+/// whatsoever for the DEK at rest and must never be used outside tests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopKeyManager;
+
+impl KeyManager for NoopKeyManager {
+    fn wrap_key(&self, dek: &[u8; 32]) -> Result<(u32, Vec<u8>)> {
+        Ok((0, dek.to_vec()))
+    }
+
+    fn unwrap_key(&self, key_id: u32, wrapped: &[u8]) -> Result<[u8; 32]> {
+        if key_id != 0 {
+            return Err(KnowledgeGraphError::InvalidOperation(format!(
+                "NoopKeyManager only ever issues key id 0, got {key_id}"
+            )));
+        }
+        wrapped.try_into().map_err(|_| {
+            KnowledgeGraphError::InvalidOperation(format!(
+                "expected a 32-byte key, got {} bytes",
+                wrapped.len()
+            ))
+        })
+    }
+}
+
+/// The default [`KeyManager`]: wrapping keys (KEKs) loaded from environment
+/// variables or a file, with AES Key Wrap (RFC 3394) protecting the DEK
+/// wherever it's persisted.
+///
+/// One of the loaded wrapping keys is marked active; `wrap_key` always
+/// wraps under that one, while `unwrap_key` can use any wrapping key this
+/// manager was given, so DEKs minted before a KEK rotation remain
+/// recoverable as long as the old KEK is still supplied alongside the new
+/// one.
+pub struct EnvKeyManager {
+    active_key_id: u32,
+    wrapping_keys: HashMap<u32, [u8; 32]>,
+}
+
+impl EnvKeyManager {
+    /// Load wrapping keys from environment variables under `prefix`:
+    /// `{prefix}_ACTIVE_KEY_ID` names the active key id, `{prefix}_KEY_IDS`
+    /// is a comma-separated list of every key id to load (the active one
+    /// must be included), and `{prefix}_KEY_{id}` holds that key's 32
+    /// bytes as 64 hex characters.
+    pub fn from_env(prefix: &str) -> Result<Self> {
+        let active_key_id = Self::read_env_u32(&format!("{prefix}_ACTIVE_KEY_ID"))?;
+
+        let ids_var = format!("{prefix}_KEY_IDS");
+        let ids = env::var(&ids_var).map_err(|_| {
+            KnowledgeGraphError::InvalidOperation(format!("missing environment variable {ids_var}"))
+        })?;
+
+        let mut wrapping_keys = HashMap::new();
+        for id_str in ids.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let id: u32 = id_str.parse().map_err(|_| {
+                KnowledgeGraphError::InvalidOperation(format!("invalid key id in {ids_var}: {id_str}"))
+            })?;
+            let key_var = format!("{prefix}_KEY_{id}");
+            let hex = env::var(&key_var).map_err(|_| {
+                KnowledgeGraphError::InvalidOperation(format!("missing environment variable {key_var}"))
+            })?;
+            wrapping_keys.insert(id, parse_hex_key(&key_var, &hex)?);
+        }
+
+        Self::validate_active_key_present(active_key_id, &wrapping_keys)?;
+        Ok(Self { active_key_id, wrapping_keys })
+    }
+
+    /// Load wrapping keys from a simple line-oriented file:
+    /// ```text
+    /// active=1
+    /// 1=000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f
+    /// 0=0101010101010101010101010101010101010101010101010101010101010101
+    /// ```
+    /// Blank lines and lines starting with `#` are ignored. Keeping an
+    /// older id's line around after rotating `active` is how a deployment
+    /// keeps being able to unwrap DEKs minted under it.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref())?;
+
+        let mut active_key_id = None;
+        let mut wrapping_keys = HashMap::new();
+
+        for (lineno, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                KnowledgeGraphError::InvalidOperation(format!(
+                    "{}: line {} is not of the form `key=value`",
+                    path.as_ref().display(),
+                    lineno + 1
+                ))
+            })?;
+
+            if key == "active" {
+                active_key_id = Some(value.parse::<u32>().map_err(|_| {
+                    KnowledgeGraphError::InvalidOperation(format!("invalid active key id: {value}"))
+                })?);
+            } else {
+                let id: u32 = key.parse().map_err(|_| {
+                    KnowledgeGraphError::InvalidOperation(format!("invalid key id: {key}"))
+                })?;
+                wrapping_keys.insert(id, parse_hex_key(key, value)?);
+            }
+        }
+
+        let active_key_id = active_key_id.ok_or_else(|| {
+            KnowledgeGraphError::InvalidOperation(format!(
+                "{}: missing `active=<key id>` line",
+                path.as_ref().display()
+            ))
+        })?;
+
+        Self::validate_active_key_present(active_key_id, &wrapping_keys)?;
+        Ok(Self { active_key_id, wrapping_keys })
+    }
+
+    fn read_env_u32(var: &str) -> Result<u32> {
+        env::var(var)
+            .map_err(|_| KnowledgeGraphError::InvalidOperation(format!("missing environment variable {var}")))?
+            .parse()
+            .map_err(|_| KnowledgeGraphError::InvalidOperation(format!("{var} is not a valid key id")))
+    }
+
+    fn validate_active_key_present(active_key_id: u32, wrapping_keys: &HashMap<u32, [u8; 32]>) -> Result<()> {
+        if !wrapping_keys.contains_key(&active_key_id) {
+            return Err(KnowledgeGraphError::InvalidOperation(format!(
+                "active key id {active_key_id} has no corresponding wrapping key"
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn parse_hex_key(name: &str, hex: &str) -> Result<[u8; 32]> {
+    let hex = hex.trim();
+    if hex.len() != 64 {
+        return Err(KnowledgeGraphError::InvalidOperation(format!(
+            "{name} must be 64 hex characters (32 bytes), got {} characters",
+            hex.len()
+        )));
+    }
+
+    let mut key = [0u8; 32];
+    for (i, chunk) in key.iter_mut().enumerate() {
+        *chunk = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| KnowledgeGraphError::InvalidOperation(format!("{name} is not valid hex")))?;
+    }
+    Ok(key)
+}
+
+impl KeyManager for EnvKeyManager {
+    fn wrap_key(&self, dek: &[u8; 32]) -> Result<(u32, Vec<u8>)> {
+        let kek = self.wrapping_keys.get(&self.active_key_id).ok_or_else(|| {
+            KnowledgeGraphError::InvalidOperation(format!(
+                "active key id {} has no corresponding wrapping key",
+                self.active_key_id
+            ))
+        })?;
+        Ok((self.active_key_id, aes_key_wrap(kek, dek).to_vec()))
+    }
+
+    fn unwrap_key(&self, key_id: u32, wrapped: &[u8]) -> Result<[u8; 32]> {
+        let kek = self
+            .wrapping_keys
+            .get(&key_id)
+            .ok_or_else(|| KnowledgeGraphError::InvalidOperation(format!("no wrapping key registered for key id {key_id}")))?;
+        let wrapped: &[u8; 40] = wrapped.try_into().map_err(|_| {
+            KnowledgeGraphError::DecryptionFailed(format!(
+                "expected a 40-byte AES key wrap blob, got {} bytes",
+                wrapped.len()
+            ))
+        })?;
+        aes_key_unwrap(kek, wrapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aes_key_wrap_roundtrips() {
+        let kek = [0x42u8; 32];
+        let dek = [0x99u8; 32];
+
+        let wrapped = aes_key_wrap(&kek, &dek);
+        assert_ne!(&wrapped[8..], &dek[..]);
+
+        let unwrapped = aes_key_unwrap(&kek, &wrapped).unwrap();
+        assert_eq!(unwrapped, dek);
+    }
+
+    #[test]
+    fn test_aes_key_unwrap_rejects_wrong_kek() {
+        let dek = [0x99u8; 32];
+        let wrapped = aes_key_wrap(&[0x42u8; 32], &dek);
+
+        let err = aes_key_unwrap(&[0x43u8; 32], &wrapped).unwrap_err();
+        assert!(matches!(err, KnowledgeGraphError::DecryptionFailed(_)));
+    }
+
+    #[test]
+    fn test_noop_key_manager_roundtrips() {
+        let manager = NoopKeyManager;
+        let dek = [0x11u8; 32];
+
+        let (key_id, wrapped) = manager.wrap_key(&dek).unwrap();
+        assert_eq!(manager.unwrap_key(key_id, &wrapped).unwrap(), dek);
+    }
+
+    #[test]
+    fn test_env_key_manager_roundtrips() {
+        let manager = EnvKeyManager {
+            active_key_id: 1,
+            wrapping_keys: HashMap::from([(1, [0x01u8; 32]), (0, [0x00u8; 32])]),
+        };
+        let dek = [0x77u8; 32];
+
+        let (key_id, wrapped) = manager.wrap_key(&dek).unwrap();
+        assert_eq!(key_id, 1);
+        assert_eq!(manager.unwrap_key(1, &wrapped).unwrap(), dek);
+
+        // A wrapping key that isn't the active one is still usable for
+        // unwrap, which is exactly what lets a rotation keep old DEKs
+        // recoverable after the active id moves on.
+        assert!(manager.unwrap_key(0, &aes_key_wrap(&[0x00u8; 32], &dek)).is_ok());
+    }
+
+    #[test]
+    fn test_env_key_manager_from_file_rejects_bad_hex_length() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.conf");
+        std::fs::write(&path, "active=1\n1=0101\n").unwrap();
+
+        let err = EnvKeyManager::from_file(&path).unwrap_err();
+        assert!(matches!(err, KnowledgeGraphError::InvalidOperation(_)));
+    }
+
+    #[test]
+    fn test_env_key_manager_from_file_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.conf");
+        std::fs::write(
+            &path,
+            format!(
+                "# wrapping keys\nactive=1\n1={}\n0={}\n",
+                "11".repeat(32),
+                "00".repeat(32)
+            ),
+        )
+        .unwrap();
+
+        let manager = EnvKeyManager::from_file(&path).unwrap();
+        let dek = [0xABu8; 32];
+        let (key_id, wrapped) = manager.wrap_key(&dek).unwrap();
+        assert_eq!(key_id, 1);
+        assert_eq!(manager.unwrap_key(1, &wrapped).unwrap(), dek);
+    }
+}
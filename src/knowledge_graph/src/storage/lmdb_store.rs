@@ -0,0 +1,337 @@
+//! LMDB storage implementation for the knowledge graph
+//!
+//! Unlike [`SledStore`](super::SledStore), LMDB requires explicit read/write
+//! transactions, so every single-key operation here opens a short-lived
+//! transaction around that one access, and [`LmdbWriteBatch`] defers its
+//! operations into a single write transaction on commit.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use heed::types::Bytes;
+use heed::{CompactionOption, Database, Env, EnvOpenOptions};
+use log::info;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::KnowledgeGraphError;
+
+use super::snapshot::Checkpoint;
+use super::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::Result;
+
+/// Default size of the LMDB memory map, in bytes (1 GiB).
+///
+/// LMDB reserves this much virtual address space up front; it only bounds
+/// the maximum database size and is not actual disk or memory usage.
+const DEFAULT_MAP_SIZE: usize = 1024 * 1024 * 1024;
+
+/// LMDB storage implementation
+#[derive(Clone)]
+pub struct LmdbStore {
+    env: Arc<Env>,
+    db: Database<Bytes, Bytes>,
+}
+
+impl LmdbStore {
+    /// Open or create a new LMDB database at the given path
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        std::fs::create_dir_all(path.as_ref())?;
+
+        // Safety: we only ever open this path as a single `Env` per process,
+        // so the map-size/no-subdir requirements LMDB places on `open` hold.
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(DEFAULT_MAP_SIZE)
+                .open(path.as_ref())
+        }
+        .map_err(KnowledgeGraphError::from)?;
+
+        let mut wtxn = env.write_txn().map_err(KnowledgeGraphError::from)?;
+        let db: Database<Bytes, Bytes> = env
+            .create_database(&mut wtxn, None)
+            .map_err(KnowledgeGraphError::from)?;
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+
+        info!("Opened LMDB database");
+        Ok(Self {
+            env: Arc::new(env),
+            db,
+        })
+    }
+
+    /// Get a reference to the underlying LMDB environment
+    pub fn inner(&self) -> &Env {
+        &self.env
+    }
+}
+
+impl Storage for LmdbStore {
+    type Batch<'a> = LmdbWriteBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(KnowledgeGraphError::from)?;
+        self.db
+            .delete(&mut wtxn, key)
+            .map_err(KnowledgeGraphError::from)?;
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+        Ok(())
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        let rtxn = self.env.read_txn().map_err(KnowledgeGraphError::from)?;
+        let found = self
+            .db
+            .get(&rtxn, key)
+            .map_err(KnowledgeGraphError::from)?
+            .is_some();
+        Ok(found)
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let rtxn = self.env.read_txn().map_err(KnowledgeGraphError::from)?;
+        Ok(self
+            .db
+            .get(&rtxn, key)
+            .map_err(KnowledgeGraphError::from)?
+            .map(|value| value.to_vec()))
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(KnowledgeGraphError::from)?;
+        self.db
+            .put(&mut wtxn, key, value)
+            .map_err(KnowledgeGraphError::from)?;
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+        Ok(())
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        // An LMDB cursor borrows from the read transaction that created it,
+        // and that transaction can't outlive this call, so we eagerly drain
+        // the matching range into an owned buffer instead of trying to
+        // return a cursor-backed iterator tied to a transaction we'd have
+        // to keep alive.
+        let scan = || -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+            let rtxn = self.env.read_txn().map_err(KnowledgeGraphError::from)?;
+            let mut items = Vec::new();
+            for entry in self
+                .db
+                .prefix_iter(&rtxn, prefix)
+                .map_err(KnowledgeGraphError::from)?
+            {
+                let (key, value) = entry.map_err(KnowledgeGraphError::from)?;
+                items.push((key.to_vec(), value.to_vec()));
+            }
+            Ok(items)
+        };
+
+        let items = scan().unwrap_or_else(|e| {
+            log::warn!("Failed to iterate LMDB prefix: {}", e);
+            Vec::new()
+        });
+
+        Box::new(items.into_iter())
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        LmdbWriteBatch::new(Arc::clone(&self.env), self.db)
+    }
+}
+
+// Implement WriteBatchExt for LmdbStore using the default single-batch behavior
+impl WriteBatchExt for LmdbStore {}
+
+impl Checkpoint for LmdbStore {
+    fn checkpoint_to_path(&self, path: &Path) -> Result<()> {
+        std::fs::create_dir_all(path)?;
+        // `copy_to_path` is LMDB's native `mdb_env_copy2` facility: it walks
+        // the B-tree and writes out a compacted copy of the environment,
+        // so the result is both crash-consistent and smaller than a raw
+        // file copy would be.
+        self.env
+            .copy_to_path(path, CompactionOption::Enabled)
+            .map_err(KnowledgeGraphError::from)?;
+        Ok(())
+    }
+
+    fn open_checkpoint(path: &Path) -> Result<Self> {
+        Self::open(path)
+    }
+}
+
+/// LMDB write batch
+///
+/// Operations are buffered and applied inside a single write transaction on
+/// commit, mirroring [`SledWriteBatch`](super::sled_store::SledWriteBatch).
+#[derive(Debug)]
+pub struct LmdbWriteBatch {
+    env: Arc<Env>,
+    db: Database<Bytes, Bytes>,
+    ops: Vec<BatchOp>,
+}
+
+#[derive(Debug, Clone)]
+enum BatchOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+impl LmdbWriteBatch {
+    /// Create a new, empty write batch
+    pub fn new(env: Arc<Env>, db: Database<Bytes, Bytes>) -> Self {
+        Self {
+            env,
+            db,
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl WriteBatch for LmdbWriteBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ops.push(BatchOp::Put(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.ops.push(BatchOp::Delete(key.to_vec()));
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    fn commit(mut self) -> Result<()> {
+        if self.ops.is_empty() {
+            return Ok(());
+        }
+
+        let mut wtxn = self.env.write_txn().map_err(KnowledgeGraphError::from)?;
+        for op in self.ops.drain(..) {
+            match op {
+                BatchOp::Put(key, value) => {
+                    self.db
+                        .put(&mut wtxn, &key, &value)
+                        .map_err(KnowledgeGraphError::from)?;
+                }
+                BatchOp::Delete(key) => {
+                    self.db
+                        .delete(&mut wtxn, &key)
+                        .map_err(KnowledgeGraphError::from)?;
+                }
+            }
+        }
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile;
+
+    #[test]
+    fn test_put_and_get() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = LmdbStore::open(dir.path())?;
+
+        let key = b"test_key";
+        let value = b"test_value";
+
+        store.put(key, &value.to_vec())?;
+        let retrieved: Option<Vec<u8>> = store.get(key)?;
+
+        assert_eq!(retrieved, Some(value.to_vec()));
+
+        let non_existent: Option<Vec<u8>> = store.get(b"non_existent")?;
+        assert_eq!(non_existent, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = LmdbStore::open(dir.path())?;
+
+        let key = b"test_key";
+        let value = b"test_value";
+        store.put(key, &value.to_vec())?;
+
+        assert!(store.exists(key)?);
+
+        store.delete(key)?;
+
+        assert!(!store.exists(key)?);
+        assert!(store.get::<Vec<u8>>(key)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_prefix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = LmdbStore::open(dir.path())?;
+
+        store.put(b"prefix:1", &b"value1".to_vec())?;
+        store.put(b"prefix:2", &b"value2".to_vec())?;
+        store.put(b"other:1", &b"other1".to_vec())?;
+
+        let mut results: Vec<_> = store
+            .iter_prefix(b"prefix:")
+            .map(|(k, v)| (k, deserialize::<Vec<u8>>(&v).unwrap()))
+            .collect();
+        results.sort();
+
+        let expected = vec![
+            (b"prefix:1".to_vec(), b"value1".to_vec()),
+            (b"prefix:2".to_vec(), b"value2".to_vec()),
+        ];
+
+        assert_eq!(results, expected);
+
+        let results: Vec<_> = store.iter_prefix(b"nonexistent").collect();
+        assert!(results.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_commit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = LmdbStore::open(dir.path())?;
+
+        let mut batch = store.create_batch();
+        batch.put_serialized(b"batch1", &serialize(&100u64)?)?;
+        batch.put_serialized(b"batch2", &serialize(&200u64)?)?;
+        batch.commit()?;
+
+        assert_eq!(store.get::<u64>(b"batch1")?, Some(100));
+        assert_eq!(store.get::<u64>(b"batch2")?, Some(200));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,231 @@
+//! In-memory storage backend for the knowledge graph.
+//!
+//! [`InMemoryStore`] keeps every key/value pair in a `BTreeMap` behind an
+//! `RwLock` — nothing ever touches disk. It's for tests and warm-read
+//! benchmarks that want a real [`Storage`] implementation without the setup
+//! cost (and teardown cleanup) of a [`SledStore`](super::SledStore) temp
+//! directory, and it's the cheapest possible baseline to compare the durable
+//! backends against in `storage_benchmark.rs`. The `BTreeMap` also makes
+//! `iter_prefix` a cheap, already-sorted range scan rather than a full-table
+//! filter.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::{Arc, RwLock};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::{KnowledgeGraphError, Result};
+
+fn lock_err(context: &str) -> KnowledgeGraphError {
+    KnowledgeGraphError::StorageError(format!("in-memory store {context}: lock poisoned"))
+}
+
+/// Smallest key strictly greater than `prefix`, used as the exclusive upper
+/// bound of a prefix range scan over the map's lexicographic key order.
+/// Returns `None` if `prefix` is empty or all `0xff` bytes, in which case
+/// there's no finite upper bound and the scan should run to the map's end.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper = prefix.to_vec();
+    while let Some(last) = upper.last() {
+        if *last == u8::MAX {
+            upper.pop();
+        } else {
+            *upper.last_mut().unwrap() += 1;
+            return Some(upper);
+        }
+    }
+    None
+}
+
+/// A [`Storage`] backend over an in-process `BTreeMap`, for tests and
+/// ephemeral graphs that don't need to survive the process.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryStore {
+    data: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+}
+
+impl InMemoryStore {
+    /// Create a new, empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for InMemoryStore {
+    type Batch<'a> = InMemoryBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.data.write().map_err(|_| lock_err("delete"))?.remove(key);
+        Ok(())
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.data.read().map_err(|_| lock_err("exists"))?.contains_key(key))
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.data.read().map_err(|_| lock_err("get"))?.get(key).cloned())
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.data
+            .write()
+            .map_err(|_| lock_err("put"))?
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let data = match self.data.read() {
+            Ok(data) => data,
+            Err(_) => return Box::new(std::iter::empty()),
+        };
+        let lower = Bound::Included(prefix.to_vec());
+        let upper = match prefix_upper_bound(prefix) {
+            Some(upper) => Bound::Excluded(upper),
+            None => Bound::Unbounded,
+        };
+        let entries: Vec<(Vec<u8>, Vec<u8>)> = data
+            .range((lower, upper))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        Box::new(entries.into_iter())
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        InMemoryBatch {
+            data: Arc::clone(&self.data),
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl WriteBatchExt for InMemoryStore {}
+
+impl super::column_family::ColumnFamilyStore for InMemoryStore {}
+
+impl super::column_family::WriteBatchCf for InMemoryBatch {}
+
+/// One write staged into an [`InMemoryBatch`].
+#[derive(Debug, Clone)]
+enum MemoryOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+/// Accumulates puts and deletes, applying them to the shared map in one
+/// locked pass on [`commit`](WriteBatch::commit).
+#[derive(Debug)]
+pub struct InMemoryBatch {
+    data: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
+    ops: Vec<MemoryOp>,
+}
+
+impl WriteBatch for InMemoryBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ops.push(MemoryOp::Put(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.ops.push(MemoryOp::Delete(key.to_vec()));
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    fn commit(self) -> Result<()> {
+        let mut data = self.data.write().map_err(|_| lock_err("batch commit"))?;
+        for op in self.ops {
+            match op {
+                MemoryOp::Put(key, value) => {
+                    data.insert(key, value);
+                }
+                MemoryOp::Delete(key) => {
+                    data.remove(&key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_get_delete() {
+        let store = InMemoryStore::new();
+        store.put(b"key", &"value".to_string()).unwrap();
+        assert_eq!(store.get::<String>(b"key").unwrap(), Some("value".to_string()));
+        assert!(store.exists(b"key").unwrap());
+
+        store.delete(b"key").unwrap();
+        assert!(!store.exists(b"key").unwrap());
+        assert_eq!(store.get::<String>(b"key").unwrap(), None);
+    }
+
+    #[test]
+    fn test_iter_prefix() {
+        let store = InMemoryStore::new();
+        store.put(b"prefix:1", &b"value1".to_vec()).unwrap();
+        store.put(b"prefix:2", &b"value2".to_vec()).unwrap();
+        store.put(b"other:1", &b"other1".to_vec()).unwrap();
+
+        let mut results: Vec<_> = store
+            .iter_prefix(b"prefix:")
+            .map(|(k, v)| (k, deserialize::<Vec<u8>>(&v).unwrap()))
+            .collect();
+        results.sort();
+
+        assert_eq!(
+            results,
+            vec![
+                (b"prefix:1".to_vec(), b"value1".to_vec()),
+                (b"prefix:2".to_vec(), b"value2".to_vec()),
+            ]
+        );
+
+        assert!(store.iter_prefix(b"nonexistent").next().is_none());
+    }
+
+    #[test]
+    fn test_batch_commit_is_atomic_from_a_readers_perspective() {
+        let store = InMemoryStore::new();
+        store.put(b"a", &1u32).unwrap();
+
+        let mut batch = store.create_batch();
+        batch.put_serialized(b"a", &serialize(&2u32).unwrap()).unwrap();
+        batch.delete_serialized(b"b").unwrap();
+        batch.commit().unwrap();
+
+        assert_eq!(store.get::<u32>(b"a").unwrap(), Some(2));
+        assert!(!store.exists(b"b").unwrap());
+    }
+}
@@ -0,0 +1,343 @@
+//! Cross-backend storage metrics.
+//!
+//! [`CacheMetrics`](super::cached_store) and `BatchStats` (in
+//! [`batch_optimizer`](super::batch_optimizer)) each track their own slice
+//! of what a storage backend is doing, but neither is exposed outside its
+//! module. [`StorageMetrics`] is the observable counterpart: a handle that
+//! [`CachedStore`](super::CachedStore) and [`RocksDBStore`](super::RocksDBStore)
+//! record into on every `get_raw`/`put_raw`/batch commit, and that operators
+//! can [`snapshot`](StorageMetrics::snapshot) into a plain, `Copy`
+//! [`StorageMetricsSnapshot`] for logging, a `/metrics` endpoint, or (behind
+//! the `prometheus-metrics` feature) direct Prometheus text exposition.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use super::batch_optimizer::BatchConfig;
+
+/// Per-backend operation counters, cache effectiveness, and batch commit
+/// latency, recorded by whichever [`Storage`](super::Storage) wrapper or
+/// backend chooses to thread a [`StorageMetrics`] through its calls.
+///
+/// The batch commit latency window mirrors [`BatchConfig`]'s tuning knobs:
+/// `window_size` is [`BatchConfig::stats_window_size`] (how many recent
+/// durations are kept for the histogram) and `slow_threshold` is
+/// [`BatchConfig::target_batch_duration_ms`] (commits at or above this are
+/// additionally counted as slow), so the same adaptive-batching target that
+/// drives `BatchProcessor`'s auto-tuner is also what this reports against.
+#[derive(Debug)]
+pub struct StorageMetrics {
+    gets: AtomicU64,
+    puts: AtomicU64,
+    deletes: AtomicU64,
+    batch_commits: AtomicU64,
+    slow_batch_commits: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    read_ahead_hits: AtomicU64,
+    read_ahead_misses: AtomicU64,
+    batch_commit_durations: parking_lot::Mutex<VecDeque<Duration>>,
+    window_size: usize,
+    slow_threshold: Duration,
+}
+
+impl Default for StorageMetrics {
+    fn default() -> Self {
+        let config = BatchConfig::default();
+        Self::with_window(config.stats_window_size, config.target_batch_duration_ms)
+    }
+}
+
+impl StorageMetrics {
+    /// Create a new, empty metrics handle using [`BatchConfig::default`]'s
+    /// window size and target duration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new, empty metrics handle with an explicit latency window
+    /// size and slow-commit threshold, in case a caller tunes its
+    /// [`BatchConfig`] away from the defaults.
+    pub fn with_window(window_size: usize, target_batch_duration_ms: u64) -> Self {
+        Self {
+            gets: AtomicU64::new(0),
+            puts: AtomicU64::new(0),
+            deletes: AtomicU64::new(0),
+            batch_commits: AtomicU64::new(0),
+            slow_batch_commits: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            read_ahead_hits: AtomicU64::new(0),
+            read_ahead_misses: AtomicU64::new(0),
+            batch_commit_durations: parking_lot::Mutex::new(VecDeque::with_capacity(window_size.max(1))),
+            window_size: window_size.max(1),
+            slow_threshold: Duration::from_millis(target_batch_duration_ms),
+        }
+    }
+
+    /// Record a `get`/`get_raw` call.
+    pub fn record_get(&self) {
+        self.gets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a `put`/`put_raw` call.
+    pub fn record_put(&self) {
+        self.puts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a `delete` call.
+    pub fn record_delete(&self) {
+        self.deletes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a cache hit against the fronting cache (not the backend
+    /// itself).
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a cache miss against the fronting cache.
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that a read-ahead prefetch produced a key that was
+    /// subsequently read.
+    pub fn record_read_ahead_hit(&self) {
+        self.read_ahead_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that a read-ahead prefetch produced a key that was never
+    /// read before being evicted.
+    pub fn record_read_ahead_miss(&self) {
+        self.read_ahead_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a completed batch/write-batch commit, pushing `duration` into
+    /// the rolling latency window and counting it as slow if it met or
+    /// exceeded the configured threshold.
+    pub fn record_batch_commit(&self, duration: Duration) {
+        self.batch_commits.fetch_add(1, Ordering::Relaxed);
+        if duration >= self.slow_threshold {
+            self.slow_batch_commits.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut durations = self.batch_commit_durations.lock();
+        if durations.len() >= self.window_size {
+            durations.pop_front();
+        }
+        durations.push_back(duration);
+    }
+
+    /// Take a point-in-time snapshot of every counter, plus percentiles
+    /// over the current batch commit latency window.
+    pub fn snapshot(&self) -> StorageMetricsSnapshot {
+        let mut durations: Vec<Duration> = self.batch_commit_durations.lock().iter().copied().collect();
+        durations.sort_unstable();
+
+        let percentile = |p: f64| -> f64 {
+            if durations.is_empty() {
+                return 0.0;
+            }
+            let rank = ((durations.len() - 1) as f64 * p).round() as usize;
+            durations[rank].as_secs_f64() * 1000.0
+        };
+
+        StorageMetricsSnapshot {
+            gets: self.gets.load(Ordering::Relaxed),
+            puts: self.puts.load(Ordering::Relaxed),
+            deletes: self.deletes.load(Ordering::Relaxed),
+            batch_commits: self.batch_commits.load(Ordering::Relaxed),
+            slow_batch_commits: self.slow_batch_commits.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            cache_misses: self.cache_misses.load(Ordering::Relaxed),
+            read_ahead_hits: self.read_ahead_hits.load(Ordering::Relaxed),
+            read_ahead_misses: self.read_ahead_misses.load(Ordering::Relaxed),
+            batch_commit_p50_ms: percentile(0.50),
+            batch_commit_p99_ms: percentile(0.99),
+            batch_commit_max_ms: durations.last().map_or(0.0, |d| d.as_secs_f64() * 1000.0),
+        }
+    }
+}
+
+/// A `Copy`-able point-in-time read of a [`StorageMetrics`] handle.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StorageMetricsSnapshot {
+    /// Total `get`/`get_raw` calls.
+    pub gets: u64,
+    /// Total `put`/`put_raw` calls.
+    pub puts: u64,
+    /// Total `delete` calls.
+    pub deletes: u64,
+    /// Total batch/write-batch commits.
+    pub batch_commits: u64,
+    /// Commits that took at least as long as the configured target duration.
+    pub slow_batch_commits: u64,
+    /// Cache hits against the fronting cache.
+    pub cache_hits: u64,
+    /// Cache misses against the fronting cache.
+    pub cache_misses: u64,
+    /// Read-ahead prefetches that were subsequently read.
+    pub read_ahead_hits: u64,
+    /// Read-ahead prefetches that were never read before eviction.
+    pub read_ahead_misses: u64,
+    /// Median batch commit duration over the current window, in milliseconds.
+    pub batch_commit_p50_ms: f64,
+    /// 99th-percentile batch commit duration over the current window, in milliseconds.
+    pub batch_commit_p99_ms: f64,
+    /// Slowest batch commit duration over the current window, in milliseconds.
+    pub batch_commit_max_ms: f64,
+}
+
+impl StorageMetricsSnapshot {
+    /// Fraction of cache lookups that hit, in `[0.0, 1.0]`. `0.0` if there
+    /// were no lookups at all.
+    pub fn cache_hit_rate(&self) -> f64 {
+        let total = self.cache_hits + self.cache_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.cache_hits as f64 / total as f64
+        }
+    }
+
+    /// Fraction of prefetched keys that were actually read before eviction,
+    /// in `[0.0, 1.0]`. `0.0` if nothing was prefetched.
+    pub fn read_ahead_hit_rate(&self) -> f64 {
+        let total = self.read_ahead_hits + self.read_ahead_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.read_ahead_hits as f64 / total as f64
+        }
+    }
+}
+
+#[cfg(feature = "prometheus-metrics")]
+impl StorageMetricsSnapshot {
+    /// Render this snapshot as Prometheus text exposition format.
+    pub fn to_prometheus_text(&self) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+        let mut counter = |out: &mut String, name: &str, help: &str, value: u64| {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            let _ = writeln!(out, "{name} {value}");
+        };
+
+        counter(&mut out, "maya_storage_gets_total", "Total get calls.", self.gets);
+        counter(&mut out, "maya_storage_puts_total", "Total put calls.", self.puts);
+        counter(&mut out, "maya_storage_deletes_total", "Total delete calls.", self.deletes);
+        counter(
+            &mut out,
+            "maya_storage_batch_commits_total",
+            "Total batch commits.",
+            self.batch_commits,
+        );
+        counter(
+            &mut out,
+            "maya_storage_slow_batch_commits_total",
+            "Batch commits at or above the target duration.",
+            self.slow_batch_commits,
+        );
+        counter(&mut out, "maya_storage_cache_hits_total", "Cache hits.", self.cache_hits);
+        counter(&mut out, "maya_storage_cache_misses_total", "Cache misses.", self.cache_misses);
+        counter(
+            &mut out,
+            "maya_storage_read_ahead_hits_total",
+            "Prefetched keys that were read.",
+            self.read_ahead_hits,
+        );
+        counter(
+            &mut out,
+            "maya_storage_read_ahead_misses_total",
+            "Prefetched keys that were never read.",
+            self.read_ahead_misses,
+        );
+
+        let _ = writeln!(out, "# HELP maya_storage_batch_commit_duration_ms Batch commit latency, in milliseconds.");
+        let _ = writeln!(out, "# TYPE maya_storage_batch_commit_duration_ms summary");
+        let _ = writeln!(
+            out,
+            "maya_storage_batch_commit_duration_ms{{quantile=\"0.5\"}} {}",
+            self.batch_commit_p50_ms
+        );
+        let _ = writeln!(
+            out,
+            "maya_storage_batch_commit_duration_ms{{quantile=\"0.99\"}} {}",
+            self.batch_commit_p99_ms
+        );
+        let _ = writeln!(
+            out,
+            "maya_storage_batch_commit_duration_ms{{quantile=\"1\"}} {}",
+            self.batch_commit_max_ms
+        );
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_accumulate() {
+        let metrics = StorageMetrics::new();
+        metrics.record_get();
+        metrics.record_get();
+        metrics.record_put();
+        metrics.record_delete();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.gets, 2);
+        assert_eq!(snapshot.puts, 1);
+        assert_eq!(snapshot.deletes, 1);
+    }
+
+    #[test]
+    fn test_cache_hit_rate() {
+        let metrics = StorageMetrics::new();
+        metrics.record_cache_hit();
+        metrics.record_cache_hit();
+        metrics.record_cache_hit();
+        metrics.record_cache_miss();
+
+        assert_eq!(metrics.snapshot().cache_hit_rate(), 0.75);
+    }
+
+    #[test]
+    fn test_cache_hit_rate_with_no_lookups_is_zero() {
+        let metrics = StorageMetrics::new();
+        assert_eq!(metrics.snapshot().cache_hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_batch_commit_window_tracks_slow_commits_and_caps_size() {
+        let metrics = StorageMetrics::with_window(3, 10);
+
+        metrics.record_batch_commit(Duration::from_millis(1));
+        metrics.record_batch_commit(Duration::from_millis(2));
+        metrics.record_batch_commit(Duration::from_millis(20));
+        metrics.record_batch_commit(Duration::from_millis(30));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.batch_commits, 4);
+        assert_eq!(snapshot.slow_batch_commits, 2);
+        // Window size 3: the first (1ms) sample should have rolled off.
+        assert_eq!(snapshot.batch_commit_max_ms, 30.0);
+        assert!(snapshot.batch_commit_p50_ms >= 2.0);
+    }
+
+    #[test]
+    fn test_read_ahead_hit_rate() {
+        let metrics = StorageMetrics::new();
+        metrics.record_read_ahead_hit();
+        metrics.record_read_ahead_miss();
+        metrics.record_read_ahead_miss();
+
+        assert!((metrics.snapshot().read_ahead_hit_rate() - (1.0 / 3.0)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,232 @@
+//! Offline migration between [`Storage`] backends
+//!
+//! Lets operators move a knowledge graph's on-disk data from one durable
+//! engine to another (sled -> LMDB, LMDB -> sled, either -> a future
+//! backend, ...) without re-encoding any values: `iter_prefix` already
+//! yields raw serialized bytes, and `WriteBatch::put_serialized` writes them
+//! back out verbatim.
+
+use super::batch_optimizer::BatchConfig;
+use super::builder::{AnyStore, StorageBuilder};
+use super::column_family::{ColumnFamily, ColumnFamilyStore, EdgesCf, IndicesCf, NodesCf, WriteBatchCf};
+use super::object_store::StoreBuilder;
+use super::{Result, Storage, WriteBatch};
+
+/// Default number of key/value pairs committed per write batch during a
+/// migration, bounding memory usage on large stores.
+pub const DEFAULT_MIGRATION_BATCH_SIZE: usize = 1_000;
+
+/// Open `src` and `dst` per their [`StorageBuilder`] config and stream
+/// every key from the former into the latter, so moving a graph between
+/// engines (e.g. [`SledStore`](super::SledStore) ->
+/// [`RocksDBStore`](super::RocksDBStore)) is a config choice rather than a
+/// hand-rolled match over concrete backend types. Uses
+/// [`BatchConfig::default`]'s `initial_batch_size` as the commit batch
+/// size -- the same starting point
+/// [`BatchProcessor`](super::batch_optimizer::BatchProcessor) auto-tunes
+/// batches from elsewhere in the storage layer.
+///
+/// This only migrates the default, un-partitioned keyspace; if both `src`
+/// and `dst` build into backends that implement
+/// [`ColumnFamilyStore`](super::column_family::ColumnFamilyStore) (for
+/// now, two [`RocksDBStore`](super::RocksDBStore)s), call [`migrate_cf`]
+/// directly on the built stores instead to also preserve column-family
+/// partitions.
+pub fn convert(src: StorageBuilder, dst: StorageBuilder) -> Result<MigrationReport> {
+    let src: AnyStore = src.build()?;
+    let dst: AnyStore = dst.build()?;
+    migrate(&src, &dst, BatchConfig::default().initial_batch_size)
+}
+
+/// Outcome of a [`migrate`] run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Number of key/value pairs copied from the source into the destination
+    pub keys_migrated: usize,
+    /// Number of keys present in the destination after migration, counted
+    /// independently as a sanity check
+    pub keys_verified: usize,
+}
+
+impl MigrationReport {
+    /// Whether the number of keys migrated matches what was actually found
+    /// present in the destination afterward
+    pub fn is_consistent(&self) -> bool {
+        self.keys_migrated == self.keys_verified
+    }
+}
+
+/// Stream every key/value pair from `src` into `dst`, committing in batches
+/// of `batch_size` to cap memory, then verify the destination holds the same
+/// number of keys that were written.
+///
+/// Values are copied via `iter_prefix`/`put_serialized` using their raw,
+/// already-serialized bytes, so no value is ever deserialized or re-encoded
+/// during the migration.
+pub fn migrate<S, D>(src: &S, dst: &D, batch_size: usize) -> Result<MigrationReport>
+where
+    S: Storage,
+    D: Storage,
+{
+    let batch_size = batch_size.max(1);
+    let mut keys_migrated = 0usize;
+    let mut batch = dst.create_batch();
+    let mut pending = 0usize;
+
+    for (key, value) in src.iter_prefix(&[]) {
+        batch.put_serialized(&key, &value)?;
+        pending += 1;
+        keys_migrated += 1;
+
+        if pending >= batch_size {
+            batch.commit()?;
+            batch = dst.create_batch();
+            pending = 0;
+        }
+    }
+
+    if pending > 0 {
+        batch.commit()?;
+    }
+
+    let keys_verified = dst.iter_prefix(&[]).count();
+
+    Ok(MigrationReport {
+        keys_migrated,
+        keys_verified,
+    })
+}
+
+/// Per-column-family breakdown of a [`migrate_cf`] run, alongside the
+/// whole-keyspace [`MigrationReport`] for the default partition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColumnFamilyMigrationReport {
+    /// The default (un-partitioned) keyspace, same as a plain [`migrate`] run.
+    pub default: MigrationReport,
+    /// The [`NodesCf`] partition.
+    pub nodes: MigrationReport,
+    /// The [`EdgesCf`] partition.
+    pub edges: MigrationReport,
+    /// The [`IndicesCf`] partition.
+    pub indices: MigrationReport,
+}
+
+impl ColumnFamilyMigrationReport {
+    /// Whether every partition migrated cleanly (see [`MigrationReport::is_consistent`]).
+    pub fn is_consistent(&self) -> bool {
+        self.default.is_consistent()
+            && self.nodes.is_consistent()
+            && self.edges.is_consistent()
+            && self.indices.is_consistent()
+    }
+}
+
+/// Like [`migrate`], but for backends that physically partition their
+/// keyspace into [`ColumnFamily`]s (right now, only
+/// [`RocksDBStore`](super::RocksDBStore)): copies the default partition
+/// plus the `nodes`, `edges`, and `indices` column families independently,
+/// so data doesn't collapse into one undifferentiated keyspace on the
+/// far side of the migration.
+pub fn migrate_cf<S, D>(src: &S, dst: &D, batch_size: usize) -> Result<ColumnFamilyMigrationReport>
+where
+    S: ColumnFamilyStore,
+    D: ColumnFamilyStore,
+    for<'a> D::Batch<'a>: WriteBatchCf,
+{
+    Ok(ColumnFamilyMigrationReport {
+        default: migrate(src, dst, batch_size)?,
+        nodes: migrate_one_cf::<NodesCf, _, _>(src, dst, batch_size)?,
+        edges: migrate_one_cf::<EdgesCf, _, _>(src, dst, batch_size)?,
+        indices: migrate_one_cf::<IndicesCf, _, _>(src, dst, batch_size)?,
+    })
+}
+
+fn migrate_one_cf<C, S, D>(src: &S, dst: &D, batch_size: usize) -> Result<MigrationReport>
+where
+    C: ColumnFamily,
+    S: ColumnFamilyStore,
+    D: ColumnFamilyStore,
+    for<'a> D::Batch<'a>: WriteBatchCf,
+{
+    let batch_size = batch_size.max(1);
+    let mut keys_migrated = 0usize;
+    let mut batch = dst.create_batch();
+    let mut pending = 0usize;
+
+    for (key, value) in src.iter_prefix_cf::<C>(&[]) {
+        batch.put_cf::<C>(&key, &value)?;
+        pending += 1;
+        keys_migrated += 1;
+
+        if pending >= batch_size {
+            batch.commit()?;
+            batch = dst.create_batch();
+            pending = 0;
+        }
+    }
+
+    if pending > 0 {
+        batch.commit()?;
+    }
+
+    let keys_verified = dst.iter_prefix_cf::<C>(&[]).count();
+
+    Ok(MigrationReport {
+        keys_migrated,
+        keys_verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{ColumnFamilyStore, LmdbStore, RocksDBStore, SledStore};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_migrate_sled_to_lmdb_preserves_all_keys() {
+        let sled_dir = tempdir().unwrap();
+        let lmdb_dir = tempdir().unwrap();
+
+        let sled = SledStore::open(sled_dir.path()).unwrap();
+        for i in 0..50u32 {
+            sled.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        let lmdb = LmdbStore::open(lmdb_dir.path()).unwrap();
+        let report = migrate(&sled, &lmdb, 8).unwrap();
+
+        assert_eq!(report.keys_migrated, 50);
+        assert!(report.is_consistent());
+
+        for i in 0..50u32 {
+            assert_eq!(lmdb.get::<u32>(format!("key-{i}").as_bytes()).unwrap(), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_migrate_cf_keeps_each_rocksdb_column_family_separate() {
+        use crate::storage::column_family::{EdgesCf, NodesCf};
+
+        let src_dir = tempdir().unwrap();
+        let dst_dir = tempdir().unwrap();
+
+        let src = RocksDBStore::open(src_dir.path()).unwrap();
+        src.put(b"default-key", &1u32).unwrap();
+        src.put_cf::<NodesCf, _>(b"node-1", &2u32).unwrap();
+        src.put_cf::<EdgesCf, _>(b"edge-1", &3u32).unwrap();
+
+        let dst = RocksDBStore::open(dst_dir.path()).unwrap();
+        let report = migrate_cf(&src, &dst, 8).unwrap();
+
+        assert!(report.is_consistent());
+        assert_eq!(report.default.keys_migrated, 1);
+        assert_eq!(report.nodes.keys_migrated, 1);
+        assert_eq!(report.edges.keys_migrated, 1);
+        assert_eq!(report.indices.keys_migrated, 0);
+
+        assert_eq!(dst.get::<u32>(b"default-key").unwrap(), Some(1));
+        assert_eq!(dst.get_cf::<NodesCf, u32>(b"node-1").unwrap(), Some(2));
+        assert_eq!(dst.get_cf::<EdgesCf, u32>(b"edge-1").unwrap(), Some(3));
+    }
+}
@@ -5,6 +5,19 @@
 //! implementation using [Sled](https://github.com/spacejam/sled), but can be
 //! implemented for other storage backends as needed.
 //!
+//! [`Storage`] (together with [`WriteBatchExt`] for transactional batches)
+//! *is* the pluggable backend trait: [`KnowledgeGraph`](crate::graph::KnowledgeGraph)
+//! is generic over any `S: Storage + WriteBatchExt`, and every method on it
+//! (`transaction`, `add_node`, `add_edge`, `query`, `query_edges_from`, ...)
+//! works unchanged no matter which backend `S` is. Sled remains the
+//! zero-config default, but it keeps a large resident working set and has
+//! known crash-recovery caveats, so other backends are also built in for
+//! workloads that need something else, among them [`LmdbStore`] (battle-tested,
+//! requires explicit transactions) and [`RedbStore`] (pure-Rust, real MVCC
+//! transactions, no native dependency to link). Swapping backends is just
+//! `KnowledgeGraph::new(RedbStore::open(path)?)` instead of
+//! `KnowledgeGraph::new(SledStore::open(path)?)`.
+//!
 //! # Features
 //! - Generic key-value storage interface
 //! - Support for transactions and batch operations
@@ -79,18 +92,59 @@
 //! ```
 
 // Make modules public for benchmarks
+pub mod batch_optimizer;
 pub mod sled_store;
+pub mod lmdb_store;
+pub mod redb_store;
 pub mod cached_store;
 pub mod hybrid_store;
+pub mod migrate;
 pub mod prefetch;
+pub mod snapshot;
+pub mod encrypted_store;
+pub mod key_manager;
+pub mod raft_store;
+pub mod object_store;
+pub mod memory_store;
+pub mod builder;
+pub mod oplog;
+pub mod postgres_store;
+pub mod graph_backend;
+pub mod rocksdb_store;
+pub mod column_family;
+pub mod metrics;
+pub mod typed_store;
+pub mod export;
+pub(crate) mod checksum;
 
 // Re-export prefetch types
-pub use prefetch::{PrefetchConfig, PrefetchExt, PrefetchingIterator};
+pub use prefetch::{AsyncPrefetchExt, PrefetchConfig, PrefetchExt, PrefetchingIterator, PrefetchPool, PrefetchStream};
 
 // Re-export public types
 pub use sled_store::SledStore;
-pub use cached_store::CachedStore;
-pub use hybrid_store::{HybridStore, HybridConfig};
+pub use lmdb_store::LmdbStore;
+pub use redb_store::{RedbStore, RedbWriteBatch};
+pub use cached_store::{CacheFactory, CacheStorage, CachedStore, CollectionId, WTinyLfuCacheFactory};
+pub use hybrid_store::{HybridStore, HybridConfig, StorageBackend, ScrubReport};
+pub use migrate::{
+    convert, migrate, migrate_cf, ColumnFamilyMigrationReport, MigrationReport,
+    DEFAULT_MIGRATION_BATCH_SIZE,
+};
+pub use snapshot::{Checkpoint, Snapshot};
+pub use encrypted_store::EncryptedStore;
+pub use key_manager::{EnvKeyManager, KeyManager, NoopKeyManager};
+pub use raft_store::{RaftConfig, RaftStore, serve as serve_raft};
+pub use object_store::{GarageStore, GarageStoreBuilder, StoreBuilder};
+pub use memory_store::InMemoryStore;
+pub use builder::{AnyBatch, AnyStore, StorageBuilder};
+pub use oplog::{LogOp, OpLog, DEFAULT_CHECKPOINT_INTERVAL};
+pub use postgres_store::{PostgresBatch, PostgresStore, PostgresStoreBuilder};
+pub use graph_backend::GraphBackend;
+pub use rocksdb_store::RocksDBStore;
+pub use column_family::{ColumnFamily, ColumnFamilyStore, WriteBatchCf, EdgesCf, IndicesCf, NodesCf};
+pub use metrics::{StorageMetrics, StorageMetricsSnapshot};
+pub use typed_store::{EvictionPolicy, TypedStore, TypedStoreConfig};
+pub use export::{export_all, import_all};
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
@@ -130,14 +184,165 @@ pub trait Storage: Send + Sync + 'static {
     
     /// Put a raw byte value by key
     fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()>;
-    
+
+    /// Get several raw byte values at once, preserving the order of `keys`.
+    ///
+    /// The default implementation issues one [`get_raw`](Self::get_raw) per
+    /// key -- correct for any backend, but no cheaper than looking them up
+    /// one at a time. Override it for a backend with a native
+    /// batched/pipelined read, the same default-then-override shape as
+    /// [`iter_prefix_lazy`](Self::iter_prefix_lazy).
+    fn multi_get_raw(&self, keys: &[Vec<u8>]) -> Result<Vec<Option<Vec<u8>>>> {
+        keys.iter().map(|key| self.get_raw(key)).collect()
+    }
+
     /// Iterate over key-value pairs with a prefix
     fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
-    
+
+    /// Iterate over key-value pairs with a prefix via a lazy, owned,
+    /// `'static` cursor that reads from the backend on demand rather than
+    /// materializing the whole scan up front.
+    ///
+    /// [`PrefetchExt`](prefetch::PrefetchExt) hands this cursor to its
+    /// background worker thread so a scan over millions of keys proceeds
+    /// with bounded memory, one batch ahead of the consumer, instead of
+    /// collecting the entire result set before prefetching can even start.
+    ///
+    /// The default implementation falls back to eagerly collecting
+    /// [`iter_prefix`](Self::iter_prefix) into a `Vec` -- correct for any
+    /// backend, but defeats the point of prefetching. Override it when the
+    /// backend's handle is itself cheaply cloned and `'static` (e.g. an
+    /// `Arc`-backed database handle), so the cursor can read ahead for real.
+    fn iter_prefix_lazy(&self, prefix: &[u8]) -> PrefixCursor {
+        PrefixCursor::new(self.iter_prefix(prefix).collect::<Vec<_>>().into_iter())
+    }
+
+    /// Iterate over key-value pairs in `[start, end)` -- `end` of `None`
+    /// means unbounded -- in the requested `direction`, optionally resuming
+    /// from an inclusive `seek` key and capped at `limit` results.
+    ///
+    /// `seek` is inclusive, matching the RocksDB `IteratorMode::From`
+    /// semantics its override delegates to: to page through results,
+    /// re-seek with the key *after* the last one returned (or the last one
+    /// itself, accepting one overlapping row, if the keyspace doesn't have
+    /// an easy successor).
+    ///
+    /// The default implementation collects [`iter_prefix`](Self::iter_prefix)
+    /// over an empty prefix (a full scan), then filters/sorts/reverses/skips
+    /// in Rust -- correct for any backend, but O(total keys) rather than
+    /// O(matching keys). Override it (as `RocksDBStore` does) when the
+    /// backend can seek to `start`/`seek` and stop at `end` natively.
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        direction: IterDirection,
+        seek: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = self
+            .iter_prefix(&[])
+            .filter(|(key, _)| key.as_slice() >= start && end.map_or(true, |end| key.as_slice() < end))
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        if direction == IterDirection::Reverse {
+            entries.reverse();
+        }
+
+        if let Some(seek) = seek {
+            // Start at the first entry at or past `seek` in iteration order,
+            // whether or not `seek` itself is a stored key.
+            let skip = entries
+                .iter()
+                .position(|(key, _)| match direction {
+                    IterDirection::Forward => key.as_slice() >= seek,
+                    IterDirection::Reverse => key.as_slice() <= seek,
+                })
+                .unwrap_or(entries.len());
+            entries.drain(..skip);
+        }
+
+        match limit {
+            Some(limit) => Box::new(entries.into_iter().take(limit)),
+            None => Box::new(entries.into_iter()),
+        }
+    }
+
+    /// Iterate over key-value pairs under `prefix`, stopping at the
+    /// prefix's successor instead of scanning past it and filtering in
+    /// Rust (as a plain [`iter_prefix`](Self::iter_prefix) call does), with
+    /// an optional inclusive `seek` key to resume a paginated scan and an
+    /// optional `limit` on the number of results.
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        seek: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let end = prefix_successor(prefix);
+        self.iter_range(prefix, end.as_deref(), IterDirection::Forward, seek, limit)
+    }
+
     /// Create a new batch
     fn create_batch(&self) -> Self::Batch<'_>;
 }
 
+/// Direction to iterate in, for [`Storage::iter_range`] and
+/// [`Storage::scan_prefix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IterDirection {
+    /// Ascending key order.
+    Forward,
+    /// Descending key order.
+    Reverse,
+}
+
+/// The smallest key that is strictly greater than every key prefixed by
+/// `prefix`, i.e. `prefix`'s upper bound in a prefix scan. `None` if
+/// `prefix` is empty or all `0xff` bytes, meaning there is no such bound
+/// short of the end of the keyspace.
+pub(crate) fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+    while let Some(last) = successor.last() {
+        if *last == u8::MAX {
+            successor.pop();
+        } else {
+            *successor.last_mut().unwrap() += 1;
+            return Some(successor);
+        }
+    }
+    None
+}
+
+/// A lazy, owned, `'static` cursor over key-value pairs, returned by
+/// [`Storage::iter_prefix_lazy`].
+///
+/// This exists so [`PrefetchExt`](prefetch::PrefetchExt) can hand a scan to
+/// a background worker thread without first collecting it into a `Vec` --
+/// the worker pulls the next batch straight from the backend each time it
+/// calls [`Iterator::next`].
+pub struct PrefixCursor {
+    inner: Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + 'static>,
+}
+
+impl PrefixCursor {
+    /// Wrap any `Send + 'static` iterator of key-value pairs as a cursor.
+    pub fn new<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = (Vec<u8>, Vec<u8>)> + Send + 'static,
+    {
+        Self { inner: Box::new(iter) }
+    }
+}
+
+impl Iterator for PrefixCursor {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
 /// Trait for generic batch operations
 pub trait GenericWriteBatch {
     /// Add a put operation to the batch
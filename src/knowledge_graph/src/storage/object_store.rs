@@ -0,0 +1,334 @@
+//! S3-compatible object storage backend, built through a [`StoreBuilder`].
+//!
+//! [`GarageStore`] implements [`Storage`] over any S3-compatible bucket
+//! (Garage, MinIO, or AWS S3 itself) instead of a local embedded engine, so
+//! a knowledge graph can live on shared, replicated blob storage that
+//! multiple MAYA instances can open concurrently. Keys map one-to-one onto
+//! object keys — there's no separate index to maintain, since the
+//! `node:`/`edge:`/`label_index:` prefixes this crate already uses as K-V
+//! keys work unchanged as S3 key prefixes (a K2V-style layout). `iter_prefix`
+//! is a paginated `ListObjectsV2` followed by one `GetObject` per key, and a
+//! [`WriteBatch`] commit fires its puts and deletes concurrently rather than
+//! as a single atomic request, since S3 has no native multi-key transaction.
+//!
+//! `GarageStore` is never constructed directly: fill in a [`GarageStoreBuilder`]
+//! with the bucket's connection details and call [`StoreBuilder::build`],
+//! mirroring Aerogramme's `IBuilder::build() -> Store` pattern.
+
+use std::sync::Arc;
+
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::runtime::Runtime;
+
+use super::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::{KnowledgeGraphError, Result};
+
+fn object_err(context: &str, e: impl std::fmt::Display) -> KnowledgeGraphError {
+    KnowledgeGraphError::StorageError(format!("object store {context}: {e}"))
+}
+
+fn key_to_object_key(key: &[u8]) -> Result<String> {
+    String::from_utf8(key.to_vec())
+        .map_err(|e| object_err("key is not valid UTF-8", e))
+}
+
+/// Produces a ready-to-use [`Storage`] backend, mirroring Aerogramme's
+/// `IBuilder` pattern: fill in whatever connection details the backend
+/// needs, then call [`build`](StoreBuilder::build) once to connect.
+pub trait StoreBuilder {
+    /// The storage backend this builder produces.
+    type Store: Storage + WriteBatchExt;
+
+    /// Consume the builder and connect to the backend, returning the store.
+    fn build(self) -> Result<Self::Store>;
+}
+
+/// Connection details for an S3-compatible bucket, analogous to Aerogramme's
+/// `K2vConfig`/`S3Config` builders: `GarageStoreBuilder { .. }.build()`
+/// returns a [`GarageStore`] backed by the AWS S3 SDK.
+///
+/// Works against Garage and MinIO as well as AWS S3 itself, since all three
+/// speak the same S3 API this builder targets via `endpoint`.
+#[derive(Clone, Debug)]
+pub struct GarageStoreBuilder {
+    /// The S3-compatible endpoint to connect to, e.g. `http://localhost:3900`
+    /// for a local Garage instance, or left as AWS's default for real S3.
+    pub endpoint: Option<String>,
+    /// The region to sign requests for. Garage accepts any non-empty value.
+    pub region: String,
+    /// The bucket holding the graph's keys.
+    pub bucket: String,
+    /// Access key for the bucket.
+    pub access_key: String,
+    /// Secret key for the bucket.
+    pub secret_key: String,
+}
+
+impl StoreBuilder for GarageStoreBuilder {
+    type Store = GarageStore;
+
+    fn build(self) -> Result<GarageStore> {
+        let runtime = Arc::new(Runtime::new().map_err(|e| object_err("spawn tokio runtime", e))?);
+
+        let credentials = Credentials::new(
+            self.access_key,
+            self.secret_key,
+            None,
+            None,
+            "garage-store-builder",
+        );
+        let mut config_loader = aws_sdk_s3::config::Builder::new()
+            .region(Region::new(self.region))
+            .credentials_provider(credentials)
+            .force_path_style(true);
+        if let Some(endpoint) = self.endpoint {
+            config_loader = config_loader.endpoint_url(endpoint);
+        }
+
+        let client = Client::from_conf(config_loader.build());
+        runtime
+            .block_on(client.head_bucket().bucket(&self.bucket).send())
+            .map_err(|e| object_err(&format!("bucket {} is unreachable", self.bucket), e))?;
+
+        Ok(GarageStore {
+            client: Arc::new(client),
+            bucket: self.bucket,
+            runtime,
+        })
+    }
+}
+
+/// A [`Storage`] backend over an S3-compatible bucket, built by
+/// [`GarageStoreBuilder::build`]; not constructed directly.
+#[derive(Clone)]
+pub struct GarageStore {
+    client: Arc<Client>,
+    bucket: String,
+    runtime: Arc<Runtime>,
+}
+
+impl std::fmt::Debug for GarageStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GarageStore").field("bucket", &self.bucket).finish_non_exhaustive()
+    }
+}
+
+impl GarageStore {
+    async fn get_object(&self, object_key: &str) -> Result<Option<Vec<u8>>> {
+        match self.client.get_object().bucket(&self.bucket).key(object_key).send().await {
+            Ok(output) => {
+                let bytes = output
+                    .body
+                    .collect()
+                    .await
+                    .map_err(|e| object_err("read object body", e))?
+                    .into_bytes();
+                Ok(Some(bytes.to_vec()))
+            }
+            Err(e) if is_not_found(&e) => Ok(None),
+            Err(e) => Err(object_err("get object", e)),
+        }
+    }
+
+    async fn put_object(&self, object_key: &str, value: Vec<u8>) -> Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .body(ByteStream::from(value))
+            .send()
+            .await
+            .map_err(|e| object_err("put object", e))?;
+        Ok(())
+    }
+
+    async fn delete_object(&self, object_key: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .send()
+            .await
+            .map_err(|e| object_err("delete object", e))?;
+        Ok(())
+    }
+
+    /// Paginated `ListObjectsV2` under `prefix`, fetching every matching
+    /// object's body. Materialized eagerly since [`Storage::iter_prefix`]'s
+    /// signature is synchronous.
+    async fn list_prefix(&self, prefix: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let mut results = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut request = self.client.list_objects_v2().bucket(&self.bucket).prefix(prefix);
+            if let Some(token) = continuation_token.take() {
+                request = request.continuation_token(token);
+            }
+            let output = request.send().await.map_err(|e| object_err("list objects", e))?;
+
+            for object in output.contents() {
+                let Some(object_key) = object.key() else { continue };
+                if let Some(value) = self.get_object(object_key).await? {
+                    results.push((object_key.as_bytes().to_vec(), value));
+                }
+            }
+
+            if output.is_truncated().unwrap_or(false) {
+                continuation_token = output.next_continuation_token().map(str::to_string);
+            } else {
+                break;
+            }
+        }
+        Ok(results)
+    }
+}
+
+fn is_not_found(error: &aws_sdk_s3::error::SdkError<impl std::fmt::Debug>) -> bool {
+    matches!(
+        error,
+        aws_sdk_s3::error::SdkError::ServiceError(service_err)
+            if format!("{service_err:?}").contains("NoSuchKey")
+    )
+}
+
+impl Storage for GarageStore {
+    type Batch<'a> = GarageBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        let object_key = key_to_object_key(key)?;
+        self.runtime.block_on(self.delete_object(&object_key))
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.get_raw(key)?.is_some())
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let object_key = key_to_object_key(key)?;
+        self.runtime.block_on(self.get_object(&object_key))
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let object_key = key_to_object_key(key)?;
+        self.runtime.block_on(self.put_object(&object_key, value.to_vec()))
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let prefix = String::from_utf8_lossy(prefix).into_owned();
+        let entries = self.runtime.block_on(self.list_prefix(&prefix)).unwrap_or_else(|e| {
+            log::warn!("iter_prefix against object store failed: {}", e);
+            Vec::new()
+        });
+        Box::new(entries.into_iter())
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        GarageBatch {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            runtime: self.runtime.clone(),
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl WriteBatchExt for GarageStore {}
+
+/// One write staged into a [`GarageBatch`].
+#[derive(Debug, Clone)]
+enum ObjectOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+/// Accumulates puts and deletes, firing them concurrently against the
+/// bucket on [`commit`](WriteBatch::commit). S3 has no native multi-key
+/// transaction, so this is "batched" in the sense of one concurrent round
+/// trip per commit, not atomic: a commit can partially apply if one of the
+/// requests fails after others have already succeeded.
+#[derive(Debug)]
+pub struct GarageBatch {
+    client: Arc<Client>,
+    bucket: String,
+    runtime: Arc<Runtime>,
+    ops: Vec<ObjectOp>,
+}
+
+impl WriteBatch for GarageBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ops.push(ObjectOp::Put(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.ops.push(ObjectOp::Delete(key.to_vec()));
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    fn commit(self) -> Result<()> {
+        let client = self.client;
+        let bucket = self.bucket;
+        let ops = self.ops;
+        self.runtime.block_on(async move {
+            let requests = ops.into_iter().map(|op| {
+                let client = client.clone();
+                let bucket = bucket.clone();
+                async move {
+                    match op {
+                        ObjectOp::Put(key, value) => {
+                            let object_key = key_to_object_key(&key)?;
+                            client
+                                .put_object()
+                                .bucket(&bucket)
+                                .key(object_key)
+                                .body(ByteStream::from(value))
+                                .send()
+                                .await
+                                .map_err(|e| object_err("put object", e))?;
+                        }
+                        ObjectOp::Delete(key) => {
+                            let object_key = key_to_object_key(&key)?;
+                            client
+                                .delete_object()
+                                .bucket(&bucket)
+                                .key(object_key)
+                                .send()
+                                .await
+                                .map_err(|e| object_err("delete object", e))?;
+                        }
+                    }
+                    Ok::<(), KnowledgeGraphError>(())
+                }
+            });
+            futures::future::try_join_all(requests).await?;
+            Ok(())
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
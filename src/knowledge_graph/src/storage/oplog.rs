@@ -0,0 +1,380 @@
+//! Bayou-style operation log with periodic checkpointing.
+//!
+//! [`OpLog<S>`] layers an append-only, timestamped log of mutating
+//! operations over any `Storage + WriteBatchExt` backend `S`, giving
+//! callers crash recovery, audit, and point-in-time ("time-travel") reads
+//! without `S` itself needing to support any of that. Every call to
+//! [`apply`](OpLog::apply) is recorded as one row keyed by a monotonic
+//! logical timestamp under the `oplog_op:` prefix, and every
+//! [`checkpoint`](OpLog::checkpoint) -- by default taken automatically
+//! every [`DEFAULT_CHECKPOINT_INTERVAL`] operations -- materializes the
+//! full current state as one blob keyed by its timestamp under
+//! `oplog_checkpoint:`, the same reserved-key-prefix-inside-the-same-engine
+//! layout [`LogStore`](super::raft_store::LogStore) uses for Raft metadata.
+//!
+//! [`open`](OpLog::open) reconstructs current state by loading the newest
+//! checkpoint blob that deserializes successfully, then replaying every
+//! operation row timestamped after it, in timestamp order --
+//! replaying an operation is idempotent (it's just `BTreeMap::insert`/
+//! `remove`), so reapplying ops a checkpoint already subsumed, or replaying
+//! the same log twice, always converges to the same state. A checkpoint is
+//! only ever written after every operation it subsumes has already been
+//! durably appended, so a checkpoint write that's interrupted partway
+//! through (or one that fails to deserialize later, e.g. truncated mid-write)
+//! just leaves every older checkpoint in place as a fallback -- `open` skips
+//! it and keeps walking backward until one loads, falling all the way back
+//! to a full replay from the beginning if none do. [`gc`](OpLog::gc) is the
+//! only thing that ever deletes operation rows, and it only removes rows
+//! the newest checkpoint actually present already subsumes (timestamped at
+//! or before it). After a `gc`, that newest checkpoint is what `open`
+//! needs: falling back to an older checkpoint or a full replay no longer
+//! has the removed rows.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::{deserialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::{KnowledgeGraphError, Result};
+
+/// Number of operations between automatic checkpoints (see [`OpLog::apply`]).
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 64;
+
+const OP_PREFIX: &[u8] = b"oplog_op:";
+const CHECKPOINT_PREFIX: &[u8] = b"oplog_checkpoint:";
+
+fn oplog_err(context: &str, e: impl std::fmt::Display) -> KnowledgeGraphError {
+    KnowledgeGraphError::TransactionError(format!("oplog {context}: {e}"))
+}
+
+fn op_key(timestamp: u64) -> Vec<u8> {
+    let mut key = OP_PREFIX.to_vec();
+    key.extend_from_slice(&timestamp.to_be_bytes());
+    key
+}
+
+fn checkpoint_key(timestamp: u64) -> Vec<u8> {
+    let mut key = CHECKPOINT_PREFIX.to_vec();
+    key.extend_from_slice(&timestamp.to_be_bytes());
+    key
+}
+
+fn timestamp_suffix(key: &[u8], prefix: &[u8]) -> Option<u64> {
+    let suffix = key.strip_prefix(prefix)?;
+    Some(u64::from_be_bytes(suffix.try_into().ok()?))
+}
+
+/// One mutating operation recorded in an [`OpLog`]. Node/edge inserts,
+/// updates, and deletes all reduce to a put or delete at the key-value
+/// layer `OpLog` operates at, the same way [`RaftOp`](super::raft_store::RaftOp)
+/// represents a Raft-replicated write.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogOp {
+    /// Write `value` at `key`.
+    Put(Vec<u8>, Vec<u8>),
+    /// Remove `key`.
+    Delete(Vec<u8>),
+}
+
+impl LogOp {
+    fn apply_to(&self, state: &mut BTreeMap<Vec<u8>, Vec<u8>>) {
+        match self {
+            LogOp::Put(key, value) => {
+                state.insert(key.clone(), value.clone());
+            }
+            LogOp::Delete(key) => {
+                state.remove(key);
+            }
+        }
+    }
+}
+
+/// A full-state checkpoint, tagged with the timestamp of the last operation
+/// it subsumes.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CheckpointData {
+    timestamp: u64,
+    state: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// Reconstruct state as of `upto` (inclusive), or the latest state if
+/// `upto` is `None`: load the newest checkpoint blob at or before `upto`
+/// that deserializes successfully, falling back to older ones if not, then
+/// replay every operation row in `(checkpoint_timestamp, upto]` in order.
+/// Returns the reconstructed state and the highest operation timestamp seen
+/// (the checkpoint's own, if no operations were replayed on top of it).
+fn reconstruct<S: Storage>(inner: &S, upto: Option<u64>) -> (BTreeMap<Vec<u8>, Vec<u8>>, u64) {
+    let mut checkpoints: Vec<(u64, Vec<u8>)> = inner
+        .iter_prefix(CHECKPOINT_PREFIX)
+        .filter_map(|(k, v)| timestamp_suffix(&k, CHECKPOINT_PREFIX).map(|ts| (ts, v)))
+        .filter(|(ts, _)| match upto {
+            Some(upto) => *ts <= upto,
+            None => true,
+        })
+        .collect();
+    checkpoints.sort_unstable_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+
+    let mut state = BTreeMap::new();
+    let mut checkpoint_timestamp = 0u64;
+    for (timestamp, bytes) in &checkpoints {
+        match deserialize::<CheckpointData>(bytes) {
+            Ok(data) => {
+                state = data.state;
+                checkpoint_timestamp = data.timestamp;
+                break;
+            }
+            Err(e) => {
+                log::warn!(
+                    "oplog checkpoint at timestamp {timestamp} failed to deserialize, \
+                     falling back to an older checkpoint: {e}"
+                );
+            }
+        }
+    }
+
+    let mut ops: Vec<(u64, LogOp)> = inner
+        .iter_prefix(OP_PREFIX)
+        .filter_map(|(k, v)| {
+            let timestamp = timestamp_suffix(&k, OP_PREFIX)?;
+            if timestamp <= checkpoint_timestamp {
+                return None;
+            }
+            if let Some(upto) = upto {
+                if timestamp > upto {
+                    return None;
+                }
+            }
+            deserialize::<LogOp>(&v).ok().map(|op| (timestamp, op))
+        })
+        .collect();
+    ops.sort_unstable_by_key(|(timestamp, _)| *timestamp);
+
+    let mut max_timestamp = checkpoint_timestamp;
+    for (timestamp, op) in &ops {
+        op.apply_to(&mut state);
+        max_timestamp = max_timestamp.max(*timestamp);
+    }
+
+    (state, max_timestamp)
+}
+
+/// An append-only operation log layered over `S`, reconstructing its
+/// current state in memory on [`open`](OpLog::open) and keeping it updated
+/// as operations are [`apply`](OpLog::apply)'d.
+pub struct OpLog<S: Storage + WriteBatchExt> {
+    inner: S,
+    state: RwLock<BTreeMap<Vec<u8>, Vec<u8>>>,
+    next_timestamp: AtomicU64,
+    ops_since_checkpoint: AtomicU64,
+    checkpoint_interval: u64,
+}
+
+impl<S: Storage + WriteBatchExt> OpLog<S> {
+    /// Open `inner`, replaying its existing operation log (if any) to
+    /// reconstruct current state. Checkpoints automatically every
+    /// [`DEFAULT_CHECKPOINT_INTERVAL`] applied operations; use
+    /// [`open_with_interval`](Self::open_with_interval) to change that.
+    pub fn open(inner: S) -> Result<Self> {
+        Self::open_with_interval(inner, DEFAULT_CHECKPOINT_INTERVAL)
+    }
+
+    /// Like [`open`](Self::open), checkpointing automatically every
+    /// `checkpoint_interval` applied operations instead of the default.
+    pub fn open_with_interval(inner: S, checkpoint_interval: u64) -> Result<Self> {
+        let (state, max_timestamp) = reconstruct(&inner, None);
+        Ok(Self {
+            inner,
+            state: RwLock::new(state),
+            next_timestamp: AtomicU64::new(max_timestamp + 1),
+            ops_since_checkpoint: AtomicU64::new(0),
+            checkpoint_interval,
+        })
+    }
+
+    /// Durably append `op`, assign it the next logical timestamp, and apply
+    /// it to the in-memory state, returning the timestamp it was recorded
+    /// at. Triggers an automatic [`checkpoint`](Self::checkpoint) once
+    /// `checkpoint_interval` operations have been applied since the last one.
+    pub fn apply(&self, op: LogOp) -> Result<u64> {
+        let timestamp = self.next_timestamp.fetch_add(1, Ordering::SeqCst);
+        self.inner.put_serialized(&op_key(timestamp), &op)?;
+
+        {
+            let mut state = self
+                .state
+                .write()
+                .map_err(|_| oplog_err("apply", "state lock poisoned"))?;
+            op.apply_to(&mut state);
+        }
+
+        let applied_since_checkpoint = self.ops_since_checkpoint.fetch_add(1, Ordering::SeqCst) + 1;
+        if applied_since_checkpoint >= self.checkpoint_interval {
+            self.checkpoint()?;
+        }
+
+        Ok(timestamp)
+    }
+
+    /// Materialize the current in-memory state as a checkpoint blob, tagged
+    /// with the most recent operation's timestamp, and return that
+    /// timestamp. Every operation the checkpoint subsumes is already
+    /// durably appended by the time this is called, so a checkpoint write
+    /// that's interrupted partway through never loses data -- [`open`](Self::open)
+    /// just falls back to an older checkpoint (or a full replay) instead.
+    pub fn checkpoint(&self) -> Result<u64> {
+        let timestamp = self.next_timestamp.load(Ordering::SeqCst).saturating_sub(1);
+        let state = self
+            .state
+            .read()
+            .map_err(|_| oplog_err("checkpoint", "state lock poisoned"))?
+            .clone();
+        let data = CheckpointData { timestamp, state };
+        self.inner.put_serialized(&checkpoint_key(timestamp), &data)?;
+        self.ops_since_checkpoint.store(0, Ordering::SeqCst);
+        Ok(timestamp)
+    }
+
+    /// Reconstruct state as it existed immediately after the operation
+    /// timestamped `timestamp` (inclusive), by replaying the log from the
+    /// newest checkpoint at or before `timestamp`. Always recomputed from
+    /// durable storage rather than served from the live in-memory state, so
+    /// it reflects `timestamp` exactly rather than whatever's been applied
+    /// since.
+    pub fn state_at(&self, timestamp: u64) -> BTreeMap<Vec<u8>, Vec<u8>> {
+        reconstruct(&self.inner, Some(timestamp)).0
+    }
+
+    /// Current value of `key`, from the in-memory state kept up to date by
+    /// [`apply`](Self::apply).
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .state
+            .read()
+            .map_err(|_| oplog_err("get", "state lock poisoned"))?
+            .get(key)
+            .cloned())
+    }
+
+    /// Delete every operation row timestamped at or before the newest
+    /// checkpoint currently present, returning how many rows were removed.
+    /// Everything it removes is already subsumed by that checkpoint. Older
+    /// checkpoints are left in place, but a fallback to one of them (if the
+    /// newest is corrupt) no longer has the removed rows to replay.
+    pub fn gc(&self) -> Result<usize> {
+        let latest_checkpoint = self
+            .inner
+            .iter_prefix(CHECKPOINT_PREFIX)
+            .filter_map(|(k, _)| timestamp_suffix(&k, CHECKPOINT_PREFIX))
+            .max();
+        let Some(latest_checkpoint) = latest_checkpoint else {
+            return Ok(0);
+        };
+
+        let stale_keys: Vec<Vec<u8>> = self
+            .inner
+            .iter_prefix(OP_PREFIX)
+            .filter_map(|(k, _)| {
+                let timestamp = timestamp_suffix(&k, OP_PREFIX)?;
+                (timestamp <= latest_checkpoint).then_some(k)
+            })
+            .collect();
+
+        let mut batch = self.inner.create_batch();
+        for key in &stale_keys {
+            batch.delete_serialized(key)?;
+        }
+        batch.commit()?;
+
+        Ok(stale_keys.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::InMemoryStore;
+
+    #[test]
+    fn test_apply_and_get_roundtrip() {
+        let log = OpLog::open(InMemoryStore::new()).unwrap();
+        log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+        log.apply(LogOp::Put(b"b".to_vec(), b"2".to_vec())).unwrap();
+        log.apply(LogOp::Delete(b"a".to_vec())).unwrap();
+
+        assert_eq!(log.get(b"a").unwrap(), None);
+        assert_eq!(log.get(b"b").unwrap(), Some(b"2".to_vec()));
+    }
+
+    #[test]
+    fn test_open_replays_log_without_a_checkpoint() {
+        let inner = InMemoryStore::new();
+        {
+            let log = OpLog::open(inner.clone()).unwrap();
+            log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+            log.apply(LogOp::Put(b"b".to_vec(), b"2".to_vec())).unwrap();
+        }
+
+        let reopened = OpLog::open(inner).unwrap();
+        assert_eq!(reopened.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(reopened.get(b"b").unwrap(), Some(b"2".to_vec()));
+    }
+
+    #[test]
+    fn test_checkpoint_then_reopen_does_not_replay_subsumed_ops() {
+        let inner = InMemoryStore::new();
+        let log = OpLog::open_with_interval(inner.clone(), 1_000).unwrap();
+        log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+        log.apply(LogOp::Put(b"b".to_vec(), b"2".to_vec())).unwrap();
+        let checkpoint_ts = log.checkpoint().unwrap();
+        log.apply(LogOp::Put(b"c".to_vec(), b"3".to_vec())).unwrap();
+        drop(log);
+
+        assert!(inner.iter_prefix(CHECKPOINT_PREFIX).count() == 1);
+
+        let reopened = OpLog::open_with_interval(inner, 1_000).unwrap();
+        assert_eq!(reopened.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(reopened.get(b"c").unwrap(), Some(b"3".to_vec()));
+        assert!(checkpoint_ts >= 1);
+    }
+
+    #[test]
+    fn test_auto_checkpoint_fires_after_interval() {
+        let inner = InMemoryStore::new();
+        let log = OpLog::open_with_interval(inner.clone(), 2).unwrap();
+        log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+        assert_eq!(inner.iter_prefix(CHECKPOINT_PREFIX).count(), 0);
+        log.apply(LogOp::Put(b"b".to_vec(), b"2".to_vec())).unwrap();
+        assert_eq!(inner.iter_prefix(CHECKPOINT_PREFIX).count(), 1);
+    }
+
+    #[test]
+    fn test_state_at_reconstructs_point_in_time() {
+        let log = OpLog::open(InMemoryStore::new()).unwrap();
+        let t1 = log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+        log.apply(LogOp::Put(b"a".to_vec(), b"2".to_vec())).unwrap();
+        log.apply(LogOp::Delete(b"a".to_vec())).unwrap();
+
+        let past_state = log.state_at(t1);
+        assert_eq!(past_state.get(b"a".as_slice()), Some(&b"1".to_vec()));
+        assert_eq!(log.get(b"a").unwrap(), None);
+    }
+
+    #[test]
+    fn test_gc_removes_only_ops_subsumed_by_a_checkpoint() {
+        let inner = InMemoryStore::new();
+        let log = OpLog::open_with_interval(inner.clone(), 1_000).unwrap();
+        log.apply(LogOp::Put(b"a".to_vec(), b"1".to_vec())).unwrap();
+        log.checkpoint().unwrap();
+        log.apply(LogOp::Put(b"b".to_vec(), b"2".to_vec())).unwrap();
+
+        let removed = log.gc().unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(inner.iter_prefix(OP_PREFIX).count(), 1);
+
+        // Still fully reconstructable after gc.
+        assert_eq!(log.get(b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(log.get(b"b").unwrap(), Some(b"2".to_vec()));
+    }
+}
@@ -0,0 +1,314 @@
+//! Postgres-backed storage, built through a [`StoreBuilder`].
+//!
+//! [`PostgresStore`] implements [`Storage`] over a single `kv(key BYTEA
+//! PRIMARY KEY, value BYTEA NOT NULL)` table, reached through a pooled
+//! async client ([`deadpool_postgres::Pool`]) the same way [`GarageStore`](super::object_store::GarageStore)
+//! reaches its S3 bucket through the AWS SDK's async client: a `tokio`
+//! [`Runtime`] bridges every call back to this crate's synchronous
+//! [`Storage`] trait via `block_on`. `put` is an upsert
+//! (`INSERT ... ON CONFLICT (key) DO UPDATE`), the primary key on `key`
+//! doubles as the index range scans and `iter_prefix` need, and a
+//! [`PostgresBatch`] commits its staged puts/deletes as one SQL transaction,
+//! so unlike [`GarageStore`]'s best-effort concurrent batch, a
+//! `PostgresBatch` commit is atomic.
+//!
+//! `PostgresStore` is never constructed directly: fill in a
+//! [`PostgresStoreBuilder`] with the connection details and call
+//! [`StoreBuilder::build`], which also runs the one-table migration on
+//! first connect so callers never need a separate migration step.
+
+use std::sync::Arc;
+
+use deadpool_postgres::{Config as PoolConfig, ManagerConfig, Pool, RecyclingMethod, Runtime as DeadpoolRuntime};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::runtime::Runtime;
+use tokio_postgres::NoTls;
+
+use super::object_store::StoreBuilder;
+use super::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::{KnowledgeGraphError, Result};
+
+fn postgres_err(context: &str, e: impl std::fmt::Display) -> KnowledgeGraphError {
+    KnowledgeGraphError::StorageError(format!("postgres store {context}: {e}"))
+}
+
+/// Creates the `kv` table this backend stores everything in, if it doesn't
+/// already exist. Run once per [`PostgresStoreBuilder::build`] so callers
+/// never need a separate migration step before using a fresh database.
+const MIGRATION_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS kv (
+        key BYTEA PRIMARY KEY,
+        value BYTEA NOT NULL
+    )
+";
+
+/// Connection details for a Postgres database, filled in and passed to
+/// [`StoreBuilder::build`] to get a connected [`PostgresStore`].
+#[derive(Clone, Debug)]
+pub struct PostgresStoreBuilder {
+    /// Database host, e.g. `localhost`.
+    pub host: String,
+    /// Database port. Postgres's default is `5432`.
+    pub port: u16,
+    /// Role to connect as.
+    pub user: String,
+    /// Password for `user`.
+    pub password: String,
+    /// Database name to connect to.
+    pub dbname: String,
+    /// Maximum number of pooled connections.
+    pub pool_size: usize,
+}
+
+impl StoreBuilder for PostgresStoreBuilder {
+    type Store = PostgresStore;
+
+    fn build(self) -> Result<PostgresStore> {
+        let runtime = Arc::new(Runtime::new().map_err(|e| postgres_err("spawn tokio runtime", e))?);
+
+        let mut config = PoolConfig::new();
+        config.host = Some(self.host);
+        config.port = Some(self.port);
+        config.user = Some(self.user);
+        config.password = Some(self.password);
+        config.dbname = Some(self.dbname);
+        config.pool_size = Some(self.pool_size);
+        config.manager = Some(ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        });
+
+        let pool = config
+            .create_pool(Some(DeadpoolRuntime::Tokio1), NoTls)
+            .map_err(|e| postgres_err("create connection pool", e))?;
+
+        runtime.block_on(async {
+            let client = pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+            client
+                .batch_execute(MIGRATION_SQL)
+                .await
+                .map_err(|e| postgres_err("run migration", e))
+        })?;
+
+        Ok(PostgresStore {
+            pool: Arc::new(pool),
+            runtime,
+        })
+    }
+}
+
+/// A [`Storage`] backend over a Postgres `kv` table, built by
+/// [`PostgresStoreBuilder::build`]; not constructed directly.
+#[derive(Clone)]
+pub struct PostgresStore {
+    pool: Arc<Pool>,
+    runtime: Arc<Runtime>,
+}
+
+impl std::fmt::Debug for PostgresStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PostgresStore").finish_non_exhaustive()
+    }
+}
+
+/// Smallest key strictly greater than `prefix` in byte-lexicographic order,
+/// used as the exclusive upper bound of a prefix range query against `kv`'s
+/// indexed `key` column. `None` means there's no finite upper bound (an
+/// empty prefix, or one that's all `0xff` bytes), so the range query should
+/// have no upper bound either.
+fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut upper = prefix.to_vec();
+    while let Some(last) = upper.last() {
+        if *last == u8::MAX {
+            upper.pop();
+        } else {
+            *upper.last_mut().unwrap() += 1;
+            return Some(upper);
+        }
+    }
+    None
+}
+
+impl PostgresStore {
+    async fn get_row(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let client = self.pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+        let row = client
+            .query_opt("SELECT value FROM kv WHERE key = $1", &[&key])
+            .await
+            .map_err(|e| postgres_err("select", e))?;
+        Ok(row.map(|row| row.get::<_, Vec<u8>>("value")))
+    }
+
+    async fn put_row(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let client = self.pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+        client
+            .execute(
+                "INSERT INTO kv (key, value) VALUES ($1, $2)
+                 ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value",
+                &[&key, &value],
+            )
+            .await
+            .map_err(|e| postgres_err("upsert", e))?;
+        Ok(())
+    }
+
+    async fn delete_row(&self, key: &[u8]) -> Result<()> {
+        let client = self.pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+        client
+            .execute("DELETE FROM kv WHERE key = $1", &[&key])
+            .await
+            .map_err(|e| postgres_err("delete", e))?;
+        Ok(())
+    }
+
+    async fn list_prefix(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let client = self.pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+        let rows = match prefix_upper_bound(prefix) {
+            Some(upper) => client
+                .query(
+                    "SELECT key, value FROM kv WHERE key >= $1 AND key < $2 ORDER BY key",
+                    &[&prefix, &upper.as_slice()],
+                )
+                .await
+                .map_err(|e| postgres_err("range scan", e))?,
+            None => client
+                .query("SELECT key, value FROM kv WHERE key >= $1 ORDER BY key", &[&prefix])
+                .await
+                .map_err(|e| postgres_err("range scan", e))?,
+        };
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.get::<_, Vec<u8>>("key"), row.get::<_, Vec<u8>>("value")))
+            .collect())
+    }
+}
+
+impl Storage for PostgresStore {
+    type Batch<'a> = PostgresBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.runtime.block_on(self.delete_row(key))
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        Ok(self.get_raw(key)?.is_some())
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.runtime.block_on(self.get_row(key))
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.runtime.block_on(self.put_row(key, value))
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let entries = self.runtime.block_on(self.list_prefix(prefix)).unwrap_or_else(|e| {
+            log::warn!("iter_prefix against postgres store failed: {}", e);
+            Vec::new()
+        });
+        Box::new(entries.into_iter())
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        PostgresBatch {
+            pool: Arc::clone(&self.pool),
+            runtime: Arc::clone(&self.runtime),
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl WriteBatchExt for PostgresStore {}
+
+/// One write staged into a [`PostgresBatch`].
+#[derive(Debug, Clone)]
+enum PostgresOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+/// Accumulates puts and deletes, applying them all inside a single SQL
+/// transaction on [`commit`](WriteBatch::commit) -- unlike
+/// [`GarageBatch`](super::object_store::GarageBatch)'s best-effort
+/// concurrent requests, a `PostgresBatch` commit is atomic: either every
+/// staged write lands, or none do.
+#[derive(Debug)]
+pub struct PostgresBatch {
+    pool: Arc<Pool>,
+    runtime: Arc<Runtime>,
+    ops: Vec<PostgresOp>,
+}
+
+impl WriteBatch for PostgresBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ops.push(PostgresOp::Put(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.ops.push(PostgresOp::Delete(key.to_vec()));
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    fn commit(self) -> Result<()> {
+        let pool = self.pool;
+        let ops = self.ops;
+        self.runtime.block_on(async move {
+            let mut client = pool.get().await.map_err(|e| postgres_err("checkout connection", e))?;
+            let transaction = client
+                .transaction()
+                .await
+                .map_err(|e| postgres_err("begin transaction", e))?;
+
+            for op in &ops {
+                match op {
+                    PostgresOp::Put(key, value) => {
+                        transaction
+                            .execute(
+                                "INSERT INTO kv (key, value) VALUES ($1, $2)
+                                 ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value",
+                                &[key, value],
+                            )
+                            .await
+                            .map_err(|e| postgres_err("batch upsert", e))?;
+                    }
+                    PostgresOp::Delete(key) => {
+                        transaction
+                            .execute("DELETE FROM kv WHERE key = $1", &[key])
+                            .await
+                            .map_err(|e| postgres_err("batch delete", e))?;
+                    }
+                }
+            }
+
+            transaction
+                .commit()
+                .await
+                .map_err(|e| KnowledgeGraphError::TransactionError(format!("postgres batch commit: {e}")))
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
@@ -17,46 +17,26 @@ GLIMMER Pattern:
 
 use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
 use std::thread;
 use std::time::Duration;
 
-use crossbeam_channel::{bounded, Sender, Receiver};
-use log::{debug, error, info, warn};
-use parking_lot::{Mutex, Condvar};
+use crossbeam_channel::{bounded, Sender, Receiver, TrySendError};
+use futures::Stream;
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
 
-use super::{Result, Storage, KnowledgeGraphError};
-
-// Simple notification mechanism for prefetch thread
-#[derive(Clone)]
-struct PrefetchNotifier {
-    condvar: Arc<(Mutex<()>, Condvar)>,
-}
-
-impl PrefetchNotifier {
-    fn new() -> Self {
-        Self {
-            condvar: Arc::new((Mutex::new(()), Condvar::new())),
-        }
-    }
-    
-    fn notify(&self) {
-        let (lock, cvar) = &*self.condvar;
-        let _guard = lock.lock().unwrap();
-        cvar.notify_all();
-    }
-    
-    fn wait_timeout(&self, timeout: Duration) -> bool {
-        let (lock, cvar) = &*self.condvar;
-        let guard = lock.lock().unwrap();
-        cvar.wait_timeout(guard, timeout).is_ok()
-    }
-}
+use super::{PrefixCursor, Result, Storage, KnowledgeGraphError};
 
 /// Configuration for prefetching behavior
 #[derive(Clone, Debug)]
 pub struct PrefetchConfig {
-    /// Number of items to prefetch ahead
+    /// Starting point for [`PrefetchingIterator`]'s prefetch depth. The
+    /// iterator adapts this value at runtime between `min_prefetch_size`
+    /// and `max_prefetch_size` as consumer/producer timing shifts, so this
+    /// is only where it starts out, not a fixed ceiling.
     pub prefetch_size: usize,
     /// Maximum number of prefetch buffers to keep
     pub max_buffers: usize,
@@ -64,6 +44,21 @@ pub struct PrefetchConfig {
     pub buffer_size: usize,
     /// Time to wait for prefetch to complete (in ms)
     pub prefetch_timeout_ms: u64,
+    /// Number of worker threads to start in the process-wide default
+    /// [`PrefetchPool`]. Only takes effect the first time the default pool
+    /// is initialized; later configs are ignored once it exists.
+    pub pool_workers: usize,
+    /// Maximum number of submissions the default [`PrefetchPool`]'s shared
+    /// queue will hold before [`PrefetchPool::execute`] reports the pool as
+    /// saturated. Same one-time-initialization caveat as `pool_workers`.
+    pub pool_queue_depth: usize,
+    /// Floor for [`PrefetchingIterator`]'s adaptive prefetch depth. Additive
+    /// decrease (one `prefetch_size` step at a time) never shrinks past this.
+    pub min_prefetch_size: usize,
+    /// Ceiling for [`PrefetchingIterator`]'s adaptive prefetch depth.
+    /// Multiplicative increase (doubling) never grows past
+    /// this.
+    pub max_prefetch_size: usize,
 }
 
 impl Default for PrefetchConfig {
@@ -73,181 +68,345 @@ impl Default for PrefetchConfig {
             max_buffers: 4,
             buffer_size: 1024,
             prefetch_timeout_ms: 100,
+            pool_workers: 4,
+            pool_queue_depth: 256,
+            min_prefetch_size: 8,
+            max_prefetch_size: 1024,
+        }
+    }
+}
+
+/// A bounded pool of reusable `Vec<(K, V)>` batch buffers, shared between a
+/// [`PrefetchingIterator`] and the pool workers filling it, so a long scan
+/// reaches zero net allocations on the hot path in steady state instead of
+/// allocating (and immediately dropping) a fresh `Vec` every batch.
+///
+/// Backed by a `parking_lot::Mutex`-guarded stack rather than a hand-rolled
+/// CAS free list: push/pop are both a single `Vec::pop`/`Vec::push` under a
+/// held-for-nanoseconds critical section, and this crate has no other
+/// unsafe, lock-free data structures to match stylistically -- a mutex gets
+/// the same "recycle the allocation" win without the ABA/memory-reclamation
+/// pitfalls of a hand-rolled atomic stack.
+struct BufferPool<K, V> {
+    free: Mutex<Vec<Vec<(K, V)>>>,
+    capacity: usize,
+    batch_capacity: usize,
+}
+
+impl<K, V> BufferPool<K, V> {
+    fn new(capacity: usize, batch_capacity: usize) -> Self {
+        Self {
+            free: Mutex::new(Vec::with_capacity(capacity)),
+            capacity,
+            batch_capacity,
         }
     }
+
+    /// Take a buffer from the pool, or allocate a fresh one sized for
+    /// `batch_capacity` items if the pool is currently empty.
+    fn take(&self) -> Vec<(K, V)> {
+        self.free
+            .lock()
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(self.batch_capacity))
+    }
+
+    /// Return a drained buffer for reuse, unless the pool is already
+    /// holding `capacity` retained buffers -- in which case drop it and let
+    /// its allocation go, rather than growing the pool unboundedly.
+    fn recycle(&self, mut buffer: Vec<(K, V)>) {
+        buffer.clear();
+        let mut free = self.free.lock();
+        if free.len() < self.capacity {
+            free.push(buffer);
+        }
+    }
+}
+
+/// A unit of work a [`PrefetchPool`] worker can run: advance some iterator
+/// by at most a requested batch size and reply with the result. Type-erased
+/// so the pool's single shared queue can hold work for any
+/// `PrefetchingIterator<K, V, I>`, regardless of what `K`/`V` it was
+/// created with.
+trait PrefetchTask: Send {
+    fn run(self: Box<Self>, batch_size: usize);
 }
 
-/// A message sent to the prefetch worker thread
-enum PrefetchMessage<K, V> {
-    /// Request to prefetch the next batch of items
-    Prefetch,
-    /// Shut down the worker thread
-    Shutdown,
-    /// A batch of prefetched items
-    Batch(Vec<(K, V)>),
+/// A [`PrefetchTask`] that pulls from one [`PrefetchingIterator`]'s shared,
+/// mutex-guarded iterator and replies on that iterator's own channel.
+struct IteratorTask<K, V> {
+    iterator: Arc<Mutex<Box<dyn Iterator<Item = (K, V)> + Send>>>,
+    buffer_pool: Arc<BufferPool<K, V>>,
+    reply: Sender<Option<Vec<(K, V)>>>,
 }
 
-/// A prefetching iterator that reads ahead in a background thread
-pub struct PrefetchingIterator<K, V, I> 
+impl<K: Send + 'static, V: Send + 'static> PrefetchTask for IteratorTask<K, V> {
+    fn run(self: Box<Self>, batch_size: usize) {
+        let mut batch = self.buffer_pool.take();
+        {
+            let mut iter = self.iterator.lock();
+            for _ in 0..batch_size {
+                match iter.next() {
+                    Some(item) => batch.push(item),
+                    None => break,
+                }
+            }
+        }
+
+        if batch.is_empty() {
+            // Nothing to hand back; recycle the buffer ourselves since the
+            // consumer never sees it to drain and return.
+            self.buffer_pool.recycle(batch);
+            let _ = self.reply.send(None);
+        } else {
+            let _ = self.reply.send(Some(batch));
+        }
+    }
+}
+
+/// A submission waiting in a [`PrefetchPool`]'s shared queue.
+struct Submission {
+    task: Box<dyn PrefetchTask>,
+    batch_size: usize,
+}
+
+/// A fixed-size pool of worker threads shared by every [`PrefetchingIterator`]
+/// drawn from it, instead of each scan spawning (and, on `Drop`, joining) its
+/// own dedicated OS thread. A query that fans out many concurrent prefix
+/// scans -- common in graph traversal -- submits work into one bounded
+/// queue that a small, fixed set of long-lived workers drain, rather than
+/// paying a thread spawn/join per scan.
+pub struct PrefetchPool {
+    tx: Sender<Submission>,
+    _workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl PrefetchPool {
+    /// Start a pool of `num_workers` threads pulling from a shared queue
+    /// that holds at most `queue_depth` unclaimed submissions.
+    pub fn new(num_workers: usize, queue_depth: usize) -> Self {
+        let (tx, rx) = bounded::<Submission>(queue_depth.max(1));
+
+        let workers = (0..num_workers.max(1))
+            .map(|_| {
+                let rx: Receiver<Submission> = rx.clone();
+                thread::spawn(move || {
+                    while let Ok(Submission { task, batch_size }) = rx.recv() {
+                        task.run(batch_size);
+                    }
+                })
+            })
+            .collect();
+
+        Self { tx, _workers: workers }
+    }
+
+    /// The process-wide default pool, lazily started on first use (sized
+    /// from [`PrefetchConfig::default`]) and shared by every `PrefetchExt`
+    /// call that doesn't bring its own pool via
+    /// [`PrefetchingIterator::with_pool`].
+    pub fn default_pool() -> &'static Arc<PrefetchPool> {
+        static DEFAULT: OnceLock<Arc<PrefetchPool>> = OnceLock::new();
+        DEFAULT.get_or_init(|| {
+            let config = PrefetchConfig::default();
+            Arc::new(PrefetchPool::new(config.pool_workers, config.pool_queue_depth))
+        })
+    }
+
+    /// Submit a unit of work without blocking the caller. Returns
+    /// `Ok(true)` if it was accepted onto the shared queue, `Ok(false)` if
+    /// the pool is already holding `queue_depth` unclaimed submissions, so
+    /// a caller like [`PrefetchingIterator::fill_buffer`] can apply
+    /// backpressure -- skip this round and try again later -- instead of
+    /// queuing unboundedly.
+    fn execute(&self, task: Box<dyn PrefetchTask>, batch_size: usize) -> Result<bool> {
+        match self.tx.try_send(Submission { task, batch_size }) {
+            Ok(()) => Ok(true),
+            Err(TrySendError::Full(_)) => Ok(false),
+            Err(TrySendError::Disconnected(_)) => Err(KnowledgeGraphError::StorageError(
+                "prefetch pool workers have shut down".to_string(),
+            )),
+        }
+    }
+}
+
+/// A prefetching iterator that reads ahead using worker threads drawn from
+/// a shared [`PrefetchPool`] instead of spawning a dedicated OS thread per
+/// scan.
+pub struct PrefetchingIterator<K, V, I>
 where
     K: Send + 'static + Clone,
     V: Send + 'static + Clone,
     I: Iterator<Item = (K, V)> + Send + 'static,
 {
-    // Channel for receiving prefetched items
-    rx: crossbeam_channel::Receiver<Option<Vec<(K, V)>>>,
-    // Channel for sending requests to the worker
-    tx: crossbeam_channel::Sender<PrefetchMessage<K, V>>,
+    // Pool this iterator draws worker threads from.
+    pool: Arc<PrefetchPool>,
+    // The underlying scan, shared with the pool worker currently advancing
+    // it (if any) via the mutex.
+    iterator: Arc<Mutex<Box<dyn Iterator<Item = (K, V)> + Send>>>,
+    // This iterator's own reply channel; cloned into each submission so
+    // batches come back here regardless of which pool worker served them.
+    reply_tx: Sender<Option<Vec<(K, V)>>>,
+    reply_rx: Receiver<Option<Vec<(K, V)>>>,
     // Current buffer of prefetched items
     buffer: VecDeque<(K, V)>,
+    // Pool of reusable batch `Vec`s shared with worker-side `IteratorTask`s,
+    // so a steady-state scan recycles its batch allocations instead of
+    // allocating and dropping one per `fill_buffer` call.
+    buffer_pool: Arc<BufferPool<K, V>>,
     // Configuration
     config: PrefetchConfig,
-    // Worker thread handle
-    worker_thread: Option<thread::JoinHandle<()>>,
-    // Notification mechanism
-    notifier: PrefetchNotifier,
-    // Marker for Send + Sync
+    // Current adaptive prefetch depth: how many items `fill_buffer` asks a
+    // pool worker for, and the basis for the low-water refill trigger.
+    // Starts at `config.prefetch_size` and drifts between
+    // `config.min_prefetch_size` and `config.max_prefetch_size` as
+    // consumer/producer timing shifts -- see `fill_buffer`.
+    current_depth: usize,
+    // Consecutive `fill_buffer` calls in a row that found the buffer
+    // comfortably full (no submission needed, or the worker was too slow to
+    // matter). Reset on every starvation signal; once it reaches
+    // `COMFORTABLE_STREAK_THRESHOLD` the depth is additively decreased.
+    comfortable_streak: usize,
+    // Set once the underlying iterator has been exhausted
+    done: bool,
+    // Marker for the concrete iterator type this was built from
     _marker: std::marker::PhantomData<fn() -> I>,
 }
 
-impl<K, V, I> Iterator for PrefetchingIterator<K, V, I> 
+/// Consecutive comfortable `fill_buffer` rounds required before
+/// [`PrefetchingIterator`] additively shrinks its adaptive depth. A single
+/// comfortable round is treated as noise; several in a row means the
+/// producer is reliably keeping ahead of the consumer.
+const COMFORTABLE_STREAK_THRESHOLD: usize = 3;
+
+impl<K, V, I> Iterator for PrefetchingIterator<K, V, I>
 where
     K: Send + 'static + Clone,
     V: Send + 'static + Clone,
     I: Iterator<Item = (K, V)> + Send + 'static,
 {
     type Item = Result<(K, V)>;
-    
+
     fn next(&mut self) -> Option<Self::Item> {
         // If buffer is empty, try to fill it
         if self.buffer.is_empty() {
             if let Err(e) = self.fill_buffer() {
                 return Some(Err(e));
             }
-            
+
             // If still empty after filling, we're done
             if self.buffer.is_empty() {
                 return None;
             }
         }
-        
+
         // Return the next item from the buffer
         self.buffer.pop_front().map(Ok)
     }
 }
 
-// Safe to send between threads
-unsafe impl<K: Send + 'static + Clone, V: Send + 'static + Clone, I: Iterator<Item = (K, V)> + Send + 'static> Send for PrefetchingIterator<K, V, I> {}
-
-// Safe to share between threads
-unsafe impl<K: Send + Sync + 'static + Clone, V: Send + Sync + 'static + Clone, I: Iterator<Item = (K, V)> + Send + Sync + 'static> Sync for PrefetchingIterator<K, V, I> {}
-
-impl<K, V, I> PrefetchingIterator<K, V, I> 
+impl<K, V, I> PrefetchingIterator<K, V, I>
 where
     K: Send + 'static + Clone,
     V: Send + 'static + Clone,
     I: Iterator<Item = (K, V)> + Send + 'static,
 {
-    /// Create a new prefetching iterator
-    pub fn new(
-        iterator: I,
-        config: PrefetchConfig,
-    ) -> Result<Self> 
-    where
-        I: Iterator<Item = (K, V)> + Send + 'static,
-    {
-        let (tx, rx) = crossbeam_channel::bounded(1);
-        let (worker_tx, worker_rx) = crossbeam_channel::bounded(1);
-        
-        let notifier = PrefetchNotifier::new();
-        let notifier_clone = notifier.clone();
-        
-        let worker_thread = thread::spawn(move || {
-            let mut iter = iterator;
-            
-            loop {
-                match worker_rx.recv() {
-                    Ok(PrefetchMessage::Prefetch) => {
-                        let mut batch = Vec::with_capacity(config.buffer_size);
-                        
-                        // Prefetch the next batch of items
-                        for _ in 0..config.buffer_size {
-                            match iter.next() {
-                                Some(item) => batch.push(item),
-                                None => break,
-                            }
-                        }
-                        
-                        // Send the batch back to the main thread
-                        if !batch.is_empty() {
-                            if let Err(e) = tx.send(Some(batch)) {
-                                log::error!("Failed to send prefetched batch: {}", e);
-                                break;
-                            }
-                        } else {
-                            // No more items to prefetch
-                            let _ = tx.send(None);
-                            break;
-                        }
-                    }
-                    Ok(PrefetchMessage::Batch(_)) => {
-                        // This variant is not used in this context, but we need to handle it
-                        log::warn!("Unexpected Batch message received in prefetch worker");
-                    }
-                    Ok(PrefetchMessage::Shutdown) => {
-                        // Shutdown signal received
-                        break;
-                    }
-                    Err(_) => {
-                        // Channel disconnected
-                        break;
-                    }
-                }
-            }
-        });
-        
-        // Request the first batch
-        let _ = worker_tx.send(PrefetchMessage::Prefetch);
-        
+    /// Create a new prefetching iterator, drawing worker threads from the
+    /// process-wide default [`PrefetchPool`] (see
+    /// [`PrefetchPool::default_pool`]).
+    pub fn new(iterator: I, config: PrefetchConfig) -> Result<Self> {
+        Self::with_pool(iterator, config, Arc::clone(PrefetchPool::default_pool()))
+    }
+
+    /// Like [`new`](Self::new), but draws workers from an explicit pool
+    /// instead of the process-wide default -- e.g. for a caller running
+    /// many independent prefetching scans that shouldn't compete with the
+    /// rest of the process for the default pool's workers.
+    pub fn with_pool(iterator: I, config: PrefetchConfig, pool: Arc<PrefetchPool>) -> Result<Self> {
+        let (reply_tx, reply_rx) = bounded(1);
+        let iterator: Box<dyn Iterator<Item = (K, V)> + Send> = Box::new(iterator);
+        let buffer_pool = Arc::new(BufferPool::new(config.max_buffers, config.buffer_size));
+        let current_depth = config.prefetch_size.clamp(config.min_prefetch_size, config.max_prefetch_size);
+
         Ok(Self {
-            rx,
-            tx: worker_tx,
+            pool,
+            iterator: Arc::new(Mutex::new(iterator)),
+            reply_tx,
+            reply_rx,
             buffer: VecDeque::with_capacity(config.buffer_size),
+            buffer_pool,
             config,
-            worker_thread: Some(worker_thread),
-            notifier,
+            current_depth,
+            comfortable_streak: 0,
+            done: false,
             _marker: std::marker::PhantomData,
         })
     }
-    
-    /// Fill the buffer with more items
-    fn fill_buffer(&mut self) -> Result<()> 
-    where
-        K: Send + 'static + Clone,
-        V: Send + 'static + Clone,
-        I: Iterator<Item = (K, V)> + Send + 'static,
-    {
-        // Request the next batch if we're running low
-        if self.buffer.len() <= self.config.prefetch_size / 2 {
-            if let Err(e) = self.tx.send(PrefetchMessage::Prefetch) {
-                return Err(KnowledgeGraphError::StorageError(
-                    format!("Failed to request prefetch: {}", e)
-                ));
-            }
+
+    /// The iterator's current adaptive prefetch depth -- how many items it
+    /// is currently asking pool workers for per `fill_buffer` round.
+    /// Exposed for observability (e.g. logging/metrics around scans whose
+    /// workload shifts between read-heavy and write-heavy phases).
+    pub fn current_depth(&self) -> usize {
+        self.current_depth
+    }
+
+    /// Fill the buffer with more items, adapting `current_depth` based on
+    /// whether this round found the consumer starved (grow, so the next
+    /// round asks further ahead) or the producer comfortably ahead (shrink,
+    /// after a few comfortable rounds in a row, so idle capacity isn't
+    /// wasted requesting more than gets consumed).
+    fn fill_buffer(&mut self) -> Result<()> {
+        if self.done {
+            return Ok(());
         }
-        
+
+        // Only submit more work if we're running low; otherwise there's
+        // nothing to wait on this round. The threshold tracks the adaptive
+        // depth, not the static config, so it shrinks and grows in lockstep
+        // with how far ahead we're actually asking.
+        if self.buffer.len() > self.current_depth / 2 {
+            self.note_comfortable_round();
+            return Ok(());
+        }
+
+        let task = Box::new(IteratorTask {
+            iterator: Arc::clone(&self.iterator),
+            buffer_pool: Arc::clone(&self.buffer_pool),
+            reply: self.reply_tx.clone(),
+        });
+
+        if !self.pool.execute(task, self.current_depth)? {
+            // Pool is saturated; the caller will retry on the next `next()`
+            // call rather than blocking here or queuing unboundedly. Not an
+            // adaptation signal either way -- the request never reached a
+            // worker.
+            return Ok(());
+        }
+
         // Wait for the next batch with a timeout
-        match self.rx.recv_timeout(Duration::from_millis(self.config.prefetch_timeout_ms)) {
-            Ok(Some(batch)) => {
-                self.buffer.extend(batch);
+        match self.reply_rx.recv_timeout(Duration::from_millis(self.config.prefetch_timeout_ms)) {
+            Ok(Some(mut batch)) => {
+                self.buffer.extend(batch.drain(..));
+                self.buffer_pool.recycle(batch);
+                // We had to go all the way to a worker and wait on it to
+                // keep the consumer fed -- the buffer ran dry before the
+                // next batch was ready. Read further ahead next time.
+                self.comfortable_streak = 0;
+                self.current_depth = (self.current_depth * 2).min(self.config.max_prefetch_size);
                 Ok(())
             }
             Ok(None) => {
                 // End of stream
+                self.done = true;
                 Ok(())
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                // No data available yet, but not an error
+                // No data available yet, but not an error. The worker
+                // couldn't keep up within the timeout either way, so
+                // growing the request further would only make the next
+                // wait longer -- count it toward backing off instead.
+                self.note_comfortable_round();
                 Ok(())
             }
             Err(e) => {
@@ -257,56 +416,123 @@ where
             }
         }
     }
-}
 
-impl<K, V, I> Drop for PrefetchingIterator<K, V, I> 
-where
-    K: Send + 'static + Clone,
-    V: Send + 'static + Clone,
-    I: Iterator<Item = (K, V)> + Send + 'static,
-{
-    fn drop(&mut self) {
-        // Signal the worker thread to shut down
-        let _ = self.tx.send(PrefetchMessage::Shutdown);
-        
-        // Wait for the worker thread to finish
-        if let Some(handle) = self.worker_thread.take() {
-            let _ = handle.join();
+    /// Record a `fill_buffer` round that didn't need to starve the consumer
+    /// waiting on a worker -- either the buffer was already comfortably
+    /// full, or a submitted request didn't come back in time to matter.
+    /// After `COMFORTABLE_STREAK_THRESHOLD` such rounds in a row, back the
+    /// adaptive depth off by one `prefetch_size` step, floored at
+    /// `min_prefetch_size`.
+    fn note_comfortable_round(&mut self) {
+        self.comfortable_streak += 1;
+        if self.comfortable_streak >= COMFORTABLE_STREAK_THRESHOLD {
+            self.comfortable_streak = 0;
+            self.current_depth = self.current_depth
+                .saturating_sub(self.config.prefetch_size)
+                .max(self.config.min_prefetch_size);
         }
     }
 }
-// that doesn't depend on the Storage trait directly, making it more flexible and easier to use.
 
 /// Extension trait for adding prefetching to Storage iterators
 pub trait PrefetchExt: Storage {
     /// Create a prefetching iterator for a key prefix
-    /// 
+    ///
     /// This is an alias for `iter_prefix_prefetch` for backward compatibility.
     fn prefetch(
-        &self, 
-        prefix: &[u8], 
+        &self,
+        prefix: &[u8],
         config: PrefetchConfig
-    ) -> Result<PrefetchingIterator<Vec<u8>, Vec<u8>, std::vec::IntoIter<(Vec<u8>, Vec<u8>)>>> {
+    ) -> Result<PrefetchingIterator<Vec<u8>, Vec<u8>, PrefixCursor>> {
         self.iter_prefix_prefetch(prefix, config)
     }
-    
+
     /// Create a prefetching iterator for a key prefix
     fn iter_prefix_prefetch(
         &self,
         prefix: &[u8],
         config: PrefetchConfig
-    ) -> Result<PrefetchingIterator<Vec<u8>, Vec<u8>, std::vec::IntoIter<(Vec<u8>, Vec<u8>)>>> {
-        // Create a standard iterator first and collect it into a Vec to ensure 'static lifetime
-        let items: Vec<(Vec<u8>, Vec<u8>)> = self.iter_prefix(prefix).collect();
-        
-        // Create a new owned iterator from the collected items
-        let iterator = items.into_iter();
-        
-        // Create the prefetching iterator
-        PrefetchingIterator::new(iterator, config)
+    ) -> Result<PrefetchingIterator<Vec<u8>, Vec<u8>, PrefixCursor>> {
+        // `iter_prefix_lazy` is a genuinely lazy, owned cursor -- unlike
+        // collecting `iter_prefix` into a `Vec`, the worker thread below
+        // pulls each batch straight from the backend on demand, so a scan
+        // over millions of keys stays bounded in memory while the worker
+        // stays one batch ahead of the consumer.
+        let cursor = self.iter_prefix_lazy(prefix);
+
+        PrefetchingIterator::new(cursor, config)
             .map_err(|e| KnowledgeGraphError::StorageError(format!("Failed to create prefetching iterator: {}", e)))
     }
 }
 
 // Implement PrefetchExt for all types that implement Storage
 impl<T: Storage> PrefetchExt for T {}
+
+/// An async-facing stream of prefetched key-value pairs, returned by
+/// [`AsyncPrefetchExt::iter_prefix_stream`].
+///
+/// [`PrefetchingIterator`] blocks its caller on `rx.recv_timeout`, which is
+/// fine for a thread but stalls an async executor's worker if a scan is
+/// driven from a future. `PrefetchStream` drives the same read-ahead, lazy
+/// [`PrefixCursor`] scan on a `tokio` blocking-pool task instead of a raw
+/// `thread::spawn`, and hands items back over a `tokio::sync::mpsc` channel
+/// rather than `crossbeam`'s blocking receiver, so polling it never
+/// occupies a runtime thread while waiting on the backend.
+pub struct PrefetchStream {
+    rx: mpsc::Receiver<Result<(Vec<u8>, Vec<u8>)>>,
+    // Kept alive for as long as the stream is; dropping the stream drops
+    // the receiver half `rx`, which the worker notices on its next send and
+    // uses to stop scanning early.
+    _worker: tokio::task::JoinHandle<()>,
+}
+
+impl PrefetchStream {
+    fn new<S: Storage>(storage: S, prefix: Vec<u8>, config: PrefetchConfig) -> Self {
+        let (tx, rx) = mpsc::channel(config.buffer_size.max(1));
+
+        let worker = tokio::task::spawn_blocking(move || {
+            let mut cursor = storage.iter_prefix_lazy(&prefix);
+            while let Some(item) = cursor.next() {
+                if tx.blocking_send(Ok(item)).is_err() {
+                    // Receiver (the stream) was dropped; stop scanning.
+                    break;
+                }
+            }
+        });
+
+        Self { rx, _worker: worker }
+    }
+}
+
+impl Stream for PrefetchStream {
+    type Item = Result<(Vec<u8>, Vec<u8>)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Async counterpart to [`PrefetchExt`], for callers driving a scan from a
+/// future instead of a blocking thread. [`iter_prefix_stream`](Self::iter_prefix_stream)
+/// starts its scan with `tokio::task::spawn_blocking`, so it must be called
+/// from within a `tokio` runtime and panics outside one (e.g. on a plain
+/// `async-std` executor); the returned stream can then be polled from any
+/// executor. Mirrors
+/// `PrefetchExt` so a caller can pick blocking or async at the call site,
+/// the same way [`SledGraphBackend`](crate::async_graph::SledGraphBackend)
+/// sits alongside the synchronous `KnowledgeGraph<SledStore>` it wraps.
+///
+/// Requires `Clone` because the scan runs on a separate blocking-pool task
+/// for the lifetime of the stream, which needs its own owned handle onto
+/// the backend rather than borrowing `&self` -- the same reason
+/// `HybridStore` requires `Storage + Clone` of its primary backend.
+pub trait AsyncPrefetchExt: Storage + Clone {
+    /// Stream a key prefix, reading ahead on a blocking-pool task instead
+    /// of materializing the whole scan or blocking the calling future.
+    fn iter_prefix_stream(&self, prefix: &[u8], config: PrefetchConfig) -> PrefetchStream {
+        PrefetchStream::new(self.clone(), prefix.to_vec(), config)
+    }
+}
+
+// Implement AsyncPrefetchExt for all types that implement Storage + Clone
+impl<T: Storage + Clone> AsyncPrefetchExt for T {}
@@ -0,0 +1,345 @@
+//! redb storage implementation for the knowledge graph
+//!
+//! Unlike [`SledStore`](super::SledStore), which keeps a large resident
+//! working set and has known crash-recovery caveats, [`RedbStore`] is a
+//! pure-Rust embedded key-value store with real copy-on-write MVCC
+//! transactions -- every write commits a whole new B-tree root atomically,
+//! so there's no write-ahead log to replay after a crash. Like
+//! [`LmdbStore`](super::lmdb_store::LmdbStore), it requires explicit
+//! read/write transactions, so every single-key operation here opens a
+//! short-lived transaction around that one access, and [`RedbWriteBatch`]
+//! defers its operations into a single write transaction on commit.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use log::info;
+use redb::{Database, ReadableTable, TableDefinition};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::KnowledgeGraphError;
+
+use super::snapshot::Checkpoint;
+use super::{deserialize, serialize, Storage, WriteBatch, WriteBatchExt};
+use crate::error::Result;
+
+/// The single key-value table redb stores everything under.
+///
+/// redb requires every table to be declared with a static, typed
+/// definition up front; since [`Storage`] works in raw bytes, one
+/// `&[u8] -> &[u8]` table is enough to hold every logical keyspace the
+/// graph layers on top (nodes, edges, indexes), the same way a single
+/// LMDB database or Sled tree does.
+const TABLE: TableDefinition<'static, &[u8], &[u8]> = TableDefinition::new("kv");
+
+/// redb storage implementation
+#[derive(Clone)]
+pub struct RedbStore {
+    db: Arc<Database>,
+    path: PathBuf,
+}
+
+impl RedbStore {
+    /// Open or create a new redb database at the given path
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let db = Database::create(path.as_ref()).map_err(KnowledgeGraphError::from)?;
+
+        // Creating the table on open, even though it's a no-op once it
+        // already exists, guarantees every later read/write transaction can
+        // assume the table is already there instead of handling
+        // `TableError::TableDoesNotExist` on first use.
+        let wtxn = db.begin_write().map_err(KnowledgeGraphError::from)?;
+        {
+            wtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+        }
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+
+        info!("Opened redb database");
+        Ok(Self {
+            db: Arc::new(db),
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Get a reference to the underlying redb database
+    pub fn inner(&self) -> &Database {
+        &self.db
+    }
+}
+
+impl Storage for RedbStore {
+    type Batch<'a> = RedbWriteBatch where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let bytes = serialize(value)?;
+        self.put_raw(key, &bytes)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        let wtxn = self.db.begin_write().map_err(KnowledgeGraphError::from)?;
+        {
+            let mut table = wtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+            table.remove(key).map_err(KnowledgeGraphError::from)?;
+        }
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+        Ok(())
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        let rtxn = self.db.begin_read().map_err(KnowledgeGraphError::from)?;
+        let table = rtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+        Ok(table.get(key).map_err(KnowledgeGraphError::from)?.is_some())
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let rtxn = self.db.begin_read().map_err(KnowledgeGraphError::from)?;
+        let table = rtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+        Ok(table
+            .get(key)
+            .map_err(KnowledgeGraphError::from)?
+            .map(|value| value.value().to_vec()))
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        let wtxn = self.db.begin_write().map_err(KnowledgeGraphError::from)?;
+        {
+            let mut table = wtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+            table.insert(key, value).map_err(KnowledgeGraphError::from)?;
+        }
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+        Ok(())
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        // Like LmdbStore, a redb cursor/range borrows from the read
+        // transaction that created it, and that transaction can't outlive
+        // this call -- so we eagerly drain the matching range into an
+        // owned buffer rather than trying to return a range iterator tied
+        // to a transaction we'd have to keep alive.
+        let scan = || -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+            let rtxn = self.db.begin_read().map_err(KnowledgeGraphError::from)?;
+            let table = rtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+            let mut items = Vec::new();
+            for entry in table.range(prefix..).map_err(KnowledgeGraphError::from)? {
+                let (key, value) = entry.map_err(KnowledgeGraphError::from)?;
+                let key = key.value();
+                if !key.starts_with(prefix) {
+                    break;
+                }
+                items.push((key.to_vec(), value.value().to_vec()));
+            }
+            Ok(items)
+        };
+
+        let items = scan().unwrap_or_else(|e| {
+            log::warn!("Failed to iterate redb prefix: {}", e);
+            Vec::new()
+        });
+
+        Box::new(items.into_iter())
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        RedbWriteBatch::new(Arc::clone(&self.db))
+    }
+}
+
+// Implement WriteBatchExt for RedbStore using the default single-batch behavior
+impl WriteBatchExt for RedbStore {}
+
+impl Checkpoint for RedbStore {
+    fn checkpoint_to_path(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // redb has no built-in "copy compacted" API like LMDB's
+        // `mdb_env_copy2`, so a checkpoint is a plain file copy. Opening and
+        // immediately committing an empty write transaction first forces
+        // redb to land its own durable commit, so the file on disk is a
+        // consistent snapshot to copy.
+        let wtxn = self.db.begin_write().map_err(KnowledgeGraphError::from)?;
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+
+        std::fs::copy(&self.path, path)?;
+        Ok(())
+    }
+
+    fn open_checkpoint(path: &Path) -> Result<Self> {
+        Self::open(path)
+    }
+}
+
+/// redb write batch
+///
+/// Operations are buffered and applied inside a single write transaction on
+/// commit, mirroring [`LmdbWriteBatch`](super::lmdb_store::LmdbWriteBatch).
+#[derive(Debug)]
+pub struct RedbWriteBatch {
+    db: Arc<Database>,
+    ops: Vec<BatchOp>,
+}
+
+#[derive(Debug, Clone)]
+enum BatchOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+}
+
+impl RedbWriteBatch {
+    /// Create a new, empty write batch
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            ops: Vec::new(),
+        }
+    }
+}
+
+impl WriteBatch for RedbWriteBatch {
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.ops.push(BatchOp::Put(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
+        self.ops.push(BatchOp::Delete(key.to_vec()));
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.ops.clear();
+    }
+
+    fn commit(mut self) -> Result<()> {
+        if self.ops.is_empty() {
+            return Ok(());
+        }
+
+        let wtxn = self.db.begin_write().map_err(KnowledgeGraphError::from)?;
+        {
+            let mut table = wtxn.open_table(TABLE).map_err(KnowledgeGraphError::from)?;
+            for op in self.ops.drain(..) {
+                match op {
+                    BatchOp::Put(key, value) => {
+                        table
+                            .insert(key.as_slice(), value.as_slice())
+                            .map_err(KnowledgeGraphError::from)?;
+                    }
+                    BatchOp::Delete(key) => {
+                        table.remove(key.as_slice()).map_err(KnowledgeGraphError::from)?;
+                    }
+                }
+            }
+        }
+        wtxn.commit().map_err(KnowledgeGraphError::from)?;
+
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_get() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RedbStore::open(dir.path().join("test.redb"))?;
+
+        let key = b"test_key";
+        let value = b"test_value";
+
+        store.put(key, &value.to_vec())?;
+        let retrieved: Option<Vec<u8>> = store.get(key)?;
+
+        assert_eq!(retrieved, Some(value.to_vec()));
+
+        let non_existent: Option<Vec<u8>> = store.get(b"non_existent")?;
+        assert_eq!(non_existent, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RedbStore::open(dir.path().join("test.redb"))?;
+
+        let key = b"test_key";
+        let value = b"test_value";
+        store.put(key, &value.to_vec())?;
+
+        assert!(store.exists(key)?);
+
+        store.delete(key)?;
+
+        assert!(!store.exists(key)?);
+        assert!(store.get::<Vec<u8>>(key)?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_prefix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RedbStore::open(dir.path().join("test.redb"))?;
+
+        store.put(b"prefix:1", &b"value1".to_vec())?;
+        store.put(b"prefix:2", &b"value2".to_vec())?;
+        store.put(b"other:1", &b"other1".to_vec())?;
+
+        let mut results: Vec<_> = store
+            .iter_prefix(b"prefix:")
+            .map(|(k, v)| (k, deserialize::<Vec<u8>>(&v).unwrap()))
+            .collect();
+        results.sort();
+
+        let expected = vec![
+            (b"prefix:1".to_vec(), b"value1".to_vec()),
+            (b"prefix:2".to_vec(), b"value2".to_vec()),
+        ];
+
+        assert_eq!(results, expected);
+
+        let results: Vec<_> = store.iter_prefix(b"nonexistent").collect();
+        assert!(results.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_commit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RedbStore::open(dir.path().join("test.redb"))?;
+
+        let mut batch = store.create_batch();
+        batch.put_serialized(b"batch1", &serialize(&100u64)?)?;
+        batch.put_serialized(b"batch2", &serialize(&200u64)?)?;
+        batch.commit()?;
+
+        assert_eq!(store.get::<u64>(b"batch1")?, Some(100));
+        assert_eq!(store.get::<u64>(b"batch2")?, Some(200));
+
+        Ok(())
+    }
+}
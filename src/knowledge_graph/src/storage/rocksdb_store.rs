@@ -1,80 +1,111 @@
 
 //! RocksDB storage implementation for the knowledge graph
+//!
+//! `RocksDBStore` opens four column families up front -- `default`,
+//! `nodes`, `edges`, `indices` -- and [`ColumnFamilyStore`]/[`WriteBatchCf`]
+//! read and write the family matching a [`ColumnFamily::NAME`] instead of
+//! `default`. The plain [`Storage`] methods, which is what the graph layer
+//! uses to store nodes, edges and index entries, all read and write
+//! `default`; only callers of the `_cf` methods get the partitioning.
+//! Partitioning this way lets each family carry its own block
+//! cache/compression tuning later and lets a scan over, say,
+//! `iter_prefix_cf::<EdgesCf>` skip every node key instead of filtering
+//! them out after the fact.
 
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
 
 use rocksdb::{
     DB, IteratorMode, Options, WriteBatch as RocksWriteBatch, DBCompressionType, Cache,
-    BlockBasedOptions, ReadOptions
+    BlockBasedOptions,
 };
 use serde::{Serialize, de::DeserializeOwned};
-use log::warn;
 
 use crate::error::{Result, KnowledgeGraphError};
-use super::{Storage, WriteBatch};
+use super::column_family::{ColumnFamily, ColumnFamilyStore, WriteBatchCf};
+use super::metrics::StorageMetrics;
+use super::snapshot::Checkpoint;
+use super::{deserialize, prefix_successor, serialize, IterDirection, Storage, WriteBatch, WriteBatchExt};
+
+/// The column families `RocksDBStore::open` creates, `default` included.
+const COLUMN_FAMILIES: &[&str] = &["default", "nodes", "edges", "indices"];
 
 /// RocksDB storage implementation
+#[derive(Clone)]
 pub struct RocksDBStore {
     db: Arc<DB>,
+    #[allow(dead_code)] // keeps the block cache alive for as long as the store is
     cache: Option<Cache>,
+    metrics: Arc<StorageMetrics>,
 }
 
 impl RocksDBStore {
     /// Open or create a new RocksDB database at the given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         let mut opts = Options::default();
-        
+
         // Configure RocksDB options
         opts.create_if_missing(true);
         opts.create_missing_column_families(true);
         opts.set_compression_type(DBCompressionType::Lz4);
         opts.set_use_fsync(false);
         opts.set_manual_wal_flush(true);
-        
+
         // Configure block-based table options
         let mut block_opts = BlockBasedOptions::default();
         let cache = Cache::new_lru_cache(128 * 1024 * 1024); // 128MB cache
         block_opts.set_block_cache(&cache);
         block_opts.set_block_size(16 * 1024); // 16KB block size
-        
+
         // Open the database
-        let db = DB::open_cf(
-            &opts,
-            path,
-            &["default", "nodes", "edges", "indices"],
-        )?;
-        
+        let db = DB::open_cf(&opts, path, COLUMN_FAMILIES)?;
+
         Ok(Self {
             db: Arc::new(db),
             cache: Some(cache),
+            metrics: Arc::new(StorageMetrics::new()),
         })
     }
-    
+
     /// Get a reference to the underlying RocksDB instance
     pub fn inner(&self) -> &DB {
         &self.db
     }
+
+    /// Cross-backend operation/batch-latency counters for this store; see
+    /// [`StorageMetrics`].
+    pub fn metrics(&self) -> &StorageMetrics {
+        &self.metrics
+    }
+
+    /// Look up a column family handle by name, erroring instead of
+    /// panicking if the backend wasn't opened with it (e.g. a store
+    /// opened against an older database file that predates a new family).
+    fn cf_handle(&self, name: &str) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| KnowledgeGraphError::RocksDbError(format!("unknown column family: {name}")))
+    }
 }
 
 impl Storage for RocksDBStore {
+    type Batch<'a> = RocksWriteBatchWrapper where Self: 'a;
+
     fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
-        match self.db.get(key)? {
-            Some(bytes) => {
-                let value = deserialize(&bytes)?;
-                Ok(Some(value))
-            }
+        match self.get_raw(key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
             None => Ok(None),
         }
     }
 
     fn put<T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
         let bytes = serialize(value)?;
-        self.db.put(key, bytes)?;
-        Ok(())
+        self.put_raw(key, &bytes)
     }
 
     fn delete(&self, key: &[u8]) -> Result<()> {
+        self.metrics.record_delete();
         self.db.delete(key)?;
         Ok(())
     }
@@ -83,53 +114,420 @@ impl Storage for RocksDBStore {
         Ok(self.db.get_pinned(key)?.is_some())
     }
 
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.metrics.record_get();
+        Ok(self.db.get(key)?.map(|bytes| bytes.to_vec()))
+    }
+
+    fn put_raw(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.metrics.record_put();
+        self.db.put(key, value)?;
+        Ok(())
+    }
+
     fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
-        let iter = self.db.iterator(IteratorMode::From(prefix, rocksdb::Direction::Forward));
-        
+        // `prefix_iterator` is RocksDB's dedicated prefix-scan entry point
+        // rather than a plain positional seek -- when a prefix extractor is
+        // configured on the column family it lets the engine skip whole
+        // blocks via the bloom filter instead of reading every key from
+        // `prefix` to the end of the database. We still filter with
+        // `starts_with` afterward: without a configured extractor (the case
+        // here) RocksDB makes no promise that iteration stops at the
+        // boundary on its own.
+        let iter = self.db.prefix_iterator(prefix);
+
         let prefix_vec = prefix.to_vec();
-        let filtered = iter.filter_map(move |item| {
-            match item {
-                Ok((key, value)) if key.starts_with(&prefix_vec) => {
-                    Some((key.to_vec(), value.to_vec()))
-                }
-                _ => None,
-            }
+        let filtered = iter.map_while(move |item| match item {
+            Ok((key, value)) if key.starts_with(&prefix_vec) => Some((key.to_vec(), value.to_vec())),
+            Ok(_) => None,
+            Err(_) => None,
         });
-        
+
         Box::new(filtered)
     }
 
-    fn batch(&self) -> Box<dyn WriteBatch> {
-        Box::new(RocksWriteBatchWrapper {
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        direction: IterDirection,
+        seek: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        // Bounds are pushed down to RocksDB via `ReadOptions` so the engine
+        // itself stops at `end` (or `start`, scanning backwards) instead of
+        // the Rust-side `key.starts_with(prefix)` filter `iter_prefix` uses,
+        // which keeps pulling and deserializing entries past the boundary
+        // until the filter happens to reject one.
+        let mut read_opts = rocksdb::ReadOptions::default();
+        read_opts.set_iterate_lower_bound(start.to_vec());
+        if let Some(end) = end {
+            read_opts.set_iterate_upper_bound(end.to_vec());
+        }
+
+        let mode = match (direction, seek) {
+            (IterDirection::Forward, Some(seek)) => IteratorMode::From(seek, rocksdb::Direction::Forward),
+            (IterDirection::Forward, None) => IteratorMode::Start,
+            (IterDirection::Reverse, Some(seek)) => IteratorMode::From(seek, rocksdb::Direction::Reverse),
+            (IterDirection::Reverse, None) => IteratorMode::End,
+        };
+
+        let iter = self.db.iterator_opt(mode, read_opts);
+        let mapped = iter.map_while(|item| item.ok().map(|(key, value)| (key.to_vec(), value.to_vec())));
+
+        match limit {
+            Some(limit) => Box::new(mapped.take(limit)),
+            None => Box::new(mapped),
+        }
+    }
+
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        seek: Option<&[u8]>,
+        limit: Option<usize>,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let end = prefix_successor(prefix);
+        self.iter_range(prefix, end.as_deref(), IterDirection::Forward, seek, limit)
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        RocksWriteBatchWrapper {
             batch: RocksWriteBatch::default(),
             db: Arc::clone(&self.db),
-        })
+            metrics: Arc::clone(&self.metrics),
+        }
+    }
+}
+
+impl WriteBatchExt for RocksDBStore {}
+
+impl Checkpoint for RocksDBStore {
+    fn checkpoint_to_path(&self, path: &Path) -> Result<()> {
+        // `set_manual_wal_flush(true)` (see `open` above) leaves the WAL
+        // buffer unflushed until `flush_wal` is called, for write
+        // throughput, so a checkpoint has
+        // to force one first -- otherwise a checkpoint taken right after a
+        // write could be missing data the caller already got an `Ok` back
+        // for.
+        self.db.flush_wal(true)?;
+
+        // RocksDB's checkpoint API hard-links the live SST files into
+        // `path` and only copies the small amount of state that can't be
+        // shared (CURRENT, the manifest, an up-to-date WAL), so the result
+        // is both crash-consistent and cheap relative to a full copy.
+        let checkpoint = rocksdb::checkpoint::Checkpoint::new(&self.db)?;
+        checkpoint.create_checkpoint(path)?;
+        Ok(())
+    }
+
+    fn open_checkpoint(path: &Path) -> Result<Self> {
+        Self::open(path)
+    }
+}
+
+impl ColumnFamilyStore for RocksDBStore {
+    fn get_cf<C: ColumnFamily, T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        let cf = self.cf_handle(C::NAME)?;
+        match self.db.get_cf(cf, key)? {
+            Some(bytes) => Ok(Some(deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put_cf<C: ColumnFamily, T: Serialize>(&self, key: &[u8], value: &T) -> Result<()> {
+        let cf = self.cf_handle(C::NAME)?;
+        let bytes = serialize(value)?;
+        self.db.put_cf(cf, key, bytes)?;
+        Ok(())
+    }
+
+    fn delete_cf<C: ColumnFamily>(&self, key: &[u8]) -> Result<()> {
+        let cf = self.cf_handle(C::NAME)?;
+        self.db.delete_cf(cf, key)?;
+        Ok(())
+    }
+
+    fn iter_prefix_cf<'a, C: ColumnFamily>(
+        &'a self,
+        prefix: &[u8],
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        let cf = match self.cf_handle(C::NAME) {
+            Ok(cf) => cf,
+            Err(_) => return Box::new(std::iter::empty()),
+        };
+
+        let iter = self
+            .db
+            .iterator_cf(cf, IteratorMode::From(prefix, rocksdb::Direction::Forward));
+
+        let prefix_vec = prefix.to_vec();
+        let filtered = iter.map_while(move |item| match item {
+            Ok((key, value)) if key.starts_with(&prefix_vec) => Some((key.to_vec(), value.to_vec())),
+            Ok(_) => None,
+            Err(_) => None,
+        });
+
+        Box::new(filtered)
     }
 }
 
-/// RocksDB write batch wrapper
-struct RocksWriteBatchWrapper {
+/// RocksDB write batch
+///
+/// Buffers operations in a native `rocksdb::WriteBatch` and applies them
+/// atomically against `db` on [`commit`](WriteBatch::commit), the same way
+/// every other `WriteBatch` impl in this module defers to a single commit
+/// call rather than writing eagerly.
+#[derive(Debug)]
+pub struct RocksWriteBatchWrapper {
     batch: RocksWriteBatch,
     db: Arc<DB>,
+    metrics: Arc<StorageMetrics>,
+}
+
+impl RocksWriteBatchWrapper {
+    fn cf_handle(&self, name: &str) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| KnowledgeGraphError::RocksDbError(format!("unknown column family: {name}")))
+    }
 }
 
 impl WriteBatch for RocksWriteBatchWrapper {
-    fn put<T: Serialize>(&mut self, key: &[u8], value: &T) -> Result<()> {
-        let bytes = serialize(value)?;
-        self.batch.put(key, &bytes);
+    fn put_serialized(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.batch.put(key, value);
         Ok(())
     }
 
-    fn delete(&mut self, key: &[u8]) -> Result<()> {
+    fn delete_serialized(&mut self, key: &[u8]) -> Result<()> {
         self.batch.delete(key);
         Ok(())
     }
 
-    fn commit(mut self: Box<Self>) -> Result<()> {
+    fn clear(&mut self) {
+        self.batch.clear();
+    }
+
+    fn commit(self) -> Result<()> {
+        let start = Instant::now();
         self.db.write(self.batch)?;
+        self.metrics.record_batch_commit(start.elapsed());
         Ok(())
     }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
 }
 
-// Re-export serialization functions from the parent module
-use super::{serialize, deserialize};
+impl WriteBatchCf for RocksWriteBatchWrapper {
+    fn put_cf<C: ColumnFamily>(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+        let cf = self.cf_handle(C::NAME)?;
+        self.batch.put_cf(cf, key, value);
+        Ok(())
+    }
+
+    fn delete_cf<C: ColumnFamily>(&mut self, key: &[u8]) -> Result<()> {
+        let cf = self.cf_handle(C::NAME)?;
+        self.batch.delete_cf(cf, key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::column_family::{EdgesCf, NodesCf};
+
+    #[test]
+    fn test_put_and_get() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put(b"key", &"value".to_string())?;
+        assert_eq!(store.get::<String>(b"key")?, Some("value".to_string()));
+        assert!(store.get::<String>(b"missing")?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_and_exists() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put(b"key", &"value".to_string())?;
+        assert!(store.exists(b"key")?);
+
+        store.delete(b"key")?;
+        assert!(!store.exists(b"key")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_prefix() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put(b"prefix:1", &1u64)?;
+        store.put(b"prefix:2", &2u64)?;
+        store.put(b"other:1", &3u64)?;
+
+        let mut results: Vec<_> = store
+            .iter_prefix(b"prefix:")
+            .map(|(k, v)| (k, deserialize::<u64>(&v).unwrap()))
+            .collect();
+        results.sort();
+
+        assert_eq!(results, vec![(b"prefix:1".to_vec(), 1), (b"prefix:2".to_vec(), 2)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_commit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        let mut batch = store.create_batch();
+        batch.put_serialized(b"batch1", &serialize(&100u64)?)?;
+        batch.put_serialized(b"batch2", &serialize(&200u64)?)?;
+        batch.commit()?;
+
+        assert_eq!(store.get::<u64>(b"batch1")?, Some(100));
+        assert_eq!(store.get::<u64>(b"batch2")?, Some(200));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_families_are_physically_partitioned() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put_cf::<NodesCf, _>(b"same-key", &"a node".to_string())?;
+        store.put_cf::<EdgesCf, _>(b"same-key", &"an edge".to_string())?;
+
+        assert_eq!(
+            store.get_cf::<NodesCf, String>(b"same-key")?,
+            Some("a node".to_string())
+        );
+        assert_eq!(
+            store.get_cf::<EdgesCf, String>(b"same-key")?,
+            Some("an edge".to_string())
+        );
+
+        // The default family never saw either write.
+        assert!(store.get::<String>(b"same-key")?.is_none());
+
+        store.delete_cf::<NodesCf>(b"same-key")?;
+        assert!(store.get_cf::<NodesCf, String>(b"same-key")?.is_none());
+        assert_eq!(
+            store.get_cf::<EdgesCf, String>(b"same-key")?,
+            Some("an edge".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_prefix_cf_only_sees_its_own_family() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put_cf::<NodesCf, _>(b"node:1", &1u64)?;
+        store.put_cf::<EdgesCf, _>(b"node:2", &2u64)?;
+
+        let results: Vec<_> = store.iter_prefix_cf::<NodesCf>(b"node:").collect();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, b"node:1".to_vec());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_prefix_stops_at_prefix_boundary() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        store.put(b"prefix:1", &1u64)?;
+        store.put(b"prefix:2", &2u64)?;
+        store.put(b"prefixed-but-different:1", &99u64)?;
+        store.put(b"other:1", &3u64)?;
+
+        let results: Vec<_> = store
+            .scan_prefix(b"prefix:", None, None)
+            .map(|(k, v)| (k, deserialize::<u64>(&v).unwrap()))
+            .collect();
+
+        assert_eq!(results, vec![(b"prefix:1".to_vec(), 1), (b"prefix:2".to_vec(), 2)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_prefix_paginates_via_seek_and_limit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        for i in 0..5u64 {
+            store.put(format!("page:{i}").as_bytes(), &i)?;
+        }
+
+        let first_page: Vec<_> = store.scan_prefix(b"page:", None, Some(2)).collect();
+        assert_eq!(first_page.len(), 2);
+        let (last_key, _) = first_page.last().unwrap().clone();
+
+        // Re-seeking from the last key of the previous page revisits it --
+        // `seek` is inclusive -- so callers skip one overlapping row.
+        let second_page: Vec<_> = store
+            .scan_prefix(b"page:", Some(&last_key), None)
+            .collect();
+        assert_eq!(second_page[0].0, last_key);
+        assert_eq!(second_page.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_range_reverse_walks_descending() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        for i in 0..3u64 {
+            store.put(format!("r:{i}").as_bytes(), &i)?;
+        }
+
+        let results: Vec<_> = store
+            .iter_range(b"r:", None, IterDirection::Reverse, None, None)
+            .map(|(k, _)| k)
+            .collect();
+
+        assert_eq!(results, vec![b"r:2".to_vec(), b"r:1".to_vec(), b"r:0".to_vec()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_batch_cf_commits_into_the_right_family() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = RocksDBStore::open(dir.path())?;
+
+        let mut batch = store.create_batch();
+        batch.put_cf::<NodesCf>(b"key", &serialize(&"a node".to_string())?)?;
+        batch.commit()?;
+
+        assert_eq!(
+            store.get_cf::<NodesCf, String>(b"key")?,
+            Some("a node".to_string())
+        );
+        assert!(store.get::<String>(b"key")?.is_none());
+
+        Ok(())
+    }
+}
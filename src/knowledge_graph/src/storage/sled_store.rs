@@ -1,6 +1,6 @@
 //! Sled storage implementation for the knowledge graph
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use sled::{Db, IVec};
 use serde::{Serialize, de::DeserializeOwned};
@@ -8,21 +8,24 @@ use log::info;
 use crate::error::KnowledgeGraphError;
 
 use crate::error::Result;
-use super::{Storage, WriteBatch, WriteBatchExt, serialize, deserialize};
+use super::{PrefixCursor, Storage, WriteBatch, WriteBatchExt, serialize, deserialize};
+use super::snapshot::Checkpoint;
 
 /// Sled storage implementation
 #[derive(Clone, Debug)]
 pub struct SledStore {
     db: Arc<Db>,
+    path: PathBuf,
 }
 
 impl SledStore {
     /// Open or create a new Sled database at the given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let db = sled::open(path)?;
+        let db = sled::open(path.as_ref())?;
         info!("Opened Sled database");
         Ok(Self {
             db: Arc::new(db),
+            path: path.as_ref().to_path_buf(),
         })
     }
 
@@ -32,6 +35,37 @@ impl SledStore {
     }
 }
 
+impl Checkpoint for SledStore {
+    fn checkpoint_to_path(&self, path: &Path) -> Result<()> {
+        // Sled has no dedicated snapshot API; flushing guarantees every
+        // acknowledged write is durable on disk, so copying the database
+        // directory afterward yields a crash-consistent point-in-time copy.
+        self.db.flush()?;
+        copy_dir_recursive(&self.path, path)
+    }
+
+    fn open_checkpoint(path: &Path) -> Result<Self> {
+        Self::open(path)
+    }
+}
+
+/// Recursively copy a directory tree, used to materialize a sled checkpoint
+/// at a new path once the source database has been flushed.
+fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let dst_path = dst.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir_recursive(&entry.path(), &dst_path)?;
+        } else {
+            std::fs::copy(entry.path(), dst_path)?;
+        }
+    }
+    Ok(())
+}
+
 impl Storage for SledStore {
     type Batch<'a> = SledWriteBatch where Self: 'a;
     
@@ -88,7 +122,20 @@ impl Storage for SledStore {
         
         Box::new(filtered)
     }
-    
+
+    fn iter_prefix_lazy(&self, prefix: &[u8]) -> PrefixCursor {
+        // `sled::Tree::scan_prefix` hands back an iterator that owns its own
+        // handle onto the database rather than borrowing `&self`, so unlike
+        // `iter_prefix` above (bound to `'a` only because the trait
+        // signature requires it) this cursor genuinely reads ahead from the
+        // backend as the worker thread in `PrefetchingIterator` pulls from
+        // it, instead of materializing the whole scan into a `Vec` first.
+        let scan = self.db.scan_prefix(prefix);
+        PrefixCursor::new(scan.filter_map(|item| {
+            item.ok().map(|(key, value)| (key.to_vec(), value.to_vec()))
+        }))
+    }
+
     fn create_batch(&self) -> Self::Batch<'_> {
         SledWriteBatch::new(Arc::clone(&self.db))
     }
@@ -0,0 +1,213 @@
+//! Point-in-time snapshots of a [`Storage`] backend.
+//!
+//! A [`Snapshot`] is a standalone, read-only copy of a backend's on-disk
+//! state at the moment it was taken, produced via that backend's native
+//! checkpoint facility (sled: flush + directory copy; LMDB: compacting
+//! `mdb_env_copy2` via [`heed::Env::copy_to_path`]). Because it's a fully
+//! independent copy rather than a view into the live store, its contents
+//! never change regardless of what happens to the store it was taken from.
+
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{migrate, DEFAULT_MIGRATION_BATCH_SIZE};
+use super::{MigrationReport, Result, Storage, WriteBatchExt};
+use crate::error::KnowledgeGraphError;
+
+/// Extension trait for backends that can materialize themselves onto a new
+/// path and be reopened from it, used by [`Snapshot`] to take crash-consistent
+/// point-in-time copies.
+pub trait Checkpoint: Storage + Sized {
+    /// Flush this store and copy its durable state to `path`, which must be
+    /// usable afterward as an independent instance of the same backend.
+    fn checkpoint_to_path(&self, path: &Path) -> Result<()>;
+
+    /// Open a directory previously written by `checkpoint_to_path` as a
+    /// standalone store.
+    fn open_checkpoint(path: &Path) -> Result<Self>;
+}
+
+/// A read-only, point-in-time copy of a [`Storage`] backend.
+///
+/// `get`/`iter_prefix` on a `Snapshot` always reflect the data as it existed
+/// when [`HybridStore::snapshot`](super::HybridStore::snapshot) was called,
+/// no matter what writes happen afterward to the live store it was taken
+/// from, since the snapshot is its own independent copy on disk rather than
+/// a view into the original.
+pub struct Snapshot<P: Storage> {
+    sequence_id: u64,
+    path: PathBuf,
+    store: P,
+}
+
+impl<P: Checkpoint> Snapshot<P> {
+    /// Take a snapshot of `source` at `path`, tagging it with `sequence_id`.
+    pub(crate) fn capture(source: &P, path: &Path, sequence_id: u64) -> Result<Self> {
+        source.checkpoint_to_path(path)?;
+        let store = P::open_checkpoint(path)?;
+        Ok(Self {
+            sequence_id,
+            path: path.to_path_buf(),
+            store,
+        })
+    }
+}
+
+impl<P: Storage> Snapshot<P> {
+    /// The monotonically increasing sequence id assigned to this snapshot by
+    /// the store that produced it.
+    pub fn sequence_id(&self) -> u64 {
+        self.sequence_id
+    }
+
+    /// The on-disk path this snapshot was materialized at.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write a small JSON manifest recording this snapshot's sequence id and
+    /// path, for backup tooling to track alongside the snapshot directory.
+    pub fn write_manifest(&self, manifest_path: impl AsRef<Path>) -> Result<()> {
+        let manifest = serde_json::json!({
+            "sequence_id": self.sequence_id,
+            "path": self.path.to_string_lossy(),
+        });
+        let bytes = serde_json::to_vec_pretty(&manifest).map_err(KnowledgeGraphError::from)?;
+        std::fs::write(manifest_path, bytes).map_err(KnowledgeGraphError::from)
+    }
+
+    /// Restore this snapshot's data into a fresh destination store, e.g. to
+    /// bring up a replica from a backup.
+    pub fn restore_into<D: Storage>(&self, dst: &D) -> Result<MigrationReport> {
+        migrate::migrate(&self.store, dst, DEFAULT_MIGRATION_BATCH_SIZE)
+    }
+}
+
+impl<P: Storage> Storage for Snapshot<P> {
+    type Batch<'a> = P::Batch<'a> where Self: 'a;
+
+    fn get<T: DeserializeOwned>(&self, key: &[u8]) -> Result<Option<T>> {
+        self.store.get(key)
+    }
+
+    fn put<T: Serialize>(&self, _key: &[u8], _value: &T) -> Result<()> {
+        Err(KnowledgeGraphError::InvalidOperation(
+            "snapshots are read-only".to_string(),
+        ))
+    }
+
+    fn delete(&self, _key: &[u8]) -> Result<()> {
+        Err(KnowledgeGraphError::InvalidOperation(
+            "snapshots are read-only".to_string(),
+        ))
+    }
+
+    fn exists(&self, key: &[u8]) -> Result<bool> {
+        self.store.exists(key)
+    }
+
+    fn get_raw(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.store.get_raw(key)
+    }
+
+    fn put_raw(&self, _key: &[u8], _value: &[u8]) -> Result<()> {
+        Err(KnowledgeGraphError::InvalidOperation(
+            "snapshots are read-only".to_string(),
+        ))
+    }
+
+    fn iter_prefix<'a>(&'a self, prefix: &[u8]) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a> {
+        self.store.iter_prefix(prefix)
+    }
+
+    fn create_batch(&self) -> Self::Batch<'_> {
+        self.store.create_batch()
+    }
+}
+
+impl<P: Storage> WriteBatchExt for Snapshot<P> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{LmdbStore, RocksDBStore, SledStore};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sled_snapshot_is_independent_of_later_writes() {
+        let source_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+
+        let source = SledStore::open(source_dir.path()).unwrap();
+        source.put(b"key", &1u64).unwrap();
+
+        let snapshot = Snapshot::capture(&source, snapshot_dir.path(), 1).unwrap();
+        source.put(b"key", &2u64).unwrap();
+        source.put(b"new-key", &3u64).unwrap();
+
+        assert_eq!(snapshot.get::<u64>(b"key").unwrap(), Some(1));
+        assert_eq!(snapshot.get::<u64>(b"new-key").unwrap(), None);
+        assert_eq!(snapshot.sequence_id(), 1);
+    }
+
+    #[test]
+    fn test_lmdb_snapshot_restores_into_fresh_store() {
+        let source_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+        let restore_dir = tempdir().unwrap();
+
+        let source = LmdbStore::open(source_dir.path()).unwrap();
+        for i in 0..10u32 {
+            source.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        let snapshot = Snapshot::capture(&source, snapshot_dir.path(), 7).unwrap();
+
+        let restored = LmdbStore::open(restore_dir.path()).unwrap();
+        let report = snapshot.restore_into(&restored).unwrap();
+
+        assert_eq!(report.keys_migrated, 10);
+        assert!(report.is_consistent());
+        assert_eq!(restored.get::<u32>(b"key-0").unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_rocksdb_checkpoint_flushes_wal_and_restores_into_fresh_store() {
+        let source_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+        let restore_dir = tempdir().unwrap();
+
+        let source = RocksDBStore::open(source_dir.path()).unwrap();
+        for i in 0..10u32 {
+            source.put(format!("key-{i}").as_bytes(), &i).unwrap();
+        }
+
+        // `RocksDBStore` opens with `set_manual_wal_flush(true)`, so this
+        // snapshot only reflects the writes above if `checkpoint_to_path`
+        // actually forces a WAL flush before handing off to the checkpoint
+        // API, rather than relying on a flush that may not have happened
+        // yet.
+        let snapshot = Snapshot::capture(&source, snapshot_dir.path(), 3).unwrap();
+        source.put(b"after-snapshot", &99u32).unwrap();
+
+        let restored = RocksDBStore::open(restore_dir.path()).unwrap();
+        let report = snapshot.restore_into(&restored).unwrap();
+
+        assert_eq!(report.keys_migrated, 10);
+        assert!(report.is_consistent());
+        assert_eq!(restored.get::<u32>(b"key-0").unwrap(), Some(0));
+        assert_eq!(restored.get::<u32>(b"after-snapshot").unwrap(), None);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_writes() {
+        let source_dir = tempdir().unwrap();
+        let snapshot_dir = tempdir().unwrap();
+
+        let source = SledStore::open(source_dir.path()).unwrap();
+        let snapshot = Snapshot::capture(&source, snapshot_dir.path(), 1).unwrap();
+
+        assert!(snapshot.put(b"key", &1u64).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! Typed, cached access layer over [`Storage`].
+//!
+//! Call sites that persist a single Rust type (see `node_key`/`edge_key` in
+//! [`batch_optimizer`](super::batch_optimizer)) hand-roll key encoding and
+//! [`serialize`]/[`deserialize`] calls themselves. [`TypedStore`] wraps that
+//! boilerplate behind a `get`/`insert`/`remove`/`multi_get` API keyed on `K`
+//! directly, backed by a write-through cache distinct from
+//! [`CachedStore`](super::CachedStore)'s raw-byte cache -- this one holds
+//! already-decoded `V`s, so a hit skips deserialization entirely. The cache
+//! is split into independent shards, each guarded by its own lock, so
+//! parallel access (e.g. from [`BatchProcessor`](super::batch_optimizer::BatchProcessor))
+//! doesn't serialize on a single `RwLock`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::storage::{deserialize, serialize, Storage, WriteBatch};
+
+/// Eviction policy for a [`TypedStore`]'s cache shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Evict the least-recently-used entry once a shard is at capacity.
+    Lru,
+    /// Never evict: every inserted key stays cached until explicitly
+    /// removed. Intended for small, fully-resident key spaces where the
+    /// backing `Storage` lookup is worth avoiding entirely.
+    Unbounded,
+}
+
+/// Configuration for a [`TypedStore`]. Distinct from
+/// [`CacheConfig`](super::cached_store::CacheConfig), which governs
+/// `CachedStore`'s raw-byte cache.
+#[derive(Debug, Clone)]
+pub struct TypedStoreConfig {
+    /// Total entries cached across all shards.
+    pub capacity: usize,
+    /// Number of independent cache shards the capacity is split across.
+    /// More shards reduce lock contention under parallel access, at the
+    /// cost of a coarser global eviction order (each shard evicts on its
+    /// own, so the overall cache isn't a single strict LRU).
+    pub shards: usize,
+    /// Eviction policy applied within each shard.
+    pub eviction: EvictionPolicy,
+}
+
+impl Default for TypedStoreConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            shards: 16,
+            eviction: EvictionPolicy::Lru,
+        }
+    }
+}
+
+/// A write-through, typed cache in front of a [`Storage`] backend.
+///
+/// `K` is both the cache key and (via [`serialize`]) the encoding of the
+/// key bytes handed to `inner`; `V` is cached already-deserialized, so a
+/// cache hit costs no (de)serialization at all.
+pub struct TypedStore<S, K, V> {
+    inner: S,
+    shards: Vec<RwLock<LruCache<K, V>>>,
+}
+
+impl<S, K, V> TypedStore<S, K, V>
+where
+    S: Storage,
+    K: Serialize + DeserializeOwned + Eq + Hash + Clone,
+    V: Serialize + DeserializeOwned + Clone,
+{
+    /// Create a new typed store with default configuration.
+    pub fn new(inner: S) -> Self {
+        Self::with_config(inner, TypedStoreConfig::default())
+    }
+
+    /// Create a new typed store with custom configuration.
+    pub fn with_config(inner: S, config: TypedStoreConfig) -> Self {
+        let shard_count = config.shards.max(1);
+        let per_shard_capacity = (config.capacity / shard_count).max(1);
+
+        let shards = (0..shard_count)
+            .map(|_| {
+                let cache = match config.eviction {
+                    EvictionPolicy::Lru => {
+                        LruCache::new(NonZeroUsize::new(per_shard_capacity).unwrap())
+                    }
+                    EvictionPolicy::Unbounded => LruCache::unbounded(),
+                };
+                RwLock::new(cache)
+            })
+            .collect();
+
+        Self { inner, shards }
+    }
+
+    /// Get a reference to the underlying storage.
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// The shard a given key's entry lives in, chosen by hashing `key`.
+    fn shard_for(&self, key: &K) -> &RwLock<LruCache<K, V>> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() as usize) % self.shards.len();
+        &self.shards[index]
+    }
+
+    fn encode_key(key: &K) -> Result<Vec<u8>> {
+        serialize(key)
+    }
+
+    /// Look up `key`, checking the cache before falling back to `inner`.
+    /// A backend hit is cached for subsequent lookups.
+    pub fn get(&self, key: &K) -> Result<Option<V>> {
+        if let Some(value) = self.shard_for(key).write().get(key).cloned() {
+            return Ok(Some(value));
+        }
+
+        let key_bytes = Self::encode_key(key)?;
+        let value: Option<V> = self.inner.get(&key_bytes)?;
+        if let Some(ref value) = value {
+            self.shard_for(key).write().put(key.clone(), value.clone());
+        }
+        Ok(value)
+    }
+
+    /// Look up several keys at once, preserving the order of `keys`.
+    pub fn multi_get(&self, keys: &[K]) -> Result<Vec<Option<V>>> {
+        keys.iter().map(|key| self.get(key)).collect()
+    }
+
+    /// Write `value` through to `inner`, then update the cache.
+    pub fn insert(&self, key: &K, value: &V) -> Result<()> {
+        let key_bytes = Self::encode_key(key)?;
+        self.inner.put(&key_bytes, value)?;
+        self.shard_for(key).write().put(key.clone(), value.clone());
+        Ok(())
+    }
+
+    /// Write every `(key, value)` pair through to `inner` in one batch,
+    /// then update the cache for each.
+    pub fn insert_batch(&self, items: &[(K, V)]) -> Result<()> {
+        let mut batch = self.inner.create_batch();
+        for (key, value) in items {
+            let key_bytes = Self::encode_key(key)?;
+            let value_bytes = serialize(value)?;
+            batch.put_serialized(&key_bytes, &value_bytes)?;
+        }
+        batch.commit()?;
+
+        for (key, value) in items {
+            self.shard_for(key).write().put(key.clone(), value.clone());
+        }
+        Ok(())
+    }
+
+    /// Remove `key` from `inner` and evict it from the cache.
+    pub fn remove(&self, key: &K) -> Result<()> {
+        let key_bytes = Self::encode_key(key)?;
+        self.inner.delete(&key_bytes)?;
+        self.shard_for(key).write().pop(key);
+        Ok(())
+    }
+
+    /// Whether `key` is currently cached, without touching `inner`.
+    pub fn contains_cached(&self, key: &K) -> bool {
+        self.shard_for(key).read().contains(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::sled_store::SledStore;
+    use serde::{Deserialize, Serialize};
+    use tempfile::tempdir;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Widget {
+        name: String,
+        count: u64,
+    }
+
+    #[test]
+    fn test_insert_then_get_roundtrips_and_is_cached() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::new(store);
+
+        let widget = Widget { name: "cog".to_string(), count: 3 };
+        typed.insert(&1, &widget).unwrap();
+
+        assert!(typed.contains_cached(&1));
+        assert_eq!(typed.get(&1).unwrap(), Some(widget));
+    }
+
+    #[test]
+    fn test_get_miss_populates_cache_from_backend() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        store.put(&serialize(&7u64).unwrap(), &Widget { name: "gear".to_string(), count: 1 }).unwrap();
+
+        let typed: TypedStore<_, u64, Widget> = TypedStore::new(store);
+        assert!(!typed.contains_cached(&7));
+
+        let value = typed.get(&7).unwrap();
+        assert_eq!(value, Some(Widget { name: "gear".to_string(), count: 1 }));
+        assert!(typed.contains_cached(&7));
+    }
+
+    #[test]
+    fn test_remove_evicts_from_cache_and_backend() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::new(store);
+
+        typed.insert(&1, &Widget { name: "cog".to_string(), count: 3 }).unwrap();
+        typed.remove(&1).unwrap();
+
+        assert!(!typed.contains_cached(&1));
+        assert_eq!(typed.get(&1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_multi_get_preserves_order_including_misses() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::new(store);
+
+        typed.insert(&1, &Widget { name: "a".to_string(), count: 1 }).unwrap();
+        typed.insert(&3, &Widget { name: "c".to_string(), count: 3 }).unwrap();
+
+        let results = typed.multi_get(&[1, 2, 3]).unwrap();
+        assert_eq!(
+            results,
+            vec![
+                Some(Widget { name: "a".to_string(), count: 1 }),
+                None,
+                Some(Widget { name: "c".to_string(), count: 3 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_batch_writes_through_in_one_batch() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::new(store);
+
+        let items: Vec<(u64, Widget)> = (0..10)
+            .map(|i| (i, Widget { name: format!("w{i}"), count: i }))
+            .collect();
+        typed.insert_batch(&items).unwrap();
+
+        for (key, value) in &items {
+            assert!(typed.contains_cached(key));
+            assert_eq!(typed.get(key).unwrap(), Some(value.clone()));
+        }
+    }
+
+    #[test]
+    fn test_unbounded_eviction_policy_never_drops_entries() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::with_config(
+            store,
+            TypedStoreConfig {
+                capacity: 2,
+                shards: 1,
+                eviction: EvictionPolicy::Unbounded,
+            },
+        );
+
+        for i in 0..50u64 {
+            typed.insert(&i, &Widget { name: format!("w{i}"), count: i }).unwrap();
+        }
+
+        for i in 0..50u64 {
+            assert!(typed.contains_cached(&i));
+        }
+    }
+
+    #[test]
+    fn test_lru_eviction_policy_respects_capacity() {
+        let dir = tempdir().unwrap();
+        let store = SledStore::open(dir.path()).unwrap();
+        let typed: TypedStore<_, u64, Widget> = TypedStore::with_config(
+            store,
+            TypedStoreConfig {
+                capacity: 4,
+                shards: 1,
+                eviction: EvictionPolicy::Lru,
+            },
+        );
+
+        for i in 0..20u64 {
+            typed.insert(&i, &Widget { name: format!("w{i}"), count: i }).unwrap();
+        }
+
+        // Capacity 4 across 1 shard: most early keys must have been evicted
+        // from the cache, even though they're still readable from the
+        // backend (write-through, not write-back).
+        assert!(!typed.contains_cached(&0));
+        assert!(typed.contains_cached(&19));
+        assert_eq!(typed.get(&0).unwrap(), Some(Widget { name: "w0".to_string(), count: 0 }));
+    }
+}
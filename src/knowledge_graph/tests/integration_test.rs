@@ -3,7 +3,7 @@
 
 use maya_knowledge_graph::{
     KnowledgeGraph, Node, Edge, Property, PropertyValue,
-    storage::SledStore,
+    storage::{LmdbStore, RedbStore, SledStore, Storage, WriteBatchExt},
     query::QueryExt,
 };
 use tempfile::tempdir;
@@ -23,18 +23,18 @@ fn create_location_node(name: &str, capacity: i32) -> Node {
     node
 }
 
-#[test]
-fn test_end_to_end_workflow() -> Result<(), Box<dyn Error>> {
-    // Create a test graph with explicit type
-    let dir = tempdir()?;
-    let store = SledStore::open(dir.path())?;
-    let graph = KnowledgeGraph::new(store);
-    
+/// The end-to-end workflow below against each of the embedded [`Storage`]
+/// backends `SledStore`, `LmdbStore` and `RedbStore`, to prove `transaction`/`add_node`/`add_edge`/`query`/
+/// `query_edges_from` behave identically regardless of which one backs the
+/// graph. Each backend gets its own `#[test]` below instead of looping over
+/// them in one test, so a failure on e.g. `RedbStore` alone still reports
+/// under its own name.
+fn run_end_to_end_workflow<S: Storage + WriteBatchExt>(graph: KnowledgeGraph<S>) -> Result<(), Box<dyn Error>> {
     // Create some nodes
     let alice = create_test_node("Person", "Alice", 30);
     let bob = create_test_node("Person", "Bob", 25);
     let office = create_location_node("Office", 50);
-    
+
     // Add nodes in a transaction
     graph.transaction(|tx| {
         tx.add_node(&alice)?;
@@ -118,6 +118,27 @@ fn test_end_to_end_workflow() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[test]
+fn test_end_to_end_workflow_sled() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let store = SledStore::open(dir.path())?;
+    run_end_to_end_workflow(KnowledgeGraph::new(store))
+}
+
+#[test]
+fn test_end_to_end_workflow_lmdb() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let store = LmdbStore::open(dir.path())?;
+    run_end_to_end_workflow(KnowledgeGraph::new(store))
+}
+
+#[test]
+fn test_end_to_end_workflow_redb() -> Result<(), Box<dyn Error>> {
+    let dir = tempdir()?;
+    let store = RedbStore::open(dir.path().join("graph.redb"))?;
+    run_end_to_end_workflow(KnowledgeGraph::new(store))
+}
+
 #[test]
 fn test_persistence() -> Result<(), Box<dyn Error>> {
     // Use a unique temporary directory for this test